@@ -42,8 +42,32 @@ pub struct AppData {
 #[serde(rename_all = "camelCase")]
 pub struct AppDataConfig {
     config_path: Option<PathBuf>,
+    /// Variant of the network configuration, see [canzero_yaml::variants].
+    #[serde(default)]
+    variant: Option<String>,
     frontend_wdg_lvl: WdgLevel,
     deadlock_wdg_lvl: WdgLevel,
+    /// Record a session log of every connection to the network.
+    #[serde(default = "default_record_sessions")]
+    record_sessions: bool,
+    /// Recording of a session stops once its log reaches this size.
+    #[serde(default = "default_max_session_log_mb")]
+    max_session_log_mb: u64,
+    /// Number of kept session logs, the oldest logs are deleted first.
+    #[serde(default = "default_max_session_logs")]
+    max_session_logs: usize,
+}
+
+fn default_record_sessions() -> bool {
+    true
+}
+
+fn default_max_session_log_mb() -> u64 {
+    512
+}
+
+fn default_max_session_logs() -> usize {
+    20
 }
 
 
@@ -75,6 +99,55 @@ impl AppData {
         appdata_path
     }
 
+    /// Creates a path for a new session log in the appdata directory,
+    /// `None` if recording sessions is disabled.
+    /// The oldest session logs are deleted, such that at most `max_session_logs` are kept.
+    pub fn new_session_log_path(&self) -> Result<Option<PathBuf>> {
+        if !self.config.record_sessions {
+            return Ok(None);
+        }
+        let mut path = Self::dir();
+        path.push("sessions");
+        Self::rec_create_directories(&path)?;
+        Self::remove_old_session_logs(&path, self.config.max_session_logs.saturating_sub(1))?;
+        path.push(format!(
+            "{}.czlog",
+            chrono::Local::now().format("%Y-%m-%d_%H-%M-%S")
+        ));
+        Ok(Some(path))
+    }
+
+    /// Deletes the oldest session logs in `dir` until at most `keep` are left.
+    fn remove_old_session_logs(dir: &Path, keep: usize) -> Result<()> {
+        let mut logs: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "czlog"))
+            .collect();
+        // the names of session logs are their creation time.
+        logs.sort();
+        let excess = logs.len().saturating_sub(keep);
+        for log in &logs[..excess] {
+            std::fs::remove_file(log)?;
+        }
+        Ok(())
+    }
+
+    /// Size limit of a session log in bytes.
+    pub fn max_session_log_size(&self) -> u64 {
+        self.config.max_session_log_mb * 1024 * 1024
+    }
+
+    pub fn get_record_sessions(&self) -> bool {
+        self.config.record_sessions
+    }
+
+    pub fn set_record_sessions(&mut self, record_sessions: bool) {
+        if record_sessions != self.config.record_sessions {
+            self.config.record_sessions = record_sessions;
+            self.config_change_flag = true;
+        }
+    }
+
     pub fn set_config_path(&mut self, path: Option<PathBuf>) -> Result<()> {
         let new_config_path = match path {
             Some(path) => Some(std::fs::canonicalize(path)?),
@@ -87,11 +160,20 @@ impl AppData {
         }
         if new_config_path != self.config.config_path {
             self.config.config_path = new_config_path;
+            // variants are defined by the configuration.
+            self.config.variant = None;
             self.config_change_flag = true;
         }
         Ok(())
     }
 
+    pub fn set_variant(&mut self, variant: Option<String>) {
+        if variant != self.config.variant {
+            self.config.variant = variant;
+            self.config_change_flag = true;
+        }
+    }
+
     pub fn set_frontend_wdg_lvl(&mut self, lvl: WdgLevel) {
         if self.get_frontend_wdg_lvl() != lvl {
             self.config.frontend_wdg_lvl = lvl;
@@ -110,6 +192,10 @@ impl AppData {
         self.config.config_path.as_ref()
     }
 
+    pub fn get_variant(&self) -> Option<&str> {
+        self.config.variant.as_deref()
+    }
+
     pub fn get_frontend_wdg_lvl(&self) -> WdgLevel {
         self.config.frontend_wdg_lvl
     }
@@ -137,9 +223,10 @@ impl AppData {
 
     pub fn config(&self) -> Result<NetworkRef> {
         match self.get_config_path() {
-            Some(path) => canzero_yaml::parse_yaml_config_from_file(
+            Some(path) => canzero_yaml::parse_yaml_config_variant_from_file(
                 path.to_str()
                     .expect("non utf file paths are not supported by CANzero"),
+                self.get_variant(),
             )
             .map_err(|err| AppDataError::ConfigError(err)),
             None => Err(canzero_yaml::errors::Error::Io(std::io::Error::new(
@@ -150,11 +237,30 @@ impl AppData {
         }
     }
 
+    /// Same as [AppData::config], but creates or updates the id lock file of the
+    /// configuration. Only used when generating code, reading never writes the lock.
+    pub fn lock_config(&self) -> Result<NetworkRef> {
+        match self.get_config_path() {
+            Some(path) => canzero_yaml::lock_yaml_config_from_file(
+                path.to_str()
+                    .expect("non utf file paths are not supported by CANzero"),
+                self.get_variant(),
+            )
+            .map_err(AppDataError::ConfigError),
+            None => Err(canzero_yaml::errors::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "No config file specified".to_owned(),
+            ))
+            .into()),
+        }
+    }
+
     pub fn config_files(&self) -> Result<Vec<PathBuf>> {
         match self.get_config_path() {
             Some(path) => canzero_yaml::parse_yaml_config_files_from_file(
                 path.to_str()
                     .expect("non utf file paths are not supported by CANzero"),
+                self.get_variant(),
             )
             .map_err(|err| AppDataError::ConfigError(err)),
             None => Err(canzero_yaml::errors::Error::Io(std::io::Error::new(
@@ -187,8 +293,12 @@ impl Default for AppData {
             config_change_flag: false,
             config: AppDataConfig {
                 config_path: None,
+                variant: None,
                 deadlock_wdg_lvl: WdgLevel::Active,
                 frontend_wdg_lvl: WdgLevel::Active,
+                record_sessions: default_record_sessions(),
+                max_session_log_mb: default_max_session_log_mb(),
+                max_session_logs: default_max_session_logs(),
             },
         }
     }
@@ -0,0 +1,223 @@
+use canzero_appdata::AppData;
+use canzero_config::config::{
+    bus_load::{analyze_bus_load, Load},
+    response_time::analyze_response_times,
+};
+use color_print::{ceprintln, cprintln};
+use serde::Serialize;
+
+use crate::{
+    errors::Result,
+    format::{print_structured, OutputFormat},
+};
+
+/// Name under which the load of clients (e.g. the control panel) is reported.
+const CLIENTS: &str = "(clients)";
+
+#[derive(Serialize)]
+struct LoadSummary<'a> {
+    name: &'a str,
+    /// worst-case utilisation in percent.
+    worst_case: f64,
+    /// average utilisation in percent.
+    average: f64,
+}
+
+impl<'a> LoadSummary<'a> {
+    fn new(name: &'a str, load: Load, baudrate: u32) -> Self {
+        Self {
+            name,
+            worst_case: load.worst_case / baudrate as f64 * 100.0,
+            average: load.average / baudrate as f64 * 100.0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct BusLoadSummary<'a> {
+    bus: &'a str,
+    baudrate: u32,
+    worst_case: f64,
+    average: f64,
+    exceeds_threshold: bool,
+    nodes: Vec<LoadSummary<'a>>,
+    messages: Vec<LoadSummary<'a>>,
+}
+
+fn print_loads(header: &str, loads: &[LoadSummary]) {
+    println!("  {header:40} {:>10} {:>10}", "worst-case", "average");
+    for load in loads {
+        println!(
+            "  {:40} {:>8.2} % {:>8.2} %",
+            load.name, load.worst_case, load.average
+        );
+    }
+}
+
+/// Prints the worst-case and average utilisation of every bus and warns about buses
+/// with a worst-case utilisation above `threshold` percent.
+pub fn command_analyze_load(threshold: f64, format: OutputFormat) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+
+    let bus_loads = analyze_bus_load(&network);
+    let summaries: Vec<BusLoadSummary> = bus_loads
+        .iter()
+        .map(|bus_load| {
+            let baudrate = bus_load.bus().baudrate();
+            BusLoadSummary {
+                bus: bus_load.bus().name(),
+                baudrate,
+                worst_case: bus_load.worst_case_utilisation() * 100.0,
+                average: bus_load.average_utilisation() * 100.0,
+                exceeds_threshold: bus_load.worst_case_utilisation() * 100.0 > threshold,
+                nodes: bus_load
+                    .nodes()
+                    .iter()
+                    .map(|node_load| {
+                        let name = node_load.node().map_or(CLIENTS, |node| node.name());
+                        LoadSummary::new(name, node_load.load(), baudrate)
+                    })
+                    .collect(),
+                messages: bus_load
+                    .messages()
+                    .iter()
+                    .map(|message_load| {
+                        LoadSummary::new(
+                            message_load.message().name(),
+                            message_load.load(),
+                            baudrate,
+                        )
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    if !format.is_table() {
+        print_structured(format, &summaries)?;
+    } else {
+        for summary in &summaries {
+            let line = format!(
+                "{} ({} baud) : worst-case {:.2} %, average {:.2} %",
+                summary.bus, summary.baudrate, summary.worst_case, summary.average
+            );
+            if summary.exceeds_threshold {
+                cprintln!("<yellow>{line}</yellow>");
+            } else {
+                println!("{line}");
+            }
+            print_loads("node", &summary.nodes);
+            print_loads("message", &summary.messages);
+            println!();
+        }
+    }
+    for summary in summaries.iter().filter(|summary| summary.exceeds_threshold) {
+        ceprintln!(
+            "<yellow>warning</yellow> : worst-case load of bus {} is {:.2} % (threshold {threshold} %)",
+            summary.bus,
+            summary.worst_case
+        );
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ResponseTimeSummary<'a> {
+    name: &'a str,
+    id: u32,
+    ide: bool,
+    /// worst-case response time in milliseconds, none if the bus is overloaded.
+    response_time_ms: Option<f64>,
+    deadline_ms: Option<f64>,
+    misses_deadline: bool,
+}
+
+#[derive(Serialize)]
+struct BusResponseTimeSummary<'a> {
+    bus: &'a str,
+    baudrate: u32,
+    messages: Vec<ResponseTimeSummary<'a>>,
+}
+
+fn fmt_ms(ms: Option<f64>) -> String {
+    match ms {
+        Some(ms) => format!("{ms:.3} ms"),
+        None => "-".to_owned(),
+    }
+}
+
+/// Prints the worst-case response time of every message against its deadline
+/// and warns about messages that miss it.
+pub fn command_analyze_response_time(format: OutputFormat) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+
+    let bus_response_times = analyze_response_times(&network);
+    let summaries: Vec<BusResponseTimeSummary> = bus_response_times
+        .iter()
+        .map(|(bus, response_times)| BusResponseTimeSummary {
+            bus: bus.name(),
+            baudrate: bus.baudrate(),
+            messages: response_times
+                .iter()
+                .map(|response_time| ResponseTimeSummary {
+                    name: response_time.message().name(),
+                    id: response_time.message().id().as_u32(),
+                    ide: response_time.message().id().ide(),
+                    response_time_ms: response_time
+                        .response_time()
+                        .map(|time| time.as_secs_f64() * 1e3),
+                    deadline_ms: response_time
+                        .deadline()
+                        .map(|time| time.as_secs_f64() * 1e3),
+                    misses_deadline: response_time.misses_deadline(),
+                })
+                .collect(),
+        })
+        .collect();
+
+    if !format.is_table() {
+        print_structured(format, &summaries)?;
+    } else {
+        for summary in &summaries {
+            println!("{} ({} baud)", summary.bus, summary.baudrate);
+            println!(
+                "  {:40} {:>12} {:>12} {:>12}",
+                "message", "id", "response", "deadline"
+            );
+            for message in &summary.messages {
+                let id = if message.ide {
+                    format!("ext:0x{:X}", message.id)
+                } else {
+                    format!("std:0x{:X}", message.id)
+                };
+                let line = format!(
+                    "  {:40} {:>12} {:>12} {:>12}",
+                    message.name,
+                    id,
+                    fmt_ms(message.response_time_ms),
+                    fmt_ms(message.deadline_ms)
+                );
+                if message.misses_deadline {
+                    cprintln!("<red>{line}</red>");
+                } else {
+                    println!("{line}");
+                }
+            }
+            println!();
+        }
+    }
+    for summary in &summaries {
+        for message in summary.messages.iter().filter(|message| message.misses_deadline) {
+            ceprintln!(
+                "<yellow>warning</yellow> : {} on bus {} misses its deadline ({} > {})",
+                message.name,
+                summary.bus,
+                fmt_ms(message.response_time_ms),
+                fmt_ms(message.deadline_ms)
+            );
+        }
+    }
+    Ok(())
+}
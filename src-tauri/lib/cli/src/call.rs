@@ -0,0 +1,120 @@
+use canzero_appdata::AppData;
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue, MessageDecoder},
+    encode::MessageEncoder,
+};
+use serde::Serialize;
+
+use crate::{
+    connection::Connection,
+    errors::{Error, Result},
+    format::{fmt_attributes, print_structured, OutputFormat, SerializeAttributes},
+    value::parse_value,
+};
+
+#[derive(Serialize)]
+struct CallResult<'a> {
+    node: &'a str,
+    command: &'a str,
+    arguments: SerializeAttributes<'a>,
+    response: SerializeAttributes<'a>,
+}
+
+/// Calls the command `command_name` of the node `node_name` with the given arguments
+/// (in the order of their definition) and waits for the response of the node.
+pub async fn command_call(
+    node_name: String,
+    command_name: String,
+    args: Vec<String>,
+    format: OutputFormat,
+) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+    let Some(node) = network.nodes().iter().find(|n| n.name() == node_name) else {
+        return Err(Error::InvalidNodeName(node_name));
+    };
+    let Some(command) = node.commands().iter().find(|c| c.name() == command_name) else {
+        return Err(Error::InvalidCommandName(format!("{node_name}::{command_name}")));
+    };
+    let path = format!("{node_name}::{command_name}");
+
+    // arguments are the attributes of the request message.
+    let parameters: Vec<(&str, &canzero_config::config::TypeRef)> = command
+        .tx_message()
+        .encoding()
+        .map(|encoding| {
+            encoding
+                .attributes()
+                .iter()
+                .map(|attribute| (attribute.name(), attribute.ty()))
+                .collect()
+        })
+        .unwrap_or_default();
+    if parameters.len() != args.len() {
+        let signature = parameters
+            .iter()
+            .map(|(name, ty)| format!("{name} : {}", ty.name()))
+            .collect::<Vec<String>>()
+            .join(", ");
+        return Err(Error::InvalidValue(format!(
+            "{path} : expected {} argument(s) ({signature}), got {}",
+            parameters.len(),
+            args.len()
+        )));
+    }
+    let arguments = parameters
+        .iter()
+        .zip(&args)
+        .map(|((name, ty), arg)| {
+            Ok(DecodedAttribute {
+                name: name.to_string(),
+                value: parse_value(ty, arg, &format!("{path}.{name}"))?,
+            })
+        })
+        .collect::<Result<Vec<DecodedAttribute>>>()?;
+
+    let req_message = command.tx_message();
+    let resp_message = command.rx_message();
+    let data = MessageEncoder::new(req_message).encode(&arguments);
+    let decoder = MessageDecoder::new(resp_message);
+
+    let connection = Connection::open().await?;
+    connection
+        .send(req_message, req_message.dlc(), &data)
+        .await?;
+    // the node is expected to respond within the expected interval of the command,
+    // we give it twice as much time.
+    let response = tokio::time::timeout(
+        *command.expected_interval() * 2,
+        connection.recv(resp_message, &decoder),
+    )
+    .await
+    .map_err(|_| Error::RequestTimeout(path.clone()))??;
+
+    let erno = response
+        .iter()
+        .find(|attribute| attribute.name == "erno")
+        .and_then(|attribute| match &attribute.value {
+            DecodedValue::Enum(erno) => Some(erno.as_str()),
+            _ => None,
+        })
+        .ok_or(Error::InvalidResponse)?;
+
+    if !format.is_table() {
+        print_structured(
+            format,
+            &CallResult {
+                node: node.name(),
+                command: command.name(),
+                arguments: SerializeAttributes(&arguments),
+                response: SerializeAttributes(&response),
+            },
+        )?;
+    } else if erno == "Success" {
+        println!("{path}({}) : {}", fmt_attributes(&arguments), fmt_attributes(&response));
+    }
+    if erno != "Success" {
+        return Err(Error::CommandFailed(path, erno.to_owned()));
+    }
+    Ok(())
+}
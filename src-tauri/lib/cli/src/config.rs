@@ -1,76 +1,268 @@
-use std::{
-    cmp::Ordering,
-    path::PathBuf,
-    sync::Arc,
+use std::path::{Path, PathBuf};
+
+use canzero_appdata::{AppData, AppDataError};
+use canzero_config::config::{
+    self, diff::diff_networks, export_dbc::export_dbc, filters::{node_filter_stats, FilterReport},
+    serialize::network_to_json, Type,
+};
+
+use serde::Serialize;
+
+use crate::{
+    errors::{Error, Result},
+    format::{print_structured, OutputFormat},
 };
 
-use canzero_appdata::AppData;
-use canzero_config::config::{self, Type};
+#[derive(Serialize)]
+struct BusSummary<'a> {
+    name: &'a str,
+    id: u32,
+    baudrate: u32,
+}
+
+#[derive(Serialize)]
+struct NodeSummary<'a> {
+    name: &'a str,
+    id: u8,
+    controller: Option<&'static str>,
+    filters: usize,
+    max_filters: Option<usize>,
+    /// share of the messages on the buses of the node, which it does not receive,
+    /// but which pass its filters.
+    false_positive_rate: f64,
+}
+
+impl<'a> NodeSummary<'a> {
+    fn new(network: &config::Network, node: &'a config::NodeRef) -> Self {
+        Self {
+            name: node.name(),
+            id: node.id(),
+            controller: node.controller().map(|controller| controller.name()),
+            filters: node.filters().len(),
+            max_filters: node.max_filters(),
+            false_positive_rate: node_filter_stats(network, node).false_positive_rate(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct NetworkSummary<'a> {
+    path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant: Option<&'a str>,
+    hash: u64,
+    build_time: String,
+    buses: Vec<BusSummary<'a>>,
+    nodes: Vec<NodeSummary<'a>>,
+    messages: usize,
+}
+
+#[derive(Serialize)]
+struct ObjectEntrySummary<'a> {
+    id: u32,
+    name: &'a str,
+    #[serde(rename = "type")]
+    ty: String,
+    unit: Option<&'a str>,
+    access: &'static str,
+}
+
+#[derive(Serialize)]
+struct MessageSummary<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    direction: Option<&'static str>,
+    bus: &'a str,
+    id: u32,
+    ide: bool,
+    dlc: u8,
+    name: &'a str,
+}
 
-use crate::errors::{Error, Result};
+impl<'a> MessageSummary<'a> {
+    fn new(message: &'a config::MessageRef, direction: Option<&'static str>) -> Self {
+        Self {
+            direction,
+            bus: message.bus().name(),
+            id: message.id().as_u32(),
+            ide: message.id().ide(),
+            dlc: message.dlc(),
+            name: message.name(),
+        }
+    }
+}
 
-pub fn command_config_show() -> Result<()> {
-    Err(Error::NotYetImplemented)
+pub fn command_config_show(format: OutputFormat) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+    let summary = NetworkSummary {
+        path: appdata
+            .get_config_path()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default(),
+        variant: appdata.get_variant(),
+        hash: network.portable_hash(),
+        build_time: network.build_time().to_rfc3339(),
+        buses: network
+            .buses()
+            .iter()
+            .map(|bus| BusSummary {
+                name: bus.name(),
+                id: bus.id(),
+                baudrate: bus.baudrate(),
+            })
+            .collect(),
+        nodes: network
+            .nodes()
+            .iter()
+            .map(|node| NodeSummary::new(&network, node))
+            .collect(),
+        messages: network.messages().len(),
+    };
+    if !format.is_table() {
+        return print_structured(format, &summary);
+    }
+    println!("path     : {}", summary.path);
+    if let Some(variant) = summary.variant {
+        println!("variant  : {variant}");
+    }
+    println!("hash     : {}", summary.hash);
+    println!("messages : {}", summary.messages);
+    println!("buses    :");
+    for bus in &summary.buses {
+        println!("  {:15} {:3} {} baud", bus.name, bus.id, bus.baudrate);
+    }
+    println!("nodes    :");
+    for node in &summary.nodes {
+        println!("  {:15} {:3}", node.name, node.id);
+    }
+    Ok(())
 }
 
-pub fn command_config_nodes_list() -> Result<()> {
+pub fn command_config_nodes_list(format: OutputFormat) -> Result<()> {
     let appdata = AppData::read()?;
     let network = appdata.config()?;
-    for node in network.nodes() {
-        println!("{:15} {:3}", node.name(), node.id());
+    let nodes: Vec<NodeSummary> = network
+        .nodes()
+        .iter()
+        .map(|node| NodeSummary::new(&network, node))
+        .collect();
+    if !format.is_table() {
+        return print_structured(format, &nodes);
+    }
+    println!("NAME             ID  CONTROLLER  FILTERS  FALSE POSITIVES");
+    for node in nodes {
+        let filters = match node.max_filters {
+            Some(max_filters) => format!("{}/{max_filters}", node.filters),
+            None => format!("{}", node.filters),
+        };
+        println!(
+            "{:15} {:3}  {:10}  {:7}  {:.1}%",
+            node.name,
+            node.id,
+            node.controller.unwrap_or("-"),
+            filters,
+            node.false_positive_rate * 100.0
+        );
     }
     Ok(())
 }
 
-pub fn command_config_object_entries_list(node: String) -> Result<()> {
+fn ty_to_name(ty: &Type) -> String {
+    match ty {
+        config::Type::Primitive(prim) => match prim {
+            config::SignalType::UnsignedInt { size } => format!("u{size}"),
+            config::SignalType::SignedInt { size } => format!("i{size}"),
+            config::SignalType::Decimal {
+                size,
+                offset: _,
+                scale,
+                signed,
+            } => {
+                let (min, max) = prim.range();
+                let prefix = if *signed { "sd" } else { "d" };
+                format!("{prefix}{size}<{min}..{max}> (scale = {scale})")
+            }
+            config::SignalType::Float { size } => format!("f{size}"),
+            config::SignalType::Bool => "bool".to_owned(),
+            config::SignalType::String { len } => format!("string<{len}>"),
+        },
+        config::Type::Struct {
+            name,
+            description: _,
+            attribs: _,
+            visibility: _,
+        } => format!("{name}"),
+        config::Type::Enum {
+            name,
+            description: _,
+            size: _,
+            entries: _,
+            visibility: _,
+        } => format!("{name}"),
+        config::Type::Array { len, ty } => {
+            format!("{}[{len}]", ty_to_name(ty))
+        }
+    }
+}
+
+pub fn command_config_object_entries_list(node: String, format: OutputFormat) -> Result<()> {
     let appdata = AppData::read()?;
     let network = appdata.config()?;
     let Some(node) = network.nodes().iter().find(|n| n.name() == node) else {
         return Err(Error::InvalidNodeName(node));
     };
-    for oe in node.object_entries() {
-        fn ty_to_name(ty: &Type) -> String {
-            match ty {
-                config::Type::Primitive(prim) => match prim {
-                    config::SignalType::UnsignedInt { size } => format!("u{size}"),
-                    config::SignalType::SignedInt { size } => format!("i{size}"),
-                    config::SignalType::Decimal {
-                        size,
-                        offset,
-                        scale,
-                    } => {
-                        let min = *offset;
-                        let max = (2u128.pow(*size as u32) as f64 / *scale) + min;
-                        format!("d{size}<{min}..{max}> (scale = {scale})")
-                    }
-                },
-                config::Type::Struct {
-                    name,
-                    description: _,
-                    attribs: _,
-                    visibility: _,
-                } => format!("{name}"),
-                config::Type::Enum {
-                    name,
-                    description: _,
-                    size: _,
-                    entries: _,
-                    visibility: _,
-                } => format!("{name}"),
-                config::Type::Array { len, ty } => {
-                    format!("{}[{len}", ty_to_name(ty))
-                }
-            }
-        }
-        println!("[{}] = {} : {}", oe.id(), oe.name(), ty_to_name(oe.ty()));
+    let object_entries: Vec<ObjectEntrySummary> = node
+        .object_entries()
+        .iter()
+        .map(|oe| ObjectEntrySummary {
+            id: oe.id(),
+            name: oe.name(),
+            ty: ty_to_name(oe.ty()),
+            unit: oe.unit(),
+            access: match oe.access() {
+                config::ObjectEntryAccess::Const => "const",
+                config::ObjectEntryAccess::Local => "local",
+                config::ObjectEntryAccess::Global => "global",
+            },
+        })
+        .collect();
+    if !format.is_table() {
+        return print_structured(format, &object_entries);
+    }
+    for oe in object_entries {
+        println!("[{}] = {} : {}", oe.id, oe.name, oe.ty);
     }
 
     Ok(())
 }
 
-pub fn command_config_set(path: PathBuf) -> Result<()> {
+pub fn command_config_set(
+    path: Option<PathBuf>,
+    variant: Option<String>,
+    no_variant: bool,
+) -> Result<()> {
     let mut appdata = AppData::read()?;
-    appdata.set_config_path(Some(path))?;
+    if let Some(path) = path {
+        appdata.set_config_path(Some(path))?;
+    }
+    if no_variant {
+        appdata.set_variant(None);
+    }
+    if let Some(variant) = variant {
+        let Some(config_path) = appdata.get_config_path() else {
+            return Err(Error::NoConfigSelected);
+        };
+        let variants = canzero_yaml::parse_yaml_config_variants_from_file(
+            config_path
+                .to_str()
+                .expect("non utf file paths are not supported by CANzero"),
+        )
+        .map_err(|err| Error::AppDataError(AppDataError::ConfigError(err)))?;
+        if !variants.contains(&variant) {
+            return Err(Error::InvalidVariant(variant));
+        }
+        appdata.set_variant(Some(variant));
+    }
     Ok(())
 }
 
@@ -80,10 +272,17 @@ pub fn command_config_get() -> Result<()> {
         Some(path) => println!("{path:?}"),
         None => println!("No path to config specificied"),
     }
+    if let Some(variant) = appdata.get_variant() {
+        println!("variant : {variant}");
+    }
     Ok(())
 }
 
-pub fn command_config_messages_list(node: Option<String>, bus: Option<String>) -> Result<()> {
+pub fn command_config_messages_list(
+    node: Option<String>,
+    bus: Option<String>,
+    format: OutputFormat,
+) -> Result<()> {
     let appdata = AppData::read()?;
     let network = appdata.config()?;
 
@@ -92,80 +291,262 @@ pub fn command_config_messages_list(node: Option<String>, bus: Option<String>) -
             return Err(Error::InvalidBusName(bus_name.clone()));
         }
     };
+    let on_bus = |m: &&config::MessageRef| match &bus {
+        Some(bus_name) => m.bus().name() == bus_name,
+        None => true,
+    };
 
-    if let Some(node_name) = node {
+    let messages: Vec<MessageSummary> = if let Some(node_name) = &node {
         let Some(node) = network.nodes().iter().find(|n| n.name() == node_name) else {
-            return Err(Error::InvalidNodeName(node_name));
+            return Err(Error::InvalidNodeName(node_name.clone()));
         };
+        let rx_messages = node
+            .rx_messages()
+            .iter()
+            .filter(on_bus)
+            .map(|m| MessageSummary::new(m, Some("rx")));
+        let tx_messages = node
+            .tx_messages()
+            .iter()
+            .filter(on_bus)
+            .map(|m| MessageSummary::new(m, Some("tx")));
+        rx_messages.chain(tx_messages).collect()
+    } else {
+        network
+            .messages()
+            .iter()
+            .filter(on_bus)
+            .map(|m| MessageSummary::new(m, None))
+            .collect()
+    };
 
-        let rx_messages = node.rx_messages().clone();
-        let rx_messages = match &bus {
-            Some(bus_name) => rx_messages
-                .iter()
-                .filter(|m| m.bus().name() == bus_name)
-                .map(Arc::clone)
-                .collect(),
-            None => rx_messages,
-        };
+    if !format.is_table() {
+        return print_structured(format, &messages);
+    }
+    if node.is_some() {
         println!("DIR BUS   ID     DLC  NAME");
-        for msg in rx_messages {
-            let name = msg.name();
-            let dlc = msg.dlc();
-            let id = msg.id();
-            let bus = msg.bus().name();
-            println!("RX  {bus}  {id} [{dlc}]  {name}");
-        }
-
-        let tx_messages = node.tx_messages().clone();
-        let tx_messages = match &bus {
-            Some(bus_name) => tx_messages
-                .iter()
-                .filter(|m| m.bus().name() == bus_name)
-                .map(Arc::clone)
-                .collect(),
-            None => tx_messages,
-        };
-        for msg in tx_messages {
-            let name = msg.name();
-            let dlc = msg.dlc();
-            let id = msg.id();
-            let bus = msg.bus().name();
-            println!("TX  {bus}  {id} [{dlc}]  {name}");
-        }
     } else {
-        let messages = match &bus {
-            Some(bus_name) => network
-                .messages()
-                .iter()
-                .filter(|m| m.bus().name() == bus_name)
-                .map(Arc::clone)
-                .collect(),
-            None => network.messages().clone(),
+        println!("BUS   ID     DLC  NAME");
+    }
+    for msg in messages {
+        let id = if msg.ide {
+            config::MessageId::ExtendedId(msg.id)
+        } else {
+            config::MessageId::StandardId(msg.id)
+        };
+        let dir = match msg.direction {
+            Some(direction) => format!("{:4}", direction.to_uppercase()),
+            None => String::new(),
         };
+        println!("{dir}{}  {id} [{}]  {}", msg.bus, msg.dlc, msg.name);
+    }
 
-        println!("BUS   ID     DLC  NAME");
-        for msg in messages {
-            let name = msg.name();
-            let dlc = msg.dlc();
-            let id = msg.id();
-            let bus = msg.bus().name();
-            println!("{bus}  {id} [{dlc}]  {name}");
+    Ok(())
+}
+
+pub fn command_config_check(relock: bool, filters: bool) -> Result<()> {
+    let appdata = AppData::read()?;
+    let Some(path) = appdata.get_config_path() else {
+        return Err(Error::NoConfigSelected);
+    };
+    let src = std::fs::read_to_string(path)?;
+    let diagnostics = canzero_yaml::validate::validate_yaml_config(&src, path);
+    for diagnostic in &diagnostics {
+        eprintln!("{diagnostic}");
+    }
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+    if errors != 0 {
+        return Err(Error::InvalidConfig(errors));
+    }
+
+    // the schema is valid, but building the network may still fail (e.g. no ids left).
+    // The builder reports some of these problems by panicking.
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let network = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if relock {
+            canzero_yaml::relock_yaml_config_from_file(
+                path.to_str()
+                    .expect("non utf file paths are not supported by CANzero"),
+                appdata.get_variant(),
+            )
+            .map_err(|err| Error::AppDataError(AppDataError::ConfigError(err)))
+        } else {
+            Ok(appdata.config()?)
+        }
+    }));
+    std::panic::set_hook(default_hook);
+    let network = match network {
+        Ok(network) => network?,
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|msg| msg.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".to_owned());
+            eprintln!("error: failed to build the network : {message}\n");
+            return Err(Error::InvalidConfig(1));
         }
     };
+    if filters {
+        print!("{}", FilterReport::new(&network));
+    }
+    if relock {
+        println!(
+            "reassigned all ids, written to {}",
+            canzero_yaml::id_lock::lock_file_path(path, appdata.get_variant()).display()
+        );
+    }
+    println!("{} is valid", path.display());
+    Ok(())
+}
 
+#[derive(Serialize)]
+struct HashSummary {
+    hash: u64,
+}
+
+pub fn command_config_hash(node: Option<String>, format: OutputFormat) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+
+    let hash = match node {
+        Some(node_name) => {
+            let Some(node) = network.nodes().iter().find(|n| n.name() == node_name) else {
+                return Err(Error::InvalidNodeName(node_name));
+            };
+            node.portable_hash()
+        }
+        None => network.portable_hash(),
+    };
+    if !format.is_table() {
+        return print_structured(format, &HashSummary { hash });
+    }
+    println!("{hash}");
     Ok(())
 }
 
-pub fn command_config_check() -> Result<()> {
+pub fn command_config_export_dbc(bus: Option<String>, output_dir: Option<PathBuf>) -> Result<()> {
     let appdata = AppData::read()?;
-    let _ = appdata.config()?;
+    let network = appdata.config()?;
+
+    let buses: Vec<&config::bus::BusRef> = match &bus {
+        Some(bus_name) => {
+            let Some(bus) = network.buses().iter().find(|b| b.name() == bus_name) else {
+                return Err(Error::InvalidBusName(bus_name.clone()));
+            };
+            vec![bus]
+        }
+        None => network.buses().iter().collect(),
+    };
+    let output_dir = match output_dir {
+        Some(dir) => dir,
+        None => std::env::current_dir()?,
+    };
+    std::fs::create_dir_all(&output_dir)?;
+
+    for bus in buses {
+        let mut path = output_dir.clone();
+        path.push(format!("{}.dbc", bus.name()));
+        std::fs::write(&path, export_dbc(&network, bus))?;
+        println!("{path:?}");
+    }
     Ok(())
 }
 
-pub fn command_config_hash() -> Result<()> {
+pub fn command_config_export(output: Option<PathBuf>) -> Result<()> {
     let appdata = AppData::read()?;
     let network = appdata.config()?;
+    let json = network_to_json(&network);
+    match output {
+        Some(path) => std::fs::write(&path, json)?,
+        None => println!("{json}"),
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct ChangeSummary<'a> {
+    compatibility: String,
+    subject: String,
+    path: &'a str,
+    change: String,
+}
 
-    println!("{}", network.portable_hash());
+#[derive(Serialize)]
+struct DiffSummary<'a> {
+    old_hash: u64,
+    new_hash: u64,
+    breaking: bool,
+    changes: Vec<ChangeSummary<'a>>,
+}
+
+fn load_config(path: &Path) -> Result<config::NetworkRef> {
+    if !path.exists() {
+        return Err(Error::FileNotFound(path.display().to_string()));
+    }
+    // the lock files of the compared configurations are used, but never written.
+    let load = || {
+        let src = std::fs::read_to_string(path)?;
+        let lock = canzero_yaml::id_lock::read_id_lock(path, None)?;
+        canzero_yaml::parse_yaml_config_locked(&src, path, lock.as_ref())
+    };
+    load()
+        .map(|(network, _)| network)
+        .map_err(|err| Error::AppDataError(AppDataError::ConfigError(err)))
+}
+
+pub fn command_config_diff(
+    old: PathBuf,
+    new: Option<PathBuf>,
+    strict: bool,
+    format: OutputFormat,
+) -> Result<()> {
+    let old_network = load_config(&old)?;
+    let new_network = match new {
+        Some(new) => load_config(&new)?,
+        None => AppData::read()?.config()?,
+    };
+    let diff = diff_networks(&old_network, &new_network);
+
+    if !format.is_table() {
+        print_structured(
+            format,
+            &DiffSummary {
+                old_hash: diff.old_hash(),
+                new_hash: diff.new_hash(),
+                breaking: diff.is_breaking(),
+                changes: diff
+                    .changes()
+                    .iter()
+                    .map(|change| ChangeSummary {
+                        compatibility: change.compatibility().to_string(),
+                        subject: change.subject().to_string(),
+                        path: change.path(),
+                        change: change.kind().to_string(),
+                    })
+                    .collect(),
+            },
+        )?;
+    } else {
+        println!("old hash : {}", diff.old_hash());
+        println!("new hash : {}", diff.new_hash());
+        if diff.is_empty() {
+            println!("no changes");
+        }
+        for change in diff.changes() {
+            println!(
+                "{:10} {:12} {} {}",
+                change.compatibility(),
+                change.subject(),
+                change.path(),
+                change.kind()
+            );
+        }
+    }
+    let breaking = diff.changes().iter().filter(|c| c.is_breaking()).count();
+    if strict && breaking != 0 {
+        return Err(Error::BreakingChanges(breaking));
+    }
     Ok(())
 }
@@ -0,0 +1,98 @@
+use std::{net::SocketAddr, time::Instant};
+
+use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame};
+use canzero_config::config::{
+    decode::{DecodedAttribute, MessageDecoder},
+    Message,
+};
+use canzero_tcp::tcpcan::{ConnectionId, TcpCan};
+
+use crate::{
+    dump::discover,
+    errors::{Error, Result},
+};
+
+/// Connection to the server for request / response exchanges (get, set and call),
+/// with a client id assigned by the server.
+pub struct Connection {
+    tcpcan: TcpCan,
+    timebase: Instant,
+    pub my_id: u8,
+}
+
+impl Connection {
+    pub async fn open() -> Result<Self> {
+        let network = discover().await?;
+        let stream = tokio::net::TcpStream::connect(SocketAddr::new(
+            network.server_addr,
+            network.service_port,
+        ))
+        .await?;
+        let tcpcan = TcpCan::new(
+            stream,
+            ConnectionId::Client {
+                request_id: true,
+                sync_history: false,
+            },
+        )
+        .await?;
+        let Some(my_id) = tcpcan.connection_id() else {
+            return Err(Error::InvalidResponse);
+        };
+        Ok(Self {
+            tcpcan,
+            timebase: network.timebase,
+            my_id,
+        })
+    }
+
+    /// Sends `data` (the little endian interpretation of the payload words) as `message`,
+    /// frames on CAN FD buses are sent as CAN FD frames.
+    pub async fn send(&self, message: &Message, dlc: u8, data: &[u64]) -> Result<()> {
+        let timestamp = Instant::now().duration_since(self.timebase);
+        let bus = message.bus();
+        let can_frame = if bus.is_fd() {
+            let mut words = [0u64; 8];
+            words[..data.len()].copy_from_slice(data);
+            CanFrame::new_fd(message.id().as_u32(), message.id().ide(), bus.brs(), dlc, words)
+        } else {
+            CanFrame::new(message.id().as_u32(), message.id().ide(), false, dlc, data[0])
+        };
+        self.tcpcan
+            .send(&TNetworkFrame::new(
+                timestamp,
+                NetworkFrame {
+                    bus_id: bus.id(),
+                    can_frame,
+                },
+            ))
+            .await?;
+        Ok(())
+    }
+
+    /// Returns the next frame of `message`.
+    pub async fn recv_frame(&self, message: &Message) -> Result<CanFrame> {
+        loop {
+            let Some(frame) = self.tcpcan.recv().await else {
+                return Err(Error::ConnectionClosed);
+            };
+            let can_frame = frame.can_frame.clone();
+            if frame.bus_id == message.bus().id()
+                && can_frame.get_id() == message.id().as_u32()
+                && can_frame.get_ide_flag() == message.id().ide()
+                && !can_frame.get_rtr_flag()
+            {
+                return Ok(can_frame);
+            }
+        }
+    }
+
+    /// Returns the decoded attributes of the next frame of `message`.
+    pub async fn recv(
+        &self,
+        message: &Message,
+        decoder: &MessageDecoder,
+    ) -> Result<Vec<DecodedAttribute>> {
+        Ok(decoder.decode(self.recv_frame(message).await?.get_data()))
+    }
+}
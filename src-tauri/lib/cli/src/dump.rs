@@ -1,12 +1,27 @@
-use std::{net::SocketAddr, time::Duration};
+use std::{
+    collections::HashMap,
+    io::{LineWriter, Write},
+    net::SocketAddr,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
 
 use canzero_appdata::AppData;
-use canzero_config::config::MessageId;
+use canzero_config::config::{
+    decode::MessageDecoder,
+    message::MessageUsage,
+    MessageId, MessageRef,
+};
 use canzero_tcp::tcpcan::ConnectionId;
 use canzero_udp::{frame::NetworkDescription, scanner::UdpNetworkScanner};
 use color_print::cprintln;
+use serde::Serialize;
 
-use crate::errors::{Error, Result};
+use crate::{
+    errors::{Error, Result},
+    format::{fmt_attributes, write_record, OutputFormat, SerializeAttributes},
+};
 
 pub async fn discover() -> Result<NetworkDescription> {
     let scanner = UdpNetworkScanner::create().await?;
@@ -17,14 +32,212 @@ pub async fn discover() -> Result<NetworkDescription> {
     }
 }
 
-pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>) -> Result<()> {
-    if !filter_ids.is_empty() {
-        return Err(Error::NotYetImplemented);
+/// Restricts a filter to standard or extended ids.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum IdKind {
+    Any,
+    Std,
+    Ext,
+}
+
+/// Selects frames by their id.
+///
+/// - `0x123` or `291` : a single id.
+/// - `0x100-0x1FF` : all ids of the (inclusive) range.
+/// - `0x100/0x700` : all ids which match `0x100` in the bits of the mask `0x700`.
+///
+/// Prefixed with `std:` or `ext:` only standard or extended ids are matched.
+#[derive(Debug, Clone)]
+pub struct IdFilter {
+    kind: IdKind,
+    matcher: IdMatcher,
+}
+
+#[derive(Debug, Clone)]
+enum IdMatcher {
+    Exact(u32),
+    Range(u32, u32),
+    Mask { id: u32, mask: u32 },
+}
+
+fn parse_id(s: &str) -> std::result::Result<u32, String> {
+    let s = s.trim();
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse(),
+    };
+    match parsed {
+        Ok(id) if id <= 0x1FFFFFFF => Ok(id),
+        Ok(_) => Err(format!("{s} is not a valid CAN id")),
+        Err(_) => Err(format!("{s} is not a number")),
+    }
+}
+
+impl FromStr for IdFilter {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let (kind, s) = if let Some(s) = s.strip_prefix("std:") {
+            (IdKind::Std, s)
+        } else if let Some(s) = s.strip_prefix("ext:") {
+            (IdKind::Ext, s)
+        } else {
+            (IdKind::Any, s)
+        };
+        let matcher = if let Some((min, max)) = s.split_once('-') {
+            let (min, max) = (parse_id(min)?, parse_id(max)?);
+            if min > max {
+                return Err(format!("empty id range {s}"));
+            }
+            IdMatcher::Range(min, max)
+        } else if let Some((id, mask)) = s.split_once('/') {
+            IdMatcher::Mask {
+                id: parse_id(id)?,
+                mask: parse_id(mask)?,
+            }
+        } else {
+            IdMatcher::Exact(parse_id(s)?)
+        };
+        Ok(IdFilter { kind, matcher })
+    }
+}
+
+impl IdFilter {
+    fn matches(&self, id: u32, ide: bool) -> bool {
+        let kind = match self.kind {
+            IdKind::Any => true,
+            IdKind::Std => !ide,
+            IdKind::Ext => ide,
+        };
+        kind && match self.matcher {
+            IdMatcher::Exact(exact) => id == exact,
+            IdMatcher::Range(min, max) => (min..=max).contains(&id),
+            IdMatcher::Mask { id: expected, mask } => id & mask == expected & mask,
+        }
+    }
+}
+
+/// Selects frames by the usage of their message.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq)]
+pub enum UsageFilter {
+    Stream,
+    Command,
+    Get,
+    Set,
+    Heartbeat,
+    /// Messages imported from DBC files.
+    External,
+}
+
+impl UsageFilter {
+    fn matches(&self, usage: &MessageUsage) -> bool {
+        matches!(
+            (self, usage),
+            (UsageFilter::Stream, MessageUsage::Stream(_))
+                | (UsageFilter::Command, MessageUsage::CommandReq(_))
+                | (UsageFilter::Command, MessageUsage::CommandResp(_))
+                | (UsageFilter::Get, MessageUsage::GetReq)
+                | (UsageFilter::Get, MessageUsage::GetResp)
+                | (UsageFilter::Set, MessageUsage::SetReq)
+                | (UsageFilter::Set, MessageUsage::SetResp)
+                | (UsageFilter::Heartbeat, MessageUsage::Heartbeat)
+                | (UsageFilter::External, MessageUsage::External { .. })
+        )
     }
-    let filter_ids: Vec<MessageId> = vec![];
+}
+
+/// Filters of the dump command, all given filters have to match.
+/// Message names and ids are alternatives, a frame passes if it matches any of them.
+pub struct DumpFilter {
+    pub messages: Vec<String>,
+    pub ids: Vec<IdFilter>,
+    pub buses: Vec<String>,
+    pub nodes: Vec<String>,
+    pub usages: Vec<UsageFilter>,
+}
+
+impl DumpFilter {
+    fn matches(
+        &self,
+        id: u32,
+        ide: bool,
+        bus: &str,
+        message: Option<&MessageRef>,
+        transmitters: &[String],
+    ) -> bool {
+        let selected = (self.messages.is_empty() && self.ids.is_empty())
+            || message.is_some_and(|m| self.messages.iter().any(|name| name == m.name()))
+            || self.ids.iter().any(|filter| filter.matches(id, ide));
+        let on_bus = self.buses.is_empty() || self.buses.iter().any(|b| b == bus);
+        let from_node =
+            self.nodes.is_empty() || transmitters.iter().any(|tx| self.nodes.contains(tx));
+        let usage = self.usages.is_empty()
+            || message.is_some_and(|m| self.usages.iter().any(|u| u.matches(m.usage())));
+        selected && on_bus && from_node && usage
+    }
+}
+
+#[derive(Serialize)]
+struct FrameRecord<'a> {
+    /// seconds since the start of the server.
+    timestamp: f64,
+    bus: &'a str,
+    id: u32,
+    ide: bool,
+    rtr: bool,
+    dlc: u8,
+    /// payload as hex string (first byte first).
+    data: String,
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signals: Option<SerializeAttributes<'a>>,
+}
+
+pub async fn command_dump(
+    filter: DumpFilter,
+    decode: bool,
+    output: Option<PathBuf>,
+    format: OutputFormat,
+) -> Result<()> {
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
 
+    for bus in &filter.buses {
+        if !network_config.buses().iter().any(|b| b.name() == bus) {
+            return Err(Error::InvalidBusName(bus.clone()));
+        }
+    }
+    for node in &filter.nodes {
+        if !network_config.nodes().iter().any(|n| n.name() == node) {
+            return Err(Error::InvalidNodeName(node.clone()));
+        }
+    }
+
+    let mut out: Box<dyn Write> = match &output {
+        Some(path) => Box::new(LineWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::stdout()),
+    };
+
+    // transmitting nodes and decoders are looked up by the name of the message.
+    let mut transmitters: HashMap<&str, Vec<String>> = HashMap::new();
+    for node in network_config.nodes() {
+        for message in node.tx_messages() {
+            transmitters
+                .entry(message.name())
+                .or_default()
+                .push(node.name().to_owned());
+        }
+    }
+    let decoders: HashMap<&str, MessageDecoder> = if decode {
+        network_config
+            .messages()
+            .iter()
+            .map(|message| (message.name(), MessageDecoder::new(message)))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
     let network = discover().await?;
 
     let connection =
@@ -32,12 +245,14 @@ pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>
             .await
             .unwrap();
 
-    cprintln!(
-        "<green>Successfully connected to {} at {}:{}</green>",
-        network.server_name,
-        network.server_addr,
-        network.service_port
-    );
+    if format.is_table() || output.is_some() {
+        cprintln!(
+            "<green>Successfully connected to {} at {}:{}</green>",
+            network.server_name,
+            network.server_addr,
+            network.service_port
+        );
+    }
 
     let tcpcan = canzero_tcp::tcpcan::TcpCan::new(
         connection,
@@ -50,7 +265,9 @@ pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>
 
     loop {
         let Some(frame) = tcpcan.recv().await else {
-            println!("Connection closed");
+            if format.is_table() || output.is_some() {
+                println!("Connection closed");
+            }
             return Ok(());
         };
         let timestamp = &frame.timestamp;
@@ -67,28 +284,74 @@ pub async fn command_dump(filter_msg_names: Vec<String>, filter_ids: Vec<String>
         } else {
             MessageId::StandardId(can_frame.get_id())
         };
-        let msg_name = network_config
+        let message = network_config
             .messages()
             .iter()
-            .find(|m| m.id() == &id)
-            .map_or("???", |m| m.name());
-        let pass = if !filter_msg_names.is_empty() {
-            filter_msg_names.iter().any(|msg| msg == msg_name)
+            .find(|m| m.id() == &id && m.bus().id() == *bus_id);
+        let msg_name = message.map_or("???", |m| m.name());
+        let tx_nodes = message
+            .and_then(|m| transmitters.get(m.name()))
+            .map_or(&[] as &[String], |tx| tx.as_slice());
+        if !filter.matches(
+            can_frame.get_id(),
+            can_frame.get_ide_flag(),
+            bus,
+            message,
+            tx_nodes,
+        ) {
+            continue;
+        }
+
+        let dlc = can_frame.get_dlc();
+        let signals = message
+            .and_then(|m| decoders.get(m.name()))
+            .filter(|_| !can_frame.get_rtr_flag())
+            .map(|decoder| decoder.decode(can_frame.get_data()));
+        if !format.is_table() {
+            write_record(
+                &mut out,
+                format,
+                &FrameRecord {
+                    timestamp: timestamp.as_secs_f64(),
+                    bus,
+                    id: can_frame.get_id(),
+                    ide: can_frame.get_ide_flag(),
+                    rtr: can_frame.get_rtr_flag(),
+                    dlc,
+                    data: can_frame
+                        .get_data_bytes()
+                        .iter()
+                        .map(|byte| format!("{byte:02X}"))
+                        .collect(),
+                    message: message.map(|m| m.name()),
+                    signals: signals.as_deref().map(SerializeAttributes),
+                },
+            )?;
         } else {
-            true
-        };
-        let pass = pass
-            || if !filter_ids.is_empty() {
-                filter_ids.iter().any(|x| x == &id)
-            } else {
-                false
-            };
-        if pass {
-            let dlc = can_frame.get_dlc();
-            let mask = 0xFFFFFFFFFFFFFFFFu64
-                .checked_shr(64u32 - dlc as u32 * 8u32).unwrap_or(0);
-            let data = can_frame.get_data_u64() & mask;
-            println!("{tsec:08.2}s : {bus:4} {id:5} [{dlc:1}] {data:016X}  ({msg_name})");
+            // one hex word per 8 bytes of the payload (CAN FD frames have up to 8 words).
+            let data = can_frame
+                .get_data()
+                .iter()
+                .enumerate()
+                .map(|(i, word)| {
+                    let bytes = (dlc as u32).saturating_sub(8 * i as u32).min(8);
+                    let mask = 0xFFFFFFFFFFFFFFFFu64
+                        .checked_shr(64u32 - bytes * 8u32).unwrap_or(0);
+                    format!("{:016X}", word & mask)
+                })
+                .collect::<Vec<String>>()
+                .join(" ");
+            match &signals {
+                Some(signals) => writeln!(
+                    out,
+                    "{tsec:08.2}s : {bus:4} {id:5} [{dlc:1}] {data}  ({msg_name}) {}",
+                    fmt_attributes(signals)
+                )?,
+                None => writeln!(
+                    out,
+                    "{tsec:08.2}s : {bus:4} {id:5} [{dlc:1}] {data}  ({msg_name})"
+                )?,
+            }
         }
     }
 }
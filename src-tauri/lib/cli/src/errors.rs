@@ -17,8 +17,24 @@ pub enum Error {
     InvalidResponse,
     InvalidNodeName(String),
     InvalidBusName(String),
+    InvalidVariant(String),
+    InvalidObjectEntryName(String),
+    InvalidCommandName(String),
+    InvalidValue(String),
     NoServerFound,
+    ConnectionClosed,
+    /// No response to the request for the given object entry.
+    RequestTimeout(String),
+    /// The node rejected the set request for the given object entry with the given erno.
+    SetRequestFailed(String, String),
+    /// The node responded to the call of the given command with the given erno.
+    CommandFailed(String, String),
     NotYetImplemented,
+    /// The config has the given number of errors, which have already been reported.
+    InvalidConfig(usize),
+    /// The compared configurations differ by the given number of breaking changes.
+    BreakingChanges(usize),
+    Serialization(String),
 }
 
 impl From<AppDataError> for Error {
@@ -47,13 +63,41 @@ impl Display for Error {
             Error::FileNotFound(path) => write!(f, "Failed to find file {path:?}"),
             Error::CodegenError(err) => write!(f, "{err:?}"),
             Error::Io(err) => write!(f, "{err:?}"),
+            Error::AppDataError(AppDataError::ConfigError(
+                canzero_yaml::errors::Error::Validation(diagnostics),
+            )) => {
+                for diagnostic in diagnostics {
+                    writeln!(f, "{diagnostic}")?;
+                }
+                let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+                write!(f, "invalid config, {errors} error(s) found")
+            }
             Error::AppDataError(err) => write!(f, "{err:?}"),
             Error::MissingDependency(dep) => write!(f, "Missing dependency {dep}"),
             Error::InvalidResponse => write!(f, "Invalid Response"),
             Error::InvalidNodeName(node_name) => write!(f, "Invalid node name : {node_name}"),
             Error::InvalidBusName(bus_name) => write!(f, "Invalid bus name : {bus_name}"),
+            Error::InvalidVariant(variant) => write!(f, "Invalid variant : {variant}"),
+            Error::InvalidObjectEntryName(oe_name) => {
+                write!(f, "Invalid object entry name : {oe_name}")
+            }
+            Error::InvalidCommandName(command_name) => {
+                write!(f, "Invalid command name : {command_name}")
+            }
+            Error::InvalidValue(err) => write!(f, "Invalid value for {err}"),
             Error::NoServerFound => write!(f, "No server found"),
+            Error::ConnectionClosed => write!(f, "Connection closed"),
+            Error::RequestTimeout(oe_name) => write!(f, "No response for {oe_name}"),
+            Error::SetRequestFailed(oe_name, erno) => {
+                write!(f, "Failed to set {oe_name} : {erno}")
+            }
+            Error::CommandFailed(command_name, erno) => {
+                write!(f, "Command {command_name} failed : {erno}")
+            }
             Error::NotYetImplemented => write!(f, "Not yet implemented"),
+            Error::InvalidConfig(count) => write!(f, "invalid config, {count} error(s) found"),
+            Error::BreakingChanges(count) => write!(f, "{count} breaking change(s) found"),
+            Error::Serialization(err) => write!(f, "Failed to serialize output : {err}"),
         }
     }
 }
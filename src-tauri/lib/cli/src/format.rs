@@ -0,0 +1,119 @@
+use std::io::Write;
+
+use canzero_config::config::decode::{DecodedAttribute, DecodedValue};
+use serde::{ser::SerializeMap, Serialize};
+
+use crate::errors::{Error, Result};
+
+/// Output format of the cli commands, selected with the global `--format` argument.
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq)]
+pub enum OutputFormat {
+    /// Human readable (colored) text.
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+impl OutputFormat {
+    pub fn is_table(&self) -> bool {
+        *self == OutputFormat::Table
+    }
+}
+
+/// Prints `value` as a single json or yaml document.
+/// Must not be called with [OutputFormat::Table], tables are printed by the commands themselves.
+pub fn print_structured<T: Serialize>(format: OutputFormat, value: &T) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json = serde_json::to_string_pretty(value)
+                .map_err(|err| Error::Serialization(err.to_string()))?;
+            println!("{json}");
+        }
+        OutputFormat::Yaml => {
+            let yaml =
+                serde_yaml::to_string(value).map_err(|err| Error::Serialization(err.to_string()))?;
+            print!("{yaml}");
+        }
+        OutputFormat::Table => unreachable!("tables are printed by the commands"),
+    }
+    Ok(())
+}
+
+/// Writes `value` as one record of a stream of records (e.g. `dump`),
+/// json records are written one per line, yaml records as separate documents.
+pub fn write_record<W: Write, T: Serialize>(
+    writer: &mut W,
+    format: OutputFormat,
+    value: &T,
+) -> Result<()> {
+    match format {
+        OutputFormat::Json => {
+            let json =
+                serde_json::to_string(value).map_err(|err| Error::Serialization(err.to_string()))?;
+            writeln!(writer, "{json}")?;
+        }
+        OutputFormat::Yaml => {
+            let yaml =
+                serde_yaml::to_string(value).map_err(|err| Error::Serialization(err.to_string()))?;
+            write!(writer, "---\n{yaml}")?;
+        }
+        OutputFormat::Table => unreachable!("tables are printed by the commands"),
+    }
+    Ok(())
+}
+
+pub struct SerializeValue<'a>(pub &'a DecodedValue);
+
+impl Serialize for SerializeValue<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self.0 {
+            DecodedValue::Unsigned(value) => serializer.serialize_u64(*value),
+            DecodedValue::Signed(value) => serializer.serialize_i64(*value),
+            DecodedValue::Real(value) => serializer.serialize_f64(*value),
+            DecodedValue::Bool(value) => serializer.serialize_bool(*value),
+            DecodedValue::String(value) => serializer.serialize_str(value),
+            DecodedValue::Enum(value) => serializer.serialize_str(value),
+            DecodedValue::Struct(attributes) => SerializeAttributes(attributes).serialize(serializer),
+            DecodedValue::Array(values) => {
+                serializer.collect_seq(values.iter().map(SerializeValue))
+            }
+        }
+    }
+}
+
+pub struct SerializeAttributes<'a>(pub &'a [DecodedAttribute]);
+
+impl Serialize for SerializeAttributes<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for attribute in self.0 {
+            map.serialize_entry(&attribute.name, &SerializeValue(&attribute.value))?;
+        }
+        map.end()
+    }
+}
+
+pub fn fmt_value(value: &DecodedValue) -> String {
+    match value {
+        DecodedValue::Unsigned(value) => value.to_string(),
+        DecodedValue::Signed(value) => value.to_string(),
+        DecodedValue::Real(value) => format!("{value:.3}"),
+        DecodedValue::Bool(value) => value.to_string(),
+        DecodedValue::String(value) => format!("{value:?}"),
+        DecodedValue::Enum(value) => value.clone(),
+        DecodedValue::Struct(attributes) => format!("{{{}}}", fmt_attributes(attributes)),
+        DecodedValue::Array(values) => format!(
+            "[{}]",
+            values.iter().map(fmt_value).collect::<Vec<String>>().join(", ")
+        ),
+    }
+}
+
+pub fn fmt_attributes(attributes: &[DecodedAttribute]) -> String {
+    attributes
+        .iter()
+        .map(|attribute| format!("{}={}", attribute.name, fmt_value(&attribute.value)))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -16,7 +16,7 @@ fn rec_create_dir(dir : &Path) -> Result<()>{
 
 pub fn command_generate(node_name : &str, output_dir : &PathBuf) -> Result<()> {
     let appdata = AppData::read()?;
-    let network_config = appdata.config()?;
+    let network_config = appdata.lock_config()?;
     let output_dir = output_dir.clone();
     if !output_dir.exists() {
         return Err(Error::FileNotFound(output_dir.to_str().unwrap().to_owned()));
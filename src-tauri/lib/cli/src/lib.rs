@@ -4,15 +4,19 @@ use build_time::build_time_local; use clap::{Parser, Subcommand};
 use config::command_config_get;
 
 use crate::{
+    analyze::{command_analyze_load, command_analyze_response_time},
+    call::command_call,
     client::command_client,
     config::{
-        command_config_check, command_config_hash, command_config_messages_list,
+        command_config_check, command_config_diff, command_config_export, command_config_export_dbc, command_config_hash, command_config_messages_list,
         command_config_nodes_list, command_config_object_entries_list, command_config_set,
         command_config_show,
     },
-    dump::command_dump,
+    dump::{command_dump, DumpFilter, IdFilter, UsageFilter},
     errors::Error,
+    format::OutputFormat,
     generate::command_generate,
+    object_entry::{command_get, command_set},
     scan::command_scan,
     server::command_server,
     ssh::{command_ssh, command_ssh_reboot},
@@ -20,17 +24,23 @@ use crate::{
     update::{command_update_self, command_update_server},
 };
 
+mod analyze;
+mod call;
 mod client;
 mod config;
+mod connection;
 mod dump;
 mod errors;
+mod format;
 mod generate;
 mod get;
+mod object_entry;
 mod scan;
 mod server;
 mod ssh;
 mod status;
 mod update;
+mod value;
 
 #[derive(Parser, Debug)]
 #[command(
@@ -40,6 +50,14 @@ mod update;
 struct Cli {
     #[command(subcommand)]
     command: Option<Command>,
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = OutputFormat::Table,
+        help = "Output format of config show, config diff, config analyze, status, dump, get, set and call"
+    )]
+    format: OutputFormat,
 }
 
 #[derive(Subcommand, Debug)]
@@ -70,14 +88,59 @@ enum Command {
     #[command(about = "Print the CAN trace to the control.")]
     Dump {
         #[clap(alias = "msg")]
-        #[arg(short, long, num_args=0..)]
+        #[arg(short, long, num_args=0.., help = "Only print the given messages")]
         messages: Vec<String>,
         #[clap(alias = "id")]
-        #[arg(short, long, num_args=0..)]
-        ids: Vec<String>,
+        #[arg(
+            short,
+            long,
+            num_args=0..,
+            help = "Only print the given ids, ranges (0x100-0x1FF) or masks (0x100/0x700), prefix with std: or ext: to restrict the id type"
+        )]
+        ids: Vec<IdFilter>,
+        #[arg(short, long, num_args=0.., help = "Only print frames of the given buses")]
+        bus: Vec<String>,
+        #[arg(short, long, num_args=0.., help = "Only print messages transmitted by the given nodes")]
+        node: Vec<String>,
+        #[arg(short, long, value_enum, num_args=0.., help = "Only print messages of the given usage")]
+        usage: Vec<UsageFilter>,
+        #[arg(short, long, action = clap::ArgAction::SetTrue, help = "Decode the payload into signal values")]
+        decode: bool,
+        #[arg(short, long, help = "Write the trace to a file instead of stdout")]
+        output: Option<PathBuf>,
     },
     #[command(about = "Check the status of all connected nodes.")]
     Status,
+    #[command(about = "Read the value of an object entry.")]
+    Get {
+        node: String,
+        object_entry: String,
+    },
+    #[command(about = "Write the value of an object entry.")]
+    Set {
+        node: String,
+        object_entry: String,
+        #[arg(
+            allow_hyphen_values = true,
+            help = "Value in yaml syntax, e.g. 1.5, Running, \"{x: 1, y: 2}\" or \"[1, 2, 3]\""
+        )]
+        value: String,
+    },
+    #[command(about = "Call a command of a node and wait for its response.")]
+    Call {
+        node: String,
+        command: String,
+        #[arg(
+            num_args = 0..,
+            allow_hyphen_values = true,
+            help = "Arguments in the order of their definition, in yaml syntax (see set)"
+        )]
+        args: Vec<String>,
+    },
+    #[command(about = "Replay a recorded session log in the graphical user interface.")]
+    Replay {
+        file: PathBuf,
+    },
     #[command(about = "Update CANzero.")]
     Update {
         #[arg(short='s', long="socketcan", action = clap::ArgAction::SetTrue)]
@@ -92,7 +155,18 @@ enum ConfigCommand {
         about = "Set path to network configuration.",
         arg_required_else_help = true
     )]
-    Set { path: PathBuf },
+    Set {
+        #[arg(help = "Path to the network configuration (default: keep the selected one)")]
+        path: Option<PathBuf>,
+        #[arg(long, help = "Build variant of the network configuration")]
+        variant: Option<String>,
+        #[arg(
+            long,
+            conflicts_with = "variant",
+            help = "Use the network configuration without a variant"
+        )]
+        no_variant: bool,
+    },
     #[command(
         about = "Display the network configuration.",
         arg_required_else_help = false
@@ -105,13 +179,81 @@ enum ConfigCommand {
         about = "Check the network configuration for errors.",
         arg_required_else_help = false
     )]
-    Check,
+    Check {
+        #[arg(
+            long,
+            help = "Discard the id lock file and assign all message and object entry ids from scratch"
+        )]
+        relock: bool,
+        #[arg(
+            long,
+            help = "Show the messages, which are dropped or received unnecessarily by the filters of a node"
+        )]
+        filters: bool,
+    },
     Where,
+    #[command(
+        about = "Export the network configuration as one DBC file per bus.",
+        arg_required_else_help = false
+    )]
+    ExportDbc {
+        #[arg(help = "Only export the given bus")]
+        bus: Option<String>,
+        #[arg(short, long, help = "Output directory (default: current directory)")]
+        output_dir: Option<PathBuf>,
+    },
+    #[command(
+        about = "Export the resolved network (ids, filters and encodings) in its canonical json form.",
+        arg_required_else_help = false
+    )]
+    Export {
+        #[arg(short, long, help = "Output file (default: stdout)")]
+        output: Option<PathBuf>,
+    },
+    #[command(
+        about = "Compare two network configurations and classify the changes as compatible or breaking.",
+        arg_required_else_help = true
+    )]
+    Diff {
+        #[arg(help = "Path to the old network configuration")]
+        old: PathBuf,
+        #[arg(help = "Path to the new network configuration (default: selected configuration)")]
+        new: Option<PathBuf>,
+        #[arg(long, help = "Fail if any change is breaking")]
+        strict: bool,
+    },
+    #[command(about = "Analyze the network configuration.")]
+    Analyze {
+        #[command(subcommand)]
+        command: ConfigAnalyzeCommand,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigAnalyzeCommand {
+    #[command(about = "Estimate the worst-case and average load of every bus.")]
+    Load {
+        #[arg(
+            short,
+            long,
+            default_value_t = 70.0,
+            help = "Warn if the worst-case load of a bus exceeds the given percentage"
+        )]
+        threshold: f64,
+    },
+    #[clap(alias = "rta")]
+    #[command(
+        about = "Compute the worst-case response time of every message and compare it to its deadline."
+    )]
+    ResponseTime,
 }
 
 #[derive(Subcommand, Debug)]
 enum ConfigShowCommand {
-    Hash,
+    Hash {
+        #[arg(help = "Only show the hash of the network as seen by the given node")]
+        node: Option<String>,
+    },
     Messages {
         #[arg(short, long)]
         node: Option<String>,
@@ -126,7 +268,12 @@ enum ConfigShowCommand {
 
 #[derive(Subcommand, Debug)]
 enum ServerCommand {
-    Start,
+    Start {
+        #[arg(short, long, help = "Path of the session log (default ~/.canzero/sessions/)")]
+        log: Option<PathBuf>,
+        #[arg(long, action = clap::ArgAction::SetTrue, help = "Do not record a session log")]
+        no_log: bool,
+    },
     Scan,
     Restart,
     Reboot,
@@ -139,36 +286,62 @@ enum ClientCommand {
     Start,
 }
 
-/// returns true iff. the gui should be started!
-pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
+/// Describes how the gui should be started.
+pub enum GuiMode {
+    /// Connect to a live network.
+    Live,
+    /// Replay a recorded session log.
+    Replay(PathBuf),
+}
+
+/// returns Some iff. the gui should be started!
+pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> Option<GuiMode> {
     let cli = Cli::parse();
+    let format = cli.format;
     let res = match cli.command {
         Some(cmd) => match cmd {
             Command::Config { command } => match command {
-                ConfigCommand::Set { path } => command_config_set(path),
+                ConfigCommand::Set {
+                    path,
+                    variant,
+                    no_variant,
+                } => command_config_set(path, variant, no_variant),
                 ConfigCommand::Show { command } => match command {
                     Some(config_show_command) => match config_show_command {
-                        ConfigShowCommand::Hash => command_config_hash(),
+                        ConfigShowCommand::Hash { node } => command_config_hash(node, format),
                         ConfigShowCommand::Messages { node, bus } => {
-                            command_config_messages_list(node, bus)
+                            command_config_messages_list(node, bus, format)
                         }
-                        ConfigShowCommand::Nodes => command_config_nodes_list(),
+                        ConfigShowCommand::Nodes => command_config_nodes_list(format),
                         ConfigShowCommand::ObjectEntries { node } => {
-                            command_config_object_entries_list(node)
+                            command_config_object_entries_list(node, format)
                         }
                     },
-                    None => command_config_show(),
+                    None => command_config_show(format),
                 },
-                ConfigCommand::Check => command_config_check(),
+                ConfigCommand::Check { relock, filters } => command_config_check(relock, filters),
                 ConfigCommand::Where => command_config_get(),
+                ConfigCommand::ExportDbc { bus, output_dir } => {
+                    command_config_export_dbc(bus, output_dir)
+                }
+                ConfigCommand::Export { output } => command_config_export(output),
+                ConfigCommand::Diff { old, new, strict } => {
+                    command_config_diff(old, new, strict, format)
+                }
+                ConfigCommand::Analyze { command } => match command {
+                    ConfigAnalyzeCommand::Load { threshold } => {
+                        command_analyze_load(threshold, format)
+                    }
+                    ConfigAnalyzeCommand::ResponseTime => command_analyze_response_time(format),
+                },
             },
             Command::Generate {
                 node_name,
                 output_dir,
             } => command_generate(&node_name, &output_dir),
-            Command::Gui => return true,
+            Command::Gui => return Some(GuiMode::Live),
             Command::Server { command } => match command {
-                ServerCommand::Start => command_server().await,
+                ServerCommand::Start { log, no_log } => command_server(log, no_log).await,
                 ServerCommand::Scan => command_scan().await,
                 ServerCommand::Restart => Err(Error::NotYetImplemented),
                 ServerCommand::Reboot => command_ssh_reboot(None).await,
@@ -178,8 +351,43 @@ pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
                 }
             },
             Command::Connect => command_client().await,
-            Command::Dump { messages, ids } => command_dump(messages, ids).await,
-            Command::Status => command_status().await,
+            Command::Dump {
+                messages,
+                ids,
+                bus,
+                node,
+                usage,
+                decode,
+                output,
+            } => {
+                let filter = DumpFilter {
+                    messages,
+                    ids,
+                    buses: bus,
+                    nodes: node,
+                    usages: usage,
+                };
+                command_dump(filter, decode, output, format).await
+            }
+            Command::Status => command_status(format).await,
+            Command::Get { node, object_entry } => command_get(node, object_entry, format).await,
+            Command::Set {
+                node,
+                object_entry,
+                value,
+            } => command_set(node, object_entry, value, format).await,
+            Command::Call {
+                node,
+                command,
+                args,
+            } => command_call(node, command, args, format).await,
+            Command::Replay { file } => {
+                if !file.exists() {
+                    Err(Error::FileNotFound(format!("{file:?}")))
+                } else {
+                    return Some(GuiMode::Replay(file));
+                }
+            }
             Command::Update { socketcan } => command_update_self(socketcan),
             Command::Version => {
                 println!("build-time : {}", build_time_local!());
@@ -191,11 +399,12 @@ pub async fn run_cli(armv7_cli_binary : Option<PathBuf>) -> bool {
                 Ok(())
             }
         },
-        None => return true,
+        None => return Some(GuiMode::Live),
     };
     if let Err(err) = res {
-        eprintln!("{err:?}");
+        eprintln!("{err}");
+        std::process::exit(1);
     }
-    return false;
+    return None;
 }
 
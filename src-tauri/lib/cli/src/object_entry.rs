@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use canzero_appdata::AppData;
+use canzero_common::CanFrame;
+use canzero_config::config::{
+    bus::can_fd_len,
+    decode::{decode_object_entry, DecodedAttribute, DecodedValue, MessageDecoder},
+    encode::encode_object_entry,
+    NetworkRef, NodeRef, ObjectEntryRef,
+};
+use serde::Serialize;
+
+use crate::{
+    connection::Connection,
+    errors::{Error, Result},
+    format::{fmt_value, print_structured, OutputFormat, SerializeValue},
+    value::parse_value,
+};
+
+/// Time between the fragments of a set request (same as the control panel).
+const FRAG_TIME_MS: u64 = 200;
+/// Time to wait for the (last fragment of the) response.
+const RESPONSE_TIMEOUT_MS: u64 = 1000;
+/// Fragments per CAN FD frame (after the 4 byte header).
+const FD_FRAGMENTS: usize = 15;
+
+fn attribute<'a>(attributes: &'a [DecodedAttribute], name: &str) -> Option<&'a DecodedValue> {
+    attributes
+        .iter()
+        .find(|attribute| attribute.name == name)
+        .map(|attribute| &attribute.value)
+}
+
+/// Fragments of a get response or set request frame.
+/// Classic frames carry one fragment, CAN FD frames up to [FD_FRAGMENTS].
+fn frame_fragments(can_frame: &CanFrame) -> Vec<u32> {
+    let bytes = can_frame.get_data_bytes();
+    bytes
+        .get(4..)
+        .unwrap_or_default()
+        .chunks(4)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        })
+        .collect()
+}
+
+fn header_unsigned(attributes: &[DecodedAttribute], name: &str) -> Result<u64> {
+    let Some(DecodedValue::Struct(header)) = attribute(attributes, "header") else {
+        return Err(Error::InvalidResponse);
+    };
+    match attribute(header, name) {
+        Some(DecodedValue::Unsigned(value)) => Ok(*value),
+        _ => Err(Error::InvalidResponse),
+    }
+}
+
+fn find_object_entry(
+    network: &NetworkRef,
+    node_name: &str,
+    oe_name: &str,
+) -> Result<(NodeRef, ObjectEntryRef)> {
+    let Some(node) = network.nodes().iter().find(|n| n.name() == node_name) else {
+        return Err(Error::InvalidNodeName(node_name.to_owned()));
+    };
+    let Some(oe) = node.object_entries().iter().find(|oe| oe.name() == oe_name) else {
+        return Err(Error::InvalidObjectEntryName(format!("{node_name}::{oe_name}")));
+    };
+    Ok((node.clone(), oe.clone()))
+}
+
+#[derive(Serialize)]
+struct ObjectEntryValue<'a> {
+    node: &'a str,
+    object_entry: &'a str,
+    value: SerializeValue<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct SetResult<'a> {
+    node: &'a str,
+    object_entry: &'a str,
+    value: SerializeValue<'a>,
+    erno: &'a str,
+}
+
+async fn get_request(
+    connection: &Connection,
+    network: &NetworkRef,
+    node: &NodeRef,
+    oe: &ObjectEntryRef,
+) -> Result<DecodedValue> {
+    let get_req = network.get_req_message();
+    let get_resp = network.get_resp_message();
+    let decoder = MessageDecoder::new(get_resp);
+
+    let mut data = oe.id() as u64;
+    data |= (connection.my_id as u64) << 13;
+    data |= (node.id() as u64) << 21;
+    connection.send(get_req, get_req.dlc(), &[data]).await?;
+
+    let fragments = (oe.ty().size().div_ceil(32) as usize).max(1);
+    let mut words: Vec<u32> = Vec::with_capacity(fragments);
+    let mut frames = 0;
+    loop {
+        let can_frame = connection.recv_frame(get_resp).await?;
+        let attributes = decoder.decode(can_frame.get_data());
+        if header_unsigned(&attributes, "client_id")? != connection.my_id as u64
+            || header_unsigned(&attributes, "server_id")? != node.id() as u64
+            || header_unsigned(&attributes, "od_index")? != oe.id() as u64
+        {
+            continue;
+        }
+        if header_unsigned(&attributes, "sof")? != 0 {
+            words.clear();
+            frames = 0;
+        } else if words.is_empty() {
+            // missed the start of the response.
+            continue;
+        }
+        // expecting toggle low on the first frame.
+        if header_unsigned(&attributes, "toggle")? != frames % 2 {
+            return Err(Error::InvalidResponse);
+        }
+        frames += 1;
+        let remaining = fragments.saturating_sub(words.len());
+        words.extend(frame_fragments(&can_frame).into_iter().take(remaining));
+        if header_unsigned(&attributes, "eof")? != 0 {
+            if words.len() != fragments {
+                return Err(Error::InvalidResponse);
+            }
+            return Ok(decode_object_entry(oe.ty(), &words));
+        }
+    }
+}
+
+pub async fn command_get(node_name: String, oe_name: String, format: OutputFormat) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+    let (node, oe) = find_object_entry(&network, &node_name, &oe_name)?;
+
+    let connection = Connection::open().await?;
+    let value = tokio::time::timeout(
+        Duration::from_millis(RESPONSE_TIMEOUT_MS),
+        get_request(&connection, &network, &node, &oe),
+    )
+    .await
+    .map_err(|_| Error::RequestTimeout(format!("{node_name}::{oe_name}")))??;
+
+    if !format.is_table() {
+        return print_structured(
+            format,
+            &ObjectEntryValue {
+                node: node.name(),
+                object_entry: oe.name(),
+                value: SerializeValue(&value),
+                unit: oe.unit(),
+            },
+        );
+    }
+    match oe.unit() {
+        Some(unit) => println!("{node_name}::{oe_name} = {} {unit}", fmt_value(&value)),
+        None => println!("{node_name}::{oe_name} = {}", fmt_value(&value)),
+    }
+    Ok(())
+}
+
+async fn set_response(
+    connection: &Connection,
+    network: &NetworkRef,
+    node: &NodeRef,
+    oe: &ObjectEntryRef,
+) -> Result<String> {
+    let set_resp = network.set_resp_message();
+    let decoder = MessageDecoder::new(set_resp);
+    loop {
+        let attributes = connection.recv(set_resp, &decoder).await?;
+        if header_unsigned(&attributes, "client_id")? != connection.my_id as u64
+            || header_unsigned(&attributes, "server_id")? != node.id() as u64
+            || header_unsigned(&attributes, "od_index")? != oe.id() as u64
+        {
+            continue;
+        }
+        let Some(DecodedValue::Struct(header)) = attribute(&attributes, "header") else {
+            return Err(Error::InvalidResponse);
+        };
+        let Some(DecodedValue::Enum(erno)) = attribute(header, "erno") else {
+            return Err(Error::InvalidResponse);
+        };
+        return Ok(erno.clone());
+    }
+}
+
+pub async fn command_set(
+    node_name: String,
+    oe_name: String,
+    value: String,
+    format: OutputFormat,
+) -> Result<()> {
+    let appdata = AppData::read()?;
+    let network = appdata.config()?;
+    let (node, oe) = find_object_entry(&network, &node_name, &oe_name)?;
+
+    let value = parse_value(oe.ty(), &value, &format!("{node_name}::{oe_name}"))?;
+    let number = match &value {
+        DecodedValue::Unsigned(v) => Some(*v as f64),
+        DecodedValue::Signed(v) => Some(*v as f64),
+        DecodedValue::Real(v) => Some(*v),
+        _ => None,
+    };
+    if number.is_some_and(|number| !oe.in_range(number)) {
+        return Err(Error::InvalidValue(format!(
+            "{node_name}::{oe_name} : out of range [{}, {}]",
+            oe.min().map_or("-inf".to_owned(), |min| min.to_string()),
+            oe.max().map_or("inf".to_owned(), |max| max.to_string()),
+        )));
+    }
+    let (words, last_fill) = encode_object_entry(&value, oe.ty());
+
+    let connection = Connection::open().await?;
+    let set_req = network.set_req_message();
+    let fd = set_req.bus().is_fd();
+    let fragments_per_frame = if fd { FD_FRAGMENTS } else { 1 };
+    let frames: Vec<&[u32]> = words.chunks(fragments_per_frame).collect();
+    let mut interval = tokio::time::interval(Duration::from_millis(FRAG_TIME_MS));
+    for (i, frame_words) in frames.iter().enumerate() {
+        let last = i == frames.len() - 1;
+        // first tick completes instantaniously
+        interval.tick().await;
+        let mut header = if i == 0 { 1u32 } else { 0u32 };
+        header |= (last as u32) << 1;
+        header |= ((i % 2) as u32) << 2;
+        header |= oe.id() << 3;
+        header |= (connection.my_id as u32) << 16;
+        header |= (node.id() as u32) << 24;
+        let mut bytes = header.to_le_bytes().to_vec();
+        for word in frame_words.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        let mut dlc = bytes.len() as u8;
+        if last {
+            dlc -= 4 - last_fill;
+        }
+        if fd {
+            // CAN FD frames are padded to the next valid length.
+            dlc = can_fd_len(dlc).unwrap();
+        }
+        connection.send(set_req, dlc, &CanFrame::data_from_bytes(&bytes)).await?;
+    }
+
+    let erno = tokio::time::timeout(
+        Duration::from_millis(RESPONSE_TIMEOUT_MS),
+        set_response(&connection, &network, &node, &oe),
+    )
+    .await
+    .map_err(|_| Error::RequestTimeout(format!("{node_name}::{oe_name}")))??;
+
+    if !format.is_table() {
+        print_structured(
+            format,
+            &SetResult {
+                node: node.name(),
+                object_entry: oe.name(),
+                value: SerializeValue(&value),
+                erno: &erno,
+            },
+        )?;
+    } else if erno == "Success" {
+        println!("{node_name}::{oe_name} = {}", fmt_value(&value));
+    }
+    if erno != "Success" {
+        return Err(Error::SetRequestFailed(format!("{node_name}::{oe_name}"), erno));
+    }
+    Ok(())
+}
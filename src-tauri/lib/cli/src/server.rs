@@ -1,14 +1,25 @@
+use std::path::PathBuf;
 
 use canzero_appdata::AppData;
 use canzero_server::Server;
 
 use crate::errors::Result;
 
-pub async fn command_server() -> Result<()> {
+pub async fn command_server(log: Option<PathBuf>, no_log: bool) -> Result<()> {
 
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
-    let server = Server::create(network_config).await?;
+    let session_log = match (log, no_log) {
+        (_, true) => None,
+        (Some(path), false) => Some(path),
+        (None, false) => appdata.new_session_log_path()?,
+    };
+    let server = Server::create(
+        network_config,
+        session_log.as_deref(),
+        Some(appdata.max_session_log_size()),
+    )
+    .await?;
 
     server.start();
     loop {
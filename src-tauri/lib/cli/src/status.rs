@@ -8,10 +8,15 @@ use canzero_appdata::AppData;
 use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame};
 use canzero_config::config;
 use canzero_tcp::tcpcan::{ConnectionId, TcpCan};
-use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
 use color_print::cprintln;
+use serde::Serialize;
 
-use crate::{dump::discover, errors::Result};
+use crate::{
+    dump::discover,
+    errors::Result,
+    format::{print_structured, OutputFormat},
+};
 
 async fn rx_get_req_hash_code(
     tcpcan: Arc<TcpCan>,
@@ -36,6 +41,11 @@ async fn rx_get_req_hash_code(
             if server_id != node_id {
                 continue;
             }
+            if rx_count == 0 && can_frame.get_dlc() >= 12 {
+                // CAN FD responses carry both fragments in one frame.
+                hash = (data >> 32) | (can_frame.get_data()[1] << 32);
+                break;
+            }
             if rx_count == 0 {
                 hash |= data >> 32;
                 rx_count = 1;
@@ -71,6 +81,11 @@ async fn rx_get_req_build_time(
             if server_id != node_id {
                 continue;
             }
+            if rx_count == 0 && can_frame.get_dlc() >= 12 {
+                // CAN FD responses carry both fragments in one frame.
+                build_time_data = (data >> 32) | (can_frame.get_data()[1] << 32);
+                break;
+            }
             if rx_count == 0 {
                 build_time_data |= data >> 32;
                 rx_count = 1;
@@ -92,7 +107,61 @@ async fn rx_get_req_build_time(
     ))
 }
 
-pub async fn command_status() -> Result<()> {
+#[derive(Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum NodeState {
+    Online,
+    Desync,
+    /// The node responded with its config hash, but not with its build time.
+    NoResponse,
+    Offline,
+}
+
+#[derive(Serialize)]
+struct NodeStatus {
+    name: String,
+    state: NodeState,
+    build_time: Option<NaiveDateTime>,
+    config_hash: Option<u64>,
+    /// hash of the selected network configuration as seen by this node.
+    expected_hash: u64,
+}
+
+#[derive(Serialize)]
+struct NetworkStatus {
+    /// hash of the selected network configuration.
+    config_hash: u64,
+    server: NodeStatus,
+    nodes: Vec<NodeStatus>,
+    /// nodes that were built with a different configuration.
+    reflash: Vec<String>,
+}
+
+fn print_node_status(status: &NodeStatus) {
+    let build_time = status
+        .build_time
+        .map(|build_time| build_time.format("(%Y-%m-%d %H:%M:%S)").to_string())
+        .unwrap_or_default();
+    match status.state {
+        NodeState::Online => {
+            cprintln!("{:25} : <green> {:7}</green> {build_time}", status.name, "ONLINE")
+        }
+        NodeState::Desync if status.name == "SERVER" => {
+            cprintln!("{:25} : <yellow> {:7}</yellow> {build_time}", status.name, "DESYNC")
+        }
+        NodeState::Desync => cprintln!(
+            "{:25} : <yellow> {:7}</yellow> {build_time} [{} expected {}]",
+            status.name,
+            "DESYNC",
+            status.config_hash.unwrap_or_default(),
+            status.expected_hash
+        ),
+        NodeState::NoResponse => cprintln!("{:25} : <red> {:7}</red>", status.name, "FUCKED"),
+        NodeState::Offline => cprintln!("{:25} : <red> {:7}</red>", status.name, "OFFLINE"),
+    }
+}
+
+pub async fn command_status(format: OutputFormat) -> Result<()> {
     let appdata = AppData::read()?;
     let network_config = appdata.config()?;
     let network_hash = network_config.portable_hash();
@@ -128,35 +197,26 @@ pub async fn command_status() -> Result<()> {
     let get_req_bus_id = network_config.get_req_message().bus().id();
 
     let server_build_time = DateTime::parse_from_rfc3339(&network.build_time).unwrap();
-
-    println!("network hash = {}", network.config_hash);
-    if network.config_hash == network_hash {
-        cprintln!(
-            "{:25} : <green> {:7}</green> ({:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2})",
-            "SERVER",
-            "ONLINE",
-            server_build_time.year(),
-            server_build_time.month(),
-            server_build_time.day(),
-            server_build_time.hour(),
-            server_build_time.minute(),
-            server_build_time.second()
-        );
-    } else {
-        cprintln!(
-            "{:25} : <yellow> {:7}</yellow> ({:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2})",
-            "SERVER",
-            "DESYNC",
-            server_build_time.year(),
-            server_build_time.month(),
-            server_build_time.day(),
-            server_build_time.hour(),
-            server_build_time.minute(),
-            server_build_time.second()
-        );
+    let server_status = NodeStatus {
+        name: "SERVER".to_owned(),
+        state: if network.config_hash == network_hash {
+            NodeState::Online
+        } else {
+            NodeState::Desync
+        },
+        build_time: Some(server_build_time.naive_local()),
+        config_hash: Some(network.config_hash),
+        expected_hash: network_hash,
+    };
+    if format.is_table() {
+        println!("network hash = {}", network.config_hash);
+        print_node_status(&server_status);
     }
 
+    let mut node_status = vec![];
     for node in network_config.nodes() {
+        // nodes embed the hash of their own view of the network (see codegen).
+        let node_hash = node.portable_hash();
         let config_hash_oe = node
             .object_entries()
             .iter()
@@ -199,7 +259,7 @@ pub async fn command_status() -> Result<()> {
             .await
             .unwrap();
 
-        if let Ok(hash) = tokio::time::timeout(
+        let status = if let Ok(hash) = tokio::time::timeout(
             Duration::from_millis(250),
             rx_get_req_hash_code(
                 rxcan.clone(),
@@ -236,39 +296,60 @@ pub async fn command_status() -> Result<()> {
             )
             .await
             {
-                if hash == network_hash {
-                    cprintln!(
-                        "{:25} : <green> {:7}</green> ({:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2})",
-                        node.name(),
-                        "ONLINE",
-                        build_time.year(),
-                        build_time.month(),
-                        build_time.day(),
-                        build_time.hour(),
-                        build_time.minute(),
-                        build_time.second()
-                    );
-                } else {
-                    cprintln!(
-                    "{:25} : <yellow> {:7}</yellow> ({:0>4}-{:0>2}-{:0>2} {:0>2}:{:0>2}:{:0>2}) [{}]",
-                    node.name(),
-                    "DESYNC",
-                    build_time.year(),
-                    build_time.month(),
-                    build_time.day(),
-                    build_time.hour(),
-                    build_time.minute(),
-                    build_time.second(),
-                    hash,
-                );
+                NodeStatus {
+                    name: node.name().to_owned(),
+                    state: if hash == node_hash {
+                        NodeState::Online
+                    } else {
+                        NodeState::Desync
+                    },
+                    build_time: Some(build_time),
+                    config_hash: Some(hash),
+                    expected_hash: node_hash,
                 }
             } else {
-                cprintln!("{:25} : <red> {:7}</red>", node.name(), "FUCKED");
+                NodeStatus {
+                    name: node.name().to_owned(),
+                    state: NodeState::NoResponse,
+                    build_time: None,
+                    config_hash: Some(hash),
+                    expected_hash: node_hash,
+                }
             }
         } else {
-            cprintln!("{:25} : <red> {:7}</red>", node.name(), "OFFLINE");
+            NodeStatus {
+                name: node.name().to_owned(),
+                state: NodeState::Offline,
+                build_time: None,
+                config_hash: None,
+                expected_hash: node_hash,
+            }
+        };
+        if format.is_table() {
+            print_node_status(&status);
         }
+        node_status.push(status);
     }
 
+    let reflash: Vec<String> = node_status
+        .iter()
+        .filter(|status| status.state == NodeState::Desync)
+        .map(|status| status.name.clone())
+        .collect();
+
+    if !format.is_table() {
+        return print_structured(
+            format,
+            &NetworkStatus {
+                config_hash: network_hash,
+                server: server_status,
+                nodes: node_status,
+                reflash,
+            },
+        );
+    }
+    if !reflash.is_empty() {
+        println!("reflash required : {}", reflash.join(", "));
+    }
     Ok(())
 }
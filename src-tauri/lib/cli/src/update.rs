@@ -10,6 +10,22 @@ use crate::{
 const PI_ARCH: &'static str = "armv7-unknown-linux-gnueabihf";
 const CANZERO_CLI_BIN_NAME: &'static str = "canzero-cli";
 
+/// Arguments of the ssh command, which selects the copied network configuration on the pi.
+/// Setting the config path resets the variant, so the selected variant is passed along.
+fn remote_config_set_args(relative_config_path: &str, variant: Option<&str>) -> Vec<String> {
+    let mut args = vec![
+        "sudo /home/pi/.canzero/canzero".to_owned(),
+        "config".to_owned(),
+        "set".to_owned(),
+        format!("/home/pi/.canzero/network-config/{relative_config_path}"),
+    ];
+    if let Some(variant) = variant {
+        args.push("--variant".to_owned());
+        args.push(variant.to_owned());
+    }
+    args
+}
+
 pub async fn command_update_server(
     host: Option<String>,
     reboot: bool,
@@ -99,11 +115,9 @@ pub async fn command_update_server(
         .arg("-i")
         .arg("~/.ssh/mu-zero")
         .arg(format!("pi@{ip_addr:?}"))
-        .arg("sudo /home/pi/.canzero/canzero")
-        .arg("config")
-        .arg("set")
-        .arg(&format!(
-            "/home/pi/.canzero/network-config/{relative_config_path}"
+        .args(remote_config_set_args(
+            relative_config_path,
+            appdata.get_variant(),
         ))
         .spawn()
         .unwrap()
@@ -158,3 +172,24 @@ pub fn command_update_self(socketcan: bool) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::remote_config_set_args;
+
+    #[test]
+    fn remote_config_set_keeps_the_variant() {
+        assert_eq!(
+            remote_config_set_args("network.yaml", Some("pod")),
+            vec![
+                "sudo /home/pi/.canzero/canzero",
+                "config",
+                "set",
+                "/home/pi/.canzero/network-config/network.yaml",
+                "--variant",
+                "pod",
+            ]
+        );
+        assert!(!remote_config_set_args("network.yaml", None).contains(&"--variant".to_owned()));
+    }
+}
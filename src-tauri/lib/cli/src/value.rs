@@ -0,0 +1,120 @@
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue},
+    SignalType, Type,
+};
+
+use crate::errors::{Error, Result};
+
+/// Parses a value given on the command line (yaml syntax) for the type `ty`,
+/// `path` names the value in error messages (e.g. `secu::position`).
+///
+/// - primitives : `42`, `-1`, `3.3`, `true`, `"name"`
+/// - enums : the name of the variant, e.g. `Running`
+/// - structs : a map of all attributes, e.g. `{x: 1, y: 2}`
+/// - arrays : a sequence of all elements, e.g. `[1, 2, 3]`
+pub fn parse_value(ty: &Type, value: &str, path: &str) -> Result<DecodedValue> {
+    let yaml_value: serde_yaml::Value =
+        serde_yaml::from_str(value).map_err(|err| Error::InvalidValue(format!("{path} : {err}")))?;
+    parse_yaml_value(ty, &yaml_value, path)
+}
+
+fn parse_yaml_value(ty: &Type, value: &serde_yaml::Value, path: &str) -> Result<DecodedValue> {
+    let invalid = |reason: String| Error::InvalidValue(format!("{path} : {reason}"));
+    match ty {
+        Type::Primitive(signal_type) => {
+            let (min, max) = signal_type.range();
+            match signal_type {
+                SignalType::UnsignedInt { size: _ } => match value.as_u64() {
+                    Some(v) if v as f64 <= max => Ok(DecodedValue::Unsigned(v)),
+                    Some(v) => Err(invalid(format!("{v} is out of range [{min}, {max}]"))),
+                    None => Err(invalid(format!("expected an unsigned integer, got {value:?}"))),
+                },
+                SignalType::SignedInt { size: _ } => match value.as_i64() {
+                    Some(v) if (min..=max).contains(&(v as f64)) => Ok(DecodedValue::Signed(v)),
+                    Some(v) => Err(invalid(format!("{v} is out of range [{min}, {max}]"))),
+                    None => Err(invalid(format!("expected an integer, got {value:?}"))),
+                },
+                SignalType::Decimal { .. } => match value.as_f64() {
+                    Some(v) if (min..=max).contains(&v) => Ok(DecodedValue::Real(v)),
+                    Some(v) => Err(invalid(format!("{v} is out of range [{min}, {max}]"))),
+                    None => Err(invalid(format!("expected a number, got {value:?}"))),
+                },
+                SignalType::Float { size: _ } => match value.as_f64() {
+                    Some(v) if (min..=max).contains(&v) => Ok(DecodedValue::Real(v)),
+                    Some(v) => Err(invalid(format!("{v} is out of range [{min}, {max}]"))),
+                    None => Err(invalid(format!("expected a number, got {value:?}"))),
+                },
+                SignalType::Bool => match value.as_bool() {
+                    Some(v) => Ok(DecodedValue::Bool(v)),
+                    None => Err(invalid(format!("expected true or false, got {value:?}"))),
+                },
+                SignalType::String { len } => match value.as_str() {
+                    Some(v) if !v.is_ascii() => {
+                        Err(invalid(format!("{v} contains non ASCII characters")))
+                    }
+                    Some(v) if v.len() > *len as usize => {
+                        Err(invalid(format!("{v} is longer than {len} characters")))
+                    }
+                    Some(v) => Ok(DecodedValue::String(v.to_owned())),
+                    None => Err(invalid(format!("expected a string, got {value:?}"))),
+                },
+            }
+        }
+        Type::Enum { name, entries, .. } => {
+            let Some(variant) = value.as_str() else {
+                return Err(invalid(format!("expected a variant of {name}, got {value:?}")));
+            };
+            if !entries.iter().any(|(entry, _)| entry == variant) {
+                let variants: Vec<&str> = entries.iter().map(|(entry, _)| entry.as_str()).collect();
+                return Err(invalid(format!(
+                    "{variant} is not a variant of {name} (expected one of {})",
+                    variants.join(", ")
+                )));
+            }
+            Ok(DecodedValue::Enum(variant.to_owned()))
+        }
+        Type::Struct { name, attribs, .. } => {
+            let Some(map) = value.as_mapping() else {
+                return Err(invalid(format!("expected a map of the attributes of {name}")));
+            };
+            for key in map.keys() {
+                let known = key
+                    .as_str()
+                    .is_some_and(|key| attribs.iter().any(|(attrib, _)| attrib == key));
+                if !known {
+                    return Err(invalid(format!("{name} has no attribute {key:?}")));
+                }
+            }
+            let attributes = attribs
+                .iter()
+                .map(|(attrib_name, attrib_ty)| {
+                    let Some(attrib_value) = map.get(attrib_name.as_str()) else {
+                        return Err(invalid(format!("missing attribute {attrib_name}")));
+                    };
+                    Ok(DecodedAttribute {
+                        name: attrib_name.clone(),
+                        value: parse_yaml_value(attrib_ty, attrib_value, &format!("{path}.{attrib_name}"))?,
+                    })
+                })
+                .collect::<Result<Vec<DecodedAttribute>>>()?;
+            Ok(DecodedValue::Struct(attributes))
+        }
+        Type::Array { len, ty } => {
+            let Some(seq) = value.as_sequence() else {
+                return Err(invalid(format!("expected a sequence of {len} elements")));
+            };
+            if seq.len() != *len {
+                return Err(invalid(format!(
+                    "expected {len} elements, got {}",
+                    seq.len()
+                )));
+            }
+            let elements = seq
+                .iter()
+                .enumerate()
+                .map(|(index, element)| parse_yaml_value(ty, element, &format!("{path}[{index}]")))
+                .collect::<Result<Vec<DecodedValue>>>()?;
+            Ok(DecodedValue::Array(elements))
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::{
     errors::Result,
+    messages::signal_type_to_c_type,
     options::Options,
+    types::to_c_type_name,
 };
 use canzero_config::config::{self, Type};
 
@@ -45,6 +47,7 @@ pub fn generate_command_hooks(
                             size,
                             offset: _,
                             scale: _,
+                            signed: _,
                         } => {
                             let s = (2 as u64).pow((*size as f64).log2().ceil().max(3.0) as u32);
                             if s <= 32 {
@@ -53,6 +56,11 @@ pub fn generate_command_hooks(
                                 "double".to_owned()
                             }
                         }
+                        config::SignalType::Float { size: _ }
+                        | config::SignalType::Bool
+                        | config::SignalType::String { len: _ } => {
+                            signal_type_to_c_type(prim).to_owned()
+                        }
                     },
                     config::Type::Struct {
                         name,
@@ -67,7 +75,7 @@ pub fn generate_command_hooks(
                         entries: _,
                         visibility: _,
                     } => name.clone(),
-                    config::Type::Array { len: _, ty: _ } => todo!(),
+                    config::Type::Array { len: _, ty: _ } => to_c_type_name(ty),
                 }
             }
             let name = attrib.name();
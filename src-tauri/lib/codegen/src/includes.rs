@@ -17,6 +17,7 @@ pub fn generate_includes(
         .to_owned();
     source.push_str(&format!("#include \"{header_name}\"\n"));
     source.push_str("#include <avr/pgmspace.h>\n");
+    source.push_str("#include <cstring>\n");
     
     header.push_str("#include <cinttypes>\n");
     header.push_str("#include <cstddef>\n");
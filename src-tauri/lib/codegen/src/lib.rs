@@ -4,6 +4,7 @@ use command_hooks::generate_command_hooks;
 use errors::{Error, Result};
 use header_guard::{generate_header_guard_top, generate_header_guard_bottom};
 use includes::generate_includes;
+use nvm::generate_nvm;
 use options::Options;
 use pil::generate_pil;
 use rx_handlers::generate_rx_handlers;
@@ -23,6 +24,7 @@ mod can_module_hooks;
 mod command_hooks;
 pub mod errors;
 mod messages;
+mod nvm;
 mod object_entries;
 pub mod options;
 mod pil;
@@ -61,7 +63,8 @@ pub fn generate(
 
     header += &format!("static const node_id CANZERO_NODE_ID = node_id_{};\n", node_name);
 
-    generate_pil(&mut src, &mut header, &options)?;
+    let fd = network_config.buses().iter().any(|bus| bus.is_fd());
+    generate_pil(&mut src, &mut header, fd, &options)?;
     generate_hooks(network_config.buses(), &mut src, &mut header, &options)?;
     generate_command_hooks(node_config.commands(), &mut src, &mut header, &options)?;
 
@@ -72,11 +75,13 @@ pub fn generate(
         &mut src,
         &options,
     )?;
+    generate_nvm(node_config, &mut src, &mut header, &options)?;
     generate_messages(
         node_config.tx_messages(),
         node_config.rx_messages(),
         &mut header,
         &mut src,
+        fd,
         &options,
     )?;
     // generate_rx_queue(&mut header, &mut src, &options)?;
@@ -1,4 +1,6 @@
-use canzero_config::config::{self, MessageRef, SignalType, Type, TypeSignalEncoding};
+use canzero_config::config::{
+    self, signal::Signal, ByteOrder, MessageRef, SignalType, Type, TypeSignalEncoding,
+};
 
 use crate::errors::Result;
 use crate::options::Options;
@@ -9,6 +11,7 @@ pub fn generate_messages(
     rx_messages: &Vec<MessageRef>,
     header: &mut String,
     source: &mut String,
+    fd: bool,
     options: &Options,
 ) -> Result<()> {
     let namespace = options.namespace();
@@ -69,17 +72,28 @@ pub fn generate_messages(
             }
         };
         let dlc = message.dlc();
+        // the data is written in long words.
+        let data_len = (dlc as usize).div_ceil(8).max(1) * 8;
         // function to serialize the message struct into a can frame!
         let serialize_func_name = format!("serialize_{message_type_name}");
         let mut serialize_def = format!(
 "static void {namespace}_{serialize_func_name}({message_type_name}* msg, {namespace}_frame* frame) {{
 {indent}volatile uint8_t* data = (volatile uint8_t*)frame->data;
-{indent}for(uint8_t i = 0; i < 8; ++i){{
+{indent}for(uint8_t i = 0; i < {data_len}; ++i){{
 {indent}{indent}data[i] = 0;
 {indent}}}
 {indent}frame->id = {id};
 {indent}frame->dlc = {dlc};
 ");
+        if fd {
+            let ns = namespace.to_uppercase();
+            let flags = match (message.bus().is_fd(), message.bus().brs()) {
+                (true, true) => format!("{ns}_FRAME_FDF_FLAG | {ns}_FRAME_BRS_FLAG"),
+                (true, false) => format!("{ns}_FRAME_FDF_FLAG"),
+                (false, _) => "0".to_owned(),
+            };
+            serialize_def.push_str(&format!("{indent}frame->flags = {flags};\n"));
+        }
 
         match message.encoding() {
             Some(encoding) => {
@@ -87,42 +101,70 @@ pub fn generate_messages(
                     serialized_def: &mut String,
                     attrib: &TypeSignalEncoding,
                     indent: &str,
-                    attribute_prefix: &str,
+                    access: &str,
+                    attrib_name: &str,
                     attrib_offset: &mut usize,
                 ) {
                     match attrib {
                         TypeSignalEncoding::Composite(composite) => {
-                            let attrib_name = composite.name();
-                            let attrib_prefix = format!("{attribute_prefix}{attrib_name}.m_");
-                            for attrib in composite.attributes() {
+                            let is_array = matches!(composite.ty() as &Type, Type::Array { .. });
+                            for (i, attrib) in composite.attributes().iter().enumerate() {
+                                // array elements are accessed by their index.
+                                let (access, attrib_name) = if is_array {
+                                    (format!("{access}.m_values[{i}]"), format!("{attrib_name}_{i}"))
+                                } else {
+                                    (format!("{access}.m_{}", attrib.name()), attrib.name().to_owned())
+                                };
                                 write_attribute_parse_code(
                                     serialized_def,
                                     attrib,
                                     indent,
-                                    &attrib_prefix,
+                                    &access,
+                                    &attrib_name,
                                     attrib_offset,
                                 );
                             }
                         }
                         TypeSignalEncoding::Primitive(primitive) => {
-                            let attrib_name = primitive.name();
+                            let signal = primitive.signal();
                             match attrib.ty() as &Type {
                                 config::Type::Primitive(signal_type) => {
                                     let var = match signal_type {
                                         SignalType::UnsignedInt { size: _ } => {
-                                            format!("{attribute_prefix}{attrib_name}")
+                                            access.to_owned()
                                         }
                                         SignalType::SignedInt { size: _ } => {
-                                            format!("{attribute_prefix}{attrib_name}")
+                                            access.to_owned()
+                                        }
+                                        SignalType::Float { size: 32 } => {
+                                            format!("f32_bits({access})")
+                                        }
+                                        SignalType::Float { size: _ } => {
+                                            format!("f64_bits({access})")
+                                        }
+                                        SignalType::Bool => {
+                                            access.to_owned()
+                                        }
+                                        SignalType::String { len } => {
+                                            format!("string_bits({access}.m_chars, {len})")
                                         }
 
                                         SignalType::Decimal {
                                             size,
                                             offset,
                                             scale,
+                                            signed,
                                         } => {
-                                            if *size <= 32 {
-                                                serialized_def.push_str(&format!("{indent}uint32_t {attrib_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5f;\n"));
+                                            if *signed {
+                                                let (bits_type, to_bits) = if *size <= 32 {
+                                                    ("uint32_t", "signed_bits_u32")
+                                                } else {
+                                                    ("uint64_t", "signed_bits_u64")
+                                                };
+                                                serialized_def.push_str(&format!("{indent}{bits_type} {attrib_name}_{attrib_offset} = {to_bits}(({access} - {offset}) / {scale}, {size});\n"));
+                                                format!("{attrib_name}_{attrib_offset}")
+                                            } else if *size <= 32 {
+                                                serialized_def.push_str(&format!("{indent}uint32_t {attrib_name}_{attrib_offset} = (({access} - {offset}) / {scale}) + 0.5f;\n"));
                                                 let u32_max = (0xFFFFFFFF as u32)
                                                     >> (32 - *size as u32);
 
@@ -133,7 +175,7 @@ pub fn generate_messages(
 "));
                                                 format!("{attrib_name}_{attrib_offset}")
                                             } else {
-                                                serialized_def.push_str(&format!("{indent}uint64_t {attrib_name}_{attrib_offset} = (({attribute_prefix}{attrib_name} - {offset}) / {scale}) + 0.5;\n"));
+                                                serialized_def.push_str(&format!("{indent}uint64_t {attrib_name}_{attrib_offset} = (({access} - {offset}) / {scale}) + 0.5;\n"));
                                                 let u64_max = (0xFFFFFFFFFFFFFFFF as u64)
                                                     >> (64 - *size as u32);
 
@@ -167,34 +209,9 @@ pub fn generate_messages(
                                         panic!("primitive data types larger than 64 bit are not supported");
                                     };
 
-                                    let word_bit_offset = *attrib_offset % 32;
-                                    let write_logic = if word_bit_offset == 0 && size <= 32 {
-                                        // word aligned word write
-                                        // is asserted to be the first write to the word!
-                                        let word_offset = *attrib_offset / 32; // intentional floor
-                                        format!(
-                                            "{indent}((volatile uint32_t*)data)[{word_offset}] = {val};\n"
-                                        )
-                                    } else if word_bit_offset == 0 && size > 32 {
-                                        // long word aligned long wrd write
-                                        // is asserted to be the first bit of the data frame
-                                        assert!(*attrib_offset == 0);
-                                        format!("{indent}((volatile uint64_t*)data)[0] = {val};\n")
-                                    } else if word_bit_offset + size as usize <= 32 {
-                                        // unaligned word write (does't cross word boundary)
-                                        // is asserted to not be the first write to the word!
-                                        let word_offset = *attrib_offset / 32; // intentional floor
-                                        format!("{indent}((volatile uint32_t*)data)[{word_offset}] |= {val} << {word_bit_offset};\n")
-                                    } else if word_bit_offset + size as usize >= 32 {
-                                        // unaligned long word write (crosses word boundary)
-                                        // is asserted to not be the first write to the word!
-                                        assert!(*attrib_offset <= 32);
-                                        format!("{indent}((volatile uint64_t*)data)[0] |= ((uint64_t){val}) << {word_bit_offset} ;\n")
-                                    } else {
-                                        panic!();
-                                    };
-                                    serialized_def.push_str(&write_logic);
-                                    *attrib_offset += size as usize;
+                                    let (code, next_offset) = attribute_write_code(signal, &val, *attrib_offset, indent);
+                                    serialized_def.push_str(&code);
+                                    *attrib_offset = next_offset;
                                 }
                                 config::Type::Enum {
                                     name: _,
@@ -203,7 +220,7 @@ pub fn generate_messages(
                                     entries: _,
                                     visibility: _,
                                 } => {
-                                    let var = format!("{attribute_prefix}{attrib_name}");
+                                    let var = access.to_owned();
                                     let size = *size;
                                     let val = if size < 8 {
                                         format!("(uint8_t)({var} & (0xFF >> (8 - {size})))")
@@ -225,36 +242,11 @@ pub fn generate_messages(
                                         panic!("primitive data types larger than 64 bit are not supported");
                                     };
 
-                                    let word_bit_offset = *attrib_offset % 32;
-                                    let write_logic = if word_bit_offset == 0 && size <= 32 {
-                                        // word aligned word write
-                                        // is asserted to be the first write to the word!
-                                        let word_offset = *attrib_offset / 32; // intentional floor
-                                        format!(
-                                            "{indent}((volatile uint32_t*)data)[{word_offset}] = {val};\n"
-                                        )
-                                    } else if word_bit_offset == 0 && size > 32 {
-                                        // long word aligned long wrd write
-                                        // is asserted to be the first bit of the data frame
-                                        assert!(*attrib_offset == 0);
-                                        format!("{indent}((volatile uint64_t*)data)[0] = {val};\n")
-                                    } else if word_bit_offset + size as usize <= 32 {
-                                        // unaligned word write (does't cross word boundary)
-                                        // is asserted to not be the first write to the word!
-                                        let word_offset = *attrib_offset / 32; // intentional floor
-                                        format!("{indent}((volatile uint32_t*)data)[{word_offset}] |= {val} << {word_bit_offset};\n")
-                                    } else if word_bit_offset + size as usize >= 32 {
-                                        // unaligned long word write (crosses word boundary)
-                                        // is asserted to not be the first write to the word!
-                                        assert!(*attrib_offset <= 32);
-                                        format!("{indent}((volatile uint64_t*)data)[0] |= ((uint64_t){val}) << {word_bit_offset} ;\n")
-                                    } else {
-                                        panic!();
-                                    };
-                                    serialized_def.push_str(&write_logic);
-                                    *attrib_offset += size as usize;
+                                    let (code, next_offset) = attribute_write_code(signal, &val, *attrib_offset, indent);
+                                    serialized_def.push_str(&code);
+                                    *attrib_offset = next_offset;
                                 }
-                                config::Type::Array { len: _, ty: _ } => todo!(),
+                                config::Type::Array { len: _, ty: _ } => panic!("arrays are not primitive"),
                                 config::Type::Struct {
                                     name: _,
                                     description: _,
@@ -272,80 +264,21 @@ pub fn generate_messages(
                         &mut serialize_def,
                         attrib,
                         &indent,
-                        "msg->m_",
+                        &format!("msg->m_{}", attrib.name()),
+                        attrib.name(),
                         &mut attrib_offset,
                     );
                 }
             }
             None => {
                 for signal in message.signals() {
-                    let attrib_offset: usize = signal.byte_offset();
-                    let signal_name = signal.name();
-                    let var = match signal.ty() {
-                        SignalType::UnsignedInt { size: _ } => {
-                            format!("msg->m_{signal_name}")
-                        }
-                        SignalType::SignedInt { size: _ } => {
-                            format!("msg->m_{signal_name}")
-                        }
-                        SignalType::Decimal {
-                            size,
-                            offset,
-                            scale,
-                        } => {
-                            if *size <= 32 {
-                                format!("(uint32_t)(msg->m_{signal_name} * {scale} + {offset})")
-                            } else {
-                                format!("(uint64_t)(msg->m_{signal_name} * {scale} + {offset})")
-                            }
-                        }
-                    };
-                    let size = signal.size();
-                    let val = if size < 8 {
-                        format!("(uint8_t)({var} & (0xFF >> (8 - {size})))")
-                    } else if size == 8 {
-                        format!("{var}")
-                    } else if size < 16 {
-                        format!("(uint16_t)({var} & (0xFFFF >> (16 - {size})))")
-                    } else if size == 16 {
-                        format!("{var}")
-                    } else if size < 32 {
-                        format!("(uint32_t)({var} & (0xFFFFFFFF >> (32 - {size})))")
-                    } else if size == 32 {
-                        format!("{var}")
-                    } else if size < 64 {
-                        format!("(uint64_t)({var} & (0xFFFFFFFFFFFFFFFF >> (64 - {size})))")
-                    } else if size == 64 {
-                        format!("{var}")
-                    } else {
-                        panic!("primitive data types larger than 64 bit are not supported");
-                    };
-
-                    let word_bit_offset = attrib_offset % 32;
-                    let write_logic = if word_bit_offset == 0 && size <= 32 {
-                        // word aligned word write
-                        // is asserted to be the first write to the word!
-                        let word_offset = attrib_offset / 32; // intentional floor
-                        format!("{indent}((volatile uint32_t*)data)[{word_offset}] = {val};\n")
-                    } else if word_bit_offset == 0 && size > 32 {
-                        // long word aligned long wrd write
-                        // is asserted to be the first bit of the data frame
-                        assert!(attrib_offset == 0);
-                        format!("{indent}((volatile uint64_t*)data)[0] = {val};\n")
-                    } else if word_bit_offset + size as usize <= 32 {
-                        // unaligned word write (does't cross word boundary)
-                        // is asserted to not be the first write to the word!
-                        let word_offset = attrib_offset / 32; // intentional floor
-                        format!("{indent}((volatile uint32_t*)data)[{word_offset}] |= {val} << {word_bit_offset};\n")
-                    } else if word_bit_offset + size as usize >= 32 {
-                        // unaligned long word write (crosses word boundary)
-                        // is asserted to not be the first write to the word!
-                        assert!(attrib_offset <= 32);
-                        format!("{indent}((volatile uint64_t*)data)[0] |= ((uint64_t){val}) << {word_bit_offset} ;\n")
-                    } else {
-                        panic!();
-                    };
-                    serialize_def.push_str(&write_logic);
+                    let bits = format!("{}_bits", signal.name());
+                    let raw = signal_raw_bits(signal, &format!("msg->m_{}", signal.name()));
+                    let mask = u64::MAX >> (64 - signal.size() as u32);
+                    serialize_def.push_str(&format!(
+                        "{indent}uint64_t {bits} = ((uint64_t)({raw})) & 0x{mask:X}ull;\n"
+                    ));
+                    serialize_def.push_str(&signal_write_code(signal, &bits, &indent));
                 }
             }
         };
@@ -370,46 +303,37 @@ pub fn generate_messages(
                     deserialized_def: &mut String,
                     attrib: &TypeSignalEncoding,
                     indent: &str,
-                    attribute_prefix: &str,
+                    access: &str,
+                    attrib_name: &str,
                     attrib_bit_offset: &mut usize,
                 ) {
                     match attrib {
                         TypeSignalEncoding::Composite(composite) => {
-                            let attrib_name = composite.name();
-                            let attrib_prefix = format!("{attribute_prefix}{attrib_name}.m_");
-                            for attrib in composite.attributes() {
+                            let is_array = matches!(composite.ty() as &Type, Type::Array { .. });
+                            for (i, attrib) in composite.attributes().iter().enumerate() {
+                                // array elements are accessed by their index.
+                                let (access, attrib_name) = if is_array {
+                                    (format!("{access}.m_values[{i}]"), format!("{attrib_name}_{i}"))
+                                } else {
+                                    (format!("{access}.m_{}", attrib.name()), attrib.name().to_owned())
+                                };
                                 write_attribute_write_code(
                                     deserialized_def,
                                     attrib,
                                     indent,
-                                    &attrib_prefix,
+                                    &access,
+                                    &attrib_name,
                                     attrib_bit_offset,
                                 );
                             }
                         }
                         TypeSignalEncoding::Primitive(primitive) => {
-                            let attrib_name = primitive.name();
                             let signal = primitive.signal();
                             match attrib.ty() as &Type {
                                 config::Type::Primitive(signal_type) => {
-                                    let var = format!("{indent}{attribute_prefix}{attrib_name}");
-                                    let size = signal_type.size() as usize;
-
-                                    let word_bit_offset = *attrib_bit_offset % 32;
-                                    assert_eq!(*attrib_bit_offset, signal.byte_offset());
-                                    let val_bits = if word_bit_offset == 0 && size <= 32 {
-                                        let word_offset = *attrib_bit_offset / 32;
-                                        format!("((uint32_t*)data)[{word_offset}] & (0xFFFFFFFF >> (32 - {size}))")
-                                    } else if word_bit_offset == 0 && size > 32 {
-                                        format!("((uint64_t*)data)[0] & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
-                                    } else if word_bit_offset + size <= 32 {
-                                        let word_offset = *attrib_bit_offset / 32; //intentional floor
-                                        format!("(((uint32_t*)data)[{word_offset}] >> {word_bit_offset}) & (0xFFFFFFFF >> (32 - {size}))")
-                                    } else if word_bit_offset + size > 32 {
-                                        format!("(((uint64_t*)data)[0] >> {attrib_bit_offset}) & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
-                                    } else {
-                                        panic!();
-                                    };
+                                    let var = format!("{indent}{access}");
+
+                                    let (val_bits, next_offset) = attribute_read_code(deserialized_def, signal, *attrib_bit_offset, indent);
 
                                     let val = match signal_type {
                                         SignalType::UnsignedInt { size: _ } => {
@@ -418,23 +342,43 @@ pub fn generate_messages(
                                         SignalType::SignedInt { size } => {
                                             if *size <= 32 {
                                                 deserialized_def.push_str(&format!("{indent}uint32_t tmp_{attrib_bit_offset} = {val_bits};\n"));
-                                                format!("(tmp_{attrib_bit_offset} & (((uint32_t)0x1) << ({size} - 1)) != 0) ? (tmp_{attrib_bit_offset} | (((uint32_t)0xFFFFFFFFul) << ({size} - 1))) : tmp_{attrib_bit_offset}")
+                                                format!("((tmp_{attrib_bit_offset} & (((uint32_t)0x1) << ({size} - 1))) != 0) ? (tmp_{attrib_bit_offset} | (((uint32_t)0xFFFFFFFFul) << ({size} - 1))) : tmp_{attrib_bit_offset}")
                                             }else {
                                                 deserialized_def.push_str(&format!("{indent}uint64_t tmp_{attrib_bit_offset} = {val_bits};\n"));
-                                                format!("(tmp_{attrib_bit_offset} & (((uint64_t)0x1) << ({size} - 1)) != 0) ? (tmp_{attrib_bit_offset} | (((uint64_t)0xFFFFFFFFFFFFFFFFull) << ({size} - 1))) : tmp_{attrib_bit_offset}")
+                                                format!("((tmp_{attrib_bit_offset} & (((uint64_t)0x1) << ({size} - 1))) != 0) ? (tmp_{attrib_bit_offset} | (((uint64_t)0xFFFFFFFFFFFFFFFFull) << ({size} - 1))) : tmp_{attrib_bit_offset}")
                                             }
                                         }
                                         SignalType::Decimal {
-                                            size: _,
+                                            size,
                                             offset,
                                             scale,
+                                            signed,
                                         } => {
-                                            format!("({val_bits}) * {scale} + {offset}")
+                                            if *signed && *size <= 32 {
+                                                format!("sign_extend_u32({val_bits}, {size}) * {scale} + {offset}")
+                                            } else if *signed {
+                                                format!("sign_extend_u64({val_bits}, {size}) * {scale} + {offset}")
+                                            } else {
+                                                format!("({val_bits}) * {scale} + {offset}")
+                                            }
+                                        }
+                                        SignalType::Float { size: 32 } => {
+                                            format!("bits_f32({val_bits})")
+                                        }
+                                        SignalType::Float { size: _ } => {
+                                            format!("bits_f64({val_bits})")
+                                        }
+                                        SignalType::Bool => format!("({val_bits}) != 0"),
+                                        SignalType::String { len } => {
+                                            // strings are copied into the character array.
+                                            deserialized_def.push_str(&format!("{indent}bits_string({access}.m_chars, {val_bits}, {len});\n"));
+                                            *attrib_bit_offset = next_offset;
+                                            return;
                                         }
                                     };
 
                                     deserialized_def.push_str(&format!("{var} = {val};\n"));
-                                    *attrib_bit_offset += size;
+                                    *attrib_bit_offset = next_offset;
                                 }
                                 config::Type::Struct {
                                     name: _,
@@ -445,35 +389,20 @@ pub fn generate_messages(
                                 config::Type::Enum {
                                     name,
                                     description: _,
-                                    size,
+                                    size: _,
                                     entries: _,
                                     visibility: _,
                                 } => {
-                                    let var = format!("{indent}{attribute_prefix}{attrib_name}");
-                                    let size = *size as usize;
-
-                                    let word_bit_offset = *attrib_bit_offset % 32;
-                                    assert_eq!(*attrib_bit_offset, signal.byte_offset());
-                                    let val_bits = if word_bit_offset == 0 && size <= 32 {
-                                        let word_offset = *attrib_bit_offset / 32;
-                                        format!("((uint32_t*)data)[{word_offset}] & (0xFFFFFFFF >> (32 - {size}))")
-                                    } else if word_bit_offset == 0 && size > 32 {
-                                        format!("((uint64_t*)data)[0] & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
-                                    } else if word_bit_offset + size <= 32 {
-                                        let word_offset = *attrib_bit_offset / 32; //intentional floor
-                                        format!("(((uint32_t*)data)[{word_offset}] >> {word_bit_offset}) & (0xFFFFFFFF >> (32 - {size}))")
-                                    } else if word_bit_offset + size > 32 {
-                                        format!("(((uint64_t*)data)[0] >> {attrib_bit_offset}) & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
-                                    } else {
-                                        panic!();
-                                    };
+                                    let var = format!("{indent}{access}");
+
+                                    let (val_bits, next_offset) = attribute_read_code(deserialized_def, signal, *attrib_bit_offset, indent);
 
                                     let val = format!("({name})({val_bits})");
 
                                     deserialized_def.push_str(&format!("{var} = {val};\n"));
-                                    *attrib_bit_offset += size;
+                                    *attrib_bit_offset = next_offset;
                                 }
-                                config::Type::Array { len: _, ty: _ } => todo!(),
+                                config::Type::Array { len: _, ty: _ } => panic!("arrays are not primitive"),
                             };
                         }
                     }
@@ -485,33 +414,29 @@ pub fn generate_messages(
                         &mut deserialize_def,
                         attrib,
                         &indent,
-                        "msg->m_",
+                        &format!("msg->m_{}", attrib.name()),
+                        attrib.name(),
                         &mut attrib_offset,
                     );
                 }
             }
             None => {
                 for signal in message.signals() {
-                    let signal_name = signal.name();
-                    let var = match signal.ty() {
-                        SignalType::UnsignedInt { size: _ } => {
-                            format!("msg->{signal_name}")
-                        }
-                        SignalType::SignedInt { size: _ } => {
-                            format!("msg->{signal_name}")
-                        }
-                        SignalType::Decimal {
-                            size: _,
-                            offset,
-                            scale,
-                        } => {
-                            format!("(msg->{signal_name} * {scale} + {offset})")
-                        }
-                    };
-                    let bit_write_code =
-                        bit_access_code(signal.byte_offset(), signal.size() as usize, "data");
-
-                    deserialize_def.push_str(&format!("{indent}{var} = {bit_write_code};"));
+                    let bits = format!("{}_bits", signal.name());
+                    deserialize_def.push_str(&signal_read_code(signal, &bits, &indent));
+                    if let SignalType::String { len } = signal.ty() {
+                        deserialize_def.push_str(&format!(
+                            "{indent}bits_string(msg->m_{}.m_chars, {bits}, {len});\n",
+                            signal.name()
+                        ));
+                        continue;
+                    }
+                    let c_type = signal_type_to_c_type(signal.ty());
+                    let val = signal_value(signal, &bits);
+                    deserialize_def.push_str(&format!(
+                        "{indent}msg->m_{} = ({c_type})({val});\n",
+                        signal.name()
+                    ));
                 }
             }
         };
@@ -557,6 +482,7 @@ pub fn signal_type_to_c_type(signal_type: &SignalType) -> &str {
             size,
             offset: _,
             scale: _,
+            signed: _,
         } => {
             if *size <= 32 {
                 "float"
@@ -564,22 +490,283 @@ pub fn signal_type_to_c_type(signal_type: &SignalType) -> &str {
                 "double"
             }
         }
+        config::SignalType::Float { size: 32 } => "float",
+        config::SignalType::Float { size: _ } => "double",
+        config::SignalType::Bool => "bool",
+        config::SignalType::String { len } => match len {
+            1 => "string1_t",
+            2 => "string2_t",
+            3 => "string3_t",
+            4 => "string4_t",
+            5 => "string5_t",
+            6 => "string6_t",
+            7 => "string7_t",
+            8 => "string8_t",
+            _ => panic!("strings have at most 8 characters"),
+        },
+    }
+}
+
+/// C expression of the raw bits of a signal with the value `var`.
+/// Decimals are rounded to the nearest raw value and clamped to the range of the signal.
+fn signal_raw_bits(signal: &Signal, var: &str) -> String {
+    let size = signal.size();
+    match signal.ty() {
+        SignalType::UnsignedInt { size: _ } => var.to_owned(),
+        SignalType::SignedInt { size: _ } => format!("(int64_t){var}"),
+        SignalType::Float { size: 32 } => format!("(uint64_t)f32_bits({var})"),
+        SignalType::Float { size: _ } => format!("f64_bits({var})"),
+        SignalType::Bool => format!("(uint64_t){var}"),
+        SignalType::String { len } => format!("string_bits({var}.m_chars, {len})"),
+        SignalType::Decimal {
+            size: _,
+            offset,
+            scale,
+            signed: true,
+        } => {
+            if size <= 32 {
+                format!("signed_bits_u32(({var} - {offset}) / {scale}, {size})")
+            } else {
+                format!("signed_bits_u64(({var} - {offset}) / {scale}, {size})")
+            }
+        }
+        SignalType::Decimal {
+            size: _,
+            offset,
+            scale,
+            signed: false,
+        } => {
+            if size <= 32 {
+                let max = u32::MAX >> (32 - size as u32);
+                format!("min_u32(({var} - {offset}) / {scale} + 0.5f, 0x{max:X}ul)")
+            } else {
+                let max = u64::MAX >> (64 - size as u32);
+                format!("min_u64(({var} - {offset}) / {scale} + 0.5, 0x{max:X}ull)")
+            }
+        }
+    }
+}
+
+/// C expression of the value of a signal from its raw bits.
+fn signal_value(signal: &Signal, bits: &str) -> String {
+    let size = signal.size();
+    match signal.ty() {
+        SignalType::UnsignedInt { size: _ } => bits.to_owned(),
+        SignalType::SignedInt { size: _ } => format!("sign_extend_u64({bits}, {size})"),
+        SignalType::Float { size: 32 } => format!("bits_f32({bits})"),
+        SignalType::Float { size: _ } => format!("bits_f64({bits})"),
+        SignalType::Bool => format!("{bits} != 0"),
+        SignalType::String { len: _ } => panic!("strings are copied with bits_string"),
+        SignalType::Decimal {
+            size: _,
+            offset,
+            scale,
+            signed,
+        } => {
+            if *signed {
+                format!("sign_extend_u64({bits}, {size}) * {scale} + {offset}")
+            } else {
+                format!("{bits} * {scale} + {offset}")
+            }
+        }
     }
 }
 
-fn bit_access_code(bit_offset: usize, bit_size: usize, buffer_name: &str) -> String {
-    if bit_size <= 32 && (bit_size + bit_offset % 32) <= 32 {
-        //access half word access!
-        let word_bit_offset = bit_offset % 32;
-        let word_index = bit_offset / 32;
-        let mask = (0xFFFFFFFF as u32) << (32 - bit_size as u32) >> word_bit_offset;
-        let shift = word_bit_offset;
-        format!("(((int32_t*){buffer_name})[{word_index}] & 0x{mask:X}) >> {shift}")
+/// Ors `val` (masked to `size` bits) at the bit `offset` into the zero initialized frame data.
+/// The data is accessed in (long) words, which are aligned within the frame.
+fn write_bits_code(val: &str, offset: usize, size: usize, indent: &str) -> String {
+    let word_bit_offset = offset % 32;
+    if word_bit_offset == 0 && size <= 32 {
+        // word aligned word write
+        // is asserted to be the first write to the word!
+        let word_offset = offset / 32; // intentional floor
+        format!("{indent}((volatile uint32_t*)data)[{word_offset}] = {val};\n")
+    } else if offset == 0 {
+        // long word aligned long word write of the first bits of the data frame
+        format!("{indent}((volatile uint64_t*)data)[0] = {val};\n")
+    } else if word_bit_offset + size <= 32 {
+        // unaligned word write (does't cross word boundary)
+        // is asserted to not be the first write to the word!
+        let word_offset = offset / 32; // intentional floor
+        format!("{indent}((volatile uint32_t*)data)[{word_offset}] |= {val} << {word_bit_offset};\n")
+    } else if offset < 32 {
+        // unaligned long word write (crosses word boundary)
+        // is asserted to not be the first write to the word!
+        format!("{indent}((volatile uint64_t*)data)[0] |= ((uint64_t){val}) << {word_bit_offset} ;\n")
     } else {
-        let mask = ((0xFFFFFFFFFFFFFFFF as u64)
-            << ((32 as usize).saturating_sub(bit_size) as u32))
-            >> bit_offset;
-        let shift = bit_offset;
-        format!("(*((int64_t*){buffer_name}) & 0x{mask:X}) >> {shift}")
+        // long word write behind the first long word (CAN FD frames),
+        // split into two writes if it crosses a long word boundary.
+        let long_word_offset = offset / 64;
+        let shift = offset % 64;
+        let mut code = format!(
+            "{indent}((volatile uint64_t*)data)[{long_word_offset}] |= ((uint64_t){val}) << {shift};\n"
+        );
+        if shift + size > 64 {
+            let upper_long_word_offset = long_word_offset + 1;
+            let upper_shift = 64 - shift;
+            code.push_str(&format!(
+                "{indent}((volatile uint64_t*)data)[{upper_long_word_offset}] |= ((uint64_t){val}) >> {upper_shift};\n"
+            ));
+        }
+        code
+    }
+}
+
+/// C expression of the `size` bits at the bit `offset` of the frame data.
+fn read_bits_code(offset: usize, size: usize) -> String {
+    let word_bit_offset = offset % 32;
+    if word_bit_offset == 0 && size <= 32 {
+        let word_offset = offset / 32;
+        format!("((uint32_t*)data)[{word_offset}] & (0xFFFFFFFF >> (32 - {size}))")
+    } else if offset == 0 {
+        format!("((uint64_t*)data)[0] & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
+    } else if word_bit_offset + size <= 32 {
+        let word_offset = offset / 32; //intentional floor
+        format!("(((uint32_t*)data)[{word_offset}] >> {word_bit_offset}) & (0xFFFFFFFF >> (32 - {size}))")
+    } else if offset + size <= 64 {
+        format!("(((uint64_t*)data)[0] >> {offset}) & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
+    } else {
+        // bits behind the first long word (CAN FD frames).
+        let long_word_offset = offset / 64;
+        let shift = offset % 64;
+        if shift + size <= 64 {
+            format!("(((uint64_t*)data)[{long_word_offset}] >> {shift}) & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
+        } else {
+            let upper_long_word_offset = long_word_offset + 1;
+            let upper_shift = 64 - shift;
+            format!("((((uint64_t*)data)[{long_word_offset}] >> {shift}) | (((uint64_t*)data)[{upper_long_word_offset}] << {upper_shift})) & (0xFFFFFFFFFFFFFFFF >> (64 - {size}))")
+        }
+    }
+}
+
+/// First byte and shift of a motorola (big endian) signal within the 8 frame data bytes,
+/// which contain the signal, read as a big endian u64.
+/// The offset of big endian signals refers to the msb in the sawtooth bit numbering of DBC files.
+fn big_endian_window(signal: &Signal) -> (usize, usize) {
+    let offset = signal.byte_offset();
+    let msb_byte = offset / 8;
+    let lsb_byte = msb_byte + (signal.size() as usize + 6 - offset % 8) / 8;
+    // signals within the first 8 bytes (all signals of classic frames) are read from the start.
+    let base = if lsb_byte < 8 { 0 } else { msb_byte.min(56) };
+    let msb = (msb_byte - base) * 8 + (7 - offset % 8);
+    (base, 64 - msb - signal.size() as usize)
+}
+
+/// Writes an attribute of a type encoded message. Little endian attributes directly
+/// follow each other, big endian attributes are written at the position of their signal.
+/// Returns the code and the offset behind the attribute.
+fn attribute_write_code(signal: &Signal, val: &str, offset: usize, indent: &str) -> (String, usize) {
+    let size = signal.size() as usize;
+    match signal.byte_order() {
+        ByteOrder::LittleEndian => (write_bits_code(val, offset, size, indent), offset + size),
+        ByteOrder::BigEndian => {
+            let bits = format!("bits_{}", signal.byte_offset());
+            let mask = u64::MAX >> (64 - size as u32);
+            let mut code = format!("{indent}uint64_t {bits} = ((uint64_t)({val})) & 0x{mask:X}ull;\n");
+            code.push_str(&signal_write_code(signal, &bits, indent));
+            (code, signal.end_bit())
+        }
+    }
+}
+
+/// C expression of the raw bits of an attribute of a type encoded message,
+/// big endian attributes are first read into a variable.
+/// Returns the expression and the offset behind the attribute.
+fn attribute_read_code(
+    deserialized_def: &mut String,
+    signal: &Signal,
+    offset: usize,
+    indent: &str,
+) -> (String, usize) {
+    let size = signal.size() as usize;
+    match signal.byte_order() {
+        ByteOrder::LittleEndian => {
+            assert_eq!(offset, signal.byte_offset());
+            (read_bits_code(offset, size), offset + size)
+        }
+        ByteOrder::BigEndian => {
+            let bits = format!("bits_{}", signal.byte_offset());
+            deserialized_def.push_str(&signal_read_code(signal, &bits, indent));
+            (bits, signal.end_bit())
+        }
+    }
+}
+
+/// Ors the raw bits of a signal into the (zero initialized) frame data.
+fn signal_write_code(signal: &Signal, bits: &str, indent: &str) -> String {
+    match signal.byte_order() {
+        ByteOrder::LittleEndian => {
+            let offset = signal.byte_offset();
+            let size = signal.size() as usize;
+            if offset + size <= 64 {
+                format!("{indent}((volatile uint64_t*)data)[0] |= {bits} << {offset};\n")
+            } else {
+                let long_word_offset = offset / 64;
+                let shift = offset % 64;
+                let mut code = format!(
+                    "{indent}((volatile uint64_t*)data)[{long_word_offset}] |= {bits} << {shift};\n"
+                );
+                if shift + size > 64 {
+                    let upper_long_word_offset = long_word_offset + 1;
+                    let upper_shift = 64 - shift;
+                    code.push_str(&format!(
+                        "{indent}((volatile uint64_t*)data)[{upper_long_word_offset}] |= {bits} >> {upper_shift};\n"
+                    ));
+                }
+                code
+            }
+        }
+        ByteOrder::BigEndian => {
+            let (base, shift) = big_endian_window(signal);
+            let index = if base == 0 {
+                "i".to_owned()
+            } else {
+                format!("{base} + i")
+            };
+            format!(
+"{indent}for (uint8_t i = 0; i < 8; ++i) {{
+{indent}{indent}data[{index}] |= (uint8_t)(({bits} << {shift}) >> (56 - 8 * i));
+{indent}}}
+")
+        }
+    }
+}
+
+/// Declares `bits` and reads the raw bits of a signal from the frame data into it.
+fn signal_read_code(signal: &Signal, bits: &str, indent: &str) -> String {
+    let mask = u64::MAX >> (64 - signal.size() as u32);
+    match signal.byte_order() {
+        ByteOrder::LittleEndian => {
+            let offset = signal.byte_offset();
+            let size = signal.size() as usize;
+            if offset + size <= 64 {
+                format!("{indent}uint64_t {bits} = (((uint64_t*)data)[0] >> {offset}) & 0x{mask:X}ull;\n")
+            } else {
+                let long_word_offset = offset / 64;
+                let shift = offset % 64;
+                if shift + size <= 64 {
+                    format!("{indent}uint64_t {bits} = (((uint64_t*)data)[{long_word_offset}] >> {shift}) & 0x{mask:X}ull;\n")
+                } else {
+                    let upper_long_word_offset = long_word_offset + 1;
+                    let upper_shift = 64 - shift;
+                    format!("{indent}uint64_t {bits} = ((((uint64_t*)data)[{long_word_offset}] >> {shift}) | (((uint64_t*)data)[{upper_long_word_offset}] << {upper_shift})) & 0x{mask:X}ull;\n")
+                }
+            }
+        }
+        ByteOrder::BigEndian => {
+            let (base, shift) = big_endian_window(signal);
+            let index = if base == 0 {
+                "i".to_owned()
+            } else {
+                format!("{base} + i")
+            };
+            format!(
+"{indent}uint64_t {bits} = 0;
+{indent}for (uint8_t i = 0; i < 8; ++i) {{
+{indent}{indent}{bits} = ({bits} << 8) | data[{index}];
+{indent}}}
+{indent}{bits} = ({bits} >> {shift}) & 0x{mask:X}ull;
+")
+        }
     }
 }
@@ -0,0 +1,108 @@
+use canzero_config::config::{NodeRef, SAVE_PARAMETERS_COMMAND};
+
+use crate::{errors::Result, options::Options, types::to_c_type_name};
+
+/// Version of the layout of the parameter block itself (header and crc),
+/// the layout of the parameters is covered by the parameter layout hash.
+const PARAMETER_BLOCK_VERSION: u32 = 1;
+
+/// Generates the parameter block of the persistent object entries,
+/// the nvm hooks, the save_parameters command and the restore on startup.
+/// Nodes without persistent object entries don't require the nvm hooks.
+pub fn generate_nvm(
+    node_config: &NodeRef,
+    source: &mut String,
+    header: &mut String,
+    options: &Options,
+) -> Result<()> {
+    let persistent: Vec<_> = node_config
+        .object_entries()
+        .iter()
+        .filter(|oe| oe.persistent())
+        .collect();
+    if persistent.is_empty() {
+        return Ok(());
+    }
+
+    let namespace = options.namespace();
+    let mut indent = String::new();
+    for _ in 0..options.indent() {
+        indent.push(' ');
+    }
+    let indent2 = format!("{indent}{indent}");
+
+    // returns 0 on success.
+    header.push_str(&format!(
+        "extern int {namespace}_nvm_read(uint8_t* data, uint32_t size);\n"
+    ));
+    header.push_str(&format!(
+        "extern int {namespace}_nvm_write(const uint8_t* data, uint32_t size);\n"
+    ));
+
+    let block_name = format!("{namespace}_parameter_block");
+    let mut block_def = format!("typedef struct {{\n{indent}uint32_t m_version;\n{indent}uint64_t m_layout_hash;\n");
+    for oe in &persistent {
+        block_def.push_str(&format!("{indent}{} m_{};\n", to_c_type_name(oe.ty()), oe.name()));
+    }
+    block_def.push_str(&format!("{indent}uint32_t m_crc;\n}} {block_name};\n"));
+    source.push_str(&block_def);
+
+    source.push_str(&format!(
+        "static uint32_t {namespace}_crc32(const uint8_t* data, uint32_t size) {{
+{indent}uint32_t crc = 0xFFFFFFFF;
+{indent}for (uint32_t i = 0; i < size; ++i) {{
+{indent2}crc ^= data[i];
+{indent2}for (uint8_t j = 0; j < 8; ++j) {{
+{indent2}{indent}crc = (crc >> 1) ^ (0xEDB88320 & (0 - (crc & 1)));
+{indent2}}}
+{indent}}}
+{indent}return ~crc;
+}}
+"
+    ));
+
+    let layout_hash = node_config.parameter_layout_hash();
+    let mut store = String::new();
+    let mut restore = String::new();
+    for oe in &persistent {
+        let oe_name = oe.name();
+        store.push_str(&format!("{indent}block.m_{oe_name} = __oe_{oe_name};\n"));
+        restore.push_str(&format!("{indent}__oe_{oe_name} = block.m_{oe_name};\n"));
+    }
+
+    // the block is zeroed first, such that the padding doesn't change the crc.
+    source.push_str(&format!(
+        "command_resp_erno {namespace}_{SAVE_PARAMETERS_COMMAND}() {{
+{indent}{block_name} block;
+{indent}memset(&block, 0, sizeof(block));
+{indent}block.m_version = {PARAMETER_BLOCK_VERSION};
+{indent}block.m_layout_hash = {layout_hash}ull;
+{store}{indent}block.m_crc = {namespace}_crc32((const uint8_t*)&block, offsetof({block_name}, m_crc));
+{indent}if ({namespace}_nvm_write((const uint8_t*)&block, sizeof(block)) != 0) {{
+{indent2}return command_resp_erno_Error;
+{indent}}}
+{indent}return command_resp_erno_Success;
+}}
+"
+    ));
+
+    // blocks of a different layout (or corrupted blocks) are ignored,
+    // the object entries keep their defaults.
+    source.push_str(&format!(
+        "static void {namespace}_load_parameters() {{
+{indent}{block_name} block;
+{indent}if ({namespace}_nvm_read((uint8_t*)&block, sizeof(block)) != 0) {{
+{indent2}return;
+{indent}}}
+{indent}if (block.m_version != {PARAMETER_BLOCK_VERSION} || block.m_layout_hash != {layout_hash}ull) {{
+{indent2}return;
+{indent}}}
+{indent}if (block.m_crc != {namespace}_crc32((const uint8_t*)&block, offsetof({block_name}, m_crc))) {{
+{indent2}return;
+{indent}}}
+{restore}}}
+"
+    ));
+
+    Ok(())
+}
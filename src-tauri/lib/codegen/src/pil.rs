@@ -6,6 +6,7 @@ use crate::{
 pub fn generate_pil(
     source: &mut String,
     header: &mut String,
+    fd: bool,
     options: &Options,
 ) -> Result<()> {
     let namespace = options.namespace();
@@ -16,13 +17,26 @@ pub fn generate_pil(
 
     // =====================can_frame definition===================
     let can_frame_name = format!("{namespace}_frame");
-    let can_frame_type_def = format!(
-        "typedef struct {{
+    // networks with CAN FD buses use frames with up to 64 data bytes,
+    // dlc is the number of data bytes.
+    let can_frame_type_def = if fd {
+        format!(
+            "typedef struct {{
+{indent}uint32_t id;
+{indent}uint8_t dlc;
+{indent}uint8_t flags;
+{indent}__attribute__((aligned(alignof(uint64_t)))) uint8_t data[64];
+}} {can_frame_name};\n"
+        )
+    } else {
+        format!(
+            "typedef struct {{
 {indent}uint32_t id;
 {indent}uint8_t dlc;
 {indent}__attribute__((aligned(alignof(uint64_t)))) uint8_t data[8];
 }} {can_frame_name};\n"
-    );
+        )
+    };
     header.push_str(&can_frame_type_def);
 
     let can_frame_id_bits_def = format!(
@@ -35,6 +49,35 @@ pub fn generate_pil(
     );
     header.push_str(&can_frame_id_bits_def);
 
+    if fd {
+        // same values as the flags of SocketCAN canfd_frames.
+        let can_frame_flags_def = format!(
+            "typedef enum : uint8_t {{
+{indent}{}_FRAME_BRS_FLAG = 0x01,
+{indent}{}_FRAME_FDF_FLAG = 0x04,
+}} can_frame_flags;\n",
+            namespace.to_uppercase(),
+            namespace.to_uppercase()
+        );
+        header.push_str(&can_frame_flags_def);
+
+        // smallest valid CAN FD data length, which holds len bytes.
+        source.push_str(&format!(
+            "static uint8_t {namespace}_fd_len(uint8_t len) {{
+{indent}if (len <= 8) {{
+{indent}{indent}return len;
+{indent}}}
+{indent}static const uint8_t lengths[7] = {{12, 16, 20, 24, 32, 48, 64}};
+{indent}for (uint8_t i = 0; i < 7; ++i) {{
+{indent}{indent}if (len <= lengths[i]) {{
+{indent}{indent}{indent}return lengths[i];
+{indent}{indent}}}
+{indent}}}
+{indent}return 64;
+}}
+"
+        ));
+    }
 
     // ============= CAN filter definitions ================
     let can_filter_name = format!("{namespace}_can_filter");
@@ -59,6 +102,69 @@ uint64_t min_u64(uint64_t v, uint64_t max) {
     }
     return v;
 }
+uint32_t signed_bits_u32(float v, uint8_t size) {
+    int32_t max = (int32_t)(0xFFFFFFFFul >> (33 - size));
+    int32_t min = -max - 1;
+    int32_t i;
+    if (v >= (float)max) {
+        i = max;
+    } else if (v <= (float)min) {
+        i = min;
+    } else {
+        i = (int32_t)(v < 0 ? v - 0.5f : v + 0.5f);
+    }
+    return (uint32_t)i & (0xFFFFFFFFul >> (32 - size));
+}
+uint64_t signed_bits_u64(double v, uint8_t size) {
+    int64_t max = (int64_t)((0xFFFFFFFFFFFFFFFFull >> (65 - size)));
+    int64_t min = -max - 1;
+    int64_t i;
+    if (v >= (double)max) {
+        i = max;
+    } else if (v <= (double)min) {
+        i = min;
+    } else {
+        i = (int64_t)(v < 0 ? v - 0.5 : v + 0.5);
+    }
+    return (uint64_t)i & (0xFFFFFFFFFFFFFFFFull >> (64 - size));
+}
+int32_t sign_extend_u32(uint32_t bits, uint8_t size) {
+    uint32_t sign = ((uint32_t)1) << (size - 1);
+    return (int32_t)((bits ^ sign) - sign);
+}
+int64_t sign_extend_u64(uint64_t bits, uint8_t size) {
+    uint64_t sign = ((uint64_t)1) << (size - 1);
+    return (int64_t)((bits ^ sign) - sign);
+}
+uint32_t f32_bits(float v) {
+    uint32_t bits;
+    memcpy(&bits, &v, sizeof(bits));
+    return bits;
+}
+float bits_f32(uint32_t bits) {
+    float v;
+    memcpy(&v, &bits, sizeof(v));
+    return v;
+}
+uint64_t f64_bits(double v) {
+    uint64_t bits;
+    memcpy(&bits, &v, sizeof(bits));
+    return bits;
+}
+double bits_f64(uint64_t bits) {
+    double v;
+    memcpy(&v, &bits, sizeof(v));
+    return v;
+}
+uint64_t string_bits(const char* chars, uint8_t len) {
+    uint64_t bits = 0;
+    memcpy(&bits, chars, len);
+    return bits;
+}
+void bits_string(char* chars, uint64_t bits, uint8_t len) {
+    memcpy(chars, &bits, len);
+    chars[len] = 0;
+}
 ");
 
     Ok(())
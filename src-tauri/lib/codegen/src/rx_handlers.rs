@@ -1,6 +1,9 @@
 use canzero_config::config::{self, message, Type};
 
-use crate::{errors::Result, options::Options, types::to_c_type_name};
+use crate::{
+    errors::Result, options::Options, scheduler::GET_RESP_FD_FRAGMENTS,
+    setters::gen_range_condition, types::to_c_type_name,
+};
 
 pub fn generate_rx_handlers(
     network_config: &config::NetworkRef,
@@ -67,7 +70,7 @@ pub fn generate_rx_handlers(
                         attribute_list += ", ";
                     }
                     let name = attrib.name();
-                    attribute_list += &format!("msg.{name}");
+                    attribute_list += &format!("msg.m_{name}");
                 }
                 let resp_msg = command.rx_message();
                 let resp_msg_name = resp_msg.name();
@@ -77,7 +80,7 @@ pub fn generate_rx_handlers(
                 (
                     format!(
                         "{indent}{namespace}_message_{resp_msg_name} resp{{}};
-{indent}resp.erno = {namespace}_{command_name}({attribute_list});
+{indent}resp.m_erno = {namespace}_{command_name}({attribute_list});
 {indent}{frame_type_name} resp_frame;
 {indent}{namespace}_serialize_{namespace}_message_{resp_msg_name}(&resp, &resp_frame);
 {indent}{namespace}_can{resp_bus_id}_send(&resp_frame);
@@ -89,6 +92,7 @@ pub fn generate_rx_handlers(
             message::MessageUsage::CommandResp(_) => ("".to_owned(), ""),
             message::MessageUsage::GetResp => panic!(),
             message::MessageUsage::GetReq => {
+                let get_resp_fd = network_config.get_resp_message().bus().is_fd();
                 let mut logic = String::new();
                 let mut case_logic = format!("{indent}switch (msg.m_header.m_od_index) {{\n");
                 for object_entry in node_config.object_entries() {
@@ -151,9 +155,15 @@ pub fn generate_rx_handlers(
                                             size,
                                             offset,
                                             scale,
+                                            signed,
                                         } => {
                                             let max_u32_value = u32::MAX >> (32u32 - *size as u32);
-                                            let parse_dec = if *size <= 32 {
+                                            let parse_dec = if *size <= 32 && *signed {
+                                                format!(
+                                                    "{indent2}resp.m_data |= signed_bits_u32(({oe_name} \
+                                                    - ({offset})) / {scale}, {size}) << {bit_offset};\n"
+                                                )
+                                            } else if *size <= 32 {
                                                 format!(
                                                     "{indent2}resp.m_data |= min_u32(({oe_name} \
                                                     - ({offset})) / {scale}, 0x{max_u32_value:X}) << {bit_offset};\n"
@@ -166,6 +176,30 @@ pub fn generate_rx_handlers(
                                             parse_code.push_str(&parse_dec);
                                             *bit_offset += size;
                                         }
+                                        config::SignalType::Float { size } => {
+                                            if *size > 32 {
+                                                panic!("values larger than 32 should be send in fragmented mode")
+                                            }
+                                            parse_code.push_str(&format!(
+                                                "{indent2}resp.m_data |= f32_bits({oe_name}) << {bit_offset};\n"
+                                            ));
+                                            *bit_offset += size;
+                                        }
+                                        config::SignalType::Bool => {
+                                            parse_code.push_str(&format!(
+                                                "{indent2}resp.m_data |= ((uint32_t)({oe_name} ? 1 : 0)) << {bit_offset};\n"
+                                            ));
+                                            *bit_offset += 1;
+                                        }
+                                        config::SignalType::String { len } => {
+                                            if *len > 4 {
+                                                panic!("values larger than 32 should be send in fragmented mode")
+                                            }
+                                            parse_code.push_str(&format!(
+                                                "{indent2}resp.m_data |= ((uint32_t)string_bits({oe_name}.m_chars, {len})) << {bit_offset};\n"
+                                            ));
+                                            *bit_offset += len * 8;
+                                        }
                                     };
                                 }
                                 Type::Struct {
@@ -211,7 +245,14 @@ pub fn generate_rx_handlers(
                                     parse_code.push_str(&parse_enum);
                                     *bit_offset += size;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        let oe_name = format!("{oe_name}.m_values[{i}]");
+                                        generate_parse_logic(
+                                            parse_code, &oe_name, ty, bit_offset, indent2,
+                                        );
+                                    }
+                                }
                             };
                         }
                         let mut parse_code = String::new();
@@ -272,9 +313,14 @@ pub fn generate_rx_handlers(
                                             size,
                                             offset,
                                             scale,
+                                            signed,
                                         } => {
                                             println!("size = {size}");
-                                            if *size <= 8 {
+                                            if *signed && *size <= 32 {
+                                                format!("signed_bits_u32(({var} - ((float){offset})) / (float){scale}, {size})")
+                                            } else if *signed && *size <= 64 {
+                                                format!("signed_bits_u64(({var} - ((double){offset})) / (double){scale}, {size})")
+                                            } else if *size <= 8 {
                                                 let max_u32_value = u32::MAX >> (32u32 - *size as u32);
                                                 format!("min_u32(({var} - ((float){offset})) / (float){scale}, 0x{max_u32_value:X}ul)")
                                             } else if *size <= 16 {
@@ -290,6 +336,18 @@ pub fn generate_rx_handlers(
                                                 panic!("singed integer larger than 64 are not supported");
                                             }
                                         }
+                                        config::SignalType::Float { size: 32 } => {
+                                            format!("f32_bits({var})")
+                                        }
+                                        config::SignalType::Float { size: _ } => {
+                                            format!("f64_bits({var})")
+                                        }
+                                        config::SignalType::Bool => {
+                                            format!("((uint32_t){var})")
+                                        }
+                                        config::SignalType::String { len } => {
+                                            format!("string_bits({var}.m_chars, {len})")
+                                        }
                                     };
                                     let size = signal_type.size() as usize;
                                     let val = if size <= 32 {
@@ -468,7 +526,19 @@ pub fn generate_rx_handlers(
                                         );
                                     }
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_fragmentation_logic(
+                                            logic,
+                                            ty,
+                                            &format!("{var}.m_values[{i}]"),
+                                            buffer,
+                                            bit_offset,
+                                            indent2,
+                                            indent3,
+                                        );
+                                    }
+                                }
                             }
                         }
                         generate_fragmentation_logic(
@@ -483,8 +553,29 @@ pub fn generate_rx_handlers(
 
                         let buffer_size = size.div_ceil(32);
                         let od_index = object_entry.id();
-                        case_logic += &format!(
-                            "{indent}case {id}: {{
+                        if get_resp_fd {
+                            // the first frame contains up to GET_RESP_FD_FRAGMENTS fragments.
+                            let fragment_count = buffer_size.min(GET_RESP_FD_FRAGMENTS);
+                            let (eof, schedule) = if buffer_size > GET_RESP_FD_FRAGMENTS {
+                                (0, format!("{indent2}schedule_get_resp_fragmentation_job({buffer_name}, {buffer_size}, {od_index}, msg.m_header.m_client_id);\n"))
+                            } else {
+                                (1, String::new())
+                            };
+                            case_logic += &format!(
+                                "{indent}case {id}: {{
+{fragmentation_logic}
+{indent2}resp.m_data = {buffer_name}[0];
+{indent2}resp.m_header.m_sof = 1;
+{indent2}resp.m_header.m_eof = {eof};
+{indent2}resp.m_header.m_toggle = 0;
+{indent2}resp_fragments = {buffer_name};
+{indent2}resp_fragment_count = {fragment_count};
+{schedule}{indent2}break;
+{indent}}}\n"
+                            );
+                        } else {
+                            case_logic += &format!(
+                                "{indent}case {id}: {{
 {fragmentation_logic}
 {indent2}resp.m_data = {buffer_name}[0];
 {indent2}resp.m_header.m_sof = 1;
@@ -493,29 +584,57 @@ pub fn generate_rx_handlers(
 {indent2}schedule_get_resp_fragmentation_job({buffer_name}, {buffer_size}, {od_index}, msg.m_header.m_client_id);
 {indent2}break;
 {indent}}}\n"
-                        );
+                            );
+                        }
                     }
                 }
                 case_logic += &format!("{indent}}}\n");
                 let node_name = node_config.name();
                 let resp_bus_name = network_config.get_resp_message().bus().name();
-                logic += &format!(
-                    "{indent}if (msg.m_header.m_server_id != node_id_{node_name}) {{
+                if get_resp_fd {
+                    logic += &format!(
+                        "{indent}if (msg.m_header.m_server_id != node_id_{node_name}) {{
 {indent2}return;
 {indent}}}
 {indent}{namespace}_message_get_resp resp{{}};
+{indent}uint32_t *resp_fragments = nullptr;
+{indent}uint8_t resp_fragment_count = 1;
 {case_logic}{indent}resp.m_header.m_od_index = msg.m_header.m_od_index;
 {indent}resp.m_header.m_client_id = msg.m_header.m_client_id;
 {indent}resp.m_header.m_server_id = msg.m_header.m_server_id;
 {indent}{frame_type_name} resp_frame;
 {indent}{namespace}_serialize_{namespace}_message_get_resp(&resp, &resp_frame);
+{indent}if (resp_fragment_count > 1) {{
+{indent2}// the remaining fragments follow the first fragment of the CAN FD frame.
+{indent2}volatile uint32_t *resp_data = (volatile uint32_t*)resp_frame.data;
+{indent2}resp_frame.dlc = {namespace}_fd_len(4 + 4 * resp_fragment_count);
+{indent2}for (uint8_t i = 1; i < resp_frame.dlc / 4 - 1; ++i) {{
+{indent3}resp_data[i + 1] = i < resp_fragment_count ? resp_fragments[i] : 0;
+{indent2}}}
+{indent}}}
 {indent}{namespace}_{resp_bus_name}_send(&resp_frame);
 "
-                );
+                    );
+                } else {
+                    logic += &format!(
+                        "{indent}if (msg.m_header.m_server_id != node_id_{node_name}) {{
+{indent2}return;
+{indent}}}
+{indent}{namespace}_message_get_resp resp{{}};
+{case_logic}{indent}resp.m_header.m_od_index = msg.m_header.m_od_index;
+{indent}resp.m_header.m_client_id = msg.m_header.m_client_id;
+{indent}resp.m_header.m_server_id = msg.m_header.m_server_id;
+{indent}{frame_type_name} resp_frame;
+{indent}{namespace}_serialize_{namespace}_message_get_resp(&resp, &resp_frame);
+{indent}{namespace}_{resp_bus_name}_send(&resp_frame);
+"
+                    );
+                }
                 (logic, "static PROGMEM")
             },
             message::MessageUsage::SetResp => panic!(),
             message::MessageUsage::SetReq => {
+                let set_req_fd = message.bus().is_fd();
                 let node_id = node_config.id();
                 let mut case_logic = format!("{indent}switch (msg.m_header.m_od_index) {{\n");
                 for object_entry in node_config.object_entries() {
@@ -526,6 +645,19 @@ pub fn generate_rx_handlers(
                     let oe_tmp_var = format!("{ty} {oe_name}_tmp;\n");
                     let mut parse_logic = format!("{oe_tmp_var}");
                     let oe_var = format!("{oe_name}_tmp");
+                    // out of range set requests are rejected and don't reach the setter.
+                    let set_logic = match gen_range_condition(object_entry, &oe_var) {
+                        Some(condition) => format!(
+                            "{indent2}if ({condition}) {{
+{indent3}{namespace}_set_{oe_name}({oe_var});
+{indent2}}} else {{
+{indent3}resp.m_header.m_erno = set_resp_erno_OutOfRange;
+{indent2}}}
+"
+                        ),
+                        None => format!("{indent2}{namespace}_set_{oe_name}({oe_var});
+"),
+                    };
                     if size <= 32 {
                         fn generate_parse_logic(
                             parse_logic: &mut String,
@@ -574,8 +706,13 @@ pub fn generate_rx_handlers(
                                             size,
                                             offset,
                                             scale,
+                                            signed,
                                         } => {
-                                            if *size <= 32 {
+                                            if *size <= 32 && *signed {
+                                                format!(
+                                                    "(float)(sign_extend_u32({masked_val}, {size}) * {scale} + {offset})"
+                                                )
+                                            } else if *size <= 32 {
                                                 format!(
                                                     "(float)({masked_val} * {scale} + {offset})"
                                                 )
@@ -587,6 +724,20 @@ pub fn generate_rx_handlers(
                                                 panic!("decimal data types larger than 64 bit are not supported");
                                             }
                                         }
+                                        config::SignalType::Float { size } => {
+                                            if *size > 32 {
+                                                panic!("floats larger than 32 bit are not supported in non fragmented set requests");
+                                            }
+                                            format!("bits_f32({masked_val})")
+                                        }
+                                        config::SignalType::Bool => {
+                                            format!("({masked_val}) != 0")
+                                        }
+                                        config::SignalType::String { len } => {
+                                            parse_logic.push_str(&format!("bits_string({var}.m_chars, {masked_val}, {len});\n"));
+                                            *attrib_offset += size;
+                                            return;
+                                        }
                                     };
                                     parse_logic.push_str(&format!("{var} = {parsed_val};\n"));
                                     *attrib_offset += size as usize;
@@ -622,7 +773,16 @@ pub fn generate_rx_handlers(
                                     parse_logic.push_str(&format!("{var} = {parsed_val};\n"));
                                     *attrib_offset += size as usize;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_parse_logic(
+                                            parse_logic,
+                                            ty,
+                                            &format!("{var}.m_values[{i}]"),
+                                            attrib_offset,
+                                        );
+                                    }
+                                }
                             }
                         }
                         generate_parse_logic(
@@ -636,8 +796,7 @@ pub fn generate_rx_handlers(
 {indent2}if (msg.m_header.m_sof != 1 || msg.m_header.m_toggle != 0 || msg.m_header.m_eof != 1) {{
 {indent3}return;
 {indent2}}}
-{indent2}{parse_logic}{indent2}{namespace}_set_{oe_name}({oe_var});
-{indent2}break;
+{indent2}{parse_logic}{set_logic}{indent2}break;
 {indent}}}
 "
                         ));
@@ -698,10 +857,33 @@ pub fn generate_rx_handlers(
                                             }
                                         }
                                         config::SignalType::Decimal {
-                                            size: _,
+                                            size,
                                             offset,
                                             scale,
-                                        } => format!("({val_bits}) * {scale} + {offset}"),
+                                            signed,
+                                        } => {
+                                            if *signed && *size <= 32 {
+                                                format!("sign_extend_u32({val_bits}, {size}) * {scale} + {offset}")
+                                            } else if *signed {
+                                                format!("sign_extend_u64({val_bits}, {size}) * {scale} + {offset}")
+                                            } else {
+                                                format!("({val_bits}) * {scale} + {offset}")
+                                            }
+                                        }
+                                        config::SignalType::Float { size: 32 } => {
+                                            format!("bits_f32({val_bits})")
+                                        }
+                                        config::SignalType::Float { size: _ } => {
+                                            format!("bits_f64({val_bits})")
+                                        }
+                                        config::SignalType::Bool => {
+                                            format!("({val_bits}) != 0")
+                                        }
+                                        config::SignalType::String { len } => {
+                                            write_logic.push_str(&format!("{indent}bits_string({var}.m_chars, {val_bits}, {len});\n"));
+                                            *bit_offset += signal_type.size() as usize;
+                                            return;
+                                        }
                                     };
                                     write_logic.push_str(&format!("{indent}{var} = {val};\n"));
                                     *bit_offset += signal_type.size() as usize;
@@ -759,7 +941,18 @@ pub fn generate_rx_handlers(
                                     write_logic.push_str(&format!("{indent}{var} = {val};\n"));
                                     *bit_offset += size;
                                 }
-                                Type::Array { len: _, ty: _ } => todo!(),
+                                Type::Array { len, ty } => {
+                                    for i in 0..*len {
+                                        generate_write_logic(
+                                            write_logic,
+                                            ty,
+                                            bit_offset,
+                                            buffer_name,
+                                            &format!("{var}.m_values[{i}]"),
+                                            indent,
+                                        )
+                                    }
+                                }
                             }
                         }
                         generate_write_logic(
@@ -772,9 +965,31 @@ pub fn generate_rx_handlers(
                         );
                         let oe_ty = to_c_type_name(object_entry.ty());
 
-                        case_logic.push_str(&format!(
-                            "{indent}case {od_index} : {{
-{indent2}if (msg.m_header.m_sof == 1) {{
+                        let receive_logic = if set_req_fd {
+                            // CAN FD frames contain multiple fragments, the offset is the
+                            // number of received fragments.
+                            format!(
+                                "{indent2}if (msg.m_header.m_sof == 1) {{
+{indent3}if (msg.m_header.m_toggle != 0) {{
+{indent4}return; //TODO proper error response frame!
+{indent3}}}
+{indent3}{buffer_offset} = 0;
+{indent2}}}
+{indent2}{{
+{indent3}volatile uint32_t *fragments = ((volatile uint32_t*)frame->data) + 1;
+{indent3}uint8_t fragment_count = frame->dlc > 4 ? (frame->dlc - 1) / 4 : 0;
+{indent3}for (uint8_t i = 0; i < fragment_count && {buffer_offset} < {word_size}; ++i) {{
+{indent4}{buffer_name}[{buffer_offset}++] = fragments[i];
+{indent3}}}
+{indent2}}}
+{indent2}if (msg.m_header.m_eof == 0 || {buffer_offset} != {word_size}) {{
+{indent3}return;
+{indent2}}}
+"
+                            )
+                        } else {
+                            format!(
+                                "{indent2}if (msg.m_header.m_sof == 1) {{
 {indent3}if (msg.m_header.m_toggle != 0 || msg.m_header.m_eof != 0) {{
 {indent4}return; //TODO proper error response frame!
 {indent3}}}
@@ -789,9 +1004,13 @@ pub fn generate_rx_handlers(
 {indent2}if (msg.m_header.m_eof == 0) {{
 {indent3}return;
 {indent2}}}
-{indent2}{oe_ty} {oe_var};
-{write_logic}{indent2}{namespace}_set_{oe_name}({oe_var});
-{indent2}break;
+"
+                            )
+                        };
+                        case_logic.push_str(&format!(
+                            "{indent}case {od_index} : {{
+{receive_logic}{indent2}{oe_ty} {oe_var};
+{write_logic}{set_logic}{indent2}break;
 {indent}}}
 "
                         ));
@@ -804,11 +1023,11 @@ pub fn generate_rx_handlers(
 {indent2}return;
 {indent}}}
 {indent}{namespace}_message_set_resp resp{{}};
+{indent}resp.m_header.m_erno = set_resp_erno_Success;
 {case_logic}
 {indent}resp.m_header.m_od_index = msg.m_header.m_od_index;
 {indent}resp.m_header.m_client_id = msg.m_header.m_client_id;
 {indent}resp.m_header.m_server_id = msg.m_header.m_server_id;
-{indent}resp.m_header.m_erno = set_resp_erno_Success;
 {indent}canzero_frame resp_frame;
 {indent}{namespace}_serialize_{namespace}_message_set_resp(&resp, &resp_frame);
 {indent}{namespace}_{resp_bus_name}_send(&resp_frame);\n
@@ -7,6 +7,8 @@ use crate::errors::Result;
 use crate::options::Options;
 
 const GET_RESP_FRAGMENTATION_INTERVAL: usize = 100usize;
+/// Number of 32 bit fragments of a get response on a CAN FD bus.
+pub(crate) const GET_RESP_FD_FRAGMENTS: usize = 15usize;
 
 pub fn generate_scheduler(
     network_config: &config::NetworkRef,
@@ -123,6 +125,13 @@ static void schedule_{stream_name}_interval_job(){{
     }
 
     let get_resp_bus_name = network_config.get_resp_message().bus().name();
+    let get_resp_fd = network_config.get_resp_message().bus().is_fd();
+    // the first frame of a get response is sent by the get request handler.
+    let first_fragmentation_offset = if get_resp_fd {
+        GET_RESP_FD_FRAGMENTS
+    } else {
+        1
+    };
     for heartbeat in network_config.heartbeat_messages() {
         source.push_str(&format!(
 "__attribute__((weak)) void {namespace}_{0}_wdg_timeout(uint8_t node_id) {{}}
@@ -295,7 +304,7 @@ static void schedule_get_resp_fragmentation_job(uint32_t *fragmentation_buffer,
 {indent}fragmentation_job->climax = canzero_get_time() + get_resp_fragmentation_interval;
 {indent}fragmentation_job->tag = GET_RESP_FRAGMENTATION_JOB_TAG;
 {indent}fragmentation_job->job.get_fragmentation_job.buffer = fragmentation_buffer;
-{indent}fragmentation_job->job.get_fragmentation_job.offset = 1;
+{indent}fragmentation_job->job.get_fragmentation_job.offset = {first_fragmentation_offset};
 {indent}fragmentation_job->job.get_fragmentation_job.size = size;
 {indent}fragmentation_job->job.get_fragmentation_job.od_index = od_index;
 {indent}fragmentation_job->job.get_fragmentation_job.client_id = client_id;
@@ -466,11 +475,43 @@ static void schedule_jobs(uint32_t time) {{
             heartbeat.bus().name()
         ));
     }
-    source.push_str(&format!(
-"{indent4}}}
+    let get_resp_fragmentation_case = if get_resp_fd {
+        format!(
+"{indent3}case GET_RESP_FRAGMENTATION_JOB_TAG: {{
+{indent4}get_resp_fragmentation_job *fragmentation_job = &job->job.get_fragmentation_job;
+{indent4}{namespace}_message_get_resp fragmentation_response;
+{indent4}fragmentation_response.m_header.m_sof = 0;
+{indent4}fragmentation_response.m_header.m_toggle = (fragmentation_job->offset / {GET_RESP_FD_FRAGMENTS}) % 2;
+{indent4}fragmentation_response.m_header.m_od_index = fragmentation_job->od_index;
+{indent4}fragmentation_response.m_header.m_client_id = fragmentation_job->client_id;
+{indent4}fragmentation_response.m_header.m_server_id = 0x{node_id:X};
+{indent4}uint32_t *fragments = fragmentation_job->buffer + fragmentation_job->offset;
+{indent4}uint8_t fragment_count = min_u32(fragmentation_job->size - fragmentation_job->offset, {GET_RESP_FD_FRAGMENTS});
+{indent4}fragmentation_response.m_data = fragments[0];
+{indent4}fragmentation_job->offset += fragment_count;
+{indent4}if (fragmentation_job->offset == fragmentation_job->size) {{
+{indent5}fragmentation_response.m_header.m_eof = 1;
+{indent5}scheduler_unschedule();
+{indent4}}} else {{
+{indent5}fragmentation_response.m_header.m_eof = 0;
+{indent5}scheduler_reschedule(time + get_resp_fragmentation_interval);
+{indent4}}}
+{indent4}canzero_frame fragmentation_frame;
+{indent4}{namespace}_serialize_{namespace}_message_get_resp(&fragmentation_response, &fragmentation_frame);
+{indent4}// the remaining fragments follow the first fragment of the CAN FD frame.
+{indent4}volatile uint32_t *fragmentation_data = (volatile uint32_t*)fragmentation_frame.data;
+{indent4}fragmentation_frame.dlc = {namespace}_fd_len(4 + 4 * fragment_count);
+{indent4}for (uint8_t i = 1; i < fragmentation_frame.dlc / 4 - 1; ++i) {{
+{indent5}fragmentation_data[i + 1] = i < fragment_count ? fragments[i] : 0;
+{indent4}}}
+{indent4}{namespace}_exit_critical();
+{indent4}{namespace}_{get_resp_bus_name}_send(&fragmentation_frame);
 {indent4}break;
 {indent3}}}
-{indent3}case GET_RESP_FRAGMENTATION_JOB_TAG: {{
+")
+    } else {
+        format!(
+"{indent3}case GET_RESP_FRAGMENTATION_JOB_TAG: {{
 {indent4}get_resp_fragmentation_job *fragmentation_job = &job->job.get_fragmentation_job;
 {indent4}{namespace}_message_get_resp fragmentation_response;
 {indent4}fragmentation_response.m_header.m_sof = 0;
@@ -493,7 +534,13 @@ static void schedule_jobs(uint32_t time) {{
 {indent4}{namespace}_{get_resp_bus_name}_send(&fragmentation_frame);
 {indent4}break;
 {indent3}}}
-{indent3}default: {{
+")
+    };
+    source.push_str(&format!(
+"{indent4}}}
+{indent4}break;
+{indent3}}}
+{get_resp_fragmentation_case}{indent3}default: {{
 {indent4}{namespace}_exit_critical();
 {indent4}break;
 {indent3}}}
@@ -101,9 +101,15 @@ pub fn generate_senders(
                                 size,
                                 offset,
                                 scale,
+                                signed,
                             } => {
                                 let max_u32_value = u32::MAX >> (32u32 - *size as u32);
-                                let parse_dec = if *size <= 32 {
+                                let parse_dec = if *size <= 32 && *signed {
+                                format!(
+                                    "{base_indent}{msg_name}.m_data |= signed_bits_u32(({oe_name} \
+                                        - ({offset})) / {scale}, {size}) << {bit_offset};\n"
+                                )
+                                } else if *size <= 32 {
                                 format!(
                                     "{base_indent}{msg_name}.m_data |= min_u32(({oe_name} \
                                         - ({offset})) / {scale}, 0x{max_u32_value:X}) << {bit_offset};\n"
@@ -116,6 +122,30 @@ pub fn generate_senders(
                                 parse_code.push_str(&parse_dec);
                                 *bit_offset += size;
                             }
+                            config::SignalType::Float { size } => {
+                                if *size > 32 {
+                                    panic!("values larger than 32 should be send in fragmented mode")
+                                }
+                                parse_code.push_str(&format!(
+                                    "{base_indent}{msg_name}.m_data |= f32_bits({oe_name}) << {bit_offset};\n"
+                                ));
+                                *bit_offset += size;
+                            }
+                            config::SignalType::Bool => {
+                                parse_code.push_str(&format!(
+                                    "{base_indent}{msg_name}.m_data |= ((uint32_t)({oe_name} ? 1 : 0)) << {bit_offset};\n"
+                                ));
+                                *bit_offset += 1;
+                            }
+                            config::SignalType::String { len } => {
+                                if *len > 4 {
+                                    panic!("values larger than 32 should be send in fragmented mode")
+                                }
+                                parse_code.push_str(&format!(
+                                    "{base_indent}{msg_name}.m_data |= ((uint32_t)string_bits({oe_name}.m_chars, {len})) << {bit_offset};\n"
+                                ));
+                                *bit_offset += len * 8;
+                            }
                         };
                     }
                     Type::Struct {
@@ -159,7 +189,14 @@ pub fn generate_senders(
                         parse_code.push_str(&parse_enum);
                         *bit_offset += size;
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len, ty } => {
+                        for i in 0..*len {
+                            let oe_name = format!("{oe_name}.m_values[{i}]");
+                            generate_parse_logic(
+                                parse_code, &oe_name, ty, bit_offset, base_indent, msg_name
+                            );
+                        }
+                    }
                 };
             }
             let mut parse_code = String::new();
@@ -215,9 +252,14 @@ pub fn generate_senders(
                                 size,
                                 offset,
                                 scale,
+                                signed,
                             } => {
                                 println!("size = {size}");
-                                if *size <= 8 {
+                                if *signed && *size <= 32 {
+                                    format!("signed_bits_u32(({var_name} - ((float){offset})) / (float){scale}, {size})")
+                                } else if *signed && *size <= 64 {
+                                    format!("signed_bits_u64(({var_name} - ((double){offset})) / (double){scale}, {size})")
+                                } else if *size <= 8 {
                                     let max_u32_value = u32::MAX >> (32u32 - *size as u32);
                                     format!("min_u32(({var_name} - ((float){offset})) / (float){scale}, 0x{max_u32_value:X}ul)")
                                 } else if *size <= 16 {
@@ -233,6 +275,18 @@ pub fn generate_senders(
                                     panic!("singed integer larger than 64 are not supported");
                                 }
                             }
+                            config::SignalType::Float { size: 32 } => {
+                                format!("f32_bits({var_name})")
+                            }
+                            config::SignalType::Float { size: _ } => {
+                                format!("f64_bits({var_name})")
+                            }
+                            config::SignalType::Bool => {
+                                format!("((uint32_t){var_name})")
+                            }
+                            config::SignalType::String { len } => {
+                                format!("string_bits({var_name}.m_chars, {len})")
+                            }
                         };
                         let size = signal_type.size() as usize;
                         let val = if size <= 32 {
@@ -411,7 +465,19 @@ pub fn generate_senders(
                             );
                         }
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len, ty } => {
+                        for i in 0..*len {
+                            generate_fragmentation_logic(
+                                logic,
+                                ty,
+                                &format!("{var_name}.m_values[{i}]"),
+                                buffer,
+                                bit_offset,
+                                indent2,
+                                indent3,
+                            );
+                        }
+                    }
                 }
             }
             let mut fragmentation_logic = String::new();
@@ -1,5 +1,6 @@
+use canzero_config::config::decode::DecodedValue;
 use canzero_config::config::stream::StreamRef;
-use canzero_config::config::{NodeRef, Type};
+use canzero_config::config::{NodeRef, ObjectEntryRef, SignalType, Type};
 
 use crate::errors::Result;
 use crate::options::Options;
@@ -53,14 +54,21 @@ pub fn generate_setters(
             
             fn gen_condition(left : &str, right : &str, ty: &Type) -> String{
                 match ty {
+                    Type::Primitive(SignalType::String { len }) => {
+                        format!("memcmp({left}.m_chars, {right}.m_chars, {len}) != 0")
+                    }
                     Type::Enum { name : _, description : _, size : _, entries : _, visibility : _ } |
                     Type::Primitive(_) => format!("{left} != {right}"),
                     Type::Struct { name : _, description : _, attribs, visibility : _ } => {
                         attribs.iter().map(|(attrib_name, attrib_ty)| {
-                            gen_condition(&format!("{left}.{attrib_name}"), &format!("{right}.{attrib_name}"), attrib_ty)
+                            gen_condition(&format!("{left}.m_{attrib_name}"), &format!("{right}.m_{attrib_name}"), attrib_ty)
+                        }).fold("0".to_owned(), |acc, x| format!("{acc} || {x}"))
+                    },
+                    Type::Array { len, ty } => {
+                        (0..*len).map(|i| {
+                            gen_condition(&format!("{left}.m_values[{i}]"), &format!("{right}.m_values[{i}]"), ty)
                         }).fold("0".to_owned(), |acc, x| format!("{acc} || {x}"))
                     },
-                    Type::Array { len : _, ty : _ } => panic!(),
                 }
             }
 
@@ -96,3 +104,57 @@ pub fn generate_setters(
 
     Ok(())
 }
+
+fn to_c_literal(value: f64, ty: &Type) -> String {
+    match ty {
+        Type::Primitive(SignalType::UnsignedInt { size: _ }) => format!("{}", value as u64),
+        Type::Primitive(SignalType::SignedInt { size: _ }) => format!("{}", value as i64),
+        // debug formatting of floats is always a valid C literal (e.g. 10.0 or 1e-7).
+        _ => format!("{value:?}"),
+    }
+}
+
+/// C expression which is true if `var` is within the range (`min` and `max`) of the object entry,
+/// None if the object entry is not constrained.
+pub fn gen_range_condition(object_entry: &ObjectEntryRef, var: &str) -> Option<String> {
+    let ty = object_entry.ty();
+    let mut conditions = vec![];
+    if let Some(min) = object_entry.min() {
+        // integers round towards the range.
+        let min = match ty.as_ref() {
+            Type::Primitive(SignalType::UnsignedInt { .. } | SignalType::SignedInt { .. }) => {
+                min.ceil()
+            }
+            _ => min,
+        };
+        conditions.push(format!("{var} >= {}", to_c_literal(min, ty)));
+    }
+    if let Some(max) = object_entry.max() {
+        let max = match ty.as_ref() {
+            Type::Primitive(SignalType::UnsignedInt { .. } | SignalType::SignedInt { .. }) => {
+                max.floor()
+            }
+            _ => max,
+        };
+        conditions.push(format!("{var} <= {}", to_c_literal(max, ty)));
+    }
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(conditions.join(" && "))
+    }
+}
+
+/// C expression of the default value of the object entry.
+pub fn gen_default_value(object_entry: &ObjectEntryRef) -> Option<String> {
+    let default = object_entry.default()?;
+    Some(match (default, object_entry.ty().as_ref()) {
+        (DecodedValue::Unsigned(v), _) => format!("{v}"),
+        (DecodedValue::Signed(v), _) => format!("{v}"),
+        (DecodedValue::Real(v), _) => format!("{v:?}"),
+        (DecodedValue::Bool(v), _) => format!("{v}"),
+        (DecodedValue::String(v), _) => format!("{{ .m_chars = {v:?} }}"),
+        (DecodedValue::Enum(entry), Type::Enum { name, .. }) => format!("{name}_{entry}"),
+        _ => panic!("defaults are only supported for primitive and enum object entries"),
+    })
+}
@@ -1,6 +1,7 @@
 use canzero_config::config;
 
 use crate::options::Options;
+use crate::setters::gen_default_value;
 
 use crate::errors::Result;
 
@@ -26,6 +27,18 @@ pub fn generate_setup(node_config : &config::NodeRef, network_config : &config::
         setup_cans.push_str(&format!("{indent}{namespace}_{bus_name}_setup({baudrate}, NULL, 0);\n"));
     }
 
+    let mut init_defaults = String::new();
+    for object_entry in node_config.object_entries() {
+        if let Some(default) = gen_default_value(object_entry) {
+            init_defaults.push_str(&format!("{indent}__oe_{} = {default};\n", object_entry.name()));
+        }
+    }
+
+    // stored parameters overwrite the defaults.
+    if node_config.object_entries().iter().any(|oe| oe.persistent()) {
+        init_defaults.push_str(&format!("{indent}{namespace}_load_parameters();\n"));
+    }
+
     let mut schedule_stream_jobs_logic = String::new();
     for tx_stream in node_config.tx_streams() {
         let stream_name = tx_stream.name();
@@ -85,7 +98,8 @@ pub fn generate_setup(node_config : &config::NodeRef, network_config : &config::
 #define BUILD_SEC   ((BUILD_TIME_IS_BAD) ? 99 :  COMPUTE_BUILD_SEC)
 ");
     
-    let config_hash = network_config.portable_hash();
+    // only the part of the network this node sees, so unrelated changes don't require a reflash.
+    let config_hash = node_config.portable_hash();
 
     let init_def = format!("void {init_name}() {{
 {indent}__oe_config_hash = {config_hash}ull;
@@ -97,7 +111,7 @@ pub fn generate_setup(node_config : &config::NodeRef, network_config : &config::
 {indent}{indent}.m_min = BUILD_MIN,
 {indent}{indent}.m_sec = BUILD_SEC
 {indent}}};
-{setup_cans}
+{init_defaults}{setup_cans}
 {indent}job_pool_allocator_init();
 {indent}scheduler.size = 0;
 {indent}schedule_heartbeat_job();
@@ -1,4 +1,6 @@
-use canzero_config::config::{self, Type};
+use std::collections::BTreeSet;
+
+use canzero_config::config::{self, SignalType, Type};
 
 use crate::errors::Result;
 use crate::messages::signal_type_to_c_type;
@@ -14,6 +16,30 @@ pub fn generate_types(
         indent.push(' ');
     }
 
+    // fixed-length strings are wrapped in structs to be passed by value,
+    // the additional character terminates the string.
+    let mut string_lengths = BTreeSet::new();
+    for object_entry in node_config.object_entries() {
+        collect_string_lengths(object_entry.ty(), &mut string_lengths);
+    }
+    for message in node_config.tx_messages().iter().chain(node_config.rx_messages()) {
+        for signal in message.signals() {
+            if let SignalType::String { len } = signal.ty() {
+                string_lengths.insert(*len);
+            }
+        }
+    }
+    for len in string_lengths {
+        let string_type = SignalType::String { len };
+        let ctype = signal_type_to_c_type(&string_type);
+        header.push_str(&format!(
+            "typedef struct {{\n{indent}char m_chars[{len} + 1];\n}} {ctype};\n"
+        ));
+    }
+
+    // arrays are wrapped in structs to be passed by value (like strings),
+    // the wrappers are defined before the first type that uses them.
+    let mut array_types = vec![];
     for ty in node_config.types() {
         match ty as &config::Type {
             config::Type::Struct {
@@ -22,6 +48,9 @@ pub fn generate_types(
                 attribs,
                 visibility: _,
             } => {
+                for (_, attrib_type) in attribs {
+                    define_array_type(attrib_type, &mut array_types, header, &indent);
+                }
                 let mut def = format!("typedef struct {{\n");
                 for (attrib_name, attrib_type) in attribs {
                     let ctype = to_c_type_name(attrib_type);
@@ -44,31 +73,79 @@ pub fn generate_types(
                 def.push_str(&format!("}} {name};\n"));
                 header.push_str(&def);
             }
-            config::Type::Array { len: _, ty: _ } => todo!(),
+            config::Type::Array { len: _, ty: _ } => {
+                panic!("arrays should not be explicitly defined as node types")
+            }
             config::Type::Primitive(_) => {
                 panic!("primitives should not be explicitly defined as node types")
             }
         }
     }
+    for object_entry in node_config.object_entries() {
+        define_array_type(object_entry.ty(), &mut array_types, header, &indent);
+    }
+    for message in node_config.tx_messages().iter().chain(node_config.rx_messages()) {
+        if let Some(encoding) = message.encoding() {
+            for attrib in encoding.attributes() {
+                define_array_type(attrib.ty(), &mut array_types, header, &indent);
+            }
+        }
+    }
     Ok(())
 }
 
-pub fn to_c_type_name(ty: &Type) -> &str {
+fn define_array_type(
+    ty: &Type,
+    array_types: &mut Vec<String>,
+    header: &mut String,
+    indent: &str,
+) {
+    let config::Type::Array { len, ty: element_ty } = ty else {
+        return;
+    };
+    define_array_type(element_ty, array_types, header, indent);
+    let ctype = to_c_type_name(ty);
+    if array_types.contains(&ctype) {
+        return;
+    }
+    let element_ctype = to_c_type_name(element_ty);
+    header.push_str(&format!(
+        "typedef struct {{\n{indent}{element_ctype} m_values[{len}];\n}} {ctype};\n"
+    ));
+    array_types.push(ctype);
+}
+
+pub fn to_c_type_name(ty: &Type) -> String {
     match ty {
-        config::Type::Primitive(signal_type) => signal_type_to_c_type(signal_type),
+        config::Type::Primitive(signal_type) => signal_type_to_c_type(signal_type).to_owned(),
         config::Type::Struct {
             name,
             description: _,
             attribs: _,
             visibility: _,
-        } => name,
+        } => name.clone(),
         config::Type::Enum {
             name,
             description: _,
             size: _,
             entries: _,
             visibility: _,
-        } => name,
-        config::Type::Array { len: _, ty: _ } => todo!(),
+        } => name.clone(),
+        config::Type::Array { len, ty } => format!("{}_array{len}", to_c_type_name(ty)),
+    }
+}
+
+fn collect_string_lengths(ty: &Type, string_lengths: &mut BTreeSet<u8>) {
+    match ty {
+        config::Type::Primitive(SignalType::String { len }) => {
+            string_lengths.insert(*len);
+        }
+        config::Type::Primitive(_) | config::Type::Enum { .. } => (),
+        config::Type::Struct { attribs, .. } => {
+            for (_, attrib_type) in attribs {
+                collect_string_lengths(attrib_type, string_lengths);
+            }
+        }
+        config::Type::Array { len: _, ty } => collect_string_lengths(ty, string_lengths),
     }
 }
@@ -0,0 +1,307 @@
+//! Compiles the generated code with the c++ compiler of the host (`CXX` or `c++`)
+//! and runs it. The tests are skipped if no compiler is available.
+
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use canzero_codegen::{generate, options::Options};
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue},
+    encode::MessageEncoder,
+    MessageRef, NetworkRef, SignalType,
+};
+use canzero_yaml::parse_yaml_config;
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    object_dictionary:
+      fine:
+        type: sd40<-1..1>
+      current:
+        type: sd12<-500..500>
+        byte_order: big_endian
+      tail:
+        type: i8
+    tx_streams:
+      status:
+        mapping: [fine, current, tail]
+        interval: 10ms
+  master:
+    rx_streams:
+      secu:
+        status:
+          fine: secu_fine
+          current: secu_current
+          tail: secu_tail
+    object_dictionary:
+      secu_fine:
+        type: sd40<-1..1>
+      secu_current:
+        type: sd12<-500..500>
+      secu_tail:
+        type: i8
+";
+
+/// Platform functions, which are required to link the generated code.
+const PLATFORM_STUBS: &str = "
+#include <cstdio>
+#include <cstdlib>
+int canzero_can0_recv(canzero_frame*) { return 0; }
+void canzero_can0_send(canzero_frame*) {}
+void canzero_can0_setup(uint32_t, canzero_can_filter*, int) {}
+void canzero_enter_critical() {}
+void canzero_exit_critical() {}
+uint32_t canzero_get_time() { return 0; }
+void canzero_request_update(uint32_t) {}
+";
+
+/// The generated code targets teensy boards, which place variables and functions with
+/// these attributes.
+const PGMSPACE_STUB: &str = "#pragma once
+#define PROGMEM
+#define DMAMEM
+#define FLASHMEM
+#define FASTRUN
+";
+
+fn compiler() -> Option<String> {
+    let cxx = std::env::var("CXX").unwrap_or("c++".to_owned());
+    let available = Command::new(&cxx)
+        .arg("--version")
+        .output()
+        .is_ok_and(|output| output.status.success());
+    if !available {
+        eprintln!("skipped, no c++ compiler found");
+    }
+    available.then_some(cxx)
+}
+
+fn build(src: &str) -> NetworkRef {
+    parse_yaml_config(src, Path::new("network.yaml")).unwrap()
+}
+
+/// Generates the code of every node into its own directory.
+fn generate_nodes(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("canzero-codegen-{name}-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("avr")).unwrap();
+    std::fs::write(dir.join("avr").join("pgmspace.h"), PGMSPACE_STUB).unwrap();
+    for node in ["secu", "master"] {
+        std::fs::create_dir_all(dir.join(node)).unwrap();
+        let mut options = Options::default();
+        options.set_source_file_path(dir.join(node).join("canzero.cpp").to_str().unwrap());
+        options.set_header_file_path(dir.join(node).join("canzero.h").to_str().unwrap());
+        generate(node, build(NETWORK), options).unwrap();
+    }
+    dir
+}
+
+/// Compiles `main` together with the generated code of `node` and runs it.
+fn run(cxx: &str, dir: &Path, node: &str, main: &str, stdin: &str) -> String {
+    let harness = dir.join(format!("{node}_main.cpp"));
+    std::fs::write(
+        &harness,
+        format!("#include \"{node}/canzero.cpp\"\n{PLATFORM_STUBS}\n{main}"),
+    )
+    .unwrap();
+    let binary = dir.join(format!("{node}_main"));
+    let output = Command::new(cxx)
+        .args(["-std=c++17", "-I"])
+        .arg(dir)
+        .arg("-o")
+        .arg(&binary)
+        .arg(&harness)
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let mut child = Command::new(&binary)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(
+        output.status.success(),
+        "{node}_main failed\n{}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    String::from_utf8(output.stdout).unwrap()
+}
+
+fn status(network: &NetworkRef) -> MessageRef {
+    network
+        .messages()
+        .iter()
+        .find(|message| message.name() == "secu_stream_status")
+        .unwrap()
+        .clone()
+}
+
+/// Scale and range of the signed decimal signal of an attribute.
+fn decimal_range(message: &MessageRef, attribute: &str) -> (f64, f64, f64) {
+    let signal = message
+        .signals()
+        .iter()
+        .find(|signal| signal.name().ends_with(attribute))
+        .unwrap();
+    let SignalType::Decimal {
+        size,
+        scale,
+        signed: true,
+        ..
+    } = signal.ty()
+    else {
+        panic!("{attribute} is not a signed decimal");
+    };
+    let half_range = 2f64.powi(*size as i32 - 1);
+    (*scale, -half_range * scale, (half_range - 1.0) * scale)
+}
+
+#[test]
+fn signed_bits_at_the_bounds() {
+    let Some(cxx) = compiler() else {
+        return;
+    };
+    let dir = generate_nodes("signed-bits");
+    let main = "
+static int failures = 0;
+static void check(bool ok, const char* what) {
+  if (!ok) {
+    printf(\"failed: %s\\n\", what);
+    failures += 1;
+  }
+}
+int main() {
+  check(signed_bits_u32(-2048.0f, 12) == 0x800, \"u32 min\");
+  check(signed_bits_u32(0.0f, 12) == 0x000, \"u32 zero\");
+  check(signed_bits_u32(2047.0f, 12) == 0x7FF, \"u32 max\");
+  check(signed_bits_u32(-5000.0f, 12) == 0x800, \"u32 below min\");
+  check(signed_bits_u32(5000.0f, 12) == 0x7FF, \"u32 above max\");
+  check(signed_bits_u32(-2.6f, 12) == 0xFFD, \"u32 rounding\");
+  check(sign_extend_u32(signed_bits_u32(-2048.0f, 12), 12) == -2048, \"u32 min roundtrip\");
+  check(sign_extend_u32(signed_bits_u32(0.0f, 12), 12) == 0, \"u32 zero roundtrip\");
+  check(sign_extend_u32(signed_bits_u32(2047.0f, 12), 12) == 2047, \"u32 max roundtrip\");
+  check(signed_bits_u32(-2147483648.0f, 32) == 0x80000000ul, \"u32 min 32 bit\");
+  check(signed_bits_u32(3e9f, 32) == 0x7FFFFFFFul, \"u32 max 32 bit\");
+
+  check(signed_bits_u64(-549755813888.0, 40) == 0x8000000000ull, \"u64 min\");
+  check(signed_bits_u64(0.0, 40) == 0x0ull, \"u64 zero\");
+  check(signed_bits_u64(549755813887.0, 40) == 0x7FFFFFFFFFull, \"u64 max\");
+  check(signed_bits_u64(-1e15, 40) == 0x8000000000ull, \"u64 below min\");
+  check(signed_bits_u64(1e15, 40) == 0x7FFFFFFFFFull, \"u64 above max\");
+  check(sign_extend_u64(signed_bits_u64(-549755813888.0, 40), 40) == -549755813888ll, \"u64 min roundtrip\");
+  check(sign_extend_u64(signed_bits_u64(0.0, 40), 40) == 0, \"u64 zero roundtrip\");
+  check(sign_extend_u64(signed_bits_u64(549755813887.0, 40), 40) == 549755813887ll, \"u64 max roundtrip\");
+  check(signed_bits_u64(-1e19, 64) == 0x8000000000000000ull, \"u64 min 64 bit\");
+  check(signed_bits_u64(1e19, 64) == 0x7FFFFFFFFFFFFFFFull, \"u64 max 64 bit\");
+  return failures;
+}
+";
+    let output = run(&cxx, &dir, "secu", main, "");
+    assert!(output.is_empty(), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn signed_decimal_roundtrip() {
+    let Some(cxx) = compiler() else {
+        return;
+    };
+    let dir = generate_nodes("signed-decimal");
+    let network = build(NETWORK);
+    let message = status(&network);
+    let (fine_scale, fine_min, fine_max) = decimal_range(&message, "fine");
+    let (current_scale, current_min, current_max) = decimal_range(&message, "current");
+    let cases = [
+        (fine_min, current_min, -128),
+        (0.0, 0.0, 0),
+        (fine_max, current_max, 127),
+    ];
+
+    let mut serialize = String::new();
+    for (fine, current, tail) in cases {
+        serialize.push_str(&format!(
+            "  msg.m_fine = {fine:.17e};
+  msg.m_current = {current:.17e};
+  msg.m_tail = {tail};
+  canzero_serialize_canzero_message_secu_stream_status(&msg, &frame);
+  for (int i = 0; i < 8; ++i) printf(\"%02x \", frame.data[i]);
+  printf(\"\\n\");
+"
+        ));
+    }
+    let tx_main = format!(
+        "int main() {{
+  canzero_message_secu_stream_status msg;
+  canzero_frame frame;
+{serialize}}}
+"
+    );
+    let payloads = run(&cxx, &dir, "secu", &tx_main, "");
+
+    // the generated code encodes like the control panel.
+    let encoder = MessageEncoder::new(&message);
+    for ((fine, current, tail), line) in std::iter::zip(cases, payloads.lines()) {
+        let bytes: Vec<u8> = line
+            .split_whitespace()
+            .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+            .collect();
+        let attribute = |name: &str, value| DecodedAttribute {
+            name: name.to_owned(),
+            value,
+        };
+        let expected = encoder.encode(&[
+            attribute("fine", DecodedValue::Real(fine)),
+            attribute("current", DecodedValue::Real(current)),
+            attribute("tail", DecodedValue::Signed(tail)),
+        ]);
+        assert_eq!(
+            vec![u64::from_le_bytes(bytes.try_into().unwrap())],
+            expected,
+            "{fine} {current} {tail}"
+        );
+    }
+
+    let rx_main = "int main() {
+  canzero_frame frame;
+  canzero_message_secu_stream_status msg;
+  for (int n = 0; n < 3; ++n) {
+    for (int i = 0; i < 8; ++i) {
+      unsigned int byte;
+      if (scanf(\"%x\", &byte) != 1) return 1;
+      frame.data[i] = byte;
+    }
+    canzero_deserialize_canzero_message_secu_stream_status(&frame, &msg);
+    printf(\"%.17g %.17g %d\\n\", (double)msg.m_fine, (double)msg.m_current, msg.m_tail);
+  }
+}
+";
+    let values = run(&cxx, &dir, "master", rx_main, &payloads);
+    for ((fine, current, tail), line) in std::iter::zip(cases, values.lines()) {
+        let decoded: Vec<f64> = line
+            .split_whitespace()
+            .map(|value| value.parse().unwrap())
+            .collect();
+        assert!((decoded[0] - fine).abs() <= fine_scale / 2.0, "{line}");
+        assert!((decoded[1] - current).abs() <= current_scale / 2.0, "{line}");
+        assert_eq!(decoded[2], tail as f64, "{line}");
+    }
+    assert_eq!(values.lines().count(), cases.len());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -1,3 +1,6 @@
+pub mod log_format;
+pub mod session_log;
+
 use serde::{Deserialize, Serialize};
 use std::{
     ops::Deref,
@@ -70,11 +73,16 @@ impl<T> Timestamped<T> {
 
 pub type TCanFrame = Timestamped<CanFrame>;
 
+/// Maximum number of data bytes of a CAN FD frame.
+pub const CAN_FD_MAX_LEN: usize = 64;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CanFrame {
     id: u32,
+    /// number of data bytes (at most 8 for classic frames and 64 for CAN FD frames).
     dlc: u8,
-    data: u64,
+    flags: u8,
+    data: [u64; 8],
 }
 
 #[repr(u32)]
@@ -84,6 +92,15 @@ enum CanFrameIdFlags {
     ExtMask = 0x1FFFFFFF,
 }
 
+/// Same values as the flags of SocketCAN `canfd_frame`s.
+#[repr(u8)]
+pub enum CanFrameFdFlags {
+    /// bit rate switch, the data phase is transmitted with the data bitrate of the bus.
+    Brs = 0x01,
+    /// the frame is a CAN FD frame.
+    Fdf = 0x04,
+}
+
 impl CanFrame {
     /// Least significant byte of data attribute corresponds to first byte of data field in CAN message.
     /// Just think about it as a char-array.
@@ -105,16 +122,47 @@ impl CanFrame {
                     0x0u32
                 }),
             dlc,
-            data,
+            flags: 0,
+            data: [data, 0, 0, 0, 0, 0, 0, 0],
         }
     }
 
+    /// CAN FD frame with `len` data bytes, `data[0]` holds the first 8 bytes (see [CanFrame::new]).
+    pub fn new_fd(id: u32, ide: bool, brs: bool, len: u8, data: [u64; 8]) -> Self {
+        let mut frame = Self::new(id, ide, false, len, 0);
+        frame.flags = CanFrameFdFlags::Fdf as u8
+            | if brs { CanFrameFdFlags::Brs as u8 } else { 0 };
+        frame.data = data;
+        frame
+    }
+
     pub fn new_raw(id_key : u32, dlc : u8, data : u64) -> Self {
         Self {
             id : id_key,
             dlc,
-            data
+            flags : 0,
+            data : [data, 0, 0, 0, 0, 0, 0, 0],
+        }
+    }
+
+    pub fn new_raw_fd(id_key: u32, flags: u8, len: u8, data: [u64; 8]) -> Self {
+        Self {
+            id: id_key,
+            dlc: len,
+            flags,
+            data,
+        }
+    }
+
+    /// Little endian interpretation of the data bytes, 8 bytes per word.
+    pub fn data_from_bytes(bytes: &[u8]) -> [u64; 8] {
+        let mut data = [0u64; 8];
+        for (word, chunk) in data.iter_mut().zip(bytes.chunks(8)) {
+            let mut buf = [0u8; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            *word = u64::from_le_bytes(buf);
         }
+        data
     }
 
     pub fn key(&self) -> u32 {
@@ -133,16 +181,39 @@ impl CanFrame {
     pub fn get_rtr_flag(&self) -> bool {
         (self.id & CanFrameIdFlags::RtrMask as u32) != 0
     }
+    pub fn get_fdf_flag(&self) -> bool {
+        (self.flags & CanFrameFdFlags::Fdf as u8) != 0
+    }
+    pub fn get_brs_flag(&self) -> bool {
+        (self.flags & CanFrameFdFlags::Brs as u8) != 0
+    }
+    pub fn get_flags(&self) -> u8 {
+        self.flags
+    }
     #[allow(unused)]
     pub fn get_dlc(&self) -> u8 {
         self.dlc
     }
+    /// First 8 data bytes.
     pub fn get_data_u64(&self) -> u64 {
+        self.data[0]
+    }
+    /// Data in words of 8 bytes, at least one word.
+    pub fn get_data(&self) -> &[u64] {
+        let words = (self.dlc as usize).div_ceil(8).clamp(1, self.data.len());
+        &self.data[..words]
+    }
+    pub fn get_data_bytes(&self) -> Vec<u8> {
+        let len = (self.dlc as usize).min(CAN_FD_MAX_LEN);
         self.data
+            .iter()
+            .flat_map(|word| word.to_le_bytes())
+            .take(len)
+            .collect()
     }
     #[allow(dead_code)]
     pub fn get_data_8u8(&self) -> [u8; 8] {
-        unsafe { std::mem::transmute::<u64, [u8; 8]>(self.data) }
+        unsafe { std::mem::transmute::<u64, [u8; 8]>(self.data[0]) }
     }
 }
 
@@ -0,0 +1,380 @@
+//! Conversion between [TNetworkFrame]s and the text log formats of the standard CAN tooling.
+//!
+//! - `candump -l` (Linux can-utils) : `(1436509052.249713) can0 123#DEADBEEF`,
+//!   CAN FD frames `(1436509052.249713) can0 123##1DEADBEEF` (flags nibble before the data)
+//! - Vector ASC : `   0.012345 1  123             Rx   d 4 DE AD BE EF`,
+//!   CAN FD frames `   0.012345 CANFD   1 Rx      123 1 0 4 4 DE AD BE EF ...`
+//!
+//! Frame timestamps are relative to the start of the log,
+//! `candump` requires absolute timestamps, which are computed from the start time of the log.
+//! When reading, timestamps are rebased such that the first frame of the log is at zero.
+
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+
+use crate::{CanFrame, CanFrameFdFlags, NetworkFrame, TNetworkFrame};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Candump,
+    Asc,
+}
+
+impl LogFormat {
+    /// `.log` and `.candump` files are candump logs, `.asc` files are Vector ASC logs.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "log" | "candump" => Some(LogFormat::Candump),
+            "asc" => Some(LogFormat::Asc),
+            _ => None,
+        }
+    }
+}
+
+fn invalid_line(line_number: usize, line: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("invalid log line {line_number} : {line:?}"),
+    )
+}
+
+/// Valid data lengths of CAN FD frames, indexed by their DLC.
+const CAN_FD_LENGTHS: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+fn can_fd_dlc(len: u8) -> Option<u8> {
+    CAN_FD_LENGTHS
+        .iter()
+        .position(|valid| *valid == len)
+        .map(|dlc| dlc as u8)
+}
+
+fn data_bytes(can_frame: &CanFrame) -> Vec<u8> {
+    if can_frame.get_fdf_flag() {
+        return can_frame.get_data_bytes();
+    }
+    let dlc = (can_frame.get_dlc() as usize).min(8);
+    can_frame.get_data_u64().to_le_bytes()[..dlc].to_vec()
+}
+
+fn data_from_bytes(bytes: &[u8]) -> u64 {
+    CanFrame::data_from_bytes(bytes)[0]
+}
+
+fn parse_hex_bytes(data: &str) -> Option<Vec<u8>> {
+    let pairs = data.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return None;
+    }
+    pairs
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+        .collect()
+}
+
+fn rebase(mut frames: Vec<TNetworkFrame>) -> Vec<TNetworkFrame> {
+    let Some(start) = frames.iter().map(|frame| frame.timestamp).min() else {
+        return frames;
+    };
+    for frame in &mut frames {
+        frame.timestamp -= start;
+    }
+    frames
+}
+
+/// Writes the frames in the `candump -l` format.
+/// `bus_name` maps a bus id to the interface name written to the log.
+pub fn write_candump<W: Write>(
+    writer: &mut W,
+    frames: &[TNetworkFrame],
+    start_time: DateTime<Local>,
+    bus_name: impl Fn(u32) -> String,
+) -> std::io::Result<()> {
+    let start_us = start_time.timestamp_micros().max(0) as u64;
+    for frame in frames {
+        let time_us = start_us + frame.timestamp.as_micros() as u64;
+        let can_frame = &frame.can_frame;
+        let id = if can_frame.get_ide_flag() {
+            format!("{:08X}", can_frame.get_id())
+        } else {
+            format!("{:03X}", can_frame.get_id())
+        };
+        let data = if can_frame.get_rtr_flag() {
+            "R".to_owned()
+        } else {
+            let bytes = data_bytes(can_frame)
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<String>();
+            if can_frame.get_fdf_flag() {
+                // only the BRS and ESI flags are logged.
+                format!("#{:X}{bytes}", can_frame.get_flags() & 0x3)
+            } else {
+                bytes
+            }
+        };
+        writeln!(
+            writer,
+            "({}.{:06}) {} {id}#{data}",
+            time_us / 1_000_000,
+            time_us % 1_000_000,
+            bus_name(frame.bus_id)
+        )?;
+    }
+    Ok(())
+}
+
+/// Reads a `candump -l` log.
+/// `bus_id` maps an interface name of the log to a bus id, frames of unknown interfaces are skipped.
+pub fn read_candump<R: BufRead>(
+    reader: R,
+    bus_id: impl Fn(&str) -> Option<u32>,
+) -> std::io::Result<Vec<TNetworkFrame>> {
+    let mut frames = vec![];
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let invalid = || invalid_line(line_number + 1, line);
+        let mut tokens = line.split_whitespace();
+        let time = tokens
+            .next()
+            .and_then(|time| time.strip_prefix('('))
+            .and_then(|time| time.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+        let (secs, micros) = time.split_once('.').ok_or_else(invalid)?;
+        let secs: u64 = secs.parse().map_err(|_| invalid())?;
+        let micros: u64 = format!("{micros:0<6}")[..6].parse().map_err(|_| invalid())?;
+        let ifname = tokens.next().ok_or_else(invalid)?;
+        let (id, data) = tokens
+            .next()
+            .and_then(|frame| frame.split_once('#'))
+            .ok_or_else(invalid)?;
+        let Some(bus_id) = bus_id(ifname) else {
+            continue;
+        };
+        let ide = id.len() > 3;
+        let id = u32::from_str_radix(id, 16).map_err(|_| invalid())?;
+        if let Some(fd_data) = data.strip_prefix('#') {
+            let flags = fd_data
+                .get(..1)
+                .and_then(|flags| u8::from_str_radix(flags, 16).ok())
+                .ok_or_else(invalid)?;
+            let bytes = parse_hex_bytes(&fd_data[1..]).ok_or_else(invalid)?;
+            can_fd_dlc(bytes.len() as u8).ok_or_else(invalid)?;
+            let brs = flags & CanFrameFdFlags::Brs as u8 != 0;
+            frames.push(TNetworkFrame::new(
+                Duration::from_secs(secs) + Duration::from_micros(micros),
+                NetworkFrame {
+                    bus_id,
+                    can_frame: CanFrame::new_fd(
+                        id,
+                        ide,
+                        brs,
+                        bytes.len() as u8,
+                        CanFrame::data_from_bytes(&bytes),
+                    ),
+                },
+            ));
+            continue;
+        }
+        let (rtr, dlc, data) = if let Some(rtr_len) = data.strip_prefix('R') {
+            let dlc = if rtr_len.is_empty() {
+                0
+            } else {
+                rtr_len.parse::<u8>().map_err(|_| invalid())?
+            };
+            (true, dlc, 0)
+        } else {
+            if data.len() > 16 {
+                return Err(invalid());
+            }
+            let bytes = parse_hex_bytes(data).ok_or_else(invalid)?;
+            (false, bytes.len() as u8, data_from_bytes(&bytes))
+        };
+        frames.push(TNetworkFrame::new(
+            Duration::from_secs(secs) + Duration::from_micros(micros),
+            NetworkFrame {
+                bus_id,
+                can_frame: CanFrame::new(id, ide, rtr, dlc, data),
+            },
+        ));
+    }
+    Ok(rebase(frames))
+}
+
+/// Writes the frames as a Vector ASC log.
+/// ASC channels are 1-based, bus `n` is written to channel `n + 1`.
+pub fn write_asc<W: Write>(
+    writer: &mut W,
+    frames: &[TNetworkFrame],
+    start_time: DateTime<Local>,
+) -> std::io::Result<()> {
+    let date = start_time.format("%a %b %d %I:%M:%S%.3f %P %Y");
+    writeln!(writer, "date {date}")?;
+    writeln!(writer, "base hex  timestamps absolute")?;
+    writeln!(writer, "internal events logged")?;
+    writeln!(writer, "Begin Triggerblock {date}")?;
+    writeln!(writer, "   0.000000 Start of measurement")?;
+    for frame in frames {
+        let can_frame = &frame.can_frame;
+        let id = if can_frame.get_ide_flag() {
+            format!("{:X}x", can_frame.get_id())
+        } else {
+            format!("{:X}", can_frame.get_id())
+        };
+        let time = frame.timestamp.as_secs_f64();
+        let channel = frame.bus_id + 1;
+        if can_frame.get_fdf_flag() {
+            let data = data_bytes(can_frame)
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<String>>()
+                .join(" ");
+            let len = can_frame.get_dlc();
+            let dlc = can_fd_dlc(len).unwrap_or(15);
+            let brs = can_frame.get_brs_flag() as u8;
+            // message duration, message length, flags (EDL and BRS), crc and bit timings.
+            let flags = 0x1000 | ((brs as u32) << 13);
+            writeln!(
+                writer,
+                "{time:>11.6} CANFD {channel:>3} Rx {id:>8} {brs} 0 {dlc:X} {len:>2} {data} 0 0 {flags:X} 0 0 0 0 0"
+            )?;
+        } else if can_frame.get_rtr_flag() {
+            writeln!(writer, "{time:>11.6} {channel:<2} {id:<15} Rx   r")?;
+        } else {
+            let data = data_bytes(can_frame)
+                .iter()
+                .map(|byte| format!("{byte:02X}"))
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(
+                writer,
+                "{time:>11.6} {channel:<2} {id:<15} Rx   d {} {data}",
+                can_frame.get_dlc()
+            )?;
+        }
+    }
+    writeln!(writer, "End TriggerBlock")?;
+    Ok(())
+}
+
+/// Reads the CAN FD frame of a `CANFD` line of an ASC log,
+/// `tokens` start after the `CANFD` keyword.
+fn read_asc_fd_frame(tokens: &[&str], radix: u32) -> Option<(u32, CanFrame)> {
+    let channel: u32 = tokens.first()?.parse().ok()?;
+    let (id, ide) = match tokens.get(2)?.strip_suffix('x') {
+        Some(id) => (id, true),
+        None => (*tokens.get(2)?, false),
+    };
+    let id = u32::from_str_radix(id, radix).ok()?;
+    // the symbolic name of the message is optional.
+    let fields = match tokens.get(3)? {
+        &"0" | &"1" => &tokens[3..],
+        _ => tokens.get(4..)?,
+    };
+    let brs = *fields.first()? == "1";
+    let len: u8 = fields.get(3)?.parse().ok()?;
+    can_fd_dlc(len)?;
+    let bytes = fields
+        .get(4..4 + len as usize)?
+        .iter()
+        .map(|byte| u8::from_str_radix(byte, radix).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    let data = CanFrame::data_from_bytes(&bytes);
+    Some((channel, CanFrame::new_fd(id, ide, brs, len, data)))
+}
+
+/// Reads a Vector ASC log. Lines that do not describe a CAN or CAN FD frame
+/// (header, error frames, statistics, ...) are skipped.
+pub fn read_asc<R: BufRead>(reader: R) -> std::io::Result<Vec<TNetworkFrame>> {
+    let mut frames = vec![];
+    let mut radix = 16;
+    for (line_number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.first() == Some(&"base") {
+            radix = if tokens.get(1) == Some(&"dec") { 10 } else { 16 };
+            continue;
+        }
+        let Some(Ok(time)) = tokens.first().map(|time| time.parse::<f64>()) else {
+            continue;
+        };
+        if tokens.get(1) == Some(&"CANFD") {
+            if !matches!(tokens.get(3), Some(&"Rx") | Some(&"Tx")) {
+                continue;
+            }
+            let (channel, can_frame) = read_asc_fd_frame(&tokens[2..], radix)
+                .ok_or_else(|| invalid_line(line_number + 1, &line))?;
+            if channel == 0 || time < 0.0 {
+                return Err(invalid_line(line_number + 1, &line));
+            }
+            frames.push(TNetworkFrame::new(
+                Duration::from_secs_f64(time),
+                NetworkFrame {
+                    bus_id: channel - 1,
+                    can_frame,
+                },
+            ));
+            continue;
+        }
+        let Some(Ok(channel)) = tokens.get(1).map(|channel| channel.parse::<u32>()) else {
+            continue;
+        };
+        let (Some(id), Some(direction), Some(ty)) = (tokens.get(2), tokens.get(3), tokens.get(4))
+        else {
+            continue;
+        };
+        if *direction != "Rx" && *direction != "Tx" {
+            continue;
+        }
+        let invalid = || invalid_line(line_number + 1, &line);
+        let (id, ide) = match id.strip_suffix('x') {
+            Some(id) => (id, true),
+            None => (*id, false),
+        };
+        let id = u32::from_str_radix(id, radix).map_err(|_| invalid())?;
+        let (rtr, dlc, data) = match *ty {
+            "r" => (true, 0, 0),
+            "d" => {
+                let dlc: u8 = tokens
+                    .get(5)
+                    .ok_or_else(invalid)?
+                    .parse()
+                    .map_err(|_| invalid())?;
+                if dlc > 8 {
+                    return Err(invalid());
+                }
+                let bytes = tokens
+                    .iter()
+                    .skip(6)
+                    .take(dlc as usize)
+                    .map(|byte| u8::from_str_radix(byte, radix))
+                    .collect::<Result<Vec<u8>, _>>()
+                    .map_err(|_| invalid())?;
+                if bytes.len() != dlc as usize {
+                    return Err(invalid());
+                }
+                (false, dlc, data_from_bytes(&bytes))
+            }
+            _ => continue,
+        };
+        if channel == 0 || time < 0.0 {
+            return Err(invalid());
+        }
+        frames.push(TNetworkFrame::new(
+            Duration::from_secs_f64(time),
+            NetworkFrame {
+                bus_id: channel - 1,
+                can_frame: CanFrame::new(id, ide, rtr, dlc, data),
+            },
+        ));
+    }
+    Ok(rebase(frames))
+}
@@ -0,0 +1,283 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+    sync::mpsc,
+    thread::JoinHandle,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{CanFrame, CanFrameFdFlags, NetworkFrame, TNetworkFrame, CAN_FD_MAX_LEN};
+
+/// Magic bytes at the start of every session log.
+const SESSION_LOG_MAGIC: [u8; 8] = *b"CZSESLOG";
+/// Version 2 added CAN FD records, version 1 logs are still readable.
+const SESSION_LOG_VERSION: u32 = 2;
+
+const HEADER_SIZE: usize = 32;
+const RECORD_SIZE: usize = 24;
+/// CAN FD records are followed by the data bytes 8..64.
+const FD_EXTENSION_SIZE: usize = CAN_FD_MAX_LEN - 8;
+
+/// Header of a session log.
+///
+/// Layout (all fields little endian):
+/// ```text
+/// | magic [u8;8] | version u32 | reserved u32 | config_hash u64 | start_time_us u64 |
+/// ```
+/// followed by a sequence of 24 byte records:
+/// ```text
+/// | timestamp_us u64 | can_id u32 | bus_id u16 | dlc u8 | flags u8 | data u64 |
+/// ```
+/// `can_id` is the raw frame key including the IDE and RTR flags,
+/// `flags` are the CAN FD flags of the frame (see [CanFrameFdFlags]).
+/// Records of CAN FD frames are followed by the remaining 56 data bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionLogHeader {
+    /// portable hash of the network configuration that was active while recording.
+    pub config_hash: u64,
+    /// wall clock time (µs since the unix epoch) at which the recording was started.
+    pub start_time_us: u64,
+}
+
+impl SessionLogHeader {
+    pub fn new(config_hash: u64) -> Self {
+        let start_time_us = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        Self {
+            config_hash,
+            start_time_us,
+        }
+    }
+
+    fn to_bin(&self, buf: &mut [u8; HEADER_SIZE]) {
+        buf[0..8].copy_from_slice(&SESSION_LOG_MAGIC);
+        buf[8..12].copy_from_slice(&SESSION_LOG_VERSION.to_le_bytes());
+        buf[12..16].copy_from_slice(&0u32.to_le_bytes());
+        buf[16..24].copy_from_slice(&self.config_hash.to_le_bytes());
+        buf[24..32].copy_from_slice(&self.start_time_us.to_le_bytes());
+    }
+
+    fn from_bin(buf: &[u8; HEADER_SIZE]) -> std::io::Result<Self> {
+        if buf[0..8] != SESSION_LOG_MAGIC {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "not a canzero session log",
+            ));
+        }
+        let version = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+        if !(1..=SESSION_LOG_VERSION).contains(&version) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unsupported session log version {version}"),
+            ));
+        }
+        Ok(Self {
+            config_hash: u64::from_le_bytes(buf[16..24].try_into().unwrap()),
+            start_time_us: u64::from_le_bytes(buf[24..32].try_into().unwrap()),
+        })
+    }
+}
+
+/// Returns the number of bytes written to `buf`.
+fn frame_into_bin(frame: &TNetworkFrame, buf: &mut [u8; RECORD_SIZE + FD_EXTENSION_SIZE]) -> usize {
+    let timestamp = frame.timestamp.as_micros() as u64;
+    let can_frame = &frame.can_frame;
+    buf[0..8].copy_from_slice(&timestamp.to_le_bytes());
+    buf[8..12].copy_from_slice(&can_frame.key().to_le_bytes());
+    buf[12..14].copy_from_slice(&(frame.bus_id as u16).to_le_bytes());
+    buf[14] = can_frame.get_dlc();
+    buf[15] = can_frame.get_flags();
+    buf[16..24].copy_from_slice(&can_frame.get_data_u64().to_le_bytes());
+    if !can_frame.get_fdf_flag() {
+        return RECORD_SIZE;
+    }
+    let mut data = can_frame.get_data_bytes();
+    data.resize(CAN_FD_MAX_LEN, 0);
+    buf[RECORD_SIZE..].copy_from_slice(&data[8..]);
+    RECORD_SIZE + FD_EXTENSION_SIZE
+}
+
+fn frame_from_bin(buf: &[u8; RECORD_SIZE], extension: &[u8]) -> TNetworkFrame {
+    let timestamp = u64::from_le_bytes(buf[0..8].try_into().unwrap());
+    let key = u32::from_le_bytes(buf[8..12].try_into().unwrap());
+    let bus_id = u16::from_le_bytes(buf[12..14].try_into().unwrap()) as u32;
+    let dlc = buf[14];
+    let flags = buf[15];
+    let can_frame = if flags & CanFrameFdFlags::Fdf as u8 != 0 {
+        let data = CanFrame::data_from_bytes(&[&buf[16..24], extension].concat());
+        CanFrame::new_raw_fd(key, flags, dlc, data)
+    } else {
+        CanFrame::new_raw(key, dlc, u64::from_le_bytes(buf[16..24].try_into().unwrap()))
+    };
+    TNetworkFrame::new(
+        Duration::from_micros(timestamp),
+        NetworkFrame { bus_id, can_frame },
+    )
+}
+
+/// Reads exactly `buf.len()` bytes, returns false at the end of the log.
+fn read_record<R: Read>(reader: &mut R, buf: &mut [u8]) -> std::io::Result<bool> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => return Ok(false),
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(true)
+}
+
+pub struct SessionLogWriter<W: Write> {
+    writer: W,
+    size: u64,
+}
+
+impl<W: Write> SessionLogWriter<W> {
+    pub fn new(mut writer: W, header: &SessionLogHeader) -> std::io::Result<Self> {
+        let mut buf = [0u8; HEADER_SIZE];
+        header.to_bin(&mut buf);
+        writer.write_all(&buf)?;
+        Ok(Self {
+            writer,
+            size: HEADER_SIZE as u64,
+        })
+    }
+
+    pub fn write_frame(&mut self, frame: &TNetworkFrame) -> std::io::Result<()> {
+        let mut buf = [0u8; RECORD_SIZE + FD_EXTENSION_SIZE];
+        let size = frame_into_bin(frame, &mut buf);
+        self.writer.write_all(&buf[..size])?;
+        self.size += size as u64;
+        Ok(())
+    }
+
+    /// Number of bytes written so far, including the header.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+pub struct SessionLogReader<R: Read> {
+    reader: R,
+    header: SessionLogHeader,
+}
+
+impl SessionLogReader<BufReader<File>> {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> SessionLogReader<R> {
+    pub fn new(mut reader: R) -> std::io::Result<Self> {
+        let mut buf = [0u8; HEADER_SIZE];
+        reader.read_exact(&mut buf)?;
+        let header = SessionLogHeader::from_bin(&buf)?;
+        Ok(Self { reader, header })
+    }
+
+    pub fn header(&self) -> &SessionLogHeader {
+        &self.header
+    }
+
+    /// Returns Ok(None) at the end of the log.
+    /// A truncated last record (e.g. after a crash while recording) is treated as the end of the log.
+    pub fn read_frame(&mut self) -> std::io::Result<Option<TNetworkFrame>> {
+        let mut buf = [0u8; RECORD_SIZE];
+        if !read_record(&mut self.reader, &mut buf)? {
+            return Ok(None);
+        }
+        let mut extension = [0u8; FD_EXTENSION_SIZE];
+        let extension: &[u8] = if buf[15] & CanFrameFdFlags::Fdf as u8 != 0 {
+            if !read_record(&mut self.reader, &mut extension)? {
+                return Ok(None);
+            }
+            &extension
+        } else {
+            &[]
+        };
+        Ok(Some(frame_from_bin(&buf, extension)))
+    }
+}
+
+impl<R: Read> Iterator for SessionLogReader<R> {
+    type Item = std::io::Result<TNetworkFrame>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_frame().transpose()
+    }
+}
+
+/// Records frames to a session log file on a background thread,
+/// such that recording never blocks the async runtime.
+/// The log is flushed whenever no frame was recorded for a second and when the logger is dropped.
+/// Recording stops once the log reaches `max_size` bytes.
+pub struct SessionLogger {
+    tx: Option<mpsc::Sender<TNetworkFrame>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SessionLogger {
+    pub fn create(path: &Path, config_hash: u64, max_size: Option<u64>) -> std::io::Result<Self> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut writer = SessionLogWriter::new(file, &SessionLogHeader::new(config_hash))?;
+        let (tx, rx) = mpsc::channel::<TNetworkFrame>();
+        let path = path.to_owned();
+        let thread = std::thread::spawn(move || {
+            let result = (|| -> std::io::Result<()> {
+                loop {
+                    match rx.recv_timeout(Duration::from_secs(1)) {
+                        Ok(frame) => {
+                            writer.write_frame(&frame)?;
+                            if max_size.is_some_and(|max_size| writer.size() >= max_size) {
+                                eprintln!(
+                                    "Session log {path:?} reached its size limit, recording stopped"
+                                );
+                                return writer.flush();
+                            }
+                        }
+                        Err(mpsc::RecvTimeoutError::Timeout) => writer.flush()?,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return writer.flush(),
+                    }
+                }
+            })();
+            if let Err(err) = result {
+                eprintln!("Failed to write session log {path:?} : {err:?}");
+            }
+        });
+        Ok(Self {
+            tx: Some(tx),
+            thread: Some(thread),
+        })
+    }
+
+    pub fn record(&self, frame: &TNetworkFrame) {
+        if let Some(tx) = &self.tx {
+            // fails only if the writer thread terminated (already reported).
+            let _ = tx.send(frame.clone());
+        }
+    }
+}
+
+impl Drop for SessionLogger {
+    fn drop(&mut self) {
+        // closing the channel terminates the writer thread.
+        self.tx.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::Duration;
+
+use canzero_common::{
+    log_format::{read_asc, read_candump, write_asc, write_candump},
+    CanFrame, NetworkFrame, TNetworkFrame,
+};
+
+fn frames() -> Vec<TNetworkFrame> {
+    vec![
+        TNetworkFrame::new(
+            Duration::from_micros(0),
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new(0x1BD, false, false, 8, 0x67452301_EFBEADDE),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_micros(1_250_017),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new(0x1ABCDEF, true, false, 3, 0x030201),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_micros(2_000_000),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new(0x7FF, false, false, 0, 0),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_micros(2_500_000),
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new_fd(
+                    0x123,
+                    false,
+                    true,
+                    12,
+                    [0x0706050403020100, 0x0B0A0908, 0, 0, 0, 0, 0, 0],
+                ),
+            },
+        ),
+    ]
+}
+
+fn assert_frames_eq(a: &[TNetworkFrame], b: &[TNetworkFrame]) {
+    assert_eq!(a.len(), b.len());
+    for (a, b) in a.iter().zip(b.iter()) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.bus_id, b.bus_id);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.get_dlc(), b.can_frame.get_dlc());
+        assert_eq!(a.can_frame.get_flags(), b.can_frame.get_flags());
+        assert_eq!(a.can_frame.get_data_bytes(), b.can_frame.get_data_bytes());
+    }
+}
+
+#[test]
+fn candump_roundtrip() {
+    let mut buf = vec![];
+    write_candump(&mut buf, &frames(), chrono::Local::now(), |bus_id| {
+        format!("can{bus_id}")
+    })
+    .unwrap();
+    let log = String::from_utf8(buf).unwrap();
+    assert!(log.lines().next().unwrap().ends_with(" can0 1BD#DEADBEEF01234567"));
+    assert!(log.lines().nth(1).unwrap().ends_with(" can1 01ABCDEF#010203"));
+    assert!(log.lines().nth(3).unwrap().ends_with(" can0 123##1000102030405060708090A0B"));
+
+    let read = read_candump(log.as_bytes(), |ifname| {
+        ifname.strip_prefix("can").and_then(|id| id.parse().ok())
+    })
+    .unwrap();
+    assert_frames_eq(&frames(), &read);
+}
+
+#[test]
+fn asc_roundtrip() {
+    let mut buf = vec![];
+    write_asc(&mut buf, &frames(), chrono::Local::now()).unwrap();
+    let log = String::from_utf8(buf).unwrap();
+    assert!(log.contains("   1.250017 2  1ABCDEFx        Rx   d 3 01 02 03"));
+    assert!(log.contains("   2.500000 CANFD   1 Rx      123 1 0 9 12 00 01 02"));
+
+    let read = read_asc(log.as_bytes()).unwrap();
+    assert_frames_eq(&frames(), &read);
+}
+
+#[test]
+fn candump_rejects_garbage() {
+    assert!(read_candump("can0 123#00".as_bytes(), |_| Some(0)).is_err());
+    // 9 bytes is not a valid CAN FD length.
+    assert!(read_candump("(0.0) can0 123##1000102030405060708".as_bytes(), |_| Some(0)).is_err());
+}
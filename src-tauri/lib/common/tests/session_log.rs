@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use canzero_common::{
+    session_log::{SessionLogHeader, SessionLogReader, SessionLogWriter, SessionLogger},
+    CanFrame, NetworkFrame, TNetworkFrame,
+};
+
+#[test]
+fn session_log_roundtrip() {
+    let frames = vec![
+        TNetworkFrame::new(
+            Duration::from_micros(10),
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new(0x1BD, false, false, 8, 0xDEADBEEF_01234567),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_millis(1500),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new(0x1ABCDEF, true, false, 3, 0xABCDEF),
+            },
+        ),
+        TNetworkFrame::new(
+            Duration::from_millis(1600),
+            NetworkFrame {
+                bus_id: 1,
+                can_frame: CanFrame::new_fd(0x42, false, true, 64, [u64::MAX - 1; 8]),
+            },
+        ),
+    ];
+    let header = SessionLogHeader {
+        config_hash: 0x1234_5678_9ABC_DEF0,
+        start_time_us: 42,
+    };
+
+    let mut writer = SessionLogWriter::new(vec![], &header).unwrap();
+    for frame in &frames {
+        writer.write_frame(frame).unwrap();
+    }
+    let mut bin = writer.into_inner();
+    // a truncated record at the end of the log is ignored.
+    bin.extend_from_slice(&[0xFF; 5]);
+
+    let reader = SessionLogReader::new(bin.as_slice()).unwrap();
+    assert_eq!(reader.header(), &header);
+    let read: Vec<TNetworkFrame> = reader.map(Result::unwrap).collect();
+    assert_eq!(read.len(), frames.len());
+    for (a, b) in frames.iter().zip(read.iter()) {
+        assert_eq!(a.timestamp, b.timestamp);
+        assert_eq!(a.bus_id, b.bus_id);
+        assert_eq!(a.can_frame.key(), b.can_frame.key());
+        assert_eq!(a.can_frame.get_dlc(), b.can_frame.get_dlc());
+        assert_eq!(a.can_frame.get_flags(), b.can_frame.get_flags());
+        assert_eq!(a.can_frame.get_data_bytes(), b.can_frame.get_data_bytes());
+    }
+}
+
+#[test]
+fn session_log_rejects_foreign_files() {
+    let bin = [0u8; 64];
+    assert!(SessionLogReader::new(bin.as_slice()).is_err());
+}
+
+#[test]
+fn session_logger_stops_at_its_size_limit() {
+    let path = std::env::temp_dir().join(format!("canzero-session-{}.czlog", std::process::id()));
+    let logger = SessionLogger::create(&path, 0, Some(1024)).unwrap();
+    for i in 0..1000 {
+        logger.record(&TNetworkFrame::new(
+            Duration::from_millis(i),
+            NetworkFrame {
+                bus_id: 0,
+                can_frame: CanFrame::new(0x42, false, false, 8, i),
+            },
+        ));
+    }
+    drop(logger);
+    let size = std::fs::metadata(&path).unwrap().len();
+    let frames = SessionLogReader::open(&path).unwrap().count();
+    std::fs::remove_file(&path).unwrap();
+    // 32 byte header and 24 byte records, the record that reaches the limit is kept.
+    assert_eq!(size, 32 + 42 * 24);
+    assert_eq!(frames, 42);
+}
@@ -10,6 +10,8 @@ pub struct BusData {
     pub id : u32,
     pub baudrate : u32,
     pub expected_utilization : u32,
+    pub data_baudrate : Option<u32>,
+    pub brs : bool,
 }
 
 impl BusBuilder {
@@ -21,11 +23,22 @@ impl BusBuilder {
             id,
             baudrate : baudrate.unwrap_or(1000000),
             expected_utilization : 0,
+            data_baudrate : None,
+            brs : false,
         }))
     }
 
     pub fn baudrate(&self, baudrate : u32) {
         self.0.borrow_mut().baudrate = baudrate;
     }
+
+    /// Makes the bus a CAN FD bus with the given bitrate of the data phase.
+    pub fn data_baudrate(&self, data_baudrate : u32) {
+        self.0.borrow_mut().data_baudrate = Some(data_baudrate);
+    }
+
+    pub fn brs(&self, brs : bool) {
+        self.0.borrow_mut().brs = brs;
+    }
 }
 
@@ -0,0 +1,37 @@
+//! Ids assigned by a previous build, which later builds keep as long as they are still valid
+//! (see [`super::NetworkBuilder::build_locked`]).
+
+/// Message with an automatically assigned id.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedMessage {
+    pub name: String,
+    pub id: u32,
+    pub ide: bool,
+    pub bus: String,
+    /// The id is only kept while the message has the same priority.
+    pub priority: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedObjectEntry {
+    pub node: String,
+    pub name: String,
+    pub id: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IdLock {
+    pub messages: Vec<LockedMessage>,
+    pub object_entries: Vec<LockedObjectEntry>,
+}
+
+impl IdLock {
+    pub fn message(&self, name: &str) -> Option<&LockedMessage> {
+        self.messages.iter().find(|message| message.name == name)
+    }
+    pub fn object_entry(&self, node: &str, name: &str) -> Option<&LockedObjectEntry> {
+        self.object_entries
+            .iter()
+            .find(|oe| oe.node == node && oe.name == name)
+    }
+}
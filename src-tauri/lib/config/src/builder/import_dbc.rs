@@ -2,24 +2,98 @@ use std::fs::File;
 use std::io::prelude::*;
 
 use super::NetworkBuilder;
-use crate::config::signal::Signal;
+use crate::config::make_config_ref;
+use crate::config::signal::{ByteOrder, Signal, SignalMultiplexing, ValueTable};
 use crate::config::SignalType;
-use crate::errors::Result;
+use crate::errors::{ConfigError, Result};
+
+const NO_NODE: &str = "Vector__XXX";
+
+fn signal_type(
+    dbc_signal: &can_dbc::Signal,
+    value_type: Option<&can_dbc::SignalExtendedValueType>,
+) -> Result<SignalType> {
+    let size = *dbc_signal.signal_size();
+    if size == 0 || size > 64 {
+        return Err(ConfigError::CanDbc(format!(
+            "signal {} has an unsupported size of {size} bits",
+            dbc_signal.name()
+        )));
+    }
+    let size = size as u8;
+    match value_type {
+        Some(can_dbc::SignalExtendedValueType::IEEEfloat32Bit) if size == 32 => {
+            return Ok(SignalType::Float { size });
+        }
+        Some(can_dbc::SignalExtendedValueType::IEEEdouble64bit) if size == 64 => {
+            return Ok(SignalType::Float { size });
+        }
+        Some(can_dbc::SignalExtendedValueType::SignedOrUnsignedInteger) | None => (),
+        Some(_) => {
+            return Err(ConfigError::CanDbc(format!(
+                "float signal {} has an invalid size of {size} bits",
+                dbc_signal.name()
+            )));
+        }
+    }
+    let signed = *dbc_signal.value_type() == can_dbc::ValueType::Signed;
+    if *dbc_signal.offset() == 0.0 && *dbc_signal.factor() == 1.0 {
+        if signed {
+            Ok(SignalType::SignedInt { size })
+        } else {
+            Ok(SignalType::UnsignedInt { size })
+        }
+    } else {
+        Ok(SignalType::Decimal {
+            size,
+            offset: *dbc_signal.offset(),
+            scale: *dbc_signal.factor(),
+            signed,
+        })
+    }
+}
+
+fn multiplexing(dbc_signal: &can_dbc::Signal) -> Result<Option<SignalMultiplexing>> {
+    match dbc_signal.multiplexer_indicator() {
+        can_dbc::MultiplexIndicator::Plain => Ok(None),
+        can_dbc::MultiplexIndicator::Multiplexor => Ok(Some(SignalMultiplexing::Multiplexor)),
+        can_dbc::MultiplexIndicator::MultiplexedSignal(value) => {
+            Ok(Some(SignalMultiplexing::Multiplexed(*value)))
+        }
+        can_dbc::MultiplexIndicator::MultiplexorAndMultiplexedSignal(_) => {
+            Err(ConfigError::CanDbc(format!(
+                "signal {} uses extended multiplexing, which is not supported",
+                dbc_signal.name()
+            )))
+        }
+    }
+}
 
 pub fn import_dbc(network_builder: &NetworkBuilder, bus: &str, dbc_path: &str) -> Result<()> {
     let mut dbc_file = File::open(dbc_path)?;
     let mut buffer = Vec::new();
-    dbc_file.read_to_end(&mut buffer).unwrap();
+    dbc_file.read_to_end(&mut buffer)?;
 
     let dbc = can_dbc::DBC::from_slice(&buffer)?;
 
     for message in dbc.messages() {
         let name = message.message_name();
+        let defined = network_builder
+            .0
+            .borrow()
+            .messages
+            .borrow()
+            .iter()
+            .any(|message_builder| &message_builder.0.borrow().name == name);
+        if defined {
+            return Err(ConfigError::CanDbc(format!(
+                "message {name} is already defined"
+            )));
+        }
         let message_builder = network_builder.create_message(name, None);
         message_builder.assign_bus(bus);
-        match dbc.message_comment(message.message_id().clone()){
-            Some(desc) => message_builder.add_description(desc),
-            None => (),
+        if let Some(desc) = dbc.message_comment(*message.message_id()) {
+            message_builder.add_description(desc);
         }
         if message.message_id().0 & 0x80000000 != 0 {
             // ext frame
@@ -29,40 +103,67 @@ pub fn import_dbc(network_builder: &NetworkBuilder, bus: &str, dbc_path: &str) -
             message_builder.set_std_id(message.message_id().0);
         }
         let signal_format = message_builder.make_signal_format();
-        
-        let mut receives = vec![];
+
+        let mut receivers: Vec<&str> = vec![];
         for dbc_signal in message.signals() {
-            let start_bit = *dbc_signal.start_bit() as usize;
-            let size = *dbc_signal.signal_size() as u8;
             let name = dbc_signal.name();
-            let ty = if *dbc_signal.offset() == 0.0 && dbc_signal.factor == 1.0 {
-                match dbc_signal.value_type() {
-                    can_dbc::ValueType::Signed => SignalType::UnsignedInt { size },
-                    can_dbc::ValueType::Unsigned => SignalType::SignedInt { size },
-                }
-            }else {
-                SignalType::Decimal { size, offset: *dbc_signal.offset(), scale: *dbc_signal.factor() }
+            let description = dbc.signal_comment(*message.message_id(), name);
+            let mut signal = Signal::new(
+                name,
+                description,
+                signal_type(
+                    dbc_signal,
+                    dbc.extended_value_type_for_signal(*message.message_id(), name),
+                )?,
+                *dbc_signal.start_bit() as usize,
+            );
+            signal.byte_order = match dbc_signal.byte_order() {
+                can_dbc::ByteOrder::LittleEndian => ByteOrder::LittleEndian,
+                can_dbc::ByteOrder::BigEndian => ByteOrder::BigEndian,
             };
-
-            signal_format.add_signal(Signal::new(&name, None, ty, start_bit))?;
+            signal.multiplexing = multiplexing(dbc_signal)?;
+            if let Some(value_descriptions) =
+                dbc.value_descriptions_for_signal(*message.message_id(), name)
+            {
+                signal.value_table = Some(make_config_ref(ValueTable(
+                    value_descriptions
+                        .iter()
+                        // negative raw values are stored in two's complement.
+                        .map(|desc| (desc.b().clone(), *desc.a() as i64 as u64))
+                        .collect(),
+                )));
+            }
+            signal_format.add_signal(signal)?;
 
             for rx in dbc_signal.receivers() {
-                receives.push(rx.clone());
+                if rx != NO_NODE && !receivers.contains(&rx.as_str()) {
+                    receivers.push(rx);
+                }
             }
         }
-        for rx in receives {
-            message_builder.add_receiver(&rx);
+        for rx in receivers {
+            message_builder.add_receiver(rx);
         }
 
-        for msg_transmitter in dbc.message_transmitters().iter().filter(|transmitter| transmitter.message_id() == message.message_id()) {
-            let transmitters = msg_transmitter.transmitter();
-            for tx in transmitters {
-                match tx {
-                    can_dbc::Transmitter::NodeName(node_name) => message_builder.add_receiver(node_name),
-                    can_dbc::Transmitter::VectorXXX => (),
+        let mut transmitters: Vec<&str> = vec![];
+        let additional_transmitters = dbc
+            .message_transmitters()
+            .iter()
+            .filter(|transmitter| transmitter.message_id() == message.message_id())
+            .flat_map(|transmitter| transmitter.transmitter().iter());
+        for tx in std::iter::once(message.transmitter()).chain(additional_transmitters) {
+            match tx {
+                can_dbc::Transmitter::NodeName(node_name) => {
+                    if !transmitters.contains(&node_name.as_str()) {
+                        transmitters.push(node_name);
+                    }
                 }
+                can_dbc::Transmitter::VectorXXX => (),
             }
         }
+        for tx in transmitters {
+            message_builder.add_transmitter(tx);
+        }
     }
 
     Ok(())
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use crate::{
-    config::{signal::Signal, Visibility},
+    config::{signal::Signal, ByteOrder, Visibility},
     errors,
 };
 
@@ -93,7 +93,7 @@ pub struct MessageSignalFormatData(pub Vec<Signal>);
 #[derive(Clone, Debug)]
 pub struct MessageTypeFormatBuilder(pub BuilderRef<MessageTypeFormatData>);
 #[derive(Debug)]
-pub struct MessageTypeFormatData(pub Vec<(String, String)>);
+pub struct MessageTypeFormatData(pub Vec<(String, String, ByteOrder)>);
 
 impl MessagePriority {
     pub fn min_id(&self) -> u32 {
@@ -276,9 +276,12 @@ impl MessageTypeFormatBuilder {
         MessageTypeFormatBuilder(make_builder_ref(MessageTypeFormatData(vec![])))
     }
     pub fn add_type(&self, type_name: &str, value_name: &str) {
+        self.add_type_with_byte_order(type_name, value_name, ByteOrder::LittleEndian);
+    }
+    pub fn add_type_with_byte_order(&self, type_name: &str, value_name: &str, byte_order: ByteOrder) {
         let mut builder_data = self.0.borrow_mut();
         builder_data
             .0
-            .push((type_name.to_owned(), value_name.to_owned()));
+            .push((type_name.to_owned(), value_name.to_owned(), byte_order));
     }
 }
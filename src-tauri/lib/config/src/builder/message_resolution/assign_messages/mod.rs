@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use crate::builder::{MessageBuilder, MessagePriority, NodeBuilder};
 
 use super::{
@@ -73,8 +75,10 @@ pub fn assign_messages_ids(
     fixed_messages: &Vec<FixedMessage>,
     minimized_network: MinimizedNetwork,
     nodes: &Vec<NodeBuilder>,
+    locked_ids: &HashMap<String, u32>,
 ) -> Vec<NodeFilterInfo> {
     let setcode_len = (minimized_network.sets().len() as f64).log2().ceil() as u32;
+    let setcode_mask = 0xFFFFFFFFu32.checked_shr(32 - setcode_len).unwrap_or(0);
     let mut setcode_allocator = SetCodeAllocator::new(setcode_len);
     let mut fixed_messages = fixed_messages.clone();
 
@@ -108,7 +112,7 @@ pub fn assign_messages_ids(
                 break;
             }
             let id = fixed_message.id();
-            let code = id & 0xFFFFFFFFu32.checked_shr(32 - setcode_len as u32).unwrap_or(0);
+            let code = id & 0xFFFFFFFFu32.checked_shr(32 - setcode_len).unwrap_or(0);
             let ok = match setcode {
                 Some(setcode) => setcode == code,
                 None => setcode_allocator.allocate_setcode(code),
@@ -143,6 +147,37 @@ pub fn assign_messages_ids(
         });
     }
 
+    // keep the setcode of locked messages, if it is still available.
+    for (assigned_set, set) in set_pair.iter_mut() {
+        if assigned_set.is_some() {
+            continue;
+        }
+        let mut setcodes: BTreeMap<u32, usize> = BTreeMap::new();
+        for id in (0..MessagePriority::count())
+            .flat_map(|prio| set.messages_with_priority(prio))
+            .filter_map(|msg| locked_ids.get(&msg.0.borrow().name))
+        {
+            *setcodes.entry(id & setcode_mask).or_default() += 1;
+        }
+        // the setcode shared by most locked messages of the set.
+        let setcode = setcodes
+            .into_iter()
+            .max_by_key(|(_, count)| *count)
+            .map(|(setcode, _)| setcode);
+        let Some(setcode) = setcode else {
+            continue;
+        };
+        if setcode_allocator.allocate_setcode(setcode) {
+            *assigned_set = Some(AssignedSet {
+                setcode,
+                fixed_ids: vec![],
+                fixed_messages: vec![],
+                ide: set.id().ide(),
+                receivers: set.id().receivers().clone(),
+            });
+        }
+    }
+
     // assign other ids.
     for (assigned_set, set) in set_pair.iter_mut() {
         let assigned_set = match assigned_set {
@@ -167,9 +202,38 @@ pub fn assign_messages_ids(
 
         let bucket_layout = minimized_network.bucket_layout();
 
+        // locked ids that still belong to this set are kept.
+        let mut locked_messages: Vec<String> = vec![];
+        for prio in 0..MessagePriority::count() {
+            for msg in set.messages_with_priority(prio) {
+                let name = msg.0.borrow().name.clone();
+                let Some(id) = locked_ids.get(&name).copied() else {
+                    continue;
+                };
+                if id & setcode_mask != setcode
+                    || (id >> setcode_len) as usize >= max_messages_per_set
+                    || reserved_ids.contains(&id)
+                {
+                    continue;
+                }
+                reserved_ids.push(id);
+                if assigned_set.ide {
+                    msg.set_ext_id(id);
+                } else {
+                    msg.set_std_id(id);
+                }
+                assigned_set.fixed_messages.push(msg.clone());
+                assigned_set.fixed_ids.push(id);
+                locked_messages.push(name);
+            }
+        }
+
         let mut bucket_offset = 0;
         for prio in 0..MessagePriority::count() {
             for msg in set.messages_with_priority(prio) {
+                if locked_messages.contains(&msg.0.borrow().name) {
+                    continue;
+                }
                 let prio_offset = (0..max_messages_per_set - bucket_offset).find(|prio_offset| {
                     let priority = (bucket_offset + *prio_offset) as u32;
                     let id = (priority << setcode_len) | setcode;
@@ -220,6 +284,24 @@ pub fn assign_messages_ids(
                         setcode_len,
                         ide: x.ide,
                     })
+                    // fixed messages, which didn't fit into a set, are filtered by their id.
+                    .chain(
+                        fixed_messages
+                            .iter()
+                            .filter(|fixed| {
+                                fixed
+                                    .message()
+                                    .0
+                                    .borrow()
+                                    .receivers
+                                    .iter()
+                                    .any(|n| n.0.borrow().name == node_name)
+                            })
+                            .map(|fixed| FilterInfo::Single {
+                                id: fixed.id(),
+                                ide: fixed.ide(),
+                            }),
+                    )
                     .collect(),
             }
         })
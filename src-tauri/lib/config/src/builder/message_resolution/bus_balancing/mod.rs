@@ -42,14 +42,16 @@ struct BusInfo {
     bus_id: u32,
     bus_name: String,
     load: f64,
+    fd: bool,
 }
 
 impl BusInfo {
-    pub fn new(bus_id: u32, bus_name: &str) -> Self {
+    pub fn new(bus_id: u32, bus_name: &str, fd: bool) -> Self {
         Self {
             bus_id,
             bus_name: bus_name.to_owned(),
             load: 0f64,
+            fd,
         }
     }
 }
@@ -96,7 +98,10 @@ impl Buses {
             buses: buses
                 .iter()
                 .enumerate()
-                .map(|(bus_id, builder)| BusInfo::new(bus_id as u32, &builder.0.borrow().name))
+                .map(|(bus_id, builder)| {
+                    let bus_data = builder.0.borrow();
+                    BusInfo::new(bus_id as u32, &bus_data.name, bus_data.data_baudrate.is_some())
+                })
                 .collect(),
         }
     }
@@ -106,7 +111,11 @@ impl Buses {
         bus.load += message.message.load;
     }
     pub fn add_message_to_min_load(&mut self, message : MessageWithLoad) {
-        let bus = self.buses.iter_mut().min().unwrap();
+        // messages with more than 8 bytes only fit on CAN FD buses.
+        let bus = match self.buses.iter_mut().filter(|b| b.fd || !message.fd_required).min() {
+            Some(bus) => bus,
+            None => self.buses.iter_mut().min().unwrap(),
+        };
         message.message.assign_bus(&bus.bus_name);
         bus.load += message.load;
     }
@@ -116,6 +125,7 @@ impl Buses {
 struct MessageWithLoad {
     message: MessageBuilder,
     load: f64,
+    fd_required: bool,
 }
 
 impl MessageWithLoad {
@@ -155,11 +165,11 @@ impl MessageWithLoad {
                             entries: _,
                             visibility: _,
                         } => *size as usize,
-                        crate::config::Type::Array { len: _, ty: _ } => todo!(),
+                        crate::config::Type::Array { len, ty } => *len * acc_dlc(ty),
                     }
                 }
                 let mut dlc = 0usize;
-                for (attr_ty, _) in &type_format.0.borrow().0 {
+                for (attr_ty, _, _) in &type_format.0.borrow().0 {
                     let ty = NetworkBuilder::resolve_type(types, attr_ty)
                         .expect("failed to resolve type");
                     dlc += acc_dlc(&ty);
@@ -197,6 +207,7 @@ impl MessageWithLoad {
         Self {
             message : msg.clone(),
             load,
+            fd_required: dlc > 64,
         }
     }
 }
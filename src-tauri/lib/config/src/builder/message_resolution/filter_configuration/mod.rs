@@ -1,4 +1,7 @@
-use crate::builder::NodeBuilder;
+use crate::{
+    builder::{message_builder::MessageIdTemplate, MessageBuilder, NodeBuilder},
+    errors::{self, ConfigError},
+};
 
 use super::assign_messages::NodeFilterInfo;
 
@@ -16,9 +19,11 @@ impl NodeFilterBank {
     }
 }
 
+#[derive(Clone)]
 pub struct Filter {
     mask: u32,
     id: u32,
+    ide: bool,
 }
 impl Filter {
     pub fn mask(&self) -> u32 {
@@ -27,27 +32,159 @@ impl Filter {
     pub fn id(&self) -> u32 {
         self.id
     }
+    pub fn ide(&self) -> bool {
+        self.ide
+    }
+    fn accepts(&self, id: u32, ide: bool) -> bool {
+        self.ide == ide && id & self.mask == self.id & self.mask
+    }
+    /// Whether every id accepted by `other` is accepted by this filter.
+    fn covers(&self, other: &Filter) -> bool {
+        self.ide == other.ide
+            && other.mask & self.mask == self.mask
+            && other.id & self.mask == self.id & self.mask
+    }
+    /// The most specific filter, which accepts all ids of both filters.
+    fn merge(&self, other: &Filter) -> Filter {
+        let mask = self.mask & other.mask & !(self.id ^ other.id);
+        Filter {
+            mask,
+            id: self.id & mask,
+            ide: self.ide,
+        }
+    }
+}
+
+/// A message on one of the buses of a node.
+struct Candidate {
+    id: u32,
+    ide: bool,
+    wanted: bool,
+}
+
+fn candidates(node: &NodeBuilder, messages: &[MessageBuilder]) -> Vec<Candidate> {
+    let node_data = node.0.borrow();
+    let bus_names: Vec<String> = node_data
+        .buses
+        .iter()
+        .map(|bus| bus.0.borrow().name.clone())
+        .collect();
+    messages
+        .iter()
+        .filter_map(|message| {
+            let message_data = message.0.borrow();
+            let on_bus = match &message_data.bus {
+                Some(bus) => bus_names.contains(&bus.0.borrow().name),
+                None => true,
+            };
+            let (id, ide) = match message_data.id {
+                MessageIdTemplate::StdId(id) => (id, false),
+                MessageIdTemplate::ExtId(id) => (id, true),
+                _ => return None,
+            };
+            on_bus.then(|| Candidate {
+                id,
+                ide,
+                wanted: message_data
+                    .receivers
+                    .iter()
+                    .any(|receiver| receiver.0.borrow().name == node_data.name),
+            })
+        })
+        .collect()
+}
+
+/// Merges filters until at most `max_filters` are left. Every step merges the pair of
+/// filters, which accepts the fewest additional unwanted messages, ties are broken by
+/// the number of bits, which become don't care.
+/// Returns `None` if the filters can't be merged any further (standard and extended filters
+/// are never merged).
+fn merge_filters(
+    mut filters: Vec<Filter>,
+    max_filters: usize,
+    candidates: &[Candidate],
+) -> Option<Vec<Filter>> {
+    while filters.len() > max_filters {
+        let accepted: Vec<bool> = candidates
+            .iter()
+            .map(|c| filters.iter().any(|f| f.accepts(c.id, c.ide)))
+            .collect();
+        let (_, _, i, j, merged) = (0..filters.len())
+            .flat_map(|i| (i + 1..filters.len()).map(move |j| (i, j)))
+            .filter(|(i, j)| filters[*i].ide == filters[*j].ide)
+            .map(|(i, j)| {
+                let merged = filters[i].merge(&filters[j]);
+                let cost = std::iter::zip(candidates, &accepted)
+                    .filter(|(c, accepted)| {
+                        !c.wanted && !**accepted && merged.accepts(c.id, c.ide)
+                    })
+                    .count();
+                (cost, merged.mask.count_zeros(), i, j, merged)
+            })
+            .min_by_key(|(cost, dont_care, i, j, _)| (*cost, *dont_care, *i, *j))?;
+        filters[i] = merged;
+        filters.remove(j);
+        // filters, which are covered by the merged filter, are no longer required.
+        let merged = filters[i].clone();
+        let mut index = 0;
+        filters.retain(|filter| {
+            let keep = index == i || !merged.covers(filter);
+            index += 1;
+            keep
+        });
+    }
+    Some(filters)
 }
 
-pub fn find_filter_configuration(filter_infos: Vec<NodeFilterInfo>) -> Vec<NodeFilterBank> {
+pub fn find_filter_configuration(
+    filter_infos: Vec<NodeFilterInfo>,
+    messages: &[MessageBuilder],
+) -> errors::Result<Vec<NodeFilterBank>> {
     filter_infos
         .iter()
-        .map(|node_filter_info| NodeFilterBank {
-            filters: node_filter_info.filter_infos().iter().map(|filter| -> Filter{
+        .map(|node_filter_info| {
+            let filters: Vec<Filter> = node_filter_info.filter_infos().iter().map(|filter| -> Filter{
                 match filter {
-                    super::assign_messages::FilterInfo::Setcode { setcode, setcode_len, ide : _ } => Filter {
-                        mask : u32::MAX >> (32 - *setcode_len),
-                        id : *setcode
+                    super::assign_messages::FilterInfo::Setcode { setcode, setcode_len, ide } => Filter {
+                        mask : u32::MAX.checked_shr(32 - *setcode_len).unwrap_or(0),
+                        id : *setcode,
+                        ide : *ide,
                     },
-                    super::assign_messages::FilterInfo::Single { id, ide : _ } => {
+                    super::assign_messages::FilterInfo::Single { id, ide } => {
                         Filter {
                             mask : 0xFFFFFFFFu32,
                             id : *id,
+                            ide : *ide,
                         }
                     }
                 }
-            }).collect(),
-            node: node_filter_info.node().clone(),
+            }).collect();
+            let node = node_filter_info.node();
+            let candidates = candidates(node, messages);
+            let node_data = node.0.borrow();
+            let max_filters = node_data.max_filters.or(node_data
+                .controller
+                .map(|controller| controller.filter_count()));
+            let filters = match max_filters {
+                Some(max_filters) => merge_filters(filters, max_filters, &candidates)
+                    .ok_or_else(|| {
+                        ConfigError::InsufficientFilters(format!(
+                            "the filters of node {} can't be merged into {max_filters} filters",
+                            node_data.name
+                        ))
+                    })?,
+                None => filters,
+            };
+            #[cfg(feature = "logging-info")]
+            println!(
+                "[CANZERO-CONFIG::build] node {} uses {} filters",
+                node_data.name,
+                filters.len(),
+            );
+            Ok(NodeFilterBank {
+                filters,
+                node: node.clone(),
+            })
         })
         .collect()
 }
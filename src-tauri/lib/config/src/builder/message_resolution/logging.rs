@@ -89,11 +89,11 @@ fn load_estimation(types: &Vec<TypeRef>, msg: &MessageBuilder) -> f64 {
                         entries: _,
                         visibility: _,
                     } => *size as usize,
-                    crate::config::Type::Array { len: _, ty: _ } => todo!(),
+                    crate::config::Type::Array { len, ty } => *len * acc_dlc(ty),
                 }
             }
             let mut dlc = 0usize;
-            for (attr_ty, _) in &type_format.0.borrow().0 {
+            for (attr_ty, _, _) in &type_format.0.borrow().0 {
                 let ty =
                     NetworkBuilder::resolve_type(types, attr_ty).expect("failed to resolve type");
                 dlc += acc_dlc(&ty);
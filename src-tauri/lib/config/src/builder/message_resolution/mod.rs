@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
 use crate::{config::TypeRef, errors};
 
 use self::{filter_configuration::NodeFilterBank, fixed_messages::MessageSplit};
 
-use super::{bus::BusBuilder, MessageBuilder, NodeBuilder};
+use super::{
+    bus::BusBuilder, message_builder::MessageIdTemplate, IdLock, MessageBuilder, NodeBuilder,
+};
 
 mod assign_messages;
 mod bus_balancing;
@@ -12,11 +16,52 @@ mod logging;
 mod receive_set;
 mod set_minimization;
 
+/// Ids of the lock that are kept, if they still fit into the set of the message.
+/// Ids of messages whose id type or priority changed are dropped.
+fn locked_message_ids(messages: &[MessageBuilder], lock: Option<&IdLock>) -> HashMap<String, u32> {
+    let Some(lock) = lock else {
+        return HashMap::new();
+    };
+    messages
+        .iter()
+        .filter_map(|message| {
+            let message_data = message.0.borrow();
+            let locked = lock.message(&message_data.name)?;
+            let (ide, priority) = match &message_data.id {
+                MessageIdTemplate::AnyStd(priority) => (false, priority),
+                MessageIdTemplate::AnyExt(priority) => (true, priority),
+                _ => return None,
+            };
+            (locked.ide == ide && locked.priority == priority.to_u32())
+                .then(|| (message_data.name.clone(), locked.id))
+        })
+        .collect()
+}
+
+/// Keeps the locked bus of messages that are not assigned to a bus explicitly.
+fn lock_buses(messages: &[MessageBuilder], buses: &[BusBuilder], lock: Option<&IdLock>) {
+    let Some(lock) = lock else {
+        return;
+    };
+    for message in messages {
+        if message.0.borrow().bus.is_some() {
+            continue;
+        }
+        let Some(locked) = lock.message(&message.0.borrow().name).cloned() else {
+            continue;
+        };
+        if buses.iter().any(|bus| bus.0.borrow().name == locked.bus) {
+            message.assign_bus(&locked.bus);
+        }
+    }
+}
+
 pub fn resolve_ids_filters_and_buses(
     buses: &Vec<BusBuilder>,
     messages: &Vec<MessageBuilder>,
     nodes: &Vec<NodeBuilder>,
     types: &Vec<TypeRef>,
+    lock: Option<&IdLock>,
 ) -> errors::Result<Vec<NodeFilterBank>> {
     let mut messages = messages.clone();
     let mut nodes = nodes.clone();
@@ -32,6 +77,7 @@ pub fn resolve_ids_filters_and_buses(
     #[cfg(feature = "logging_idrp")]
     let logging_info = logging::cache_logging_info(&types, &messages);
 
+    let locked_ids = locked_message_ids(&messages, lock);
     let message_split = MessageSplit::from(&messages);
     let network_info =
         receive_set::generate_receive_sets_from_messages(&nodes, message_split.prio_messages());
@@ -40,9 +86,11 @@ pub fn resolve_ids_filters_and_buses(
         message_split.fixed_messages(),
         minimized_network,
         &nodes,
+        &locked_ids,
     );
+    lock_buses(&messages, &buses, lock);
     bus_balancing::balance_buses(&messages, &types, &buses);
-    let filter_banks = filter_configuration::find_filter_configuration(filter_infos);
+    let filter_banks = filter_configuration::find_filter_configuration(filter_infos, &messages)?;
 
     #[cfg(feature = "logging_idrp")]
     logging::log_info(logging_info);
@@ -60,6 +60,9 @@ impl PartialEq for SetIdentifier {
         if other.ide != self.ide {
             return false;
         }
+        if other.receivers.len() != self.receivers.len() {
+            return false;
+        }
         for (a,b) in std::iter::zip(other.receivers.iter(), self.receivers.iter()) {
             let name_a = a.0.borrow().name.clone();
             if name_a != b.0.borrow().name {
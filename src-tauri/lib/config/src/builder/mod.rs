@@ -1,6 +1,7 @@
 use std::{rc::Rc, cell::RefCell};
 
 pub use self::command_builder::CommandBuilder;
+pub use self::id_lock::IdLock;
 pub use self::message_builder::MessageBuilder;
 pub use self::message_builder::MessageFormat;
 pub use self::message_builder::MessagePriority;
@@ -14,6 +15,7 @@ pub use self::type_builder::EnumBuilder;
 pub use self::type_builder::StructBuilder;
 
 pub mod command_builder;
+pub mod id_lock;
 pub mod message_builder;
 pub mod network_builder;
 pub mod node;
@@ -12,23 +12,34 @@ use crate::{
         self,
         bus::BusRef,
         encoding::{CompositeSignalEncoding, PrimitiveSignalEncoding},
+        filters::FilterReport,
         make_config_ref,
         message::MessageUsage,
-        signal::Signal,
+        signal::{Signal, MAX_STRING_LEN},
         stream::Stream,
-        Command, ConfigRef, Message, MessageEncoding, MessageId, Network, NetworkRef, Node,
+        ByteOrder, Command, ConfigRef, Message, MessageEncoding, MessageId, Network, NetworkRef, Node,
+        decode::DecodedValue,
         ObjectEntry, SignalRef, SignalType, Type, TypeRef, TypeSignalEncoding,
+        SAVE_PARAMETERS_COMMAND,
     },
     errors::Result,
     errors::{self},
 };
 
 use super::{
-    bus::BusBuilder, import_dbc::import_dbc, make_builder_ref, message_builder::MessageIdTemplate,
-    BuilderRef, EnumBuilder, MessageBuilder, MessageFormat, MessagePriority, NodeBuilder,
+    bus::BusBuilder,
+    id_lock::{LockedMessage, LockedObjectEntry},
+    import_dbc::import_dbc,
+    make_builder_ref,
+    message_builder::MessageIdTemplate,
+    object_entry_builder::ObjectEntryData,
+    BuilderRef, EnumBuilder, IdLock, MessageBuilder, MessageFormat, MessagePriority, NodeBuilder,
     StructBuilder, TypeBuilder,
 };
 
+/// Object entries are addressed by the 13 bit od_index of get and set requests.
+const MAX_OBJECT_ENTRY_COUNT: u32 = 1 << 13;
+
 #[derive(Debug, Clone)]
 pub struct NetworkBuilder(pub BuilderRef<NetworkData>);
 
@@ -73,6 +84,7 @@ impl NetworkBuilder {
         let set_resp_erno = network_builder.define_enum("set_resp_erno");
         set_resp_erno.add_entry("Success", Some(0)).unwrap();
         set_resp_erno.add_entry("Error", Some(1)).unwrap();
+        set_resp_erno.add_entry("OutOfRange", Some(2)).unwrap();
 
         let get_req_message = network_builder.create_message("get_req", None);
         get_req_message.set_any_std_id(MessagePriority::Low);
@@ -298,17 +310,67 @@ impl NetworkBuilder {
                         size,
                         offset,
                         scale,
+                        signed: false,
                     })));
                 }
             }
             None => (),
         }
-        let array_regex =
-                regex::Regex::new(r#"^(?<type>[a-zA-Z][a-zA-Z0-9]*(<[+-]?([0-9]*[.])?[0-9]+\.\.[+-]?([0-9]*[.])?[0-9]+>)?)\[(?<len>[0-9]+)\]$"#).unwrap();
+        // signed decimals encode zero exactly (offset = 0),
+        // the scale is chosen such that both bounds of the range are representable.
+        let signed_dec_regex = regex::Regex::new(r"^sd(?<size>[0-9]{1,2})<(?<min>[+-]?([0-9]*[.])?[0-9]+)\.\.(?<max>[+-]?([0-9]*[.])?[0-9]+)>$").unwrap();
+        if let Some(cap) = signed_dec_regex.captures(type_name) {
+            let size = &cap["size"];
+            let size = size.parse::<u8>().unwrap();
+            let min = &cap["min"];
+            let min = min.parse::<f64>().unwrap();
+            let max = &cap["max"];
+            let max = max.parse::<f64>().unwrap();
+            if min >= 0.0 || max <= 0.0 {
+                return Err(errors::ConfigError::InvalidRange(
+                    "invalid signed decimal range min has to be negative and max positive"
+                        .to_owned(),
+                ));
+            }
+            if (2..=64).contains(&size) {
+                let half_range = 2f64.powi(size as i32 - 1);
+                let scale = (-min / half_range).max(max / (half_range - 1.0));
+                return Ok(make_config_ref(Type::Primitive(SignalType::Decimal {
+                    size,
+                    offset: 0.0,
+                    scale,
+                    signed: true,
+                })));
+            }
+        }
+        match type_name {
+            "f32" => return Ok(make_config_ref(Type::Primitive(SignalType::Float { size: 32 }))),
+            "f64" => return Ok(make_config_ref(Type::Primitive(SignalType::Float { size: 64 }))),
+            "bool" => return Ok(make_config_ref(Type::Primitive(SignalType::Bool))),
+            _ => (),
+        }
+        let string_regex = regex::Regex::new(r#"^string<(?<len>[0-9]{1,3})>$"#).unwrap();
+        if let Some(cap) = string_regex.captures(type_name) {
+            let len = &cap["len"];
+            let len = len.parse::<u8>().unwrap_or(u8::MAX);
+            if len == 0 || len > MAX_STRING_LEN {
+                return Err(errors::ConfigError::InvalidRange(format!(
+                    "invalid string length, strings have between 1 and {MAX_STRING_LEN} characters"
+                )));
+            }
+            return Ok(make_config_ref(Type::Primitive(SignalType::String { len })));
+        }
+        // the last dimension is the outermost array, e.g. u8[3][4] are 4 arrays of 3 bytes.
+        let array_regex = regex::Regex::new(r#"^(?<type>.+)\[(?<len>[0-9]+)\]$"#).unwrap();
         match array_regex.captures(type_name) {
             Some(cap) => {
                 let len = &cap["len"];
-                let len = len.parse::<usize>().unwrap();
+                let len = len.parse::<usize>().unwrap_or(0);
+                if len == 0 {
+                    return Err(errors::ConfigError::InvalidRange(format!(
+                        "invalid array length of type {type_name:?}, arrays have at least one element"
+                    )));
+                }
                 let ty = &cap["type"];
                 let inner_type = Self::resolve_type(defined_types, ty)?;
                 return Ok(make_config_ref(Type::Array {
@@ -341,6 +403,100 @@ impl NetworkBuilder {
         )));
     }
 
+    /// Checks the `min`, `max` and `default` of a object entry against its type,
+    /// returns the bounds and the parsed default value.
+    fn resolve_constraints(
+        object_entry_data: &ObjectEntryData,
+        ty: &Type,
+    ) -> errors::Result<(Option<f64>, Option<f64>, Option<DecodedValue>)> {
+        let name = &object_entry_data.name;
+        let (min, max) = (object_entry_data.min, object_entry_data.max);
+        if min.is_some() || max.is_some() {
+            let Type::Primitive(
+                signal_type @ (SignalType::UnsignedInt { .. }
+                | SignalType::SignedInt { .. }
+                | SignalType::Decimal { .. }
+                | SignalType::Float { .. }),
+            ) = ty
+            else {
+                return Err(errors::ConfigError::InvalidType(format!(
+                    "min and max of object entry {name:?} require a numeric type"
+                )));
+            };
+            let (ty_min, ty_max) = signal_type.range();
+            if min.is_some_and(|min| min < ty_min || min > ty_max)
+                || max.is_some_and(|max| max < ty_min || max > ty_max)
+            {
+                return Err(errors::ConfigError::InvalidRange(format!(
+                    "min and max of object entry {name:?} have to be within [{ty_min}, {ty_max}]"
+                )));
+            }
+            if let (Some(min), Some(max)) = (min, max) {
+                if min > max {
+                    return Err(errors::ConfigError::InvalidRange(format!(
+                        "invalid range of object entry {name:?} min has to be less or equal to max"
+                    )));
+                }
+            }
+        }
+        let Some(literal) = &object_entry_data.default else {
+            return Ok((min, max, None));
+        };
+        let invalid_default = || {
+            errors::ConfigError::InvalidType(format!(
+                "invalid default {literal:?} of object entry {name:?}"
+            ))
+        };
+        let default = match ty {
+            Type::Primitive(SignalType::UnsignedInt { .. }) => {
+                DecodedValue::Unsigned(literal.parse().map_err(|_| invalid_default())?)
+            }
+            Type::Primitive(SignalType::SignedInt { .. }) => {
+                DecodedValue::Signed(literal.parse().map_err(|_| invalid_default())?)
+            }
+            Type::Primitive(SignalType::Decimal { .. } | SignalType::Float { .. }) => {
+                DecodedValue::Real(literal.parse().map_err(|_| invalid_default())?)
+            }
+            Type::Primitive(SignalType::Bool) => {
+                DecodedValue::Bool(literal.parse().map_err(|_| invalid_default())?)
+            }
+            Type::Primitive(SignalType::String { len }) => {
+                if !literal.is_ascii() || literal.len() > *len as usize {
+                    return Err(invalid_default());
+                }
+                DecodedValue::String(literal.clone())
+            }
+            Type::Enum { entries, .. } => {
+                if !entries.iter().any(|(entry, _)| entry == literal) {
+                    return Err(invalid_default());
+                }
+                DecodedValue::Enum(literal.clone())
+            }
+            _ => {
+                return Err(errors::ConfigError::InvalidType(format!(
+                    "defaults are only supported for object entries of primitive or enum type, {name:?} is neither"
+                )))
+            }
+        };
+        let value = match &default {
+            DecodedValue::Unsigned(v) => Some(*v as f64),
+            DecodedValue::Signed(v) => Some(*v as f64),
+            DecodedValue::Real(v) => Some(*v),
+            _ => None,
+        };
+        if let (Some(value), Type::Primitive(signal_type)) = (value, ty) {
+            let (ty_min, ty_max) = signal_type.range();
+            let below = value < min.unwrap_or(ty_min);
+            let above = value > max.unwrap_or(ty_max);
+            if below || above {
+                return Err(errors::ConfigError::InvalidRange(format!(
+                    "default {literal} of object entry {name:?} is out of range"
+                )));
+            }
+        }
+        Ok((min, max, Some(default)))
+    }
+
     fn topo_sort_types(types: &Vec<TypeRef>) -> Vec<TypeRef> {
         let n = types.len();
         struct Node {
@@ -359,16 +515,18 @@ impl NetworkBuilder {
                     visibility: _,
                 } => {
                     for (_, attrib_type) in attribs {
+                        let attrib_type = Self::element_type(attrib_type);
                         match types.iter().position(|t| t == attrib_type) {
                             Some(adj) => adj_list.push(adj),
                             None => (),
                         }
                     }
                 }
-                Type::Array { len: _, ty } => match types.iter().position(|t| t == ty) {
-                    Some(adj) => adj_list.push(adj),
-                    None => (),
-                },
+                Type::Array { len: _, ty } => {
+                    if let Some(adj) = types.iter().position(|t| t == Self::element_type(ty)) {
+                        adj_list.push(adj);
+                    }
+                }
                 _ => (),
             }
             nodes.push(Node {
@@ -401,6 +559,22 @@ impl NetworkBuilder {
         stack.iter().map(|index| types[*index].clone()).collect()
     }
 
+    /// Returns the type of the elements of (nested) arrays, other types are returned as is.
+    fn element_type(ty: &TypeRef) -> &TypeRef {
+        match ty as &Type {
+            Type::Array { len: _, ty } => Self::element_type(ty),
+            _ => ty,
+        }
+    }
+
+    /// Strips all array dimensions of a type name, e.g. `point[3][2]` -> `point`.
+    fn element_type_name(type_name: &str) -> &str {
+        match type_name.find('[') {
+            Some(index) => &type_name[..index],
+            None => type_name,
+        }
+    }
+
     fn topo_sort_type_builders(
         type_builders: &Vec<TypeBuilder>,
     ) -> errors::Result<Vec<TypeBuilder>> {
@@ -427,9 +601,10 @@ impl NetworkBuilder {
                         if is_inplace {
                             continue;
                         }
+                        let element_type_name = Self::element_type_name(attrib_type_name);
                         let opt = type_builders
                             .iter()
-                            .position(|builder| &builder.name() == attrib_type_name);
+                            .position(|builder| builder.name() == element_type_name);
                         match opt {
                             Some(adj_index) => {
                                 dependencies.push(adj_index);
@@ -478,12 +653,53 @@ impl NetworkBuilder {
     }
 
     pub fn build(self) -> errors::Result<NetworkRef> {
+        Ok(self.build_locked(None)?.0)
+    }
+
+    /// Builds the network and keeps the automatically assigned message ids, buses and
+    /// object entry ids of `lock` as long as they are still valid.
+    /// Returns the network together with the lock of the ids assigned by this build.
+    pub fn build_locked(self, lock: Option<&IdLock>) -> errors::Result<(NetworkRef, IdLock)> {
         if self.0.borrow().buses.borrow().is_empty() {
             // ensure that there is always at least one bus defined!
             self.create_bus("can0", None);
             #[cfg(feature = "logging_info")]
             println!("[CANZERO-CONFIG::build] Implicitly added can0 as the only bus");
         }
+        // nodes with persistent object entries can be asked to store them.
+        let persistent_nodes: Vec<NodeBuilder> = self
+            .0
+            .borrow()
+            .nodes
+            .borrow()
+            .iter()
+            .filter(|node_builder| {
+                let node_data = node_builder.0.borrow();
+                node_data.object_entries.iter().any(|oe| oe.0.borrow().persistent)
+            })
+            .cloned()
+            .collect();
+        for node_builder in persistent_nodes {
+            let node_data = node_builder.0.borrow();
+            if node_data
+                .commands
+                .iter()
+                .any(|command| command.0.borrow().name == SAVE_PARAMETERS_COMMAND)
+            {
+                return Err(errors::ConfigError::InvalidType(format!(
+                    "the command {SAVE_PARAMETERS_COMMAND:?} of node {:?} is reserved for persistent object entries",
+                    node_data.name
+                )));
+            }
+            drop(node_data);
+            let command = node_builder.create_command(SAVE_PARAMETERS_COMMAND, None);
+            command.add_description("stores all persistent object entries in non-volatile memory");
+            #[cfg(feature = "logging-info")]
+            println!(
+                "[CANZERO-CONFIG::build] Implicitly added {SAVE_PARAMETERS_COMMAND} command to {}",
+                node_builder.0.borrow().name
+            );
+        }
         let builder = self.0.borrow();
 
         #[cfg(feature = "logging_info")]
@@ -494,11 +710,16 @@ impl NetworkBuilder {
             .iter()
             .map(|bus_builder| {
                 let bus_data = bus_builder.0.borrow();
-                make_config_ref(config::bus::Bus::new(
-                    &bus_data.name,
-                    bus_data.id,
-                    bus_data.baudrate,
-                ))
+                make_config_ref(match bus_data.data_baudrate {
+                    Some(data_baudrate) => config::bus::Bus::new_fd(
+                        &bus_data.name,
+                        bus_data.id,
+                        bus_data.baudrate,
+                        data_baudrate,
+                        bus_data.brs,
+                    ),
+                    None => config::bus::Bus::new(&bus_data.name, bus_data.id, bus_data.baudrate),
+                })
             })
             .collect();
 
@@ -604,9 +825,23 @@ impl NetworkBuilder {
         // and buses!
         let nodes = builder.nodes.borrow().clone();
         drop(builder);
+        // only automatically assigned ids are locked.
+        let priorities: Vec<(String, u32)> = tmp_messages
+            .iter()
+            .filter_map(|message| {
+                let message_data = message.0.borrow();
+                match &message_data.id {
+                    MessageIdTemplate::AnyStd(priority) | MessageIdTemplate::AnyExt(priority) => {
+                        Some((message_data.name.clone(), priority.to_u32()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
         #[cfg(feature = "logging_info")]
         println!("[CANZERO-CONFIG::build] Resolving message ids and bus assignments");
-        resolve_ids_filters_and_buses(&tmp_buses, &tmp_messages, &nodes, &types)?;
+        let filter_banks =
+            resolve_ids_filters_and_buses(&tmp_buses, &tmp_messages, &nodes, &types, lock)?;
         let builder = self.0.borrow();
 
         #[cfg(feature = "logging_info")]
@@ -623,16 +858,15 @@ impl NetworkBuilder {
             };
             let (signals, encoding) = match &message_data.format {
                 MessageFormat::Signals(signal_format_builder) => {
-                    let mut offset: usize = 0;
+                    // signal formats are imported from DBC files, which define the
+                    // position of every signal (multiplexed signals may overlap).
                     let signal_format_data = signal_format_builder.0.borrow();
                     let mut signals = vec![];
                     for signal_data in signal_format_data.0.iter() {
                         signals.push(make_config_ref(Signal {
                             name: format!("{}_{}", message_data.name, signal_data.name),
-                            offset,
                             ..signal_data.clone()
                         }));
-                        offset += signal_data.size() as usize;
                     }
                     (signals, None)
                 }
@@ -642,23 +876,52 @@ impl NetworkBuilder {
                     let mut signals = vec![];
                     let mut offset: usize = 0;
 
+                    /// Places the signal behind the previous signals. Big endian signals
+                    /// start at the next byte boundary and fill whole bytes, their lsb
+                    /// is the lsb of their last byte.
+                    fn place_signal(
+                        name: &str,
+                        signal_type: SignalType,
+                        offset: &mut usize,
+                        byte_order: ByteOrder,
+                    ) -> SignalRef {
+                        let size = signal_type.size() as usize;
+                        let signal = match byte_order {
+                            ByteOrder::LittleEndian => {
+                                let signal = Signal::new(name, None, signal_type, *offset);
+                                *offset += size;
+                                signal
+                            }
+                            ByteOrder::BigEndian => {
+                                let first_byte = offset.div_ceil(8);
+                                let padding = size.div_ceil(8) * 8 - size;
+                                let msb = (first_byte + padding / 8) * 8 + 7 - padding % 8;
+                                let mut signal = Signal::new(name, None, signal_type, msb);
+                                signal.byte_order = ByteOrder::BigEndian;
+                                *offset = signal.end_bit();
+                                signal
+                            }
+                        };
+                        make_config_ref(signal)
+                    }
+
                     pub fn build_attribute(
                         ty: &TypeRef,
                         name: &str,
                         offset: &mut usize,
                         prefix: &str,
+                        byte_order: ByteOrder,
                         signals: &mut Vec<SignalRef>,
                     ) -> TypeSignalEncoding {
                         match ty as &Type {
                             Type::Primitive(signal_type) => {
-                                let signal = make_config_ref(Signal::new(
+                                let signal = place_signal(
                                     &format!("{prefix}_{name}"),
-                                    None,
                                     signal_type.clone(),
-                                    *offset,
-                                ));
+                                    offset,
+                                    byte_order,
+                                );
                                 signals.push(signal.clone());
-                                *offset += signal.size() as usize;
                                 TypeSignalEncoding::Primitive(PrimitiveSignalEncoding::new(
                                     name.to_owned(),
                                     ty.clone(),
@@ -678,6 +941,7 @@ impl NetworkBuilder {
                                         attrib_name,
                                         offset,
                                         &format!("{prefix}_{struct_name}"),
+                                        byte_order,
                                         signals,
                                     ));
                                 }
@@ -694,32 +958,52 @@ impl NetworkBuilder {
                                 entries: _,
                                 visibility: _,
                             } => {
-                                let size = *size;
-                                let signal = make_config_ref(Signal::new(
+                                let signal = place_signal(
                                     &format!("{prefix}_{enum_name}"),
-                                    None,
-                                    SignalType::UnsignedInt { size },
-                                    *offset,
-                                ));
+                                    SignalType::UnsignedInt { size: *size },
+                                    offset,
+                                    byte_order,
+                                );
                                 signals.push(signal.clone());
-                                *offset += signal.size() as usize;
                                 TypeSignalEncoding::Primitive(PrimitiveSignalEncoding::new(
                                     name.to_owned(),
                                     ty.clone(),
                                     signal,
                                 ))
                             }
-                            Type::Array { len: _, ty: _ } => todo!(),
+                            Type::Array {
+                                len,
+                                ty: element_ty,
+                            } => {
+                                // elements are named by their index.
+                                let mut elements = vec![];
+                                for i in 0..*len {
+                                    elements.push(build_attribute(
+                                        element_ty,
+                                        &i.to_string(),
+                                        offset,
+                                        &format!("{prefix}_{name}"),
+                                        byte_order,
+                                        signals,
+                                    ));
+                                }
+                                TypeSignalEncoding::Composite(CompositeSignalEncoding::new(
+                                    name.to_owned(),
+                                    elements,
+                                    ty.clone(),
+                                ))
+                            }
                         }
                     }
 
-                    for (type_name, var_name) in &type_format_data.0 {
+                    for (type_name, var_name, byte_order) in &type_format_data.0 {
                         let type_ref = Self::resolve_type(&types, type_name)?;
                         attributes.push(build_attribute(
                             &type_ref,
                             var_name,
                             &mut offset,
                             &format!("value_name"),
+                            *byte_order,
                             &mut signals,
                         ));
                     }
@@ -732,7 +1016,7 @@ impl NetworkBuilder {
 
             let mut max_bit = 0;
             for signal in &signals {
-                let signal_max_bit = signal.byte_offset() + signal.size() as usize;
+                let signal_max_bit = signal.end_bit();
                 max_bit = max_bit.max(signal_max_bit);
             }
             let dlc = ((max_bit + 8 - 1) / 8) as u8;
@@ -742,6 +1026,12 @@ impl NetworkBuilder {
                 .find(|bus| bus.id() == message_data.bus.clone().unwrap().0.borrow().id)
                 .unwrap()
                 .clone();
+            // CAN FD frames only support a few payload lengths above 8 bytes.
+            let dlc = if bus.is_fd() {
+                config::bus::can_fd_len(dlc).unwrap_or(dlc)
+            } else {
+                dlc
+            };
 
             messages.push(make_config_ref(Message::new(
                 message_data.name.clone(),
@@ -791,7 +1081,9 @@ impl NetworkBuilder {
         pub fn rec_type_acc(node_types: &mut Vec<TypeRef>, encoding: &TypeSignalEncoding) {
             match encoding {
                 TypeSignalEncoding::Composite(composite) => {
-                    if !node_types.contains(composite.ty()) {
+                    // arrays are not named types, only their elements are added.
+                    let is_array = matches!(composite.ty() as &Type, Type::Array { .. });
+                    if !is_array && !node_types.contains(composite.ty()) {
                         node_types.push(composite.ty().clone());
                     }
                     for attribute in composite.attributes() {
@@ -817,7 +1109,7 @@ impl NetworkBuilder {
                             node_types.push(primitive.ty().clone());
                         }
                     }
-                    Type::Array { len: _, ty: _ } => todo!(),
+                    Type::Array { len: _, ty: _ } => panic!("not a primitive"),
                 },
             }
         }
@@ -925,10 +1217,30 @@ impl NetworkBuilder {
                 &node_data.name
             );
             let mut object_entries = vec![];
-            let mut id_acc = 0;
-            for object_entry_builder in &node_builder.0.borrow().object_entries {
+            // locked ids are kept, all other object entries get the lowest free id.
+            let mut used_ids: Vec<u32> = vec![];
+            let locked_ids: Vec<Option<u32>> = node_builder
+                .0
+                .borrow()
+                .object_entries
+                .iter()
+                .map(|object_entry_builder| {
+                    let id = lock
+                        .and_then(|lock| {
+                            lock.object_entry(&node_data.name, &object_entry_builder.0.borrow().name)
+                        })
+                        .map(|locked| locked.id)
+                        .filter(|id| *id < MAX_OBJECT_ENTRY_COUNT && !used_ids.contains(id));
+                    used_ids.extend(id);
+                    id
+                })
+                .collect();
+            for (object_entry_builder, locked_id) in
+                std::iter::zip(&node_builder.0.borrow().object_entries, locked_ids)
+            {
                 let object_entry_data = object_entry_builder.0.borrow();
                 let ty = Self::resolve_type(&mut types, &object_entry_data.ty)?;
+                let (min, max, default) = Self::resolve_constraints(&object_entry_data, &ty)?;
                 fn rec_add_type(node_types: &mut Vec<TypeRef>, ty: &TypeRef) {
                     match ty as &Type {
                         Type::Primitive(_) => (),
@@ -956,12 +1268,18 @@ impl NetworkBuilder {
                                 node_types.push(ty.clone());
                             }
                         }
-                        Type::Array { len: _, ty: _ } => todo!(),
+                        Type::Array { len: _, ty } => rec_add_type(node_types, ty),
                     };
                 }
                 rec_add_type(&mut node_types, &ty);
-                let id = id_acc;
-                id_acc += 1;
+                let id = match locked_id {
+                    Some(id) => id,
+                    None => {
+                        let id = (0..).find(|id| !used_ids.contains(id)).unwrap();
+                        used_ids.push(id);
+                        id
+                    }
+                };
                 object_entries.push(make_config_ref(ObjectEntry::new(
                     object_entry_data.name.clone(),
                     object_entry_data.description.clone(),
@@ -971,6 +1289,10 @@ impl NetworkBuilder {
                     ty,
                     object_entry_data.access.clone(),
                     object_entry_data.visibility.clone(),
+                    min,
+                    max,
+                    default,
+                    object_entry_data.persistent,
                 )));
             }
 
@@ -1046,6 +1368,17 @@ impl NetworkBuilder {
                 "[CANZERO-CONFIG::build] Successfully build transmitting part of node {}",
                 node_data.name
             );
+            let node_filters = filter_banks
+                .iter()
+                .find(|bank| bank.node().0.borrow().name == node_data.name)
+                .map(|bank| {
+                    bank.filters()
+                        .iter()
+                        .map(|filter| config::Filter::new(filter.id(), filter.mask(), filter.ide()))
+                        .collect()
+                })
+                .unwrap_or_default();
+
             nodes.push(RefCell::new(Node::new(
                 node_data.name.clone(),
                 node_data.description.clone(),
@@ -1060,6 +1393,11 @@ impl NetworkBuilder {
                 object_entries,
                 node_buses,
                 node_data.heartbeat_timeout,
+                config::FilterSetup {
+                    filters: node_filters,
+                    controller: node_data.controller,
+                    max_filters: node_data.max_filters,
+                },
             )));
         }
 
@@ -1274,10 +1612,12 @@ impl NetworkBuilder {
             if !valid_c_var.is_match(msg_name) || is_c_keyword.is_match(msg_name) {
                 panic!("{msg_name} is not a valid name for a message.");
             }
-            if dlc > 8 {
-                panic!(
-                    "All messages have to have a dlc less than 8. \n{msg_name} has dlc = {dlc}."
-                );
+            let max_dlc = message.bus().max_dlc();
+            if dlc > max_dlc {
+                return Err(errors::ConfigError::MessageTooLarge(format!(
+                    "all messages on bus {} have to have a dlc of at most {max_dlc}, {msg_name} has dlc = {dlc}",
+                    message.bus().name()
+                )));
             }
 
             if dlc == 0 {
@@ -1323,6 +1663,47 @@ impl NetworkBuilder {
             check_ty(ty, &valid_c_var, &is_c_keyword);
         }
 
-        Ok(network_ref)
+        // every message a node receives has to pass its filters.
+        let filter_report = FilterReport::new(&network_ref);
+        let dropped = filter_report.dropped();
+        if !dropped.is_empty() {
+            let dropped: Vec<String> = dropped
+                .iter()
+                .map(|(node, message)| format!("{} drops {}", node.name(), message.name()))
+                .collect();
+            return Err(errors::ConfigError::DroppedMessages(dropped.join(", ")));
+        }
+
+        let id_lock = IdLock {
+            messages: network_ref
+                .messages()
+                .iter()
+                .filter_map(|message| {
+                    let (_, priority) = priorities
+                        .iter()
+                        .find(|(name, _)| name == message.name())?;
+                    Some(LockedMessage {
+                        name: message.name().to_owned(),
+                        id: message.id().as_u32(),
+                        ide: message.id().ide(),
+                        bus: message.bus().name().to_owned(),
+                        priority: *priority,
+                    })
+                })
+                .collect(),
+            object_entries: network_ref
+                .nodes()
+                .iter()
+                .flat_map(|node| {
+                    node.object_entries().iter().map(|oe| LockedObjectEntry {
+                        node: node.name().to_owned(),
+                        name: oe.name().to_owned(),
+                        id: oe.id(),
+                    })
+                })
+                .collect(),
+        };
+
+        Ok((network_ref, id_lock))
     }
 }
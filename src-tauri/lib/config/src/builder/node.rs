@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::config::ObjectEntryAccess;
+use crate::config::{CanController, ObjectEntryAccess};
 
 use super::{stream_builder::{ReceiveStreamBuilder, StreamBuilder}, ObjectEntryBuilder, MessageBuilder, NetworkBuilder, CommandBuilder, BuilderRef, MessagePriority, make_builder_ref, bus::BusBuilder};
 
@@ -21,6 +21,8 @@ pub struct NodeData {
     pub rx_streams: Vec<ReceiveStreamBuilder>,
     pub buses : Vec<BusBuilder>,
     pub heartbeat_timeout : Duration,
+    pub controller : Option<CanController>,
+    pub max_filters : Option<usize>,
 }
 
 
@@ -41,6 +43,8 @@ impl NodeBuilder {
             rx_streams: vec![],
             buses : vec![],
             heartbeat_timeout : Duration::from_millis(200),
+            controller : None,
+            max_filters : None,
         }));
         node_builder.add_rx_message(&network_builder._get_req_message());
         node_builder.add_tx_message(&network_builder._get_resp_message());
@@ -48,7 +52,7 @@ impl NodeBuilder {
         node_builder.add_tx_message(&network_builder._set_resp_message());
 
         let config_hash = node_builder.create_object_entry("config_hash", "u64");
-        config_hash.add_description("Hash of the local network configuration");
+        config_hash.add_description("Hash of the network configuration as seen by this node");
         config_hash.set_access(ObjectEntryAccess::Const);
 
         let build_time = node_builder.create_object_entry("build_time", "date_time");
@@ -61,6 +65,13 @@ impl NodeBuilder {
         let mut node_data = self.0.borrow_mut();
         node_data.heartbeat_timeout = heartbeat_timeout;
     }
+    pub fn set_controller(&self, controller : CanController) {
+        self.0.borrow_mut().controller = Some(controller);
+    }
+    /// Overrides the filter count of the controller.
+    pub fn set_max_filters(&self, max_filters : usize) {
+        self.0.borrow_mut().max_filters = Some(max_filters);
+    }
     pub fn assign_bus(&self, bus_name : &str) -> BusBuilder{
         let mut node_data = self.0.borrow_mut();       
         let network_data = node_data.network_builder.0.borrow_mut();
@@ -98,23 +109,19 @@ impl NodeBuilder {
     }
     pub fn create_command(&self, name: &str, expected_interval : Option<Duration>) -> CommandBuilder {
         let command_builder = CommandBuilder::new(name, &self, expected_interval);
-        let mut node_data = self.0.borrow_mut();
-        node_data.commands.push(command_builder.clone());
-        node_data
-            .rx_messages
-            .push(command_builder.0.borrow().call_message.clone());
-        node_data
+        self.0.borrow_mut().commands.push(command_builder.clone());
+        self.add_rx_message(&command_builder.0.borrow().call_message);
+        self.0
+            .borrow_mut()
             .tx_messages
             .push(command_builder.0.borrow().resp_message.clone());
         command_builder
     }
     pub fn add_extern_command(&self, message_builder: &CommandBuilder) {
-        let mut node_data = self.0.borrow_mut();
-        node_data.extern_commands.push(message_builder.clone());
-        node_data
-            .rx_messages
-            .push(message_builder.0.borrow().resp_message.clone());
-        node_data
+        self.0.borrow_mut().extern_commands.push(message_builder.clone());
+        self.add_rx_message(&message_builder.0.borrow().resp_message);
+        self.0
+            .borrow_mut()
             .tx_messages
             .push(message_builder.0.borrow().call_message.clone());
     }
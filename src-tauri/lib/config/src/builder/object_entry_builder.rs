@@ -1,4 +1,4 @@
-use crate::config::{ObjectEntryAccess, Visibility};
+use crate::config::{ByteOrder, ObjectEntryAccess, Visibility};
 
 use super::{make_builder_ref, BuilderRef};
 
@@ -14,6 +14,13 @@ pub struct ObjectEntryData {
     pub ty: String,
     pub access: ObjectEntryAccess,
     pub visibility: Visibility,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    /// literal of the default value, resolved once the type is known.
+    pub default: Option<String>,
+    pub persistent: bool,
+    /// byte order of the signals of the object entry in streams.
+    pub byte_order: ByteOrder,
 }
 
 
@@ -29,6 +36,11 @@ impl ObjectEntryBuilder {
             unit: None,
             access: ObjectEntryAccess::Global,
             visibility: Visibility::Global,
+            min: None,
+            max: None,
+            default: None,
+            persistent: false,
+            byte_order: ByteOrder::LittleEndian,
         }))
     }
     pub fn hide(&self) {
@@ -50,5 +62,19 @@ impl ObjectEntryBuilder {
         let mut data = self.0.borrow_mut();
         data.unit = Some(unit.to_owned());
     }
+    pub fn set_min(&self, min: f64) {
+        self.0.borrow_mut().min = Some(min);
+    }
+    pub fn set_max(&self, max: f64) {
+        self.0.borrow_mut().max = Some(max);
+    }
+    pub fn set_default(&self, default: &str) {
+        self.0.borrow_mut().default = Some(default.to_owned());
+    }
+    pub fn make_persistent(&self) {
+        self.0.borrow_mut().persistent = true;
+    }
+    pub fn set_byte_order(&self, byte_order: ByteOrder) {
+        self.0.borrow_mut().byte_order = byte_order;
+    }
 }
-
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use crate::config::Visibility;
+use crate::{config::Visibility, errors};
 
 use super::{
     make_builder_ref, BuilderRef, MessageBuilder, MessagePriority, MessageTypeFormatBuilder,
@@ -61,9 +61,16 @@ impl StreamBuilder {
     }
     // max : max time between two messages
     // min : min time between two messages
-    pub fn set_interval(&self, min: Duration, max: Duration) {
-        assert!(min.as_micros() <= max.as_micros());
-        self.0.borrow_mut().interval = (min, max);
+    pub fn set_interval(&self, min: Duration, max: Duration) -> errors::Result<()> {
+        let mut stream_data = self.0.borrow_mut();
+        if min > max {
+            return Err(errors::ConfigError::InvalidRange(format!(
+                "the min interval {min:?} of stream {} is greater than its max interval {max:?}",
+                stream_data.name
+            )));
+        }
+        stream_data.interval = (min, max);
+        Ok(())
     }
     pub fn hide(&self) {
         let mut stream_data = self.0.borrow_mut();
@@ -97,7 +104,9 @@ impl StreamBuilder {
         // .unwrap_or_else(|| node.create_object_entry(name, "u1"));
         stream_data.object_entries.push(oe.clone());
         let oe_data = oe.0.borrow();
-        stream_data.format.add_type(&oe_data.ty, &oe_data.name);
+        stream_data
+            .format
+            .add_type_with_byte_order(&oe_data.ty, &oe_data.name, oe_data.byte_order);
     }
     pub fn set_priority(&self, priority: MessagePriority) {
         self.0.borrow().message.set_any_std_id(priority);
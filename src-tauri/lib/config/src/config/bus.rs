@@ -12,6 +12,17 @@ pub struct Bus {
     id : u32,
     baudrate : u32,
     name : String,
+    /// bitrate of the data phase, only present for CAN FD buses.
+    data_baudrate : Option<u32>,
+    brs : bool,
+}
+
+/// Valid payload lengths of CAN FD frames (dlc codes 0 to 15).
+pub const CAN_FD_LENGTHS: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 12, 16, 20, 24, 32, 48, 64];
+
+/// Smallest valid CAN FD payload length, which can hold `len` bytes.
+pub fn can_fd_len(len: u8) -> Option<u8> {
+    CAN_FD_LENGTHS.iter().copied().find(|fd_len| *fd_len >= len)
 }
 
 impl Bus {
@@ -20,6 +31,15 @@ impl Bus {
             id,
             baudrate,
             name : name.to_owned(),
+            data_baudrate : None,
+            brs : false,
+        }
+    }
+    pub fn new_fd(name : &str, id : u32, baudrate : u32, data_baudrate : u32, brs : bool) -> Self {
+        Self {
+            data_baudrate : Some(data_baudrate),
+            brs,
+            ..Self::new(name, id, baudrate)
         }
     }
     pub fn id(&self) -> u32 {
@@ -31,6 +51,21 @@ impl Bus {
     pub fn name(&self) -> &str {
         &self.name
     }
+    pub fn is_fd(&self) -> bool {
+        self.data_baudrate.is_some()
+    }
+    /// bitrate of the data phase of CAN FD frames, `None` for classic CAN buses.
+    pub fn data_baudrate(&self) -> Option<u32> {
+        self.data_baudrate
+    }
+    /// whether CAN FD frames switch to the data bitrate (bit rate switch).
+    pub fn brs(&self) -> bool {
+        self.brs
+    }
+    /// maximum payload of a frame in bytes.
+    pub fn max_dlc(&self) -> u8 {
+        if self.is_fd() { 64 } else { 8 }
+    }
 }
 
 
@@ -41,5 +76,10 @@ impl Hash for Bus {
         for b in self.name.bytes() {
             state.write_u8(b);
         }
+        // classic buses hash as before.
+        if let Some(data_baudrate) = self.data_baudrate {
+            state.write_u32(data_baudrate);
+            state.write_u8(self.brs as u8);
+        }
     }
 }
@@ -0,0 +1,361 @@
+//! Worst-case and average bus load of a network configuration.
+//!
+//! The worst case assumes that every stream is sent at its minimum interval, every command
+//! is called once per expected interval, every node answers a get and a set request for
+//! its largest object entry every [`REQUEST_INTERVAL`] and that every frame contains
+//! the maximum number of stuff bits.
+//! The average assumes streams at the mean of their interval, get and set requests for an
+//! average object entry every [`AVERAGE_REQUEST_INTERVAL`] and no stuff bits.
+
+use std::{sync::Arc, time::Duration};
+
+use super::{bus::{can_fd_len, Bus, BusRef}, MessageRef, MessageUsage, Network, NodeRef};
+
+/// Interval in which the worst case assumes a get and a set request to every node.
+pub const REQUEST_INTERVAL: Duration = Duration::from_millis(100);
+/// Interval in which the average assumes a get and a set request to every node.
+pub const AVERAGE_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+/// Streams without a minimum interval are sent at most once per scheduler tick.
+const MIN_STREAM_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Number of bits on the bus of a data frame with `dlc` bytes including the interframe space.
+/// With `stuffing` the maximum number of stuff bits is included.
+pub fn frame_bits(dlc: u8, ide: bool, stuffing: bool) -> u32 {
+    let payload = 8 * dlc as u32;
+    // stuffing applies to the bits from SOF up to the end of the CRC.
+    let (overhead, stuffed) = if ide {
+        (67, 54 + payload)
+    } else {
+        (47, 34 + payload)
+    };
+    let stuff_bits = if stuffing { (stuffed - 1) / 4 } else { 0 };
+    overhead + payload + stuff_bits
+}
+
+/// Number of nominal bit times of a CAN FD frame with `len` bytes including the interframe space.
+/// The control field, the payload and the crc are sent at `data_baudrate` if `brs` is set.
+/// With `stuffing` the maximum number of dynamic and the fixed stuff bits are included.
+pub fn fd_frame_bits(len: u8, ide: bool, stuffing: bool, baudrate: u32, data_baudrate: u32, brs: bool) -> u32 {
+    let payload = 8 * len as u32;
+    // SOF, identifier, RRS/SRR, IDE, FDF, res and BRS.
+    let arbitration = if ide { 36 } else { 17 };
+    // ESI, DLC, payload, stuff count and crc (crc17 up to 16 bytes, crc21 above).
+    let crc = if len > 16 { 21 } else { 17 };
+    let data = 1 + 4 + payload + 4 + crc;
+    // crc delimiter, ack slot, ack delimiter, EOF and interframe space.
+    let tail = 13;
+    let (arbitration_stuff, data_stuff) = if stuffing {
+        ((arbitration - 1) / 4, (5 + payload - 1) / 4 + crc.div_ceil(4))
+    } else {
+        (0, 0)
+    };
+    let ratio = if brs {
+        baudrate as f64 / data_baudrate as f64
+    } else {
+        1.0
+    };
+    arbitration + arbitration_stuff + tail + ((data + data_stuff) as f64 * ratio).ceil() as u32
+}
+
+/// Number of nominal bit times of a frame with `dlc` bytes on `bus`,
+/// see [frame_bits] and [fd_frame_bits].
+pub fn bus_frame_bits(bus: &Bus, dlc: u8, ide: bool, stuffing: bool) -> u32 {
+    match bus.data_baudrate() {
+        Some(data_baudrate) => {
+            fd_frame_bits(dlc, ide, stuffing, bus.baudrate(), data_baudrate, bus.brs())
+        }
+        None => frame_bits(dlc, ide, stuffing),
+    }
+}
+
+/// Load in bits per second.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Load {
+    pub worst_case: f64,
+    pub average: f64,
+}
+
+impl Load {
+    fn add(&mut self, other: Load) {
+        self.worst_case += other.worst_case;
+        self.average += other.average;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageLoad {
+    message: MessageRef,
+    load: Load,
+}
+
+impl MessageLoad {
+    pub fn message(&self) -> &MessageRef {
+        &self.message
+    }
+    pub fn load(&self) -> Load {
+        self.load
+    }
+}
+
+/// Load caused by the frames a node transmits. Requests of the control panel or
+/// other clients are accounted to no node.
+#[derive(Debug, Clone)]
+pub struct NodeLoad {
+    node: Option<NodeRef>,
+    load: Load,
+}
+
+impl NodeLoad {
+    pub fn node(&self) -> Option<&NodeRef> {
+        self.node.as_ref()
+    }
+    pub fn load(&self) -> Load {
+        self.load
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BusLoad {
+    bus: BusRef,
+    load: Load,
+    nodes: Vec<NodeLoad>,
+    messages: Vec<MessageLoad>,
+}
+
+impl BusLoad {
+    pub fn bus(&self) -> &BusRef {
+        &self.bus
+    }
+    /// Load in bits per second.
+    pub fn load(&self) -> Load {
+        self.load
+    }
+    /// Worst-case utilisation of the bus (1.0 = 100%).
+    pub fn worst_case_utilisation(&self) -> f64 {
+        self.load.worst_case / self.bus.baudrate() as f64
+    }
+    /// Average utilisation of the bus (1.0 = 100%).
+    pub fn average_utilisation(&self) -> f64 {
+        self.load.average / self.bus.baudrate() as f64
+    }
+    /// Load per transmitting node, sorted by worst-case load.
+    pub fn nodes(&self) -> &Vec<NodeLoad> {
+        &self.nodes
+    }
+    /// Load per message, sorted by worst-case load.
+    pub fn messages(&self) -> &Vec<MessageLoad> {
+        &self.messages
+    }
+}
+
+/// Frames of a message sent by one node.
+pub(super) struct Traffic {
+    pub message: MessageRef,
+    pub node: Option<NodeRef>,
+    /// In the worst case `frames` frames are sent at most once every `interval`.
+    pub frames: u32,
+    pub interval: Duration,
+    /// Average number of frames per second.
+    pub average_rate: f64,
+    /// Payload of the frames in bytes.
+    pub dlc: u8,
+}
+
+impl Traffic {
+    fn new(message: &MessageRef, node: Option<NodeRef>, interval: Duration) -> Self {
+        Self {
+            message: message.clone(),
+            node,
+            frames: 1,
+            interval,
+            average_rate: rate(interval),
+            dlc: message.dlc(),
+        }
+    }
+
+    /// Nominal bit times of one frame including the maximum number of stuff bits.
+    pub fn worst_case_bits(&self) -> u32 {
+        bus_frame_bits(self.message.bus(), self.dlc, self.message.id().ide(), true)
+    }
+
+    fn load(&self) -> Load {
+        let dlc = self.dlc;
+        let ide = self.message.id().ide();
+        let bus = self.message.bus();
+        Load {
+            worst_case: (bus_frame_bits(bus, dlc, ide, true) * self.frames) as f64
+                * rate(self.interval),
+            average: bus_frame_bits(bus, dlc, ide, false) as f64 * self.average_rate,
+        }
+    }
+}
+
+fn rate(interval: Duration) -> f64 {
+    1.0 / interval.as_secs_f64()
+}
+
+/// Size in bits of the largest and the average object entry of a node.
+fn object_entry_sizes(node: &NodeRef) -> (u32, f64) {
+    let sizes: Vec<u32> = node
+        .object_entries()
+        .iter()
+        .map(|oe| oe.ty().size())
+        .collect();
+    let max = sizes.iter().copied().max().unwrap_or(0);
+    let mean = if sizes.is_empty() {
+        0.0
+    } else {
+        sizes.iter().sum::<u32>() as f64 / sizes.len() as f64
+    };
+    (max, mean)
+}
+
+pub(super) fn message_traffic(network: &Network, message: &MessageRef) -> Vec<Traffic> {
+    let nodes = network.nodes();
+    match message.usage() {
+        MessageUsage::Stream(stream) => {
+            let min = (*stream.min_interval()).max(MIN_STREAM_INTERVAL);
+            let mean = (min + (*stream.max_interval()).max(min)) / 2;
+            let node = nodes
+                .iter()
+                .find(|node| node.tx_streams().iter().any(|s| Arc::ptr_eq(s, stream)))
+                .cloned();
+            vec![Traffic {
+                average_rate: rate(mean),
+                ..Traffic::new(message, node, min)
+            }]
+        }
+        MessageUsage::CommandReq(command) => {
+            let callers: Vec<Option<NodeRef>> = nodes
+                .iter()
+                .filter(|node| {
+                    node.extern_commands()
+                        .iter()
+                        .any(|(_, c)| Arc::ptr_eq(c, command))
+                })
+                .map(|node| Some(node.clone()))
+                .collect();
+            let callers = if callers.is_empty() { vec![None] } else { callers };
+            callers
+                .into_iter()
+                .map(|caller| Traffic::new(message, caller, *command.expected_interval()))
+                .collect()
+        }
+        MessageUsage::CommandResp(command) => {
+            let node = nodes
+                .iter()
+                .find(|node| node.commands().iter().any(|c| Arc::ptr_eq(c, command)))
+                .cloned();
+            vec![Traffic::new(message, node, *command.expected_interval())]
+        }
+        MessageUsage::Heartbeat => nodes
+            .iter()
+            .filter(|node| node.buses().iter().any(|bus| bus.id() == message.bus().id()))
+            .map(|node| {
+                // see heartbeat_interval in the generated scheduler.
+                let interval = Duration::from_millis(100 + 3 * node.id() as u64);
+                Traffic::new(message, Some(node.clone()), interval)
+            })
+            .collect(),
+        MessageUsage::GetReq | MessageUsage::SetReq => {
+            // one frame per request to every node, sent by the clients
+            // (the fragments of a set request are sent 200ms apart).
+            let requests = nodes.len() as u32;
+            vec![Traffic {
+                frames: requests,
+                average_rate: requests as f64 * rate(AVERAGE_REQUEST_INTERVAL),
+                ..Traffic::new(message, None, REQUEST_INTERVAL)
+            }]
+        }
+        MessageUsage::SetResp => nodes
+            .iter()
+            .map(|node| Traffic {
+                average_rate: rate(AVERAGE_REQUEST_INTERVAL),
+                ..Traffic::new(message, Some(node.clone()), REQUEST_INTERVAL)
+            })
+            .collect(),
+        MessageUsage::GetResp => nodes
+            .iter()
+            .map(|node| {
+                // one fragment per 32 bits of the object entry,
+                // CAN FD frames carry up to 15 fragments after the header.
+                let (max, mean) = object_entry_sizes(node);
+                let words_per_frame = if message.bus().is_fd() { 15 } else { 1 };
+                let max_words = max.div_ceil(32).max(1);
+                let average_frames = (mean / (32.0 * words_per_frame as f64)).ceil().max(1.0);
+                let dlc = if message.bus().is_fd() {
+                    can_fd_len(4 + 4 * max_words.min(words_per_frame) as u8)
+                        .unwrap_or(message.dlc())
+                        .max(message.dlc())
+                } else {
+                    message.dlc()
+                };
+                Traffic {
+                    frames: max_words.div_ceil(words_per_frame),
+                    average_rate: average_frames * rate(AVERAGE_REQUEST_INTERVAL),
+                    dlc,
+                    ..Traffic::new(message, Some(node.clone()), REQUEST_INTERVAL)
+                }
+            })
+            .collect(),
+        MessageUsage::External { interval } => {
+            let node = nodes
+                .iter()
+                .find(|node| node.tx_messages().iter().any(|m| Arc::ptr_eq(m, message)))
+                .cloned();
+            vec![Traffic::new(message, node, *interval)]
+        }
+    }
+}
+
+/// Estimates the load of every bus of the network.
+pub fn analyze_bus_load(network: &Network) -> Vec<BusLoad> {
+    let traffic: Vec<Traffic> = network
+        .messages()
+        .iter()
+        .flat_map(|message| message_traffic(network, message))
+        .collect();
+
+    network
+        .buses()
+        .iter()
+        .map(|bus| {
+            let mut load = Load::default();
+            let mut nodes: Vec<NodeLoad> = vec![];
+            let mut messages: Vec<MessageLoad> = vec![];
+            for t in traffic.iter().filter(|t| t.message.bus().id() == bus.id()) {
+                let t_load = t.load();
+                load.add(t_load);
+                match nodes.iter_mut().find(|n| match (&n.node, &t.node) {
+                    (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }) {
+                    Some(node_load) => node_load.load.add(t_load),
+                    None => nodes.push(NodeLoad {
+                        node: t.node.clone(),
+                        load: t_load,
+                    }),
+                }
+                match messages
+                    .iter_mut()
+                    .find(|m| Arc::ptr_eq(&m.message, &t.message))
+                {
+                    Some(message_load) => message_load.load.add(t_load),
+                    None => messages.push(MessageLoad {
+                        message: t.message.clone(),
+                        load: t_load,
+                    }),
+                }
+            }
+            nodes.sort_by(|a, b| b.load.worst_case.total_cmp(&a.load.worst_case));
+            messages.sort_by(|a, b| b.load.worst_case.total_cmp(&a.load.worst_case));
+            BusLoad {
+                bus: bus.clone(),
+                load,
+                nodes,
+                messages,
+            }
+        })
+        .collect()
+}
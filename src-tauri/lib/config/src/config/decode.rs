@@ -0,0 +1,408 @@
+//! Decoding of frame payloads into the values of their signals,
+//! shared by the control panel and the cli.
+//!
+//! Payloads are given as the little endian interpretation of the 8 byte words of the frame data
+//! (one word for classic CAN frames, up to 8 for CAN FD frames).
+//! Object entry values (get and set requests) are given as the sequence of fragments (`u32`),
+//! see [decode_object_entry].
+
+use super::{ByteOrder, Message, SignalMultiplexing, SignalRef, SignalType, Type, TypeSignalEncoding};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodedValue {
+    Unsigned(u64),
+    Signed(i64),
+    Real(f64),
+    Bool(bool),
+    String(String),
+    /// name of the enum entry, `UNDEFINED` if the value does not name an entry.
+    Enum(String),
+    Struct(Vec<DecodedAttribute>),
+    Array(Vec<DecodedValue>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAttribute {
+    pub name: String,
+    pub value: DecodedValue,
+}
+
+/// Position of a signal within the payload.
+/// The payload is given as the little endian interpretation of its 8 byte words.
+pub(super) enum SignalBits {
+    /// shift of the lsb within the words, the signal may cross a word boundary.
+    LittleEndian { offset: u32, mask: u64 },
+    /// bits of the signal within the words, lsb first.
+    BigEndian(Vec<u32>),
+}
+
+impl SignalBits {
+    pub(super) fn new(signal: &SignalRef) -> Self {
+        let size = signal.size() as u32;
+        match signal.byte_order() {
+            ByteOrder::LittleEndian => Self::LittleEndian {
+                offset: signal.byte_offset() as u32, // NOTE stupid naming =^)
+                mask: u64::MAX >> (u64::BITS - size),
+            },
+            ByteOrder::BigEndian => {
+                // NOTE the offset refers to the msb in the sawtooth numbering of DBC files,
+                // the following bits are the lower bits of the byte and then the next byte (msb first).
+                let mut position = signal.byte_offset() as u32;
+                let mut positions = Vec::with_capacity(size as usize);
+                for _ in 0..size {
+                    positions.push(position);
+                    position = if position % 8 == 0 {
+                        position + 15
+                    } else {
+                        position - 1
+                    };
+                }
+                positions.reverse();
+                Self::BigEndian(positions)
+            }
+        }
+    }
+
+    pub(super) fn read(&self, data: &[u64]) -> u64 {
+        let word = |index: u32| data.get(index as usize).copied().unwrap_or(0);
+        match self {
+            Self::LittleEndian { offset, mask } => {
+                let shift = offset % 64;
+                let mut bits = word(offset / 64) >> shift;
+                if shift != 0 {
+                    bits |= word(offset / 64 + 1) << (64 - shift);
+                }
+                bits & mask
+            }
+            Self::BigEndian(positions) => positions
+                .iter()
+                .enumerate()
+                .map(|(i, position)| ((word(position / 64) >> (position % 64)) & 0x1) << i)
+                .fold(0, |bits, bit| bits | bit),
+        }
+    }
+
+    pub(super) fn write(&self, data: &mut [u64], raw: u64) {
+        let mut write_word = |index: u32, bits: u64| {
+            if let Some(word) = data.get_mut(index as usize) {
+                *word |= bits;
+            }
+        };
+        match self {
+            Self::LittleEndian { offset, mask } => {
+                let bits = raw & mask;
+                let shift = offset % 64;
+                write_word(offset / 64, bits << shift);
+                if shift != 0 {
+                    write_word(offset / 64 + 1, bits >> (64 - shift));
+                }
+            }
+            Self::BigEndian(positions) => {
+                for (i, position) in positions.iter().enumerate() {
+                    write_word(position / 64, ((raw >> i) & 0x1) << (position % 64));
+                }
+            }
+        }
+    }
+}
+
+pub struct SignalDecoder {
+    bits: SignalBits,
+    ty: SignalType,
+}
+
+fn sign_extend(unsigned_bits: u64, size: u8) -> i64 {
+    // shift the sign bit into the msb and back (arithmetic shift pads with the sign).
+    let shift = u64::BITS - size as u32;
+    ((unsigned_bits << shift) as i64) >> shift
+}
+
+/// Interprets the raw bits of a primitive value.
+fn decode_primitive(signal_type: &SignalType, unsigned_bits: u64) -> DecodedValue {
+    match signal_type {
+        SignalType::UnsignedInt { size: _ } => DecodedValue::Unsigned(unsigned_bits),
+        SignalType::SignedInt { size } => DecodedValue::Signed(sign_extend(unsigned_bits, *size)),
+        SignalType::Decimal {
+            size,
+            offset,
+            scale,
+            signed,
+        } => {
+            let raw = if *signed {
+                sign_extend(unsigned_bits, *size) as f64
+            } else {
+                unsigned_bits as f64
+            };
+            DecodedValue::Real(raw * scale + offset)
+        }
+        SignalType::Float { size: 32 } => {
+            DecodedValue::Real(f32::from_bits(unsigned_bits as u32) as f64)
+        }
+        SignalType::Float { size: _ } => DecodedValue::Real(f64::from_bits(unsigned_bits)),
+        SignalType::Bool => DecodedValue::Bool(unsigned_bits != 0),
+        SignalType::String { len } => DecodedValue::String(
+            unsigned_bits.to_le_bytes()[..*len as usize]
+                .iter()
+                // strings are padded with zeros.
+                .take_while(|c| **c != 0)
+                .map(|c| *c as char)
+                .collect(),
+        ),
+    }
+}
+
+impl SignalDecoder {
+    pub fn new(signal: &SignalRef) -> Self {
+        Self {
+            bits: SignalBits::new(signal),
+            ty: signal.ty().clone(),
+        }
+    }
+
+    /// Returns the raw bits of the signal (without sign extension or scaling).
+    pub fn raw(&self, data: &[u64]) -> u64 {
+        self.bits.read(data)
+    }
+
+    pub fn decode(&self, data: &[u64]) -> DecodedValue {
+        decode_primitive(&self.ty, self.raw(data))
+    }
+}
+
+enum EncodingDecoder {
+    Primitive(SignalDecoder),
+    Composite(Vec<AttributeDecoder>),
+    Array(Vec<EncodingDecoder>),
+    Enum {
+        entries: Vec<(String, u64)>,
+        signal_decoder: SignalDecoder,
+    },
+}
+
+impl EncodingDecoder {
+    fn new(encoding: &TypeSignalEncoding) -> Self {
+        match encoding {
+            TypeSignalEncoding::Primitive(prim) => match prim.ty() as &Type {
+                // NOTE: config refers to unsigned, signed and decimal values as primitives
+                Type::Primitive(_) => Self::Primitive(SignalDecoder::new(prim.signal())),
+                Type::Enum { entries, .. } => Self::Enum {
+                    entries: entries.clone(),
+                    signal_decoder: SignalDecoder::new(prim.signal()),
+                },
+                Type::Struct { .. } => panic!(
+                    "DETECTED INVALID CONFIG : primitive encoding associated with a structured type!"
+                ),
+                Type::Array { len: _, ty: _ } => panic!(
+                    "DETECTED INVALID CONFIG : primitive encoding associated with an array type!"
+                ),
+            },
+            TypeSignalEncoding::Composite(comp)
+                if matches!(comp.ty() as &Type, Type::Array { .. }) =>
+            {
+                Self::Array(comp.attributes().iter().map(EncodingDecoder::new).collect())
+            }
+            TypeSignalEncoding::Composite(comp) => Self::Composite(
+                comp.attributes()
+                    .iter()
+                    .map(|attribute_encoding| {
+                        AttributeDecoder::new(
+                            attribute_encoding.name(),
+                            EncodingDecoder::new(attribute_encoding),
+                        )
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    /// signals can only be decoded into primitive values
+    /// or enums (unsigned signals with a value table),
+    /// no type information is required!
+    fn new_from_signal(signal: &SignalRef) -> Self {
+        match (signal.ty(), signal.value_table()) {
+            (SignalType::UnsignedInt { size: _ }, Some(value_table)) => Self::Enum {
+                entries: value_table.0.clone(),
+                signal_decoder: SignalDecoder::new(signal),
+            },
+            _ => Self::Primitive(SignalDecoder::new(signal)),
+        }
+    }
+
+    fn decode(&self, data: &[u64]) -> DecodedValue {
+        match &self {
+            Self::Primitive(signal_decoder) => signal_decoder.decode(data),
+            Self::Composite(attribute_decoders) => DecodedValue::Struct(
+                attribute_decoders
+                    .iter()
+                    .map(|attribute_decoder| attribute_decoder.decode(data))
+                    .collect(),
+            ),
+            Self::Array(element_decoders) => DecodedValue::Array(
+                element_decoders
+                    .iter()
+                    .map(|element_decoder| element_decoder.decode(data))
+                    .collect(),
+            ),
+            Self::Enum {
+                entries,
+                signal_decoder,
+            } => {
+                let unsigned_value = signal_decoder.raw(data);
+                // NOTE map unsigned value to enum value (string)
+                // if not a valid unsigned value set enum value to UNDEFINED
+                // UNKNOWN is capitalized to avoid confusion with js frontend.
+                DecodedValue::Enum(
+                    entries
+                        .iter()
+                        .find(|(_, value)| value == &unsigned_value)
+                        .map_or("UNDEFINED", |(name, _)| name.as_str())
+                        .to_owned(),
+                )
+            }
+        }
+    }
+}
+
+struct AttributeDecoder {
+    attribute_name: String,
+    decoder: EncodingDecoder,
+    // value of the multiplexor for which the attribute is present.
+    multiplexed: Option<u64>,
+}
+
+impl AttributeDecoder {
+    fn new(name: &str, decoder: EncodingDecoder) -> Self {
+        Self {
+            attribute_name: name.to_owned(),
+            decoder,
+            multiplexed: None,
+        }
+    }
+
+    fn decode(&self, data: &[u64]) -> DecodedAttribute {
+        DecodedAttribute {
+            name: self.attribute_name.clone(),
+            value: self.decoder.decode(data),
+        }
+    }
+}
+
+/// Decodes the payload of a message into its attributes,
+/// based on the encoding of the message or on its signals (e.g. messages imported from DBC files).
+pub struct MessageDecoder {
+    attribute_decoders: Vec<AttributeDecoder>,
+    multiplexor: Option<SignalDecoder>,
+}
+
+impl MessageDecoder {
+    pub fn new(message: &Message) -> Self {
+        match message.encoding() {
+            Some(message_encoding) => Self {
+                attribute_decoders: message_encoding
+                    .attributes()
+                    .iter()
+                    .map(|type_encoding| {
+                        AttributeDecoder::new(
+                            type_encoding.name(),
+                            EncodingDecoder::new(type_encoding),
+                        )
+                    })
+                    .collect(),
+                multiplexor: None,
+            },
+            None => Self {
+                attribute_decoders: message
+                    .signals()
+                    .iter()
+                    .map(|signal| {
+                        let mut attribute_decoder = AttributeDecoder::new(
+                            signal.name(),
+                            EncodingDecoder::new_from_signal(signal),
+                        );
+                        if let Some(SignalMultiplexing::Multiplexed(value)) = signal.multiplexing()
+                        {
+                            attribute_decoder.multiplexed = Some(*value);
+                        }
+                        attribute_decoder
+                    })
+                    .collect(),
+                multiplexor: message
+                    .signals()
+                    .iter()
+                    .find(|signal| signal.multiplexing() == Some(&SignalMultiplexing::Multiplexor))
+                    .map(SignalDecoder::new),
+            },
+        }
+    }
+
+    pub fn decode(&self, data: &[u64]) -> Vec<DecodedAttribute> {
+        let multiplexor_value = self
+            .multiplexor
+            .as_ref()
+            .map(|multiplexor| multiplexor.raw(data));
+        self.attribute_decoders
+            .iter()
+            // multiplexed signals are only present if the multiplexor selects them.
+            .filter(|attribute_decoder| match attribute_decoder.multiplexed {
+                Some(value) => multiplexor_value == Some(value),
+                None => true,
+            })
+            .map(|attribute_decoder| attribute_decoder.decode(data))
+            .collect()
+    }
+}
+
+/// Reads consecutive bits (lsb first) from the fragments of a get or set request.
+struct BitReader<'a> {
+    words: &'a [u32],
+    position: usize,
+}
+
+impl BitReader<'_> {
+    fn read(&mut self, size: u32) -> u64 {
+        let mut value = 0u64;
+        for i in 0..size as usize {
+            let position = self.position + i;
+            let word = self.words.get(position / 32).copied().unwrap_or(0);
+            value |= (((word >> (position % 32)) & 0x1) as u64) << i;
+        }
+        self.position += size as usize;
+        value
+    }
+}
+
+fn decode_type(ty: &Type, reader: &mut BitReader) -> DecodedValue {
+    match ty {
+        Type::Primitive(signal_type) => {
+            decode_primitive(signal_type, reader.read(signal_type.size() as u32))
+        }
+        Type::Struct { attribs, .. } => DecodedValue::Struct(
+            attribs
+                .iter()
+                .map(|(name, attrib_ty)| DecodedAttribute {
+                    name: name.clone(),
+                    value: decode_type(attrib_ty, reader),
+                })
+                .collect(),
+        ),
+        Type::Enum { size, entries, .. } => {
+            let unsigned_value = reader.read(*size as u32);
+            DecodedValue::Enum(
+                entries
+                    .iter()
+                    .find(|(_, value)| value == &unsigned_value)
+                    .map_or("UNDEFINED", |(name, _)| name.as_str())
+                    .to_owned(),
+            )
+        }
+        Type::Array { len, ty } => {
+            DecodedValue::Array((0..*len).map(|_| decode_type(ty, reader)).collect())
+        }
+    }
+}
+
+/// Decodes the value of an object entry from the data of its get response (or set request) fragments.
+/// The value is packed lsb first, struct attributes and array elements are stored consecutively.
+pub fn decode_object_entry(ty: &Type, words: &[u32]) -> DecodedValue {
+    decode_type(ty, &mut BitReader { words, position: 0 })
+}
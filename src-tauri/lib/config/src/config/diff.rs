@@ -0,0 +1,639 @@
+//! Comparison of two network configurations.
+//!
+//! Every change is classified the way [`Network::portable_hash`] sees it:
+//! a change of anything that is part of the hash (names, ids, types, buses, intervals, ...)
+//! is [`Compatibility::Breaking`], because nodes that were built with the old
+//! configuration no longer match the new one. Changes of attributes that are
+//! not hashed (descriptions, heartbeat timeouts, filter banks, ...) are
+//! [`Compatibility::WireCompatible`].
+
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+use super::{
+    bus::BusRef, node::Filter, stream::StreamRef, CommandRef, MessageRef, Network, NodeRef,
+    ObjectEntryRef, SignalRef, SignalType, Type, TypeRef, TypeSignalEncoding,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compatibility {
+    WireCompatible,
+    Breaking,
+}
+
+impl Display for Compatibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Compatibility::WireCompatible => write!(f, "compatible"),
+            Compatibility::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffSubject {
+    Bus,
+    Node,
+    ObjectEntry,
+    Stream,
+    Command,
+    Message,
+    FilterBank,
+}
+
+impl Display for DiffSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffSubject::Bus => write!(f, "bus"),
+            DiffSubject::Node => write!(f, "node"),
+            DiffSubject::ObjectEntry => write!(f, "object entry"),
+            DiffSubject::Stream => write!(f, "stream"),
+            DiffSubject::Command => write!(f, "command"),
+            DiffSubject::Message => write!(f, "message"),
+            DiffSubject::FilterBank => write!(f, "filter bank"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    /// Renamed from the given name, the path of the change refers to the new name.
+    Renamed(String),
+    /// The given attribute changed from `old` to `new`.
+    Modified {
+        attribute: String,
+        old: String,
+        new: String,
+    },
+}
+
+impl Display for ChangeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ChangeKind::Added => write!(f, "added"),
+            ChangeKind::Removed => write!(f, "removed"),
+            ChangeKind::Renamed(from) => write!(f, "renamed from {from}"),
+            ChangeKind::Modified {
+                attribute,
+                old,
+                new,
+            } => write!(f, "{attribute} changed from {old} to {new}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Change {
+    subject: DiffSubject,
+    path: String,
+    kind: ChangeKind,
+    compatibility: Compatibility,
+}
+
+impl Change {
+    pub fn subject(&self) -> DiffSubject {
+        self.subject
+    }
+    /// Path of the changed element, e.g. `secu::position` for an object entry.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+    pub fn kind(&self) -> &ChangeKind {
+        &self.kind
+    }
+    pub fn compatibility(&self) -> Compatibility {
+        self.compatibility
+    }
+    pub fn is_breaking(&self) -> bool {
+        self.compatibility == Compatibility::Breaking
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "[{}] {} {} {}",
+            self.compatibility, self.subject, self.path, self.kind
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkDiff {
+    old_hash: u64,
+    new_hash: u64,
+    changes: Vec<Change>,
+}
+
+impl NetworkDiff {
+    pub fn old_hash(&self) -> u64 {
+        self.old_hash
+    }
+    pub fn new_hash(&self) -> u64 {
+        self.new_hash
+    }
+    pub fn changes(&self) -> &Vec<Change> {
+        &self.changes
+    }
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(Change::is_breaking)
+    }
+}
+
+/// Compares the network configuration `old` against `new`.
+pub fn diff_networks(old: &Network, new: &Network) -> NetworkDiff {
+    let mut diff = Differ::default();
+    diff.buses(old.buses(), new.buses());
+    diff.nodes(old.nodes(), new.nodes());
+    diff.messages(old.messages(), new.messages());
+    NetworkDiff {
+        old_hash: old.portable_hash(),
+        new_hash: new.portable_hash(),
+        changes: diff.changes,
+    }
+}
+
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = seahash::SeaHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn fmt_option<T: Display>(value: Option<T>) -> String {
+    match value {
+        Some(value) => format!("{value}"),
+        None => "none".to_owned(),
+    }
+}
+
+fn fmt_signal_type(ty: &SignalType) -> String {
+    match ty {
+        SignalType::UnsignedInt { size } => format!("u{size}"),
+        SignalType::SignedInt { size } => format!("i{size}"),
+        SignalType::Decimal { size, signed, .. } => {
+            let (min, max) = ty.range();
+            let prefix = if *signed { "sd" } else { "d" };
+            format!("{prefix}{size}<{min}..{max}>")
+        }
+        SignalType::Float { size } => format!("f{size}"),
+        SignalType::Bool => "bool".to_owned(),
+        SignalType::String { len } => format!("string<{len}>"),
+    }
+}
+
+fn fmt_type(ty: &Type) -> String {
+    match ty {
+        Type::Primitive(signal_type) => fmt_signal_type(signal_type),
+        Type::Struct { name, .. } | Type::Enum { name, .. } => name.clone(),
+        Type::Array { len, ty } => format!("{}[{len}]", fmt_type(ty)),
+    }
+}
+
+fn fmt_filters(filters: &[Filter]) -> String {
+    let filters = filters
+        .iter()
+        .map(|filter| {
+            let ide = if filter.ide() { "ext" } else { "std" };
+            format!("{ide}:0x{:X}/0x{:X}", filter.id(), filter.mask())
+        })
+        .collect::<Vec<String>>();
+    format!("[{}]", filters.join(", "))
+}
+
+/// Pairs the elements of `old` and `new` by name. Unpaired elements that share the same
+/// `identity` are considered renamed. Returns the pairs, the removed and the added elements.
+#[allow(clippy::type_complexity)]
+fn pair<'a, T, K: PartialEq>(
+    old: &'a [T],
+    new: &'a [T],
+    name: impl Fn(&T) -> &str,
+    identity: impl Fn(&T) -> Option<K>,
+) -> (Vec<(&'a T, &'a T)>, Vec<&'a T>, Vec<&'a T>) {
+    let mut pairs = vec![];
+    let mut removed = vec![];
+    let mut added: Vec<&T> = new
+        .iter()
+        .filter(|n| !old.iter().any(|o| name(o) == name(n)))
+        .collect();
+    for o in old {
+        if let Some(n) = new.iter().find(|n| name(n) == name(o)) {
+            pairs.push((o, n));
+        } else if let Some(pos) = identity(o)
+            .and_then(|id| added.iter().position(|n| identity(n).as_ref() == Some(&id)))
+        {
+            pairs.push((o, added.remove(pos)));
+        } else {
+            removed.push(o);
+        }
+    }
+    (pairs, removed, added)
+}
+
+#[derive(Default)]
+struct Differ {
+    changes: Vec<Change>,
+}
+
+impl Differ {
+    fn push(&mut self, subject: DiffSubject, path: String, kind: ChangeKind, breaking: bool) {
+        let compatibility = if breaking {
+            Compatibility::Breaking
+        } else {
+            Compatibility::WireCompatible
+        };
+        self.changes.push(Change {
+            subject,
+            path,
+            kind,
+            compatibility,
+        });
+    }
+
+    fn modified<T: PartialEq + Display>(
+        &mut self,
+        subject: DiffSubject,
+        path: &str,
+        attribute: &str,
+        old: T,
+        new: T,
+        breaking: bool,
+    ) {
+        if old != new {
+            self.push(
+                subject,
+                path.to_owned(),
+                ChangeKind::Modified {
+                    attribute: attribute.to_owned(),
+                    old: old.to_string(),
+                    new: new.to_string(),
+                },
+                breaking,
+            );
+        }
+    }
+
+    /// Added, removed and renamed elements always change the hash.
+    fn structural<'a, T>(
+        &mut self,
+        subject: DiffSubject,
+        prefix: &str,
+        name: impl Fn(&T) -> &str,
+        pairs: &[(&'a T, &'a T)],
+        removed: &[&'a T],
+        added: &[&'a T],
+    ) {
+        for o in removed {
+            self.push(subject, format!("{prefix}{}", name(o)), ChangeKind::Removed, true);
+        }
+        for n in added {
+            self.push(subject, format!("{prefix}{}", name(n)), ChangeKind::Added, true);
+        }
+        for (o, n) in pairs {
+            if name(o) != name(n) {
+                self.push(
+                    subject,
+                    format!("{prefix}{}", name(n)),
+                    ChangeKind::Renamed(name(o).to_owned()),
+                    true,
+                );
+            }
+        }
+    }
+
+    fn buses(&mut self, old: &[BusRef], new: &[BusRef]) {
+        let (pairs, removed, added) = pair(old, new, |b| b.name(), |b| Some(b.id()));
+        self.structural(DiffSubject::Bus, "", |b| b.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = n.name();
+            self.modified(DiffSubject::Bus, path, "id", o.id(), n.id(), true);
+            self.modified(DiffSubject::Bus, path, "baudrate", o.baudrate(), n.baudrate(), true);
+            self.modified(
+                DiffSubject::Bus,
+                path,
+                "data_baudrate",
+                fmt_option(o.data_baudrate()),
+                fmt_option(n.data_baudrate()),
+                true,
+            );
+            self.modified(DiffSubject::Bus, path, "brs", o.brs(), n.brs(), true);
+        }
+    }
+
+    fn nodes(&mut self, old: &[NodeRef], new: &[NodeRef]) {
+        let (pairs, removed, added) = pair(old, new, |n| n.name(), |n| Some(n.id()));
+        self.structural(DiffSubject::Node, "", |n| n.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = n.name();
+            self.modified(DiffSubject::Node, path, "id", o.id(), n.id(), true);
+            self.modified(
+                DiffSubject::Node,
+                path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                false,
+            );
+            self.modified(
+                DiffSubject::Node,
+                path,
+                "heartbeat timeout",
+                format!("{:?}", o.heartbeat_timeout()),
+                format!("{:?}", n.heartbeat_timeout()),
+                false,
+            );
+            let bus_names = |node: &NodeRef| {
+                node.buses()
+                    .iter()
+                    .map(|bus| bus.name().to_owned())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            self.modified(
+                DiffSubject::Node,
+                path,
+                "buses",
+                format!("[{}]", bus_names(o)),
+                format!("[{}]", bus_names(n)),
+                false,
+            );
+            self.modified(
+                DiffSubject::FilterBank,
+                path,
+                "filters",
+                fmt_filters(o.filters()),
+                fmt_filters(n.filters()),
+                false,
+            );
+            let prefix = format!("{path}::");
+            self.object_entries(&prefix, o.object_entries(), n.object_entries());
+            self.streams(&prefix, o.tx_streams(), n.tx_streams());
+            self.commands(&prefix, o.commands(), n.commands());
+        }
+    }
+
+    fn object_entries(&mut self, prefix: &str, old: &[ObjectEntryRef], new: &[ObjectEntryRef]) {
+        let (pairs, removed, added) = pair(old, new, |oe| oe.name(), |oe| Some(oe.id()));
+        let subject = DiffSubject::ObjectEntry;
+        self.structural(subject, prefix, |oe| oe.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = format!("{prefix}{}", n.name());
+            self.modified(subject, &path, "id", o.id(), n.id(), true);
+            self.ty(subject, &path, "type", o.ty(), n.ty());
+            self.modified(subject, &path, "unit", fmt_option(o.unit()), fmt_option(n.unit()), true);
+            // the range is enforced by the node itself, so changing it doesn't break the protocol.
+            self.modified(subject, &path, "min", fmt_option(o.min()), fmt_option(n.min()), false);
+            self.modified(subject, &path, "max", fmt_option(o.max()), fmt_option(n.max()), false);
+            self.modified(
+                subject,
+                &path,
+                "default",
+                format!("{:?}", o.default()),
+                format!("{:?}", n.default()),
+                false,
+            );
+            self.modified(subject, &path, "persistent", o.persistent(), n.persistent(), false);
+            self.modified(
+                subject,
+                &path,
+                "access",
+                format!("{:?}", o.access()),
+                format!("{:?}", n.access()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "visibility",
+                format!("{:?}", o.visibility()),
+                format!("{:?}", n.visibility()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                false,
+            );
+            self.modified(
+                subject,
+                &path,
+                "friend",
+                fmt_option(o.friend()),
+                fmt_option(n.friend()),
+                false,
+            );
+        }
+    }
+
+    fn ty(&mut self, subject: DiffSubject, path: &str, attribute: &str, old: &TypeRef, new: &TypeRef) {
+        let (old_name, new_name) = (fmt_type(old), fmt_type(new));
+        if old_name != new_name {
+            self.modified(subject, path, attribute, old_name, new_name, true);
+        } else if hash_of(old.as_ref()) != hash_of(new.as_ref()) {
+            // same name, but the definition of the type changed.
+            self.modified(
+                subject,
+                path,
+                attribute,
+                format!("{old_name} (old definition)"),
+                format!("{new_name} (new definition)"),
+                true,
+            );
+        }
+    }
+
+    fn streams(&mut self, prefix: &str, old: &[StreamRef], new: &[StreamRef]) {
+        let mapping = |stream: &StreamRef| {
+            stream
+                .mapping()
+                .iter()
+                .map(|oe| oe.as_ref().map(|oe| oe.id()))
+                .collect::<Vec<Option<u32>>>()
+        };
+        let mapping = |stream: &StreamRef| Some(mapping(stream));
+        let (pairs, removed, added) = pair(old, new, |s| s.name(), mapping);
+        let subject = DiffSubject::Stream;
+        self.structural(subject, prefix, |s| s.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = format!("{prefix}{}", n.name());
+            let mapping_names = |stream: &StreamRef| {
+                let names = stream
+                    .mapping()
+                    .iter()
+                    .map(|oe| oe.as_ref().map_or("_", |oe| oe.name()))
+                    .collect::<Vec<&str>>();
+                format!("[{}]", names.join(", "))
+            };
+            self.modified(subject, &path, "mapping", mapping_names(o), mapping_names(n), true);
+            self.modified(
+                subject,
+                &path,
+                "interval",
+                format!("{:?}", o.interval()),
+                format!("{:?}", n.interval()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "visibility",
+                format!("{:?}", o.visibility()),
+                format!("{:?}", n.visibility()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                false,
+            );
+        }
+    }
+
+    fn commands(&mut self, prefix: &str, old: &[CommandRef], new: &[CommandRef]) {
+        let signature = |command: &CommandRef| {
+            command
+                .tx_message()
+                .encoding()
+                .map(|encoding| {
+                    encoding
+                        .attributes()
+                        .iter()
+                        .map(|attribute| (attribute.name().to_owned(), fmt_type(attribute.ty())))
+                        .collect::<Vec<(String, String)>>()
+                })
+        };
+        let (pairs, removed, added) = pair(old, new, |c| c.name(), signature);
+        let subject = DiffSubject::Command;
+        self.structural(subject, prefix, |c| c.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = format!("{prefix}{}", n.name());
+            self.modified(
+                subject,
+                &path,
+                "expected interval",
+                format!("{:?}", o.expected_interval()),
+                format!("{:?}", n.expected_interval()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "visibility",
+                format!("{:?}", o.visibility()),
+                format!("{:?}", n.visibility()),
+                true,
+            );
+            self.modified(
+                subject,
+                &path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                false,
+            );
+        }
+    }
+
+    fn messages(&mut self, old: &[MessageRef], new: &[MessageRef]) {
+        // messages are named after their streams and commands, so renames are reported there.
+        let (pairs, removed, added) = pair(old, new, |m| m.name(), |_| None::<()>);
+        let subject = DiffSubject::Message;
+        self.structural(subject, "", |m| m.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = n.name();
+            self.modified(subject, path, "id", o.id().to_string(), n.id().to_string(), true);
+            self.modified(subject, path, "bus", o.bus().name(), n.bus().name(), true);
+            self.modified(subject, path, "dlc", o.dlc(), n.dlc(), true);
+            self.modified(
+                subject,
+                path,
+                "visibility",
+                format!("{:?}", o.visibility()),
+                format!("{:?}", n.visibility()),
+                true,
+            );
+            self.modified(
+                subject,
+                path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                false,
+            );
+            match (o.encoding(), n.encoding()) {
+                (Some(old_encoding), Some(new_encoding)) => {
+                    self.encodings(
+                        path,
+                        old_encoding.attributes(),
+                        new_encoding.attributes(),
+                    );
+                }
+                _ => self.signals(path, o.signals(), n.signals()),
+            }
+        }
+    }
+
+    fn encodings(&mut self, path: &str, old: &[TypeSignalEncoding], new: &[TypeSignalEncoding]) {
+        let (pairs, removed, added) = pair(old, new, |a| a.name(), |_| None::<()>);
+        let prefix = format!("{path}.");
+        let subject = DiffSubject::Message;
+        self.structural(subject, &prefix, |a| a.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = format!("{prefix}{}", n.name());
+            self.ty(subject, &path, "type", o.ty(), n.ty());
+            if hash_of(o) != hash_of(n) && fmt_type(o.ty()) == fmt_type(n.ty()) {
+                // same type, but packed differently.
+                self.modified(subject, &path, "layout", "old", "new", true);
+            }
+        }
+    }
+
+    fn signals(&mut self, path: &str, old: &[SignalRef], new: &[SignalRef]) {
+        let (pairs, removed, added) = pair(old, new, |s| s.name(), |_| None::<()>);
+        let prefix = format!("{path}.");
+        let subject = DiffSubject::Message;
+        self.structural(subject, &prefix, |s| s.name(), &pairs, &removed, &added);
+        for (o, n) in pairs {
+            let path = format!("{prefix}{}", n.name());
+            self.modified(
+                subject,
+                &path,
+                "type",
+                fmt_signal_type(o.ty()),
+                fmt_signal_type(n.ty()),
+                true,
+            );
+            self.modified(subject, &path, "byte offset", o.byte_offset(), n.byte_offset(), true);
+            self.modified(
+                subject,
+                &path,
+                "byte order",
+                format!("{:?}", o.byte_order()),
+                format!("{:?}", n.byte_order()),
+                true,
+            );
+            // signal descriptions are part of the hash of messages without an encoding.
+            self.modified(
+                subject,
+                &path,
+                "description",
+                fmt_option(o.description()),
+                fmt_option(n.description()),
+                true,
+            );
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Encoding of values into frame payloads, the inverse of [super::decode].
+//! Used by the cli and the control panel to transmit commands and set requests.
+
+use super::{
+    decode::{DecodedAttribute, DecodedValue, SignalBits},
+    Message, SignalRef, SignalType, Type, TypeSignalEncoding,
+};
+
+/// Returns the raw bits of a primitive value.
+/// Decimals are rounded and clamped to the range of the signal type,
+/// floats are stored as their IEEE 754 bits.
+fn raw_bits(value: &DecodedValue, signal_type: &SignalType) -> u64 {
+    match (value, signal_type) {
+        (DecodedValue::Real(value), SignalType::Float { size: 32 }) => {
+            (*value as f32).to_bits() as u64
+        }
+        (DecodedValue::Real(value), SignalType::Float { size: _ }) => value.to_bits(),
+        (DecodedValue::Unsigned(value), SignalType::Float { size: _ }) => {
+            raw_bits(&DecodedValue::Real(*value as f64), signal_type)
+        }
+        (DecodedValue::Signed(value), SignalType::Float { size: _ }) => {
+            raw_bits(&DecodedValue::Real(*value as f64), signal_type)
+        }
+        (DecodedValue::Bool(value), _) => *value as u64,
+        (DecodedValue::String(value), SignalType::String { len }) => {
+            // characters beyond the length of the string type are dropped.
+            let mut bytes = [0u8; 8];
+            for (byte, c) in bytes.iter_mut().zip(value.bytes().take(*len as usize)) {
+                *byte = c;
+            }
+            u64::from_le_bytes(bytes)
+        }
+        (DecodedValue::Unsigned(value), _) => *value,
+        (DecodedValue::Signed(value), _) => *value as u64,
+        (
+            DecodedValue::Real(value),
+            SignalType::Decimal {
+                size,
+                offset,
+                scale,
+                signed,
+            },
+        ) => {
+            let base_float = ((value - offset) / scale).round();
+            if *signed {
+                let max_ivalue = u64::MAX.checked_shr(65 - *size as u32).unwrap_or(0) as i64;
+                // two's complement, bits above size are masked by the caller.
+                (base_float as i64).clamp(-max_ivalue - 1, max_ivalue) as u64
+            } else {
+                (base_float as u64).min(u64::MAX >> (64 - *size as u32))
+            }
+        }
+        (DecodedValue::Real(value), _) => value.round() as i64 as u64,
+        _ => panic!("value does not match the primitive type"),
+    }
+}
+
+fn enum_bits(variant: &str, entries: &[(String, u64)]) -> u64 {
+    entries
+        .iter()
+        .find(|(name, _)| name == variant)
+        .map(|(_, value)| *value)
+        .unwrap_or_else(|| panic!("{variant} is not a valid enum variant"))
+}
+
+struct SignalEncoder {
+    bits: SignalBits,
+    ty: SignalType,
+}
+
+impl SignalEncoder {
+    fn new(signal: &SignalRef) -> Self {
+        Self {
+            bits: SignalBits::new(signal),
+            ty: signal.ty().clone(),
+        }
+    }
+
+    fn insert(&self, data: &mut [u64], raw: u64) {
+        self.bits.write(data, raw);
+    }
+}
+
+enum EncodingEncoder {
+    Primitive(SignalEncoder),
+    Enum {
+        entries: Vec<(String, u64)>,
+        signal_encoder: SignalEncoder,
+    },
+    Composite(Vec<(String, EncodingEncoder)>),
+    Array(Vec<EncodingEncoder>),
+}
+
+impl EncodingEncoder {
+    fn new(encoding: &TypeSignalEncoding) -> Self {
+        match encoding {
+            TypeSignalEncoding::Primitive(prim) => match prim.ty() as &Type {
+                Type::Enum { entries, .. } => Self::Enum {
+                    entries: entries.clone(),
+                    signal_encoder: SignalEncoder::new(prim.signal()),
+                },
+                _ => Self::Primitive(SignalEncoder::new(prim.signal())),
+            },
+            TypeSignalEncoding::Composite(comp)
+                if matches!(comp.ty() as &Type, Type::Array { .. }) =>
+            {
+                Self::Array(comp.attributes().iter().map(EncodingEncoder::new).collect())
+            }
+            TypeSignalEncoding::Composite(comp) => Self::Composite(
+                comp.attributes()
+                    .iter()
+                    .map(|attribute| (attribute.name().to_owned(), EncodingEncoder::new(attribute)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn encode(&self, value: &DecodedValue, data: &mut [u64]) {
+        match (self, value) {
+            (Self::Primitive(signal_encoder), value) => {
+                signal_encoder.insert(data, raw_bits(value, &signal_encoder.ty))
+            }
+            (
+                Self::Enum {
+                    entries,
+                    signal_encoder,
+                },
+                DecodedValue::Enum(variant),
+            ) => signal_encoder.insert(data, enum_bits(variant, entries)),
+            (Self::Composite(attribute_encoders), DecodedValue::Struct(attributes)) => {
+                encode_attributes(attribute_encoders, attributes, data)
+            }
+            (Self::Array(element_encoders), DecodedValue::Array(elements)) => {
+                for (encoder, element) in element_encoders.iter().zip(elements) {
+                    encoder.encode(element, data);
+                }
+            }
+            _ => panic!("value does not match the encoding of the message"),
+        }
+    }
+}
+
+fn encode_attributes(
+    attribute_encoders: &[(String, EncodingEncoder)],
+    attributes: &[DecodedAttribute],
+    data: &mut [u64],
+) {
+    for (name, encoder) in attribute_encoders {
+        // missing attributes are transmitted as zeros.
+        if let Some(attribute) = attributes.iter().find(|attribute| &attribute.name == name) {
+            encoder.encode(&attribute.value, data);
+        }
+    }
+}
+
+/// Encodes the attributes of a message into its payload,
+/// based on the encoding of the message or on its signals (e.g. messages imported from DBC files).
+pub struct MessageEncoder {
+    attribute_encoders: Vec<(String, EncodingEncoder)>,
+    words: usize,
+}
+
+impl MessageEncoder {
+    pub fn new(message: &Message) -> Self {
+        let attribute_encoders = match message.encoding() {
+            Some(message_encoding) => message_encoding
+                .attributes()
+                .iter()
+                .map(|encoding| (encoding.name().to_owned(), EncodingEncoder::new(encoding)))
+                .collect(),
+            None => message
+                .signals()
+                .iter()
+                .map(|signal| {
+                    let signal_encoder = SignalEncoder::new(signal);
+                    let encoder = match signal.value_table() {
+                        Some(value_table) => EncodingEncoder::Enum {
+                            entries: value_table.0.clone(),
+                            signal_encoder,
+                        },
+                        None => EncodingEncoder::Primitive(signal_encoder),
+                    };
+                    (signal.name().to_owned(), encoder)
+                })
+                .collect(),
+        };
+        Self {
+            attribute_encoders,
+            words: (message.dlc() as usize).div_ceil(8).max(1),
+        }
+    }
+
+    /// Returns the little endian interpretation of the 8 byte words of the payload
+    /// (one word for messages with up to 8 bytes).
+    pub fn encode(&self, attributes: &[DecodedAttribute]) -> Vec<u64> {
+        let mut data = vec![0; self.words];
+        encode_attributes(&self.attribute_encoders, attributes, &mut data);
+        data
+    }
+}
+
+/// Writes consecutive bits (lsb first) into the fragments of a set request.
+#[derive(Default)]
+struct BitWriter {
+    words: Vec<u32>,
+    len: usize,
+}
+
+impl BitWriter {
+    fn write(&mut self, value: u64, size: u32) {
+        for i in 0..size {
+            if self.len % 32 == 0 {
+                self.words.push(0);
+            }
+            let bit = ((value >> i) & 0x1) as u32;
+            *self.words.last_mut().unwrap() |= bit << (self.len % 32);
+            self.len += 1;
+        }
+    }
+}
+
+fn encode_type(value: &DecodedValue, ty: &Type, writer: &mut BitWriter) {
+    match (value, ty) {
+        (value, Type::Primitive(signal_type)) => {
+            writer.write(raw_bits(value, signal_type), signal_type.size() as u32)
+        }
+        (DecodedValue::Enum(variant), Type::Enum { size, entries, .. }) => {
+            writer.write(enum_bits(variant, entries), *size as u32)
+        }
+        (DecodedValue::Struct(attributes), Type::Struct { attribs, .. }) => {
+            for (attribute, (_, attrib_ty)) in attributes.iter().zip(attribs) {
+                encode_type(&attribute.value, attrib_ty, writer);
+            }
+        }
+        (DecodedValue::Array(elements), Type::Array { len: _, ty }) => {
+            for element in elements {
+                encode_type(element, ty, writer);
+            }
+        }
+        _ => panic!("value does not match the type of the object entry"),
+    }
+}
+
+/// Encodes the value of an object entry into the data of its set request fragments,
+/// the inverse of [super::decode::decode_object_entry].
+/// Returns the fragments and the number of bytes used in the last fragment.
+pub fn encode_object_entry(value: &DecodedValue, ty: &Type) -> (Vec<u32>, u8) {
+    let mut writer = BitWriter::default();
+    encode_type(value, ty, &mut writer);
+    if writer.words.is_empty() {
+        writer.words.push(0);
+    }
+    let last_fill = match writer.len.div_ceil(8) % 4 {
+        0 => 4,
+        fill => fill as u8,
+    };
+    (writer.words, last_fill)
+}
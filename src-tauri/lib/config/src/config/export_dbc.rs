@@ -0,0 +1,324 @@
+use std::{collections::HashMap, fmt::Write};
+
+use super::{
+    bus::BusRef, message::MessageUsage, ByteOrder, MessageId, MessageRef, Network,
+    SignalMultiplexing, SignalRef, SignalSign, SignalType, Type, TypeSignalEncoding,
+};
+
+const NO_NODE: &str = "Vector__XXX";
+
+/// Additional information about a signal that is not part of the signal itself.
+#[derive(Default)]
+struct SignalInfo {
+    unit: Option<String>,
+    value_table: Option<Vec<(String, u64)>>,
+}
+
+fn dbc_id(id: &MessageId) -> u32 {
+    match id {
+        MessageId::StandardId(id) => *id,
+        MessageId::ExtendedId(id) => *id | 0x80000000,
+    }
+}
+
+/// Raw value of a value table entry, values of signed signals are stored in two's complement.
+fn dbc_value(signal: &SignalRef, value: u64) -> String {
+    match signal.sign() {
+        SignalSign::Signed => {
+            let shift = 64 - signal.size() as u32;
+            (((value << shift) as i64) >> shift).to_string()
+        }
+        SignalSign::Unsigned => value.to_string(),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn collect_encoding_info(
+    encoding: &TypeSignalEncoding,
+    unit: Option<&str>,
+    infos: &mut HashMap<String, SignalInfo>,
+) {
+    match encoding {
+        TypeSignalEncoding::Composite(composite) => {
+            for attrib in composite.attributes() {
+                collect_encoding_info(attrib, unit, infos);
+            }
+        }
+        TypeSignalEncoding::Primitive(primitive) => {
+            let info = infos
+                .entry(primitive.signal().name().to_owned())
+                .or_default();
+            info.unit = unit.map(str::to_owned);
+            if let Type::Enum { entries, .. } = primitive.ty().as_ref() {
+                info.value_table = Some(entries.clone());
+            }
+        }
+    }
+}
+
+/// Collects units (from the object entries of streams) and value tables (from enums) of all signals of a message.
+fn signal_infos(message: &MessageRef) -> HashMap<String, SignalInfo> {
+    let mut infos: HashMap<String, SignalInfo> = HashMap::new();
+    for signal in message.signals() {
+        if let Some(value_table) = &signal.value_table {
+            infos.entry(signal.name().to_owned()).or_default().value_table =
+                Some(value_table.0.clone());
+        }
+    }
+    if let Some(encoding) = message.encoding() {
+        let mapping = match message.usage() {
+            MessageUsage::Stream(stream) => Some(stream.mapping()),
+            _ => None,
+        };
+        for (i, attrib) in encoding.attributes().iter().enumerate() {
+            let unit = mapping
+                .and_then(|mapping| mapping.get(i))
+                .and_then(|oe| oe.as_ref())
+                .and_then(|oe| oe.unit());
+            collect_encoding_info(attrib, unit, &mut infos);
+        }
+    }
+    infos
+}
+
+fn cycle_time(message: &MessageRef) -> Option<u128> {
+    match message.usage() {
+        MessageUsage::Stream(stream) => Some(stream.max_interval().as_millis()),
+        MessageUsage::External { interval } => Some(interval.as_millis()),
+        _ => None,
+    }
+}
+
+fn write_signal(
+    dbc: &mut String,
+    signal: &SignalRef,
+    info: Option<&SignalInfo>,
+    receivers: &str,
+) -> std::fmt::Result {
+    let (min, max) = signal.ty().range();
+    let sign = match signal.sign() {
+        SignalSign::Signed => '-',
+        SignalSign::Unsigned => '+',
+    };
+    let byte_order = match signal.byte_order() {
+        ByteOrder::LittleEndian => '1',
+        ByteOrder::BigEndian => '0',
+    };
+    let multiplexing = match signal.multiplexing() {
+        Some(SignalMultiplexing::Multiplexor) => " M".to_owned(),
+        Some(SignalMultiplexing::Multiplexed(value)) => format!(" m{value}"),
+        None => String::new(),
+    };
+    let unit = info
+        .and_then(|info| info.unit.as_deref())
+        .map(escape)
+        .unwrap_or_default();
+    writeln!(
+        dbc,
+        " SG_ {}{multiplexing} : {}|{}@{byte_order}{sign} ({},{}) [{min}|{max}] \"{unit}\" {receivers}",
+        signal.name(),
+        signal.byte_offset(),
+        signal.size(),
+        signal.scale(),
+        signal.offset(),
+    )
+}
+
+/// Generates a DBC file describing all messages of `bus`.
+///
+/// Messages are written with their assigned ids, signals keep their byte order and multiplexing.
+/// Enums are exported as value tables and the object entry units of streams as signal units.
+pub fn export_dbc(network: &Network, bus: &BusRef) -> String {
+    let mut dbc = String::new();
+    // writing into a String never fails.
+    write_dbc(&mut dbc, network, bus).expect("Failed to write DBC");
+    dbc
+}
+
+fn write_dbc(dbc: &mut String, network: &Network, bus: &BusRef) -> std::fmt::Result {
+    let nodes: Vec<_> = network
+        .nodes()
+        .iter()
+        .filter(|node| node.buses().iter().any(|b| b.id() == bus.id()))
+        .collect();
+    let messages: Vec<&MessageRef> = network
+        .messages()
+        .iter()
+        .filter(|msg| msg.bus().id() == bus.id())
+        .collect();
+
+    writeln!(dbc, "VERSION \"{}\"", network.build_time().format("%Y-%m-%d %H:%M:%S"))?;
+    writeln!(dbc)?;
+    writeln!(dbc, "NS_ :")?;
+    for ns in ["CM_", "BA_DEF_", "BA_", "VAL_", "BA_DEF_DEF_", "BO_TX_BU_"] {
+        writeln!(dbc, "\t{ns}")?;
+    }
+    writeln!(dbc)?;
+    writeln!(dbc, "BS_: {}", bus.baudrate() / 1000)?;
+    writeln!(dbc)?;
+    writeln!(
+        dbc,
+        "BU_: {}",
+        nodes
+            .iter()
+            .map(|node| node.name())
+            .collect::<Vec<&str>>()
+            .join(" ")
+    )?;
+    writeln!(dbc)?;
+
+    let mut transmitters_of = vec![];
+    let mut infos_of = vec![];
+    for message in &messages {
+        let transmitters: Vec<&str> = nodes
+            .iter()
+            .filter(|node| node.tx_messages().iter().any(|m| m.name() == message.name()))
+            .map(|node| node.name())
+            .collect();
+        let receivers: Vec<&str> = nodes
+            .iter()
+            .filter(|node| node.rx_messages().iter().any(|m| m.name() == message.name()))
+            .map(|node| node.name())
+            .collect();
+        let receivers = if receivers.is_empty() {
+            NO_NODE.to_owned()
+        } else {
+            receivers.join(",")
+        };
+        let infos = signal_infos(message);
+
+        writeln!(
+            dbc,
+            "BO_ {} {}: {} {}",
+            dbc_id(message.id()),
+            message.name(),
+            message.dlc(),
+            transmitters.first().copied().unwrap_or(NO_NODE)
+        )?;
+        for signal in message.signals() {
+            write_signal(dbc, signal, infos.get(signal.name()), &receivers)?;
+        }
+        writeln!(dbc)?;
+        transmitters_of.push(transmitters);
+        infos_of.push(infos);
+    }
+
+    for (message, transmitters) in messages.iter().zip(transmitters_of.iter()) {
+        if transmitters.len() > 1 {
+            writeln!(
+                dbc,
+                "BO_TX_BU_ {} : {};",
+                dbc_id(message.id()),
+                transmitters.join(",")
+            )?;
+        }
+    }
+    writeln!(dbc)?;
+
+    for node in &nodes {
+        if let Some(description) = node.description() {
+            writeln!(dbc, "CM_ BU_ {} \"{}\";", node.name(), escape(description))?;
+        }
+    }
+    for message in &messages {
+        if let Some(description) = message.description() {
+            writeln!(
+                dbc,
+                "CM_ BO_ {} \"{}\";",
+                dbc_id(message.id()),
+                escape(description)
+            )?;
+        }
+        for signal in message.signals() {
+            if let Some(description) = signal.description() {
+                writeln!(
+                    dbc,
+                    "CM_ SG_ {} {} \"{}\";",
+                    dbc_id(message.id()),
+                    signal.name(),
+                    escape(description)
+                )?;
+            }
+        }
+    }
+    writeln!(dbc)?;
+
+    writeln!(dbc, "BA_DEF_ BO_ \"GenMsgCycleTime\" INT 0 65535;")?;
+    if bus.is_fd() {
+        // the frame formats of CAN FD frames are the values 14 and 15 of the vector enum.
+        writeln!(
+            dbc,
+            "BA_DEF_ BO_ \"VFrameFormat\" ENUM \"StandardCAN\",\"ExtendedCAN\",{}\"StandardCAN_FD\",\"ExtendedCAN_FD\";",
+            "\"reserved\",".repeat(12)
+        )?;
+        writeln!(dbc, "BA_DEF_ \"BusType\" STRING ;")?;
+    } else {
+        writeln!(dbc, "BA_DEF_ BO_ \"VFrameFormat\" ENUM \"StandardCAN\",\"ExtendedCAN\";")?;
+    }
+    writeln!(dbc, "BA_DEF_DEF_ \"GenMsgCycleTime\" 0;")?;
+    writeln!(dbc, "BA_DEF_DEF_ \"VFrameFormat\" \"StandardCAN\";")?;
+    if bus.is_fd() {
+        writeln!(dbc, "BA_DEF_DEF_ \"BusType\" \"\";")?;
+        writeln!(dbc, "BA_ \"BusType\" \"CAN FD\";")?;
+    }
+    for message in &messages {
+        if let Some(cycle_time) = cycle_time(message) {
+            writeln!(
+                dbc,
+                "BA_ \"GenMsgCycleTime\" BO_ {} {cycle_time};",
+                dbc_id(message.id())
+            )?;
+        }
+        let frame_format = match (bus.is_fd(), message.id().ide()) {
+            (false, false) => None,
+            (false, true) => Some(1),
+            (true, false) => Some(14),
+            (true, true) => Some(15),
+        };
+        if let Some(frame_format) = frame_format {
+            writeln!(
+                dbc,
+                "BA_ \"VFrameFormat\" BO_ {} {frame_format};",
+                dbc_id(message.id())
+            )?;
+        }
+    }
+    writeln!(dbc)?;
+
+    for (message, infos) in messages.iter().zip(infos_of.iter()) {
+        for signal in message.signals() {
+            let Some(value_table) = infos
+                .get(signal.name())
+                .and_then(|info| info.value_table.as_ref())
+            else {
+                continue;
+            };
+            write!(dbc, "VAL_ {} {}", dbc_id(message.id()), signal.name())?;
+            for (name, value) in value_table {
+                write!(dbc, " {} \"{}\"", dbc_value(signal, *value), escape(name))?;
+            }
+            writeln!(dbc, " ;")?;
+        }
+    }
+
+    // signals are integers unless declared as IEEE floats.
+    for message in &messages {
+        for signal in message.signals() {
+            let value_type = match signal.ty() {
+                SignalType::Float { size: 32 } => 1,
+                SignalType::Float { size: _ } => 2,
+                _ => continue,
+            };
+            writeln!(
+                dbc,
+                "SIG_VALTYPE_ {} {} : {value_type};",
+                dbc_id(message.id()),
+                signal.name()
+            )?;
+        }
+    }
+    Ok(())
+}
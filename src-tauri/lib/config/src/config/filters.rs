@@ -0,0 +1,199 @@
+//! Acceptance of the messages of a network by the filters of its nodes.
+//!
+//! A node only sees the messages on its buses. Messages, which pass its filters
+//! without being received by the node, are false positives, which the node has to
+//! drop in software. Messages, which the node receives, but which do not pass its
+//! filters, never reach the node and fail the build.
+
+use std::fmt::Display;
+
+use super::{Message, MessageRef, Network, Node, NodeRef};
+
+/// How the filters of a node handle a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reception {
+    /// the node receives the message and its filters accept it.
+    Accepted,
+    /// the node receives the message, but its filters reject it.
+    Dropped,
+    /// the node does not receive the message, but its filters accept it.
+    Unnecessary,
+    /// the node does not receive the message and its filters reject it.
+    Rejected,
+    /// the message is not on a bus of the node.
+    OffBus,
+}
+
+pub fn reception(node: &Node, message: &Message) -> Reception {
+    if !node.buses().iter().any(|bus| bus.id() == message.bus().id()) {
+        return Reception::OffBus;
+    }
+    let required = node.rx_messages().iter().any(|rx| rx.name() == message.name());
+    let accepted = node.filters().iter().any(|filter| filter.accepts(message.id()));
+    match (required, accepted) {
+        (true, true) => Reception::Accepted,
+        (true, false) => Reception::Dropped,
+        (false, true) => Reception::Unnecessary,
+        (false, false) => Reception::Rejected,
+    }
+}
+
+/// Filter statistics of a node over the messages on its buses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FilterStats {
+    /// messages on the buses of the node, which the node does not receive.
+    pub unwanted: usize,
+    /// unwanted messages, which pass the filters of the node.
+    pub accepted_unwanted: usize,
+}
+
+impl FilterStats {
+    fn from_receptions(receptions: impl Iterator<Item = Reception>) -> Self {
+        let mut stats = FilterStats {
+            unwanted: 0,
+            accepted_unwanted: 0,
+        };
+        for reception in receptions {
+            match reception {
+                Reception::Unnecessary => {
+                    stats.unwanted += 1;
+                    stats.accepted_unwanted += 1;
+                }
+                Reception::Rejected => stats.unwanted += 1,
+                _ => (),
+            }
+        }
+        stats
+    }
+
+    /// Share of the unwanted messages, which pass the filters (0 if there are none).
+    pub fn false_positive_rate(&self) -> f64 {
+        if self.unwanted == 0 {
+            0.0
+        } else {
+            self.accepted_unwanted as f64 / self.unwanted as f64
+        }
+    }
+}
+
+pub fn node_filter_stats(network: &Network, node: &Node) -> FilterStats {
+    FilterStats::from_receptions(
+        network
+            .messages()
+            .iter()
+            .map(|message| reception(node, message)),
+    )
+}
+
+/// Reception of every message of a network by every node.
+pub struct FilterReport {
+    nodes: Vec<NodeRef>,
+    messages: Vec<MessageRef>,
+    /// indexed by message, then by node.
+    receptions: Vec<Vec<Reception>>,
+}
+
+impl FilterReport {
+    pub fn new(network: &Network) -> Self {
+        let receptions = network
+            .messages()
+            .iter()
+            .map(|message| {
+                network
+                    .nodes()
+                    .iter()
+                    .map(|node| reception(node, message))
+                    .collect()
+            })
+            .collect();
+        Self {
+            nodes: network.nodes().clone(),
+            messages: network.messages().clone(),
+            receptions,
+        }
+    }
+    pub fn nodes(&self) -> &Vec<NodeRef> {
+        &self.nodes
+    }
+    pub fn messages(&self) -> &Vec<MessageRef> {
+        &self.messages
+    }
+    fn with_reception(&self, reception: Reception) -> Vec<(&NodeRef, &MessageRef)> {
+        let mut pairs = vec![];
+        for (message, receptions) in std::iter::zip(&self.messages, &self.receptions) {
+            for (node, r) in std::iter::zip(&self.nodes, receptions) {
+                if *r == reception {
+                    pairs.push((node, message));
+                }
+            }
+        }
+        pairs
+    }
+    /// Messages, which a node receives, but which do not pass its filters.
+    pub fn dropped(&self) -> Vec<(&NodeRef, &MessageRef)> {
+        self.with_reception(Reception::Dropped)
+    }
+    /// Messages, which pass the filters of a node, without being received by it.
+    pub fn unnecessary(&self) -> Vec<(&NodeRef, &MessageRef)> {
+        self.with_reception(Reception::Unnecessary)
+    }
+    pub fn node_stats(&self, node_index: usize) -> FilterStats {
+        FilterStats::from_receptions(
+            self.receptions
+                .iter()
+                .map(|receptions| receptions[node_index]),
+        )
+    }
+}
+
+/// Matrix of the messages, which are dropped (`!`) or received unnecessarily (`x`) by any node,
+/// followed by the false positive rate of every node.
+impl Display for FilterReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<usize> = (0..self.messages.len())
+            .filter(|m| {
+                self.receptions[*m]
+                    .iter()
+                    .any(|r| matches!(r, Reception::Dropped | Reception::Unnecessary))
+            })
+            .collect();
+        if rows.is_empty() {
+            return writeln!(f, "no message is dropped or received unnecessarily");
+        }
+        let name_width = rows
+            .iter()
+            .map(|m| self.messages[*m].name().len())
+            .max()
+            .unwrap_or(0)
+            .max("false positives".len());
+        let widths: Vec<usize> = self
+            .nodes
+            .iter()
+            .map(|node| node.name().len().max(6))
+            .collect();
+
+        write!(f, "{:name_width$}", "")?;
+        for (node, width) in std::iter::zip(&self.nodes, &widths) {
+            write!(f, "  {:>width$}", node.name())?;
+        }
+        writeln!(f)?;
+        for m in rows {
+            write!(f, "{:name_width$}", self.messages[m].name())?;
+            for (r, width) in std::iter::zip(&self.receptions[m], &widths) {
+                let cell = match r {
+                    Reception::Dropped => "!",
+                    Reception::Unnecessary => "x",
+                    _ => "",
+                };
+                write!(f, "  {cell:>width$}")?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "{:name_width$}", "false positives")?;
+        for (n, width) in widths.iter().enumerate() {
+            let rate = format!("{:.1}%", self.node_stats(n).false_positive_rate() * 100.0);
+            write!(f, "  {rate:>width$}")?;
+        }
+        writeln!(f)
+    }
+}
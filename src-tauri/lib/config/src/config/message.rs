@@ -1,4 +1,4 @@
-use std::{fmt::Display, hash::Hash, sync::OnceLock, time::Duration};
+use std::{fmt::Display, hash::{Hash, Hasher}, sync::OnceLock, time::Duration};
 
 use super::{ConfigRef, MessageEncoding, SignalRef, Visibility, bus::BusRef, stream::StreamRef, CommandRef};
 
@@ -93,6 +93,15 @@ impl Hash for Message {
 }
 
 
+impl Message {
+    /// Hash of the message as seen on the wire (name, id, encoding, dlc and bus).
+    pub fn portable_hash(&self) -> u64 {
+        let mut hasher = seahash::SeaHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
 impl Message {
     pub fn new(name : String,
                description : Option<String>,
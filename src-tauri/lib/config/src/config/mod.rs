@@ -5,15 +5,22 @@ pub use self::command::CommandRef;
 pub use self::encoding::MessageEncoding;
 pub use self::encoding::TypeSignalEncoding;
 pub use self::message::MessageId;
+pub use self::message::MessageUsage;
 pub use self::message::Message;
 pub use self::message::MessageRef;
 pub use self::network::Network;
 pub use self::network::NetworkRef;
+pub use self::node::CanController;
+pub use self::node::Filter;
+pub use self::node::FilterSetup;
 pub use self::node::Node;
 pub use self::node::NodeRef;
 pub use self::object_entry::ObjectEntryAccess;
 pub use self::object_entry::ObjectEntry;
 pub use self::object_entry::ObjectEntryRef;
+pub use self::object_entry::SAVE_PARAMETERS_COMMAND;
+pub use self::signal::ByteOrder;
+pub use self::signal::SignalMultiplexing;
 pub use self::signal::SignalSign;
 pub use self::signal::SignalType;
 pub use self::signal::SignalRef;
@@ -34,6 +41,14 @@ pub mod stream;
 pub mod types;
 pub mod visibility;
 pub mod bus;
+pub mod export_dbc;
+pub mod decode;
+pub mod encode;
+pub mod diff;
+pub mod bus_load;
+pub mod response_time;
+pub mod serialize;
+pub mod filters;
 
 pub type ConfigRef<T> = Arc<T>;
 
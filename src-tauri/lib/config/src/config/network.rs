@@ -119,6 +119,10 @@ impl Display for Network {
         for bus in &self.buses {
             writeln!(f, "{s2}{}", bus.id())?;
             writeln!(f, "{s3}baudrate : {}", bus.baudrate())?;
+            if let Some(data_baudrate) = bus.data_baudrate() {
+                writeln!(f, "{s3}data_baudrate : {data_baudrate}")?;
+                writeln!(f, "{s3}brs : {}", bus.brs())?;
+            }
         }
         writeln!(f, "{s1}types:")?;
         for ty in &self.types {
@@ -194,9 +198,14 @@ impl Display for Network {
                                 size,
                                 offset,
                                 scale,
+                                signed,
                             } => {
-                                write!(f, "d{size}<offset={offset}, scale={scale}>")?;
+                                let prefix = if *signed { "sd" } else { "d" };
+                                write!(f, "{prefix}{size}<offset={offset}, scale={scale}>")?;
                             }
+                            SignalType::Float { size } => write!(f, "f{size}")?,
+                            SignalType::Bool => write!(f, "bool")?,
+                            SignalType::String { len } => write!(f, "string<{len}>")?,
                         },
                         Type::Struct {
                             name,
@@ -1,7 +1,11 @@
-use std::{hash::Hash, time::Duration};
+use std::{
+    hash::{Hash, Hasher},
+    time::Duration,
+};
 
 use super::{
-    bus::BusRef, stream::StreamRef, CommandRef, ConfigRef, MessageRef, ObjectEntryRef, TypeRef,
+    bus::BusRef, stream::StreamRef, CommandRef, ConfigRef, MessageId, MessageRef,
+    ObjectEntryRef, TypeRef,
 };
 
 pub type NodeRef = ConfigRef<Node>;
@@ -27,6 +31,87 @@ pub struct Node {
     buses: Vec<BusRef>,
 
     heartbeat_timeout : Duration,
+
+    filter_setup: FilterSetup,
+}
+
+/// Acceptance filters of a node and the limits they have to fit into.
+#[derive(Debug, Clone)]
+pub struct FilterSetup {
+    pub filters: Vec<Filter>,
+    pub controller: Option<CanController>,
+    /// Overrides the filter count of the controller.
+    pub max_filters: Option<usize>,
+}
+
+/// CAN controller of a node, determines how many acceptance filters are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CanController {
+    /// bxCAN of the STM32F families, 14 filter banks in 32 bit mask mode.
+    BxCan,
+    /// FlexCAN of the NXP i.MX RT families, 32 individual masks.
+    FlexCan,
+    /// FDCAN of the STM32G4 and STM32H7 families, 28 standard id filters.
+    FdCan,
+    /// Bosch M_CAN, 128 standard id filters.
+    MCan,
+}
+
+impl CanController {
+    pub const ALL: [CanController; 4] = [
+        CanController::BxCan,
+        CanController::FlexCan,
+        CanController::FdCan,
+        CanController::MCan,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            CanController::BxCan => "bxcan",
+            CanController::FlexCan => "flexcan",
+            CanController::FdCan => "fdcan",
+            CanController::MCan => "mcan",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|controller| controller.name() == name.to_lowercase())
+    }
+    /// Number of id/mask filters of the controller.
+    pub fn filter_count(&self) -> usize {
+        match self {
+            CanController::BxCan => 14,
+            CanController::FlexCan => 32,
+            CanController::FdCan => 28,
+            CanController::MCan => 128,
+        }
+    }
+}
+
+/// Acceptance filter of a node, a frame is accepted iff `frame_id & mask == id & mask`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Filter {
+    id: u32,
+    mask: u32,
+    ide: bool,
+}
+
+impl Filter {
+    pub fn new(id: u32, mask: u32, ide: bool) -> Self {
+        Self { id, mask, ide }
+    }
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+    pub fn mask(&self) -> u32 {
+        self.mask
+    }
+    pub fn ide(&self) -> bool {
+        self.ide
+    }
+    pub fn accepts(&self, id: &MessageId) -> bool {
+        self.ide == id.ide() && id.as_u32() & self.mask == self.id & self.mask
+    }
 }
 
 impl Hash for Node {
@@ -56,6 +141,39 @@ impl Hash for Node {
     }
 }
 
+impl Node {
+    /// Hash of the part of the network this node sees: its object dictionary, commands,
+    /// streams and the ids, encodings and buses of the messages it sends and receives.
+    /// Unlike [`super::Network::portable_hash`] it does not change if an unrelated node changes.
+    pub fn portable_hash(&self) -> u64 {
+        let mut hasher = seahash::SeaHasher::new();
+        self.hash(&mut hasher);
+        for m in &self.tx_messages {
+            m.hash(&mut hasher);
+        }
+        for m in &self.rx_messages {
+            m.hash(&mut hasher);
+        }
+        for b in &self.buses {
+            b.hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Hash of the names and types of the persistent object entries,
+    /// changes whenever the layout of the stored parameter block changes.
+    pub fn parameter_layout_hash(&self) -> u64 {
+        let mut hasher = seahash::SeaHasher::new();
+        for oe in self.object_entries.iter().filter(|oe| oe.persistent()) {
+            for b in oe.name().bytes() {
+                hasher.write_u8(b);
+            }
+            oe.ty().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+}
+
 impl Node {
     pub fn new(
         name: String,
@@ -71,6 +189,7 @@ impl Node {
         object_entries: Vec<ObjectEntryRef>,
         buses: Vec<BusRef>,
         heartbeat_timeout : Duration,
+        filter_setup: FilterSetup,
     ) -> Self {
         Self {
             name,
@@ -86,6 +205,7 @@ impl Node {
             object_entries,
             buses,
             heartbeat_timeout,
+            filter_setup,
         }
     }
 
@@ -137,4 +257,17 @@ impl Node {
     pub fn buses(&self) -> &Vec<BusRef> {
         &self.buses
     }
+    pub fn filters(&self) -> &Vec<Filter> {
+        &self.filter_setup.filters
+    }
+    pub fn controller(&self) -> Option<CanController> {
+        self.filter_setup.controller
+    }
+    /// Maximum number of filters of the node, defaults to the filter count of its controller.
+    /// `None` if neither is configured.
+    pub fn max_filters(&self) -> Option<usize> {
+        self.filter_setup
+            .max_filters
+            .or(self.filter_setup.controller.map(|controller| controller.filter_count()))
+    }
 }
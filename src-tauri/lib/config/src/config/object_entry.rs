@@ -1,9 +1,12 @@
 use std::{hash::Hash, sync::OnceLock};
 
-use super::{ConfigRef, NodeRef, TypeRef, Visibility};
+use super::{decode::DecodedValue, ConfigRef, NodeRef, TypeRef, Visibility};
 
 pub type ObjectEntryRef = ConfigRef<ObjectEntry>;
 
+/// Command, which is implicitly added to nodes with persistent object entries.
+pub const SAVE_PARAMETERS_COMMAND: &str = "save_parameters";
+
 #[derive(Debug, Clone)]
 pub enum ObjectEntryAccess {
     Const,  // no write
@@ -31,9 +34,40 @@ pub struct ObjectEntry {
     ty: TypeRef,
     access: ObjectEntryAccess,
     visibility: Visibility,
+    /// valid range of numeric object entries (physical value).
+    min: Option<f64>,
+    max: Option<f64>,
+    /// initial value of the object entry.
+    default: Option<DecodedValue>,
+    /// stored in non-volatile memory and restored on startup.
+    persistent: bool,
     node: OnceLock<NodeRef>,
 }
 
+fn hash_value<H: std::hash::Hasher>(value: &DecodedValue, state: &mut H) {
+    match value {
+        DecodedValue::Unsigned(v) => state.write_u64(*v),
+        DecodedValue::Signed(v) => state.write_i64(*v),
+        DecodedValue::Real(v) => state.write_u64(v.to_bits()),
+        DecodedValue::Bool(v) => state.write_u8(*v as u8),
+        DecodedValue::String(v) | DecodedValue::Enum(v) => {
+            for b in v.bytes() {
+                state.write_u8(b);
+            }
+        }
+        DecodedValue::Struct(attributes) => {
+            for attribute in attributes {
+                hash_value(&attribute.value, state);
+            }
+        }
+        DecodedValue::Array(elements) => {
+            for element in elements {
+                hash_value(element, state);
+            }
+        }
+    }
+}
+
 impl Hash for ObjectEntry {
     fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
         for b in self.name.bytes() {
@@ -52,6 +86,23 @@ impl Hash for ObjectEntry {
         self.ty.hash(state);
         self.access.hash(state);
         self.visibility.hash(state);
+        for bound in [self.min, self.max] {
+            match bound {
+                Some(bound) => {
+                    state.write_u8(1);
+                    state.write_u64(bound.to_bits());
+                }
+                None => state.write_u8(0),
+            }
+        }
+        match &self.default {
+            Some(default) => {
+                state.write_u8(1);
+                hash_value(default, state);
+            }
+            None => state.write_u8(0),
+        }
+        state.write_u8(self.persistent as u8);
     }
 }
 
@@ -65,6 +116,10 @@ impl ObjectEntry {
         ty: TypeRef,
         access: ObjectEntryAccess,
         visibility: Visibility,
+        min: Option<f64>,
+        max: Option<f64>,
+        default: Option<DecodedValue>,
+        persistent: bool,
     ) -> Self {
         Self {
             name,
@@ -75,6 +130,10 @@ impl ObjectEntry {
             ty,
             access,
             visibility,
+            min,
+            max,
+            default,
+            persistent,
             node: OnceLock::new(),
         }
     }
@@ -96,12 +155,31 @@ impl ObjectEntry {
     pub fn access(&self) -> &ObjectEntryAccess {
         &self.access
     }
+    pub fn visibility(&self) -> &Visibility {
+        &self.visibility
+    }
     pub fn unit(&self) -> Option<&str> {
         match &self.unit {
             Some(unit) => Some(&unit),
             None => None,
         }
     }
+    pub fn min(&self) -> Option<f64> {
+        self.min
+    }
+    pub fn max(&self) -> Option<f64> {
+        self.max
+    }
+    /// true if the (physical) value is within `min` and `max`.
+    pub fn in_range(&self, value: f64) -> bool {
+        self.min.map_or(true, |min| value >= min) && self.max.map_or(true, |max| value <= max)
+    }
+    pub fn default(&self) -> Option<&DecodedValue> {
+        self.default.as_ref()
+    }
+    pub fn persistent(&self) -> bool {
+        self.persistent
+    }
     pub fn friend(&self) -> Option<&str> {
         self.friend.as_ref().map(|f| f.as_str())
     }
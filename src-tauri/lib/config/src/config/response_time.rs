@@ -0,0 +1,157 @@
+//! Worst-case response time analysis of the messages of a network configuration.
+//!
+//! Classic CAN response time analysis (Tindell et al.) with the sufficient test of
+//! Davis et al. (2007): a message is blocked by at most one frame of lower or equal
+//! priority and interfered by every frame of higher priority that is queued within its
+//! busy period. The traffic of every message is the worst-case traffic of [`super::bus_load`],
+//! frames contain the maximum number of stuff bits.
+
+use std::{sync::Arc, time::Duration};
+
+use super::{
+    bus::BusRef,
+    bus_load::{bus_frame_bits, message_traffic, Traffic},
+    MessageId, MessageRef, MessageUsage, Network,
+};
+
+#[derive(Debug, Clone)]
+pub struct MessageResponseTime {
+    message: MessageRef,
+    response_time: Option<Duration>,
+    deadline: Option<Duration>,
+}
+
+impl MessageResponseTime {
+    pub fn message(&self) -> &MessageRef {
+        &self.message
+    }
+    /// Worst-case time from queuing a frame until it is received,
+    /// `None` if the busy period does not end (bus overloaded).
+    pub fn response_time(&self) -> Option<Duration> {
+        self.response_time
+    }
+    /// Time within which the message has to be received, the max interval of streams
+    /// and the expected interval of commands. Other messages have no deadline.
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline
+    }
+    pub fn misses_deadline(&self) -> bool {
+        match (self.response_time, self.deadline) {
+            (None, _) => true,
+            (Some(response_time), Some(deadline)) => response_time > deadline,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Order of the arbitration on the bus, smaller wins. Standard frames win against
+/// extended frames with the same base id.
+fn arbitration_key(id: &MessageId) -> (u32, u32, u32) {
+    match id {
+        MessageId::StandardId(id) => (*id, 0, 0),
+        MessageId::ExtendedId(id) => (id >> 18, 1, id & 0x3FFFF),
+    }
+}
+
+fn transmission_bits(message: &MessageRef) -> u64 {
+    bus_frame_bits(message.bus(), message.dlc(), message.id().ide(), true) as u64
+}
+
+fn deadline(message: &MessageRef) -> Option<Duration> {
+    match message.usage() {
+        MessageUsage::Stream(stream) => Some(*stream.max_interval()),
+        MessageUsage::CommandReq(command) | MessageUsage::CommandResp(command) => {
+            Some(*command.expected_interval())
+        }
+        _ => None,
+    }
+}
+
+/// Worst-case response time in bit times of `message` on a bus with the given traffic.
+fn response_bits(message: &MessageRef, traffic: &[Traffic], baudrate: u32) -> Option<u64> {
+    let key = arbitration_key(message.id());
+    let bits = traffic
+        .iter()
+        .filter(|t| Arc::ptr_eq(&t.message, message))
+        .map(|t| t.worst_case_bits() as u64)
+        .max()
+        .unwrap_or_else(|| transmission_bits(message));
+    let blocking = traffic
+        .iter()
+        .filter(|t| arbitration_key(t.message.id()) >= key)
+        .map(|t| t.worst_case_bits() as u64)
+        .max()
+        .unwrap_or(bits);
+
+    // (transmission bits, period in bit times, frames per period) of interfering traffic.
+    // Frames of the same message that are sent by other nodes (or other fragments)
+    // are treated like frames of higher priority.
+    let mut skipped_own_frame = false;
+    let interference: Vec<(u64, f64, u64)> = traffic
+        .iter()
+        .filter_map(|t| {
+            let t_key = arbitration_key(t.message.id());
+            let mut frames = t.frames as u64;
+            if Arc::ptr_eq(&t.message, message) && !skipped_own_frame {
+                skipped_own_frame = true;
+                frames -= 1;
+            } else if t_key >= key && !Arc::ptr_eq(&t.message, message) {
+                return None;
+            }
+            let period = t.interval.as_secs_f64() * baudrate as f64;
+            (frames != 0).then_some((t.worst_case_bits() as u64, period, frames))
+        })
+        .collect();
+
+    // the busy period can't end if it exceeds one second.
+    let limit = baudrate as u64;
+    let mut queuing = blocking;
+    loop {
+        let next = blocking
+            + interference
+                .iter()
+                .map(|(bits, period, frames)| {
+                    ((queuing + 1) as f64 / period).ceil() as u64 * frames * bits
+                })
+                .sum::<u64>();
+        if next + bits > limit {
+            return None;
+        }
+        if next == queuing {
+            return Some(queuing + bits);
+        }
+        queuing = next;
+    }
+}
+
+fn analyze_bus(network: &Network, bus: &BusRef) -> Vec<MessageResponseTime> {
+    let mut messages: Vec<&MessageRef> = network
+        .messages()
+        .iter()
+        .filter(|message| message.bus().id() == bus.id())
+        .collect();
+    messages.sort_by_key(|message| arbitration_key(message.id()));
+    let traffic: Vec<Traffic> = messages
+        .iter()
+        .flat_map(|message| message_traffic(network, message))
+        .collect();
+    let bit_time = 1.0 / bus.baudrate() as f64;
+    messages
+        .into_iter()
+        .map(|message| MessageResponseTime {
+            message: message.clone(),
+            response_time: response_bits(message, &traffic, bus.baudrate())
+                .map(|bits| Duration::from_secs_f64(bits as f64 * bit_time)),
+            deadline: deadline(message),
+        })
+        .collect()
+}
+
+/// Worst-case response times of all messages, grouped by bus and sorted by priority.
+pub fn analyze_response_times(network: &Network) -> Vec<(BusRef, Vec<MessageResponseTime>)> {
+    network
+        .buses()
+        .iter()
+        .map(|bus| (bus.clone(), analyze_bus(network, bus)))
+        .collect()
+}
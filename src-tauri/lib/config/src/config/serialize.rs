@@ -0,0 +1,1047 @@
+//! Canonical serialized form of a resolved [Network].
+//!
+//! Unlike the configuration, the serialized network contains everything the builder resolved:
+//! message ids, buses, acceptance filters, signal layouts and encodings. Loading it does not
+//! run the message resolution again and yields a network with the same portable hash.
+//!
+//! Objects which are shared between nodes and messages (types, object entries, streams and
+//! commands) are stored once and referenced by their index, durations are given in microseconds.
+//! The json form is meant to be read by tools written in other languages, see [SERIALIZATION_VERSION].
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::{ConfigError, Result};
+
+use super::{
+    bus::{Bus, BusRef},
+    decode::{DecodedAttribute, DecodedValue},
+    encoding::{CompositeSignalEncoding, PrimitiveSignalEncoding},
+    make_config_ref,
+    signal::Signal,
+    stream::Stream,
+    ByteOrder, CanController, Command, CommandRef, Filter, FilterSetup, Message,
+    MessageEncoding, MessageId, MessageRef, MessageUsage, Network, NetworkRef, Node, ObjectEntry,
+    ObjectEntryAccess, ObjectEntryRef, SignalMultiplexing, SignalRef, SignalType, Type, TypeRef,
+    TypeSignalEncoding, ValueTable, Visibility,
+};
+
+/// Version of the serialized form, incremented whenever the form changes incompatibly.
+pub const SERIALIZATION_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedNetwork {
+    pub version: u32,
+    /// rfc 3339 timestamp.
+    pub build_time: String,
+    pub buses: Vec<SerializedBus>,
+    /// every type only refers to types before it.
+    pub types: Vec<SerializedType>,
+    pub messages: Vec<SerializedMessage>,
+    pub object_entries: Vec<SerializedObjectEntry>,
+    pub streams: Vec<SerializedStream>,
+    pub commands: Vec<SerializedCommand>,
+    pub nodes: Vec<SerializedNode>,
+    /// types of the network, i.e. all user defined types.
+    pub network_types: Vec<usize>,
+    pub get_req_message: usize,
+    pub get_resp_message: usize,
+    pub set_req_message: usize,
+    pub set_resp_message: usize,
+    pub heartbeat_messages: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedBus {
+    pub name: String,
+    pub id: u32,
+    pub baudrate: u32,
+    /// only present for CAN FD buses.
+    pub data_baudrate: Option<u32>,
+    pub brs: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerializedVisibility {
+    Global,
+    Static,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SerializedSignalType {
+    UnsignedInt { size: u8 },
+    SignedInt { size: u8 },
+    Decimal { size: u8, offset: f64, scale: f64, signed: bool },
+    Float { size: u8 },
+    Bool,
+    String { len: u8 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SerializedType {
+    Primitive {
+        signal_type: SerializedSignalType,
+    },
+    Struct {
+        name: String,
+        description: Option<String>,
+        attributes: Vec<(String, usize)>,
+        visibility: SerializedVisibility,
+    },
+    Enum {
+        name: String,
+        description: Option<String>,
+        size: u8,
+        entries: Vec<(String, u64)>,
+        visibility: SerializedVisibility,
+    },
+    Array {
+        len: usize,
+        ty: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SerializedMultiplexing {
+    Multiplexor,
+    Multiplexed { value: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedSignal {
+    pub name: String,
+    pub description: Option<String>,
+    pub signal_type: SerializedSignalType,
+    pub value_table: Option<Vec<(String, u64)>>,
+    /// bit offset of the signal within the payload.
+    pub offset: usize,
+    pub big_endian: bool,
+    pub multiplexing: Option<SerializedMultiplexing>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SerializedEncoding {
+    Composite {
+        name: String,
+        ty: usize,
+        attributes: Vec<SerializedEncoding>,
+    },
+    /// `signal` is the index of the signal within the message.
+    Primitive { name: String, ty: usize, signal: usize },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SerializedUsage {
+    Stream { stream: usize },
+    CommandReq { command: usize },
+    CommandResp { command: usize },
+    GetResp,
+    GetReq,
+    SetResp,
+    SetReq,
+    Heartbeat,
+    External { interval_us: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedMessage {
+    pub name: String,
+    pub description: Option<String>,
+    pub id: u32,
+    pub ide: bool,
+    pub dlc: u8,
+    pub bus: usize,
+    pub visibility: SerializedVisibility,
+    pub signals: Vec<SerializedSignal>,
+    /// `None` for messages, which only consist of signals (e.g. imported from DBC files).
+    pub encoding: Option<Vec<SerializedEncoding>>,
+    pub usage: SerializedUsage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerializedAccess {
+    Const,
+    Local,
+    Global,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum SerializedValue {
+    Unsigned(u64),
+    Signed(i64),
+    Real(f64),
+    Bool(bool),
+    String(String),
+    Enum(String),
+    Struct(Vec<(String, SerializedValue)>),
+    Array(Vec<SerializedValue>),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedObjectEntry {
+    pub name: String,
+    pub description: Option<String>,
+    pub friend: Option<String>,
+    pub unit: Option<String>,
+    pub id: u32,
+    pub ty: usize,
+    pub access: SerializedAccess,
+    pub visibility: SerializedVisibility,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub default: Option<SerializedValue>,
+    pub persistent: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedStream {
+    pub name: String,
+    pub description: Option<String>,
+    /// object entries of the signals of the message, `None` for unmapped signals.
+    pub mapping: Vec<Option<usize>>,
+    pub message: usize,
+    pub visibility: SerializedVisibility,
+    pub min_interval_us: u64,
+    pub max_interval_us: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedCommand {
+    pub name: String,
+    pub description: Option<String>,
+    pub tx_message: usize,
+    pub rx_message: usize,
+    pub visibility: SerializedVisibility,
+    pub expected_interval_us: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedFilter {
+    pub id: u32,
+    pub mask: u32,
+    pub ide: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SerializedNode {
+    pub name: String,
+    pub description: Option<String>,
+    pub id: u8,
+    pub types: Vec<usize>,
+    pub commands: Vec<usize>,
+    /// commands of other nodes, which this node calls, with the name of their node.
+    pub extern_commands: Vec<(String, usize)>,
+    pub tx_streams: Vec<usize>,
+    pub rx_streams: Vec<usize>,
+    pub rx_messages: Vec<usize>,
+    pub tx_messages: Vec<usize>,
+    pub object_entries: Vec<usize>,
+    pub buses: Vec<usize>,
+    pub heartbeat_timeout_us: u64,
+    pub filters: Vec<SerializedFilter>,
+    /// name of the CAN controller, see [CanController::name].
+    pub controller: Option<String>,
+    pub max_filters: Option<usize>,
+}
+
+fn micros(duration: &Duration) -> u64 {
+    duration.as_micros() as u64
+}
+
+fn visibility_of(visibility: &Visibility) -> SerializedVisibility {
+    match visibility {
+        Visibility::Global => SerializedVisibility::Global,
+        Visibility::Static => SerializedVisibility::Static,
+    }
+}
+
+fn visibility_from(visibility: SerializedVisibility) -> Visibility {
+    match visibility {
+        SerializedVisibility::Global => Visibility::Global,
+        SerializedVisibility::Static => Visibility::Static,
+    }
+}
+
+fn signal_type_of(signal_type: &SignalType) -> SerializedSignalType {
+    match *signal_type {
+        SignalType::UnsignedInt { size } => SerializedSignalType::UnsignedInt { size },
+        SignalType::SignedInt { size } => SerializedSignalType::SignedInt { size },
+        SignalType::Decimal {
+            size,
+            offset,
+            scale,
+            signed,
+        } => SerializedSignalType::Decimal {
+            size,
+            offset,
+            scale,
+            signed,
+        },
+        SignalType::Float { size } => SerializedSignalType::Float { size },
+        SignalType::Bool => SerializedSignalType::Bool,
+        SignalType::String { len } => SerializedSignalType::String { len },
+    }
+}
+
+fn signal_type_from(signal_type: &SerializedSignalType) -> SignalType {
+    match *signal_type {
+        SerializedSignalType::UnsignedInt { size } => SignalType::UnsignedInt { size },
+        SerializedSignalType::SignedInt { size } => SignalType::SignedInt { size },
+        SerializedSignalType::Decimal {
+            size,
+            offset,
+            scale,
+            signed,
+        } => SignalType::Decimal {
+            size,
+            offset,
+            scale,
+            signed,
+        },
+        SerializedSignalType::Float { size } => SignalType::Float { size },
+        SerializedSignalType::Bool => SignalType::Bool,
+        SerializedSignalType::String { len } => SignalType::String { len },
+    }
+}
+
+fn value_of(value: &DecodedValue) -> SerializedValue {
+    match value {
+        DecodedValue::Unsigned(v) => SerializedValue::Unsigned(*v),
+        DecodedValue::Signed(v) => SerializedValue::Signed(*v),
+        DecodedValue::Real(v) => SerializedValue::Real(*v),
+        DecodedValue::Bool(v) => SerializedValue::Bool(*v),
+        DecodedValue::String(v) => SerializedValue::String(v.clone()),
+        DecodedValue::Enum(v) => SerializedValue::Enum(v.clone()),
+        DecodedValue::Struct(attributes) => SerializedValue::Struct(
+            attributes
+                .iter()
+                .map(|attribute| (attribute.name.clone(), value_of(&attribute.value)))
+                .collect(),
+        ),
+        DecodedValue::Array(elements) => {
+            SerializedValue::Array(elements.iter().map(value_of).collect())
+        }
+    }
+}
+
+fn value_from(value: &SerializedValue) -> DecodedValue {
+    match value {
+        SerializedValue::Unsigned(v) => DecodedValue::Unsigned(*v),
+        SerializedValue::Signed(v) => DecodedValue::Signed(*v),
+        SerializedValue::Real(v) => DecodedValue::Real(*v),
+        SerializedValue::Bool(v) => DecodedValue::Bool(*v),
+        SerializedValue::String(v) => DecodedValue::String(v.clone()),
+        SerializedValue::Enum(v) => DecodedValue::Enum(v.clone()),
+        SerializedValue::Struct(attributes) => DecodedValue::Struct(
+            attributes
+                .iter()
+                .map(|(name, value)| DecodedAttribute {
+                    name: name.clone(),
+                    value: value_from(value),
+                })
+                .collect(),
+        ),
+        SerializedValue::Array(elements) => {
+            DecodedValue::Array(elements.iter().map(value_from).collect())
+        }
+    }
+}
+
+/// Indices of the objects of a network, objects are identified by their address.
+struct Indices<T> {
+    indices: HashMap<*const T, usize>,
+}
+
+impl<T> Indices<T> {
+    fn new() -> Self {
+        Self {
+            indices: HashMap::new(),
+        }
+    }
+    /// Returns the index of `value` and whether it was newly inserted.
+    fn insert(&mut self, value: &T) -> (usize, bool) {
+        let len = self.indices.len();
+        match self.indices.get(&(value as *const T)) {
+            Some(index) => (*index, false),
+            None => {
+                self.indices.insert(value as *const T, len);
+                (len, true)
+            }
+        }
+    }
+    fn get(&self, value: &T, what: &str) -> usize {
+        *self
+            .indices
+            .get(&(value as *const T))
+            .unwrap_or_else(|| panic!("{what} is not part of the network"))
+    }
+}
+
+struct Serializer {
+    type_indices: Indices<Type>,
+    types: Vec<SerializedType>,
+}
+
+impl Serializer {
+    fn ty(&mut self, ty: &TypeRef) -> usize {
+        if let Some(index) = self.type_indices.indices.get(&Arc::as_ptr(ty)) {
+            return *index;
+        }
+        // referenced types are serialized first.
+        let serialized = match &**ty {
+            Type::Primitive(signal_type) => SerializedType::Primitive {
+                signal_type: signal_type_of(signal_type),
+            },
+            Type::Struct {
+                name,
+                description,
+                attribs,
+                visibility,
+            } => SerializedType::Struct {
+                name: name.clone(),
+                description: description.clone(),
+                attributes: attribs
+                    .iter()
+                    .map(|(name, ty)| (name.clone(), self.ty(ty)))
+                    .collect(),
+                visibility: visibility_of(visibility),
+            },
+            Type::Enum {
+                name,
+                description,
+                size,
+                entries,
+                visibility,
+            } => SerializedType::Enum {
+                name: name.clone(),
+                description: description.clone(),
+                size: *size,
+                entries: entries.clone(),
+                visibility: visibility_of(visibility),
+            },
+            Type::Array { len, ty } => SerializedType::Array {
+                len: *len,
+                ty: self.ty(ty),
+            },
+        };
+        let (index, _) = self.type_indices.insert(ty);
+        self.types.push(serialized);
+        index
+    }
+
+    fn encoding(&mut self, encoding: &TypeSignalEncoding, signals: &[SignalRef]) -> SerializedEncoding {
+        match encoding {
+            TypeSignalEncoding::Composite(composite) => SerializedEncoding::Composite {
+                name: composite.name().to_owned(),
+                ty: self.ty(composite.ty()),
+                attributes: composite
+                    .attributes()
+                    .iter()
+                    .map(|attribute| self.encoding(attribute, signals))
+                    .collect(),
+            },
+            TypeSignalEncoding::Primitive(primitive) => SerializedEncoding::Primitive {
+                name: primitive.name().to_owned(),
+                ty: self.ty(primitive.ty()),
+                signal: signals
+                    .iter()
+                    .position(|signal| Arc::ptr_eq(signal, primitive.signal()))
+                    .expect("encoded signal is not a signal of the message"),
+            },
+        }
+    }
+}
+
+fn signal_of(signal: &Signal) -> SerializedSignal {
+    SerializedSignal {
+        name: signal.name().to_owned(),
+        description: signal.description().map(str::to_owned),
+        signal_type: signal_type_of(signal.ty()),
+        value_table: signal.value_table().map(|table| table.0.clone()),
+        offset: signal.offset,
+        big_endian: signal.byte_order() == ByteOrder::BigEndian,
+        multiplexing: signal.multiplexing().map(|multiplexing| match multiplexing {
+            SignalMultiplexing::Multiplexor => SerializedMultiplexing::Multiplexor,
+            SignalMultiplexing::Multiplexed(value) => {
+                SerializedMultiplexing::Multiplexed { value: *value }
+            }
+        }),
+    }
+}
+
+impl SerializedNetwork {
+    pub fn from_network(network: &Network) -> Self {
+        let mut serializer = Serializer {
+            type_indices: Indices::new(),
+            types: vec![],
+        };
+        let network_types = network
+            .types()
+            .iter()
+            .map(|ty| serializer.ty(ty))
+            .collect();
+
+        let bus_index = |bus: &BusRef| {
+            network
+                .buses()
+                .iter()
+                .position(|b| b.id() == bus.id())
+                .expect("bus is not part of the network")
+        };
+
+        let mut message_indices = Indices::<Message>::new();
+        for message in network.messages() {
+            message_indices.insert(message);
+        }
+
+        let mut oe_indices = Indices::<ObjectEntry>::new();
+        let mut object_entries = vec![];
+        for node in network.nodes() {
+            for oe in node.object_entries() {
+                if !oe_indices.insert(oe).1 {
+                    continue;
+                }
+                object_entries.push(SerializedObjectEntry {
+                    name: oe.name().to_owned(),
+                    description: oe.description().map(str::to_owned),
+                    friend: oe.friend().map(str::to_owned),
+                    unit: oe.unit().map(str::to_owned),
+                    id: oe.id(),
+                    ty: serializer.ty(oe.ty()),
+                    access: match oe.access() {
+                        ObjectEntryAccess::Const => SerializedAccess::Const,
+                        ObjectEntryAccess::Local => SerializedAccess::Local,
+                        ObjectEntryAccess::Global => SerializedAccess::Global,
+                    },
+                    visibility: visibility_of(oe.visibility()),
+                    min: oe.min(),
+                    max: oe.max(),
+                    default: oe.default().map(value_of),
+                    persistent: oe.persistent(),
+                });
+            }
+        }
+
+        let mut stream_indices = Indices::<Stream>::new();
+        let mut streams = vec![];
+        for node in network.nodes() {
+            for stream in node.tx_streams().iter().chain(node.rx_streams()) {
+                if !stream_indices.insert(stream).1 {
+                    continue;
+                }
+                streams.push(SerializedStream {
+                    name: stream.name().to_owned(),
+                    description: stream.description().map(str::to_owned),
+                    mapping: stream
+                        .mapping()
+                        .iter()
+                        .map(|oe| oe.as_ref().map(|oe| oe_indices.get(oe, "object entry")))
+                        .collect(),
+                    message: message_indices.get(stream.message(), "message"),
+                    visibility: visibility_of(stream.visibility()),
+                    min_interval_us: micros(stream.min_interval()),
+                    max_interval_us: micros(stream.max_interval()),
+                });
+            }
+        }
+
+        let mut command_indices = Indices::<Command>::new();
+        let mut commands = vec![];
+        for node in network.nodes() {
+            let extern_commands = node.extern_commands().iter().map(|(_, command)| command);
+            for command in node.commands().iter().chain(extern_commands) {
+                if !command_indices.insert(command).1 {
+                    continue;
+                }
+                commands.push(SerializedCommand {
+                    name: command.name().to_owned(),
+                    description: command.description().cloned(),
+                    tx_message: message_indices.get(command.tx_message(), "message"),
+                    rx_message: message_indices.get(command.rx_message(), "message"),
+                    visibility: visibility_of(command.visibility()),
+                    expected_interval_us: micros(command.expected_interval()),
+                });
+            }
+        }
+
+        let messages = network
+            .messages()
+            .iter()
+            .map(|message| SerializedMessage {
+                name: message.name().to_owned(),
+                description: message.description().map(str::to_owned),
+                id: message.id().as_u32(),
+                ide: message.id().ide(),
+                dlc: message.dlc(),
+                bus: bus_index(message.bus()),
+                visibility: visibility_of(message.visibility()),
+                signals: message.signals().iter().map(|s| signal_of(s)).collect(),
+                encoding: message.encoding().map(|encoding| {
+                    encoding
+                        .attributes()
+                        .iter()
+                        .map(|attribute| serializer.encoding(attribute, message.signals()))
+                        .collect()
+                }),
+                usage: match message.usage() {
+                    MessageUsage::Stream(stream) => SerializedUsage::Stream {
+                        stream: stream_indices.get(stream, "stream"),
+                    },
+                    MessageUsage::CommandReq(command) => SerializedUsage::CommandReq {
+                        command: command_indices.get(command, "command"),
+                    },
+                    MessageUsage::CommandResp(command) => SerializedUsage::CommandResp {
+                        command: command_indices.get(command, "command"),
+                    },
+                    MessageUsage::GetResp => SerializedUsage::GetResp,
+                    MessageUsage::GetReq => SerializedUsage::GetReq,
+                    MessageUsage::SetResp => SerializedUsage::SetResp,
+                    MessageUsage::SetReq => SerializedUsage::SetReq,
+                    MessageUsage::Heartbeat => SerializedUsage::Heartbeat,
+                    MessageUsage::External { interval } => SerializedUsage::External {
+                        interval_us: micros(interval),
+                    },
+                },
+            })
+            .collect();
+
+        let nodes = network
+            .nodes()
+            .iter()
+            .map(|node| SerializedNode {
+                name: node.name().to_owned(),
+                description: node.description().cloned(),
+                id: node.id(),
+                types: node.types().iter().map(|ty| serializer.ty(ty)).collect(),
+                commands: node
+                    .commands()
+                    .iter()
+                    .map(|command| command_indices.get(command, "command"))
+                    .collect(),
+                extern_commands: node
+                    .extern_commands()
+                    .iter()
+                    .map(|(node_name, command)| {
+                        (node_name.clone(), command_indices.get(command, "command"))
+                    })
+                    .collect(),
+                tx_streams: node
+                    .tx_streams()
+                    .iter()
+                    .map(|stream| stream_indices.get(stream, "stream"))
+                    .collect(),
+                rx_streams: node
+                    .rx_streams()
+                    .iter()
+                    .map(|stream| stream_indices.get(stream, "stream"))
+                    .collect(),
+                rx_messages: node
+                    .rx_messages()
+                    .iter()
+                    .map(|message| message_indices.get(message, "message"))
+                    .collect(),
+                tx_messages: node
+                    .tx_messages()
+                    .iter()
+                    .map(|message| message_indices.get(message, "message"))
+                    .collect(),
+                object_entries: node
+                    .object_entries()
+                    .iter()
+                    .map(|oe| oe_indices.get(oe, "object entry"))
+                    .collect(),
+                buses: node.buses().iter().map(bus_index).collect(),
+                heartbeat_timeout_us: micros(&node.heartbeat_timeout()),
+                filters: node
+                    .filters()
+                    .iter()
+                    .map(|filter| SerializedFilter {
+                        id: filter.id(),
+                        mask: filter.mask(),
+                        ide: filter.ide(),
+                    })
+                    .collect(),
+                controller: node
+                    .controller()
+                    .map(|controller| controller.name().to_owned()),
+                max_filters: node.max_filters(),
+            })
+            .collect();
+
+        SerializedNetwork {
+            version: SERIALIZATION_VERSION,
+            build_time: network.build_time().to_rfc3339(),
+            buses: network
+                .buses()
+                .iter()
+                .map(|bus| SerializedBus {
+                    name: bus.name().to_owned(),
+                    id: bus.id(),
+                    baudrate: bus.baudrate(),
+                    data_baudrate: bus.data_baudrate(),
+                    brs: bus.brs(),
+                })
+                .collect(),
+            types: serializer.types,
+            messages,
+            object_entries,
+            streams,
+            commands,
+            nodes,
+            network_types,
+            get_req_message: message_indices.get(network.get_req_message(), "message"),
+            get_resp_message: message_indices.get(network.get_resp_message(), "message"),
+            set_req_message: message_indices.get(network.set_req_message(), "message"),
+            set_resp_message: message_indices.get(network.set_resp_message(), "message"),
+            heartbeat_messages: network
+                .heartbeat_messages()
+                .iter()
+                .map(|message| message_indices.get(message, "message"))
+                .collect(),
+        }
+    }
+
+    /// Rebuilds the network, fails if an index does not refer to an object.
+    pub fn to_network(&self) -> Result<NetworkRef> {
+        if self.version != SERIALIZATION_VERSION {
+            return Err(ConfigError::Serialization(format!(
+                "unsupported version {}, expected {SERIALIZATION_VERSION}",
+                self.version
+            )));
+        }
+        let build_time = chrono::DateTime::parse_from_rfc3339(&self.build_time)
+            .map_err(|err| ConfigError::Serialization(format!("invalid build time : {err}")))?
+            .with_timezone(&chrono::Local);
+
+        let buses: Vec<BusRef> = self
+            .buses
+            .iter()
+            .map(|bus| {
+                make_config_ref(match bus.data_baudrate {
+                    Some(data_baudrate) => {
+                        Bus::new_fd(&bus.name, bus.id, bus.baudrate, data_baudrate, bus.brs)
+                    }
+                    None => Bus::new(&bus.name, bus.id, bus.baudrate),
+                })
+            })
+            .collect();
+
+        let mut types: Vec<TypeRef> = vec![];
+        for ty in &self.types {
+            let ty = match ty {
+                SerializedType::Primitive { signal_type } => {
+                    Type::Primitive(signal_type_from(signal_type))
+                }
+                SerializedType::Struct {
+                    name,
+                    description,
+                    attributes,
+                    visibility,
+                } => Type::Struct {
+                    name: name.clone(),
+                    description: description.clone(),
+                    attribs: attributes
+                        .iter()
+                        .map(|(name, ty)| Ok((name.clone(), lookup(&types, *ty, "type")?.clone())))
+                        .collect::<Result<_>>()?,
+                    visibility: visibility_from(*visibility),
+                },
+                SerializedType::Enum {
+                    name,
+                    description,
+                    size,
+                    entries,
+                    visibility,
+                } => Type::Enum {
+                    name: name.clone(),
+                    description: description.clone(),
+                    size: *size,
+                    entries: entries.clone(),
+                    visibility: visibility_from(*visibility),
+                },
+                SerializedType::Array { len, ty } => Type::Array {
+                    len: *len,
+                    ty: lookup(&types, *ty, "type")?.clone(),
+                },
+            };
+            types.push(make_config_ref(ty));
+        }
+
+        let mut messages: Vec<MessageRef> = vec![];
+        for message in &self.messages {
+            let signals: Vec<SignalRef> = message
+                .signals
+                .iter()
+                .map(|signal| {
+                    make_config_ref(Signal {
+                        name: signal.name.clone(),
+                        description: signal.description.clone(),
+                        ty: signal_type_from(&signal.signal_type),
+                        value_table: signal
+                            .value_table
+                            .clone()
+                            .map(|table| make_config_ref(ValueTable(table))),
+                        offset: signal.offset,
+                        byte_order: if signal.big_endian {
+                            ByteOrder::BigEndian
+                        } else {
+                            ByteOrder::LittleEndian
+                        },
+                        multiplexing: signal.multiplexing.as_ref().map(|multiplexing| {
+                            match multiplexing {
+                                SerializedMultiplexing::Multiplexor => {
+                                    SignalMultiplexing::Multiplexor
+                                }
+                                SerializedMultiplexing::Multiplexed { value } => {
+                                    SignalMultiplexing::Multiplexed(*value)
+                                }
+                            }
+                        }),
+                    })
+                })
+                .collect();
+            let encoding = match &message.encoding {
+                Some(attributes) => Some(MessageEncoding::new(
+                    attributes
+                        .iter()
+                        .map(|attribute| encoding_from(attribute, &types, &signals))
+                        .collect::<Result<_>>()?,
+                )),
+                None => None,
+            };
+            let id = if message.ide {
+                MessageId::ExtendedId(message.id)
+            } else {
+                MessageId::StandardId(message.id)
+            };
+            messages.push(make_config_ref(Message::new(
+                message.name.clone(),
+                message.description.clone(),
+                id,
+                encoding,
+                signals,
+                visibility_from(message.visibility),
+                message.dlc,
+                lookup(&buses, message.bus, "bus")?.clone(),
+            )));
+        }
+
+        let mut object_entries: Vec<ObjectEntryRef> = vec![];
+        for oe in &self.object_entries {
+            object_entries.push(make_config_ref(ObjectEntry::new(
+                oe.name.clone(),
+                oe.description.clone(),
+                oe.friend.clone(),
+                oe.unit.clone(),
+                oe.id,
+                lookup(&types, oe.ty, "type")?.clone(),
+                match oe.access {
+                    SerializedAccess::Const => ObjectEntryAccess::Const,
+                    SerializedAccess::Local => ObjectEntryAccess::Local,
+                    SerializedAccess::Global => ObjectEntryAccess::Global,
+                },
+                visibility_from(oe.visibility),
+                oe.min,
+                oe.max,
+                oe.default.as_ref().map(value_from),
+                oe.persistent,
+            )));
+        }
+
+        let mut streams = vec![];
+        for stream in &self.streams {
+            let mapping = stream
+                .mapping
+                .iter()
+                .map(|oe| match oe {
+                    Some(oe) => Ok(Some(lookup(&object_entries, *oe, "object entry")?.clone())),
+                    None => Ok(None),
+                })
+                .collect::<Result<_>>()?;
+            streams.push(make_config_ref(Stream::new(
+                stream.name.clone(),
+                stream.description.clone(),
+                mapping,
+                lookup(&messages, stream.message, "message")?.clone(),
+                visibility_from(stream.visibility),
+                (
+                    Duration::from_micros(stream.min_interval_us),
+                    Duration::from_micros(stream.max_interval_us),
+                ),
+            )));
+        }
+
+        let mut commands: Vec<CommandRef> = vec![];
+        for command in &self.commands {
+            commands.push(make_config_ref(Command::new(
+                command.name.clone(),
+                command.description.clone(),
+                lookup(&messages, command.tx_message, "message")?.clone(),
+                lookup(&messages, command.rx_message, "message")?.clone(),
+                visibility_from(command.visibility),
+                Duration::from_micros(command.expected_interval_us),
+            )));
+        }
+
+        for (message, serialized) in messages.iter().zip(&self.messages) {
+            message.__set_usage(match &serialized.usage {
+                SerializedUsage::Stream { stream } => {
+                    MessageUsage::Stream(lookup(&streams, *stream, "stream")?.clone())
+                }
+                SerializedUsage::CommandReq { command } => {
+                    MessageUsage::CommandReq(lookup(&commands, *command, "command")?.clone())
+                }
+                SerializedUsage::CommandResp { command } => {
+                    MessageUsage::CommandResp(lookup(&commands, *command, "command")?.clone())
+                }
+                SerializedUsage::GetResp => MessageUsage::GetResp,
+                SerializedUsage::GetReq => MessageUsage::GetReq,
+                SerializedUsage::SetResp => MessageUsage::SetResp,
+                SerializedUsage::SetReq => MessageUsage::SetReq,
+                SerializedUsage::Heartbeat => MessageUsage::Heartbeat,
+                SerializedUsage::External { interval_us } => MessageUsage::External {
+                    interval: Duration::from_micros(*interval_us),
+                },
+            });
+        }
+
+        let mut nodes = vec![];
+        let mut has_node = vec![false; object_entries.len()];
+        for node in &self.nodes {
+            let extern_commands = node
+                .extern_commands
+                .iter()
+                .map(|(node_name, command)| {
+                    Ok((node_name.clone(), lookup(&commands, *command, "command")?.clone()))
+                })
+                .collect::<Result<_>>()?;
+            let controller = match &node.controller {
+                Some(name) => Some(CanController::from_name(name).ok_or_else(|| {
+                    ConfigError::Serialization(format!("unknown controller {name}"))
+                })?),
+                None => None,
+            };
+            let node_ref = make_config_ref(Node::new(
+                node.name.clone(),
+                node.description.clone(),
+                node.id,
+                lookup_all(&node.types, &types, "type")?,
+                lookup_all(&node.commands, &commands, "command")?,
+                extern_commands,
+                lookup_all(&node.tx_streams, &streams, "stream")?,
+                lookup_all(&node.rx_streams, &streams, "stream")?,
+                lookup_all(&node.rx_messages, &messages, "message")?,
+                lookup_all(&node.tx_messages, &messages, "message")?,
+                lookup_all(&node.object_entries, &object_entries, "object entry")?,
+                lookup_all(&node.buses, &buses, "bus")?,
+                Duration::from_micros(node.heartbeat_timeout_us),
+                FilterSetup {
+                    filters: node
+                        .filters
+                        .iter()
+                        .map(|filter| Filter::new(filter.id, filter.mask, filter.ide))
+                        .collect(),
+                    controller,
+                    max_filters: node.max_filters,
+                },
+            ));
+            for oe in &node.object_entries {
+                if std::mem::replace(&mut has_node[*oe], true) {
+                    return Err(ConfigError::Serialization(format!(
+                        "object entry {} belongs to multiple nodes",
+                        object_entries[*oe].name()
+                    )));
+                }
+                object_entries[*oe].__set_node(node_ref.clone());
+            }
+            nodes.push(node_ref);
+        }
+
+        Ok(make_config_ref(Network::new(
+            build_time,
+            nodes,
+            messages.clone(),
+            self.network_types
+                .iter()
+                .map(|ty| lookup(&types, *ty, "type").cloned())
+                .collect::<Result<_>>()?,
+            lookup(&messages, self.get_req_message, "message")?.clone(),
+            lookup(&messages, self.get_resp_message, "message")?.clone(),
+            lookup(&messages, self.set_req_message, "message")?.clone(),
+            lookup(&messages, self.set_resp_message, "message")?.clone(),
+            self.heartbeat_messages
+                .iter()
+                .map(|message| lookup(&messages, *message, "message").cloned())
+                .collect::<Result<_>>()?,
+            buses,
+        )))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("failed to serialize the network")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).map_err(|err| ConfigError::Serialization(err.to_string()))
+    }
+}
+
+fn lookup<'a, T>(table: &'a [T], index: usize, what: &str) -> Result<&'a T> {
+    table
+        .get(index)
+        .ok_or_else(|| ConfigError::Serialization(format!("invalid {what} index {index}")))
+}
+
+fn lookup_all<T: Clone>(indices: &[usize], table: &[T], what: &str) -> Result<Vec<T>> {
+    indices
+        .iter()
+        .map(|index| lookup(table, *index, what).cloned())
+        .collect()
+}
+
+fn encoding_from(
+    encoding: &SerializedEncoding,
+    types: &[TypeRef],
+    signals: &[SignalRef],
+) -> Result<TypeSignalEncoding> {
+    Ok(match encoding {
+        SerializedEncoding::Composite {
+            name,
+            ty,
+            attributes,
+        } => TypeSignalEncoding::Composite(CompositeSignalEncoding::new(
+            name.clone(),
+            attributes
+                .iter()
+                .map(|attribute| encoding_from(attribute, types, signals))
+                .collect::<Result<_>>()?,
+            lookup(types, *ty, "type")?.clone(),
+        )),
+        SerializedEncoding::Primitive { name, ty, signal } => {
+            TypeSignalEncoding::Primitive(PrimitiveSignalEncoding::new(
+                name.clone(),
+                lookup(types, *ty, "type")?.clone(),
+                lookup(signals, *signal, "signal")?.clone(),
+            ))
+        }
+    })
+}
+
+/// Canonical json form of `network`.
+pub fn network_to_json(network: &Network) -> String {
+    SerializedNetwork::from_network(network).to_json()
+}
+
+/// Loads a network from its canonical json form (see [network_to_json]).
+pub fn network_from_json(json: &str) -> Result<NetworkRef> {
+    SerializedNetwork::from_json(json)?.to_network()
+}
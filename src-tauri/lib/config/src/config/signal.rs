@@ -32,7 +32,48 @@ impl Display for SignalSign {
 pub enum SignalType {
     UnsignedInt { size: u8 },
     SignedInt { size: u8 },
-    Decimal { size: u8, offset: f64, scale: f64 },
+    Decimal { size: u8, offset: f64, scale: f64, signed: bool },
+    /// IEEE 754 floating point number with 32 or 64 bits.
+    Float { size: u8 },
+    Bool,
+    /// ASCII string with a fixed number of characters (at most 8), shorter strings are
+    /// padded with zeros. The first character is stored in the lowest byte.
+    String { len: u8 },
+}
+
+/// Maximum number of characters of a [SignalType::String], all characters fit into one signal.
+pub const MAX_STRING_LEN: u8 = 8;
+
+/// Order in which the bytes of a signal are packed into the frame.
+/// Motorola (big endian) signals are addressed by their most significant bit
+/// in the sawtooth bit numbering of DBC files.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    pub const ALL: [ByteOrder; 2] = [ByteOrder::LittleEndian, ByteOrder::BigEndian];
+    pub fn name(&self) -> &'static str {
+        match self {
+            ByteOrder::LittleEndian => "little_endian",
+            ByteOrder::BigEndian => "big_endian",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|byte_order| byte_order.name() == name.to_lowercase())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignalMultiplexing {
+    /// The value of this signal selects which multiplexed signals are present.
+    Multiplexor,
+    /// The signal is only present if the multiplexor of the message has this value.
+    Multiplexed(u64),
 }
 
 impl Hash for SignalType {
@@ -46,11 +87,24 @@ impl Hash for SignalType {
                 state.write_u8(1);
                 state.write_u128(*size as u128);
             },
-            SignalType::Decimal { size, offset, scale } => {
+            SignalType::Decimal { size, offset, scale, signed } => {
                 state.write_u8(2);
                 state.write_u128(*size as u128);
                 ((*offset * 1e4) as u128).hash(state);
                 ((*scale * 1e4) as u128).hash(state);
+                // unsigned decimals keep the hash they had before signed decimals existed.
+                if *signed {
+                    state.write_u8(0);
+                }
+            }
+            SignalType::Float { size } => {
+                state.write_u8(3);
+                state.write_u128(*size as u128);
+            }
+            SignalType::Bool => state.write_u8(4),
+            SignalType::String { len } => {
+                state.write_u8(5);
+                state.write_u128(*len as u128);
             }
         }
     }
@@ -65,7 +119,9 @@ impl SignalType {
                 size: _,
                 offset,
                 scale: _,
+                signed: _,
             } => *offset,
+            SignalType::Float { size: _ } | SignalType::Bool | SignalType::String { len: _ } => 0.0,
         }
     }
     pub fn size(&self) -> u8 {
@@ -76,7 +132,11 @@ impl SignalType {
                 size,
                 offset: _,
                 scale: _,
+                signed: _,
             } => *size,
+            SignalType::Float { size } => *size,
+            SignalType::Bool => 1,
+            SignalType::String { len } => *len * 8,
         }
     }
     pub fn scale(&self) -> f64 {
@@ -87,7 +147,9 @@ impl SignalType {
                 size: _,
                 offset: _,
                 scale,
+                signed: _,
             } => *scale,
+            SignalType::Float { size: _ } | SignalType::Bool | SignalType::String { len: _ } => 1.0,
         }
     }
     pub fn sign(&self) -> SignalSign {
@@ -98,8 +160,34 @@ impl SignalType {
                 size: _,
                 offset: _,
                 scale: _,
-            } => SignalSign::Unsigned,
+                signed,
+            } => {
+                if *signed {
+                    SignalSign::Signed
+                } else {
+                    SignalSign::Unsigned
+                }
+            }
+            SignalType::Float { size: _ } => SignalSign::Signed,
+            SignalType::Bool | SignalType::String { len: _ } => SignalSign::Unsigned,
+        }
+    }
+    /// Smallest and largest value representable by the signal type.
+    pub fn range(&self) -> (f64, f64) {
+        match self {
+            SignalType::Float { size: 32 } => return (f32::MIN as f64, f32::MAX as f64),
+            SignalType::Float { size: _ } => return (f64::MIN, f64::MAX),
+            _ => (),
         }
+        let size = self.size() as i32;
+        let (min_raw, max_raw) = match self.sign() {
+            SignalSign::Unsigned => (0.0, 2f64.powi(size) - 1.0),
+            SignalSign::Signed => (-(2f64.powi(size - 1)), 2f64.powi(size - 1) - 1.0),
+        };
+        (
+            min_raw * self.scale() + self.offset(),
+            max_raw * self.scale() + self.offset(),
+        )
     }
 }
 
@@ -113,6 +201,8 @@ pub struct Signal {
     pub value_table: Option<ValueTableRef>,
     // refers to the byte offset!
     pub offset: usize,
+    pub byte_order: ByteOrder,
+    pub multiplexing: Option<SignalMultiplexing>,
 }
 
 impl Hash for Signal {
@@ -138,6 +228,18 @@ impl Hash for Signal {
             None => state.write_u8(1),
         }
         state.write_u128(self.offset as u128);
+        // default signals keep the hash they had before byte order and multiplexing existed.
+        if self.byte_order == ByteOrder::BigEndian {
+            state.write_u8(0);
+        }
+        match &self.multiplexing {
+            Some(SignalMultiplexing::Multiplexor) => state.write_u8(1),
+            Some(SignalMultiplexing::Multiplexed(value)) => {
+                state.write_u8(2);
+                state.write_u64(*value);
+            }
+            None => (),
+        }
     }
 }
 
@@ -149,6 +251,8 @@ impl Signal {
             ty,
             offset,
             value_table : None,
+            byte_order : ByteOrder::LittleEndian,
+            multiplexing : None,
         }
     }
     pub fn create(name : &str, description : Option<&str>, ty : SignalType) -> Signal {
@@ -158,6 +262,8 @@ impl Signal {
             ty,
             offset : 0,
             value_table : None,
+            byte_order : ByteOrder::LittleEndian,
+            multiplexing : None,
         }
     }
     pub fn name(&self) -> &str {
@@ -187,6 +293,28 @@ impl Signal {
     pub fn size(&self) -> u8 {
         self.ty.size()
     }
+    pub fn byte_order(&self) -> ByteOrder {
+        self.byte_order
+    }
+    pub fn multiplexing(&self) -> Option<&SignalMultiplexing> {
+        self.multiplexing.as_ref()
+    }
+    pub fn value_table(&self) -> Option<&ValueTableRef> {
+        self.value_table.as_ref()
+    }
+    /// Number of payload bits required to hold the signal,
+    /// i.e. the exclusive end of the signal in the little endian bit numbering.
+    pub fn end_bit(&self) -> usize {
+        let size = self.size() as usize;
+        match self.byte_order {
+            ByteOrder::LittleEndian => self.offset + size,
+            ByteOrder::BigEndian => {
+                // position of the msb if bits were numbered from the msb of the first byte.
+                let msb = (self.offset / 8) * 8 + (7 - self.offset % 8);
+                ((msb + size - 1) / 8 + 1) * 8
+            }
+        }
+    }
 }
 
 pub type ValueTableRef = ConfigRef<ValueTable>;
@@ -91,19 +91,20 @@ impl Type {
     pub fn name(&self) -> String {
         match &self {
             Type::Primitive(signal_type) => match signal_type {
-                SignalType::UnsignedInt { size } => {
-                    return format!("u{size}");
-                }
-                SignalType::SignedInt { size } => {
-                    return format!("i{size}");
-                }
+                SignalType::UnsignedInt { size } => format!("u{size}"),
+                SignalType::SignedInt { size } => format!("i{size}"),
                 SignalType::Decimal {
                     size,
                     offset,
                     scale,
+                    signed,
                 } => {
-                    return format!("d{size}<offset={offset}, scale={scale}>");
+                    let prefix = if *signed { "sd" } else { "d" };
+                    format!("{prefix}{size}<offset={offset}, scale={scale}>")
                 }
+                SignalType::Float { size } => format!("f{size}"),
+                SignalType::Bool => "bool".to_owned(),
+                SignalType::String { len } => format!("string<{len}>"),
             },
             Type::Struct {
                 name,
@@ -12,8 +12,15 @@ pub enum ConfigError {
     InvalidDecimalDefinition(String),
     FailedToResolveId,
     NoBusAvaiable,
+    /// The filters of a node can't be merged into the number of filters of its controller.
+    InsufficientFilters(String),
+    /// Messages, which do not pass the filters of a node receiving them.
+    DroppedMessages(String),
+    /// Messages, which don't fit into the frames of their bus.
+    MessageTooLarge(String),
     Io(std::io::Error),
     CanDbc(String),
+    Serialization(String),
 }
 
 impl From<std::io::Error> for ConfigError {
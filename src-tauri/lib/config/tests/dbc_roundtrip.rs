@@ -0,0 +1,309 @@
+//! Exports a network as DBC, imports the DBC into a new network and checks that
+//! every message of the bus survives the round trip.
+
+use std::time::Duration;
+
+use canzero_config::{
+    builder::NetworkBuilder,
+    config::{
+        export_dbc::export_dbc, ByteOrder, MessageId, MessageRef, MessageUsage, NetworkRef, SignalMultiplexing,
+        SignalRef, SignalSign, SignalType,
+    },
+};
+
+const BUS: &str = "can0";
+
+/// External messages, which are imported before the export.
+const EXTERNAL_DBC: &str = r#"VERSION ""
+
+NS_ :
+
+BS_:
+
+BU_: secu master pdu
+
+BO_ 1536 drive: 12 secu
+ SG_ mode M : 0|4@1+ (1,0) [0|15] "" master
+ SG_ speed m0 : 8|16@1- (0.1,0) [-3276.8|3276.7] "km/h" master
+ SG_ torque m1 : 8|12@1- (1,0) [-2048|2047] "Nm" master
+ SG_ gear : 24|8@1- (1,0) [-128|127] "" master,pdu
+ SG_ current : 39|16@0- (0.01,0) [-327.68|327.67] "A" master
+ SG_ energy : 48|40@1+ (1,0) [0|1099511627775] "J" pdu
+
+BO_ 2147491840 fault: 2 pdu
+ SG_ code : 0|8@1+ (1,0) [0|255] "" master
+ SG_ level : 15|8@0- (1,0) [-128|127] "" master
+
+BO_TX_BU_ 2147491840 : pdu,secu;
+
+VAL_ 1536 mode 0 "speed" 1 "torque" ;
+VAL_ 1536 gear -1 "reverse" 0 "neutral" 1 "first" ;
+"#;
+
+fn temp_path(name: &str) -> String {
+    std::env::temp_dir()
+        .join(format!("canzero-config-{name}-{}.dbc", std::process::id()))
+        .to_str()
+        .unwrap()
+        .to_owned()
+}
+
+fn network_builder() -> NetworkBuilder {
+    let network_builder = NetworkBuilder::new();
+    let bus = network_builder.create_bus(BUS, Some(1000000));
+    bus.data_baudrate(4000000);
+    bus.brs(true);
+    network_builder
+}
+
+/// A network with a stream of enums, signed and big endian object entries and the
+/// external messages of `EXTERNAL_DBC` on a CAN FD bus.
+fn network() -> NetworkRef {
+    let network_builder = network_builder();
+    let state = network_builder.define_enum("sdc_state");
+    state.add_entry("open", Some(0)).unwrap();
+    state.add_entry("closed", Some(1)).unwrap();
+    state.add_entry("fault", Some(5)).unwrap();
+
+    let secu = network_builder.create_node("secu");
+    secu.assign_bus(BUS);
+    secu.create_object_entry("state", "sdc_state");
+    let temperature = secu.create_object_entry("temperature", "sd12<-100..100>");
+    temperature.add_unit("C");
+    let pressure = secu.create_object_entry("pressure", "i16");
+    pressure.set_byte_order(ByteOrder::BigEndian);
+    let status = secu.create_stream("status");
+    status.add_entry("state");
+    status.add_entry("temperature");
+    status.add_entry("pressure");
+    status
+        .set_interval(Duration::from_millis(10), Duration::from_millis(50))
+        .unwrap();
+
+    let master = network_builder.create_node("master");
+    master.assign_bus(BUS);
+    master.create_object_entry("secu_state", "sdc_state");
+    master.create_object_entry("secu_temperature", "sd12<-100..100>");
+    master.create_object_entry("secu_pressure", "i16");
+    let rx_status = master.receive_stream("secu", "status");
+    rx_status.map("state", "secu_state");
+    rx_status.map("temperature", "secu_temperature");
+    rx_status.map("pressure", "secu_pressure");
+
+    let pdu = network_builder.create_node("pdu");
+    pdu.assign_bus(BUS);
+
+    let path = temp_path("external");
+    std::fs::write(&path, EXTERNAL_DBC).unwrap();
+    network_builder.include_dbc(BUS, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    network_builder.build().unwrap()
+}
+
+/// Messages, which every network defines itself.
+fn is_builtin(message: &MessageRef) -> bool {
+    matches!(
+        message.usage(),
+        MessageUsage::GetReq
+            | MessageUsage::GetResp
+            | MessageUsage::SetReq
+            | MessageUsage::SetResp
+            | MessageUsage::Heartbeat
+    )
+}
+
+/// Removes the messages with the DBC ids `ids` and everything that refers to them.
+fn without_messages(dbc: &str, ids: &[u32]) -> String {
+    let mut filtered = String::new();
+    let mut in_removed_message = false;
+    for line in dbc.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let refers_to = |position: usize| {
+            tokens
+                .get(position)
+                .and_then(|id| id.parse::<u32>().ok())
+                .is_some_and(|id| ids.contains(&id))
+        };
+        if line.starts_with("BO_ ") {
+            in_removed_message = refers_to(1);
+        } else if line.trim().is_empty() {
+            in_removed_message = false;
+        }
+        let removed = in_removed_message
+            || (line.starts_with("BO_TX_BU_ ") && refers_to(1))
+            || (line.starts_with("VAL_ ") && refers_to(1))
+            || (line.starts_with("SIG_VALTYPE_ ") && refers_to(1))
+            || (line.starts_with("CM_ ") && refers_to(2))
+            || (line.starts_with("BA_ ") && refers_to(3));
+        if !removed {
+            filtered.push_str(line);
+            filtered.push('\n');
+        }
+    }
+    filtered
+}
+
+/// Imports the DBC export of `network` into a new network.
+fn roundtrip(network: &NetworkRef) -> (String, NetworkRef) {
+    let bus = network
+        .buses()
+        .iter()
+        .find(|bus| bus.name() == BUS)
+        .unwrap();
+    let builtin: Vec<u32> = network
+        .messages()
+        .iter()
+        .filter(|message| is_builtin(message))
+        .map(|message| match message.id() {
+            MessageId::StandardId(id) => *id,
+            MessageId::ExtendedId(id) => *id | 0x80000000,
+        })
+        .collect();
+    let dbc = without_messages(&export_dbc(network, bus), &builtin);
+    let path = temp_path("roundtrip");
+    std::fs::write(&path, &dbc).unwrap();
+    let network_builder = network_builder();
+    network_builder.include_dbc(BUS, &path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    (dbc, network_builder.build().unwrap())
+}
+
+fn message(network: &NetworkRef, name: &str) -> MessageRef {
+    network
+        .messages()
+        .iter()
+        .find(|message| message.name() == name)
+        .unwrap_or_else(|| panic!("message {name} is missing"))
+        .clone()
+}
+
+fn signal(message: &MessageRef, name: &str) -> SignalRef {
+    message
+        .signals()
+        .iter()
+        .find(|signal| signal.name() == name)
+        .unwrap_or_else(|| panic!("signal {name} of {} is missing", message.name()))
+        .clone()
+}
+
+fn node_names(network: &NetworkRef, message: &MessageRef, tx: bool) -> Vec<String> {
+    let mut names: Vec<String> = network
+        .nodes()
+        .iter()
+        .filter(|node| {
+            let messages = if tx {
+                node.tx_messages()
+            } else {
+                node.rx_messages()
+            };
+            messages.iter().any(|m| m.name() == message.name())
+        })
+        .map(|node| node.name().to_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+fn value_table(signal: &SignalRef) -> Option<Vec<(String, u64)>> {
+    signal.value_table().map(|value_table| value_table.0.clone())
+}
+
+#[test]
+fn every_message_survives_the_roundtrip() {
+    let network = network();
+    let (dbc, imported) = roundtrip(&network);
+    let bus = &network.buses()[0];
+    let messages = network
+        .messages()
+        .iter()
+        .filter(|message| message.bus().id() == bus.id() && !is_builtin(message));
+    for original in messages {
+        let copy = message(&imported, original.name());
+        assert_eq!(copy.id(), original.id(), "{}\n{dbc}", original.name());
+        assert_eq!(copy.dlc(), original.dlc(), "{}", original.name());
+        assert_eq!(
+            node_names(&imported, &copy, true),
+            node_names(&network, original, true),
+            "transmitters of {}",
+            original.name()
+        );
+        assert_eq!(
+            node_names(&imported, &copy, false),
+            node_names(&network, original, false),
+            "receivers of {}",
+            original.name()
+        );
+        assert_eq!(copy.signals().len(), original.signals().len());
+        for signal_a in original.signals() {
+            // imported signals are prefixed with the name of their message.
+            let signal_b = signal(&copy, &format!("{}_{}", original.name(), signal_a.name()));
+            let what = format!("{} of {}", signal_a.name(), original.name());
+            assert_eq!(signal_b.byte_offset(), signal_a.byte_offset(), "{what}");
+            assert_eq!(signal_b.size(), signal_a.size(), "{what}");
+            assert_eq!(signal_b.byte_order(), signal_a.byte_order(), "{what}");
+            assert_eq!(signal_b.sign().to_string(), signal_a.sign().to_string(), "{what}");
+            assert_eq!(signal_b.scale(), signal_a.scale(), "{what}");
+            assert_eq!(signal_b.offset(), signal_a.offset(), "{what}");
+            assert_eq!(signal_b.multiplexing(), signal_a.multiplexing(), "{what}");
+        }
+    }
+}
+
+#[test]
+fn signal_properties_survive_the_roundtrip() {
+    let network = network();
+    let (_, imported) = roundtrip(&network);
+
+    let status = message(&imported, "secu_stream_status");
+    assert!(status.dlc() <= 8);
+    let state = signal(&status, "secu_stream_status_value_name_sdc_state");
+    assert_eq!(
+        value_table(&state),
+        Some(vec![
+            ("open".to_owned(), 0),
+            ("closed".to_owned(), 1),
+            ("fault".to_owned(), 5),
+        ])
+    );
+    let temperature = signal(&status, "secu_stream_status_value_name_temperature");
+    assert!(matches!(
+        temperature.ty(),
+        SignalType::Decimal {
+            size: 12,
+            signed: true,
+            ..
+        }
+    ));
+    let pressure = signal(&status, "secu_stream_status_value_name_pressure");
+    assert_eq!(pressure.ty(), &SignalType::SignedInt { size: 16 });
+    assert_eq!(pressure.byte_order(), ByteOrder::BigEndian);
+
+    // the FD frame keeps its length, multiplexing and signed value tables.
+    let drive = message(&imported, "drive");
+    assert_eq!(drive.dlc(), 12);
+    assert_eq!(
+        signal(&drive, "drive_drive_mode").multiplexing(),
+        Some(&SignalMultiplexing::Multiplexor)
+    );
+    assert_eq!(
+        signal(&drive, "drive_drive_torque").multiplexing(),
+        Some(&SignalMultiplexing::Multiplexed(1))
+    );
+    let gear = signal(&drive, "drive_drive_gear");
+    assert!(matches!(gear.sign(), SignalSign::Signed));
+    assert_eq!(
+        value_table(&gear),
+        Some(vec![
+            ("reverse".to_owned(), u64::MAX),
+            ("neutral".to_owned(), 0),
+            ("first".to_owned(), 1),
+        ])
+    );
+    let current = signal(&drive, "drive_drive_current");
+    assert_eq!(current.byte_order(), ByteOrder::BigEndian);
+    assert_eq!(current.byte_offset(), 39);
+
+    // additional transmitters are exported as BO_TX_BU_.
+    let fault = message(&imported, "fault");
+    assert_eq!(node_names(&imported, &fault, true), vec!["pdu", "secu"]);
+}
@@ -1,8 +1,10 @@
 use std::{
+    path::Path,
     sync::{Arc, Mutex},
     time::Instant,
 };
 
+use canzero_common::session_log::SessionLogger;
 use canzero_config::config::NetworkRef;
 use canzero_tcp::tcpcan::{ConnectionId, ConnectionIdHost, TcpCan};
 use canzero_udp::beacon::UdpNetworkBeacon;
@@ -24,7 +26,13 @@ pub struct Server {
 }
 
 impl Server {
-    pub async fn create(config: NetworkRef) -> std::io::Result<Self> {
+    /// Every frame that passes the server is recorded to `session_log` if a path is given,
+    /// until the log reaches `max_session_log_size` bytes.
+    pub async fn create(
+        config: NetworkRef,
+        session_log: Option<&Path>,
+        max_session_log_size: Option<u64>,
+    ) -> std::io::Result<Self> {
         let timebase = Instant::now();
 
         let id_host = ConnectionIdHost::new((config.nodes().len() + 16) as u8);
@@ -32,7 +40,15 @@ impl Server {
             id_host.alloc_specific_id(n.id());
         }
 
-        let network = Network::new();
+        let session_log = match session_log {
+            Some(path) => {
+                let logger = SessionLogger::create(path, config.portable_hash(), max_session_log_size)?;
+                cprintln!("<green>Recording session to {path:?}</green>");
+                Some(logger)
+            }
+            None => None,
+        };
+        let network = Network::new(session_log);
 
         #[cfg(feature = "socket-can")]
         {
@@ -1,6 +1,6 @@
 use std::sync::{atomic::AtomicU32, Arc};
 
-use canzero_common::TNetworkFrame;
+use canzero_common::{session_log::SessionLogger, TNetworkFrame};
 use color_print::cprintln;
 use tokio::sync::{Mutex, RwLock};
 
@@ -12,14 +12,16 @@ pub struct Network {
     nodes: Arc<RwLock<Vec<(u32, Arc<NetworkNode>)>>>,
     history: Arc<Mutex<Vec<TNetworkFrame>>>,
     id_acc: AtomicU32,
+    session_log: Option<Arc<SessionLogger>>,
 }
 
 impl Network {
-    pub fn new() -> Self {
+    pub fn new(session_log: Option<SessionLogger>) -> Self {
         Self {
             nodes: Arc::new(RwLock::new(vec![])),
             id_acc: AtomicU32::new(0),
             history: Arc::new(Mutex::new(vec![])),
+            session_log: session_log.map(Arc::new),
         }
     }
 
@@ -51,11 +53,15 @@ impl Network {
         let node = Arc::new(node);
         nodes.write().await.push((node_id, node.clone()));
         // let history = self.history.clone();
+        let session_log = self.session_log.clone();
         tokio::spawn(async move {
             loop {
                 let Some(frame) = node.recv().await else {
                     break;
                 };
+                if let Some(session_log) = &session_log {
+                    session_log.record(&frame);
+                }
                 let nodes = nodes.clone();
                 // let history = history.clone();
                 tokio::spawn(async move {
@@ -1,10 +1,10 @@
 
 use std::mem;
 
-use libc::{c_int, c_void, can_frame, read, sa_family_t, sockaddr_can, write};
+use libc::{c_int, c_void, can_frame, canfd_frame, read, sa_family_t, sockaddr_can, write};
 pub use libc::{
-    AF_CAN, CAN_EFF_FLAG, CAN_EFF_MASK, CAN_ERR_FLAG, CAN_RAW, CAN_RTR_FLAG, CAN_SFF_MASK, PF_CAN,
-    SOCK_RAW,
+    AF_CAN, CANFD_BRS, CANFD_FDF, CANFD_MTU, CAN_EFF_FLAG, CAN_EFF_MASK, CAN_ERR_FLAG, CAN_MTU,
+    CAN_RAW, CAN_RAW_FD_FRAMES, CAN_RTR_FLAG, CAN_SFF_MASK, PF_CAN, SOCK_RAW, SOL_CAN_RAW,
 };
 
 use canzero_common::{CanError, CanFrame};
@@ -28,6 +28,19 @@ impl CanSocket {
             return Err(std::io::Error::last_os_error());
         }
 
+        // receive and transmit CAN FD frames (in addition to classic frames).
+        // Fails on kernels without CAN FD support, which are left with classic frames.
+        let enable: c_int = 1;
+        unsafe {
+            libc::setsockopt(
+                fd,
+                SOL_CAN_RAW,
+                CAN_RAW_FD_FRAMES,
+                (&enable as *const c_int).cast(),
+                mem::size_of::<c_int>() as u32,
+            )
+        };
+
         let ret = unsafe { libc::bind(fd, addr.cast(), mem::size_of::<sockaddr_can>() as u32) };
 
         if ret == -1 {
@@ -44,18 +57,21 @@ impl CanSocket {
         }
     }
     pub fn receive(&self) -> Result<Result<CanFrame, CanError>, std::io::Error> {
-        let mut frame: can_frame = unsafe { mem::zeroed() };
-        let n = mem::size_of::<can_frame>();
+        // classic frames are read as the prefix of a canfd_frame (CAN_MTU bytes).
+        let mut fd_frame: canfd_frame = unsafe { mem::zeroed() };
 
-        let rd = unsafe { read(self.fd, &mut frame as *mut _ as *mut c_void, n) };
+        let rd = unsafe { read(self.fd, &mut fd_frame as *mut _ as *mut c_void, CANFD_MTU) };
 
-        if rd as usize == n {
+        if rd as usize == CAN_MTU {
+            let frame: can_frame = unsafe { mem::transmute_copy(&fd_frame) };
             // parse can_frame into CanFrame
             if frame.can_id & CAN_ERR_FLAG != 0 {
                 return Ok(Err(unsafe { std::mem::transmute(frame.data) }));
             } else {
                 Ok(Ok(frame_from_socket_can_frame(&frame)))
             }
+        } else if rd as usize == CANFD_MTU {
+            Ok(Ok(frame_from_socket_canfd_frame(&fd_frame)))
         } else {
             Err(std::io::Error::last_os_error())
         }
@@ -63,17 +79,24 @@ impl CanSocket {
 
     pub fn transmit(&self, frame: &CanFrame) -> Result<(), std::io::Error> {
         let fd = self.fd;
-        let canframe = frame_to_socket_can_frame(frame);
 
-        let ret = unsafe {
-            write(
-                fd,
-                (&canframe as *const can_frame).cast(),
-                mem::size_of::<can_frame>(),
-            )
+        let (ret, n) = if frame.get_fdf_flag() {
+            let canframe = frame_to_socket_canfd_frame(frame);
+            let ret = unsafe { write(fd, (&canframe as *const canfd_frame).cast(), CANFD_MTU) };
+            (ret, CANFD_MTU)
+        } else {
+            let canframe = frame_to_socket_can_frame(frame);
+            let ret = unsafe {
+                write(
+                    fd,
+                    (&canframe as *const can_frame).cast(),
+                    mem::size_of::<can_frame>(),
+                )
+            };
+            (ret, mem::size_of::<can_frame>())
         };
 
-        if ret as usize == mem::size_of::<can_frame>() {
+        if ret as usize == n {
             Ok(())
         } else {
             Err(std::io::Error::last_os_error())
@@ -113,3 +136,36 @@ pub fn frame_to_socket_can_frame(frame: &CanFrame) -> can_frame {
     canframe.data = frame.get_data_8u8();
     canframe
 }
+
+pub fn frame_from_socket_canfd_frame(frame: &canfd_frame) -> CanFrame {
+    let ide = (frame.can_id & CAN_EFF_FLAG) != 0;
+    let id = if ide {
+        frame.can_id & CAN_EFF_MASK
+    } else {
+        frame.can_id & CAN_SFF_MASK
+    };
+    CanFrame::new_fd(
+        id,
+        ide,
+        (frame.flags as c_int & CANFD_BRS) != 0,
+        frame.len,
+        CanFrame::data_from_bytes(&frame.data),
+    )
+}
+
+pub fn frame_to_socket_canfd_frame(frame: &CanFrame) -> canfd_frame {
+    let mut canframe: canfd_frame = unsafe { std::mem::zeroed() };
+    if frame.get_ide_flag() {
+        canframe.can_id = CAN_EFF_FLAG | (frame.get_id() & CAN_EFF_MASK);
+    } else {
+        canframe.can_id = frame.get_id() & CAN_SFF_MASK;
+    }
+    canframe.flags = CANFD_FDF as u8;
+    if frame.get_brs_flag() {
+        canframe.flags |= CANFD_BRS as u8;
+    }
+    let data = frame.get_data_bytes();
+    canframe.len = data.len() as u8;
+    canframe.data[..data.len()].copy_from_slice(&data);
+    canframe
+}
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame};
+use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame, CAN_FD_MAX_LEN};
 use color_print::cprintln;
 
 
@@ -63,13 +63,31 @@ pub enum TcpFrame {
     KeepAlive,
 }
 
+/// Size of every frame on the wire, CAN FD frames are followed by an extension.
+pub const TCP_FRAME_SIZE: usize = 24;
+/// Data bytes 8..64 of a CAN FD frame.
+pub const TCP_FD_EXTENSION_SIZE: usize = CAN_FD_MAX_LEN - 8;
+pub const TCP_FRAME_MAX_SIZE: usize = TCP_FRAME_SIZE + TCP_FD_EXTENSION_SIZE;
+
 impl TcpFrame {
 
     pub fn bin_size() -> usize {
-        return 24;
+        return TCP_FRAME_SIZE;
+    }
+
+    /// Number of bytes that follow the frame `buf` on the wire.
+    pub fn extension_size(buf : &[u8;24]) -> usize {
+        if buf[0] == 0x3 {
+            TCP_FD_EXTENSION_SIZE
+        } else {
+            0
+        }
     }
 
-    pub fn into_bin(&self, buf8 : &mut [u8;24]) {
+    /// Returns the number of bytes written to `buf`.
+    pub fn into_bin(&self, buf : &mut [u8;TCP_FRAME_MAX_SIZE]) -> usize {
+        let (buf8, extension) = buf.split_at_mut(TCP_FRAME_SIZE);
+        let buf8 : &mut [u8;24] = buf8.try_into().unwrap();
         match &self {
             TcpFrame::NetworkFrame(timestamped) => {
                 let frame = &timestamped.value;
@@ -78,17 +96,24 @@ impl TcpFrame {
                 let can_id = frame.can_frame.key();
                 let data = frame.can_frame.get_data_u64();
                 let dlc = frame.can_frame.get_dlc();
+                let fd = frame.can_frame.get_fdf_flag();
                 
-                buf8[0] = 0x1;
+                buf8[0] = if fd { 0x3 } else { 0x1 };
                 buf8[1] = *bus_id as u8;
                 buf8[2] = dlc;
+                buf8[3] = frame.can_frame.get_flags();
                 let buf32 : &mut [u32;6] = unsafe {std::mem::transmute(buf8)};
                 buf32[1] = can_id;
                 let buf64 : &mut [u64;3] = unsafe {std::mem::transmute(buf32)};
                 buf64[1] = timestamp;
                 buf64[2] = data;
 
-
+                if fd {
+                    let mut bytes = frame.can_frame.get_data_bytes();
+                    bytes.resize(CAN_FD_MAX_LEN, 0);
+                    extension.copy_from_slice(&bytes[8..]);
+                    return TCP_FRAME_MAX_SIZE;
+                }
             }
             TcpFrame::KeepAlive => {
                 buf8[0] = 0x0;
@@ -97,21 +122,34 @@ impl TcpFrame {
                 buf8[0] = 0x2;
             },
         }
+        TCP_FRAME_SIZE
     }
 
-    pub fn from_bin(buf : &[u8;24]) -> Result<Self, ()> {
+    /// `extension` are the [TcpFrame::extension_size] bytes following the frame.
+    pub fn from_bin(buf : &[u8;24], extension : &[u8]) -> Result<Self, ()> {
         let tag = buf[0];
         if tag == 0x0 {
             Ok(TcpFrame::KeepAlive)
-        }else if tag == 0x1 {
+        }else if tag == 0x1 || tag == 0x3 {
             let bus_id = buf[1] as u32;
             let dlc = buf[2];
+            let flags = buf[3];
+            let data_bytes = &buf[16..24];
             let buf : &[u32;6] = unsafe {std::mem::transmute(buf)};
             let can_id = buf[1];
             let buf : &[u64;3] = unsafe {std::mem::transmute(buf)};
             let timestamp = Duration::from_micros(buf[1]);
             let data = buf[2];
-            Ok(TcpFrame::NetworkFrame(TNetworkFrame::new(timestamp, NetworkFrame { bus_id, can_frame: CanFrame::new_raw(can_id, dlc, data) })))
+            let can_frame = if tag == 0x3 {
+                if extension.len() != TCP_FD_EXTENSION_SIZE {
+                    return Err(());
+                }
+                let data = CanFrame::data_from_bytes(&[data_bytes, extension].concat());
+                CanFrame::new_raw_fd(can_id, flags, dlc, data)
+            } else {
+                CanFrame::new_raw(can_id, dlc, data)
+            };
+            Ok(TcpFrame::NetworkFrame(TNetworkFrame::new(timestamp, NetworkFrame { bus_id, can_frame })))
         }else if tag == 0x2 {
             Ok(TcpFrame::SyncEnd)
         } else {
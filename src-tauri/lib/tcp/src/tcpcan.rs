@@ -13,7 +13,7 @@ use tokio::{
 use canzero_common::TNetworkFrame;
 
 use crate::{
-    frame::{ConnectionHandshakeFrame, TcpFrame},
+    frame::{ConnectionHandshakeFrame, TcpFrame, TCP_FRAME_MAX_SIZE, TCP_FRAME_SIZE},
     wdg::Watchdog,
 };
 
@@ -201,16 +201,16 @@ impl TcpCan {
                                 if sync {
                                     if let Some(sync_history) = sync_history {
                                         for frame in sync_history {
-                                            let mut bytes = [0; 24];
-                                            TcpFrame::NetworkFrame(frame.clone())
+                                            let mut bytes = [0; TCP_FRAME_MAX_SIZE];
+                                            let size = TcpFrame::NetworkFrame(frame.clone())
                                                 .into_bin(&mut bytes);
-                                            if let Err(_) = tx.write_all(&bytes).await {
+                                            if let Err(_) = tx.write_all(&bytes[..size]).await {
                                                 cprintln!("<yellow>Failed to transmit HistorySyncFrame.</yellow>")
                                             };
                                         }
-                                        let mut bytes = [0; 24];
-                                        TcpFrame::SyncEnd.into_bin(&mut bytes);
-                                        if let Err(_) = tx.write_all(&bytes).await {
+                                        let mut bytes = [0; TCP_FRAME_MAX_SIZE];
+                                        let size = TcpFrame::SyncEnd.into_bin(&mut bytes);
+                                        if let Err(_) = tx.write_all(&bytes[..size]).await {
                                             cprintln!("<red>Failed to send SYNC_END frame.</red>");
                                             return Err(std::io::Error::new(
                                                 std::io::ErrorKind::UnexpectedEof,
@@ -290,20 +290,29 @@ impl TcpCan {
     }
 
     pub async fn send(&self, frame: &TNetworkFrame) -> std::io::Result<()> {
-        let mut bytes = [0; 24];
-        TcpFrame::NetworkFrame(frame.clone()).into_bin(&mut bytes);
-        self.tx_stream.lock().await.write_all(&bytes).await
+        let mut bytes = [0; TCP_FRAME_MAX_SIZE];
+        let size = TcpFrame::NetworkFrame(frame.clone()).into_bin(&mut bytes);
+        self.tx_stream.lock().await.write_all(&bytes[..size]).await
     }
 
     pub async fn recv(&self) -> Option<TNetworkFrame> {
         let mut rx_lock = self.rx_stream.lock().await;
         let rx_stream = rx_lock.deref_mut();
-        let mut rx_buffer = [0; 24];
+        let mut rx_buffer = [0; TCP_FRAME_SIZE];
+        let mut extension = [0; TCP_FRAME_MAX_SIZE - TCP_FRAME_SIZE];
         loop {
             tokio::select! {
                 rx_res = rx_stream.read_exact(&mut rx_buffer) => {
+                    // CAN FD frames are immediately followed by their extension.
+                    let extension_size = TcpFrame::extension_size(&rx_buffer);
+                    let rx_res = match rx_res {
+                        Ok(_) if extension_size != 0 => rx_stream
+                            .read_exact(&mut extension[..extension_size])
+                            .await,
+                        rx_res => rx_res,
+                    };
                     match rx_res {
-                        Ok(_) => match TcpFrame::from_bin(&rx_buffer).unwrap() {
+                        Ok(_) => match TcpFrame::from_bin(&rx_buffer, &extension[..extension_size]).unwrap() {
                             TcpFrame::NetworkFrame(network_frame) => return Some(network_frame),
                             TcpFrame::KeepAlive => {
                                 self.wdg.reset().await;
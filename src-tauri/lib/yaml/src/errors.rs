@@ -4,10 +4,13 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     YamlScanError(yaml_rust::ScanError),
+    TomlError(toml::de::Error),
     YamlInvalidFormat(String),
     YamlInvalidType(String),
     ConfigError(canzero_config::errors::ConfigError),
     Io(std::io::Error),
+    /// The configuration does not match the schema, see [crate::validate].
+    Validation(Vec<crate::validate::Diagnostic>),
 }
 
 impl From<yaml_rust::ScanError> for Error {
@@ -16,6 +19,12 @@ impl From<yaml_rust::ScanError> for Error {
     }
 }
 
+impl From<toml::de::Error> for Error {
+    fn from(value: toml::de::Error) -> Self {
+        Error::TomlError(value)
+    }
+}
+
 impl From<canzero_config::errors::ConfigError> for Error {
     fn from(value: canzero_config::errors::ConfigError) -> Self {
         Error::ConfigError(value)
@@ -0,0 +1,64 @@
+//! File formats of the network configuration.
+//!
+//! The configuration and every included file can be written in yaml, json or toml,
+//! the format is chosen by the extension of the file. All formats are translated
+//! into the same yaml document, such that they share the schema, the validation and the parser.
+//!
+//! json is a subset of yaml and read by the yaml loader. toml has no null,
+//! so overlays written in toml can not remove anything (see [crate::variants]).
+
+use std::path::Path;
+
+use yaml_rust::{Yaml, YamlLoader};
+
+use crate::errors::Result;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Format of the file at `path`, files without a known extension are read as yaml.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => ConfigFormat::Json,
+            Some("toml") => ConfigFormat::Toml,
+            _ => ConfigFormat::Yaml,
+        }
+    }
+}
+
+/// Translates a toml value into the equivalent yaml node, tables keep the order of their keys.
+pub fn toml_to_yaml(value: &toml::Value) -> Yaml {
+    match value {
+        toml::Value::String(s) => Yaml::String(s.clone()),
+        toml::Value::Integer(i) => Yaml::Integer(*i),
+        toml::Value::Float(f) => Yaml::Real(format!("{f:?}")),
+        toml::Value::Boolean(b) => Yaml::Boolean(*b),
+        toml::Value::Datetime(datetime) => Yaml::String(datetime.to_string()),
+        toml::Value::Array(elements) => Yaml::Array(elements.iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Yaml::Hash(
+            table
+                .iter()
+                .map(|(key, value)| (Yaml::String(key.clone()), toml_to_yaml(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Loads the document `src` read from `path`, `Yaml::Null` if the document is empty.
+pub fn load_document(src: &str, path: &Path) -> Result<Yaml> {
+    match ConfigFormat::from_path(path) {
+        ConfigFormat::Toml => {
+            let table: toml::Table = toml::from_str(src)?;
+            Ok(toml_to_yaml(&toml::Value::Table(table)))
+        }
+        ConfigFormat::Yaml | ConfigFormat::Json => Ok(YamlLoader::load_from_str(src)?
+            .into_iter()
+            .next()
+            .unwrap_or(Yaml::Null)),
+    }
+}
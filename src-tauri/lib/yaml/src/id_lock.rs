@@ -0,0 +1,133 @@
+//! The id lock file next to the yaml configuration (`network.yaml` -> `network.lock`),
+//! variants have their own lock file (`network.yaml` -> `network.pod.lock`).
+//! It records the automatically assigned message ids, buses and object entry ids,
+//! such that rebuilding a modified configuration keeps all ids that are still valid.
+//!
+//! ```yaml
+//! messages:
+//!   heartbeat_can0: { id: 0x7E0, ide: false, bus: can0, priority: 5 }
+//! object_entries:
+//!   mother_board:
+//!     position: 0
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use canzero_config::builder::{
+    id_lock::{LockedMessage, LockedObjectEntry},
+    IdLock,
+};
+use yaml_rust::{yaml::Hash, Yaml, YamlEmitter, YamlLoader};
+
+use crate::errors::{Error, Result};
+
+pub fn lock_file_path(config_path: &Path, variant: Option<&str>) -> PathBuf {
+    match variant {
+        Some(variant) => config_path.with_extension(format!("{variant}.lock")),
+        None => config_path.with_extension("lock"),
+    }
+}
+
+fn invalid(what: &str) -> Error {
+    Error::YamlInvalidFormat(format!("invalid id lock file: {what}"))
+}
+
+fn parse_u32(yaml: &Yaml, what: &str) -> Result<u32> {
+    yaml.as_i64()
+        .and_then(|value| u32::try_from(value).ok())
+        .ok_or_else(|| invalid(what))
+}
+
+fn parse_id_lock(doc: &Yaml) -> Result<IdLock> {
+    let mut lock = IdLock::default();
+    if let Some(messages) = doc["messages"].as_hash() {
+        for (name, message) in messages {
+            let name = name.as_str().ok_or_else(|| invalid("message names have to be strings"))?;
+            lock.messages.push(LockedMessage {
+                name: name.to_owned(),
+                id: parse_u32(&message["id"], "message ids have to be integers")?,
+                ide: message["ide"]
+                    .as_bool()
+                    .ok_or_else(|| invalid("ide has to be a bool"))?,
+                bus: message["bus"]
+                    .as_str()
+                    .ok_or_else(|| invalid("buses have to be strings"))?
+                    .to_owned(),
+                priority: parse_u32(&message["priority"], "priorities have to be integers")?,
+            });
+        }
+    }
+    if let Some(nodes) = doc["object_entries"].as_hash() {
+        for (node, object_entries) in nodes {
+            let node = node.as_str().ok_or_else(|| invalid("node names have to be strings"))?;
+            let object_entries = object_entries
+                .as_hash()
+                .ok_or_else(|| invalid("object entries have to be maps"))?;
+            for (name, id) in object_entries {
+                lock.object_entries.push(LockedObjectEntry {
+                    node: node.to_owned(),
+                    name: name
+                        .as_str()
+                        .ok_or_else(|| invalid("object entry names have to be strings"))?
+                        .to_owned(),
+                    id: parse_u32(id, "object entry ids have to be integers")?,
+                });
+            }
+        }
+    }
+    Ok(lock)
+}
+
+/// Reads the id lock of the configuration at `config_path`, `None` if there is no lock file.
+pub fn read_id_lock(config_path: &Path, variant: Option<&str>) -> Result<Option<IdLock>> {
+    let path = lock_file_path(config_path, variant);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let src = std::fs::read_to_string(&path)?;
+    let docs = YamlLoader::load_from_str(&src)?;
+    match docs.first() {
+        Some(doc) => Ok(Some(parse_id_lock(doc)?)),
+        None => Ok(None),
+    }
+}
+
+fn key(value: &str) -> Yaml {
+    Yaml::String(value.to_owned())
+}
+
+fn emit_id_lock(lock: &IdLock) -> String {
+    let mut messages = Hash::new();
+    for message in &lock.messages {
+        let mut map = Hash::new();
+        map.insert(key("id"), Yaml::Integer(message.id as i64));
+        map.insert(key("ide"), Yaml::Boolean(message.ide));
+        map.insert(key("bus"), key(&message.bus));
+        map.insert(key("priority"), Yaml::Integer(message.priority as i64));
+        messages.insert(key(&message.name), Yaml::Hash(map));
+    }
+    let mut nodes = Hash::new();
+    for oe in &lock.object_entries {
+        let node = nodes
+            .entry(key(&oe.node))
+            .or_insert_with(|| Yaml::Hash(Hash::new()));
+        if let Yaml::Hash(object_entries) = node {
+            object_entries.insert(key(&oe.name), Yaml::Integer(oe.id as i64));
+        }
+    }
+    let mut doc = Hash::new();
+    doc.insert(key("messages"), Yaml::Hash(messages));
+    doc.insert(key("object_entries"), Yaml::Hash(nodes));
+
+    let mut out = String::from("# generated by canzero, do not edit.\n");
+    YamlEmitter::new(&mut out)
+        .dump(&Yaml::Hash(doc))
+        .expect("failed to emit id lock");
+    out.push('\n');
+    out
+}
+
+pub fn write_id_lock(config_path: &Path, variant: Option<&str>, lock: &IdLock) -> Result<()> {
+    std::fs::write(lock_file_path(config_path, variant), emit_id_lock(lock))?;
+    Ok(())
+}
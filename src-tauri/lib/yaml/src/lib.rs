@@ -1,42 +1,159 @@
 use std::{path::{Path, PathBuf}, str::FromStr};
 
-use canzero_config::{builder::NetworkBuilder, config::NetworkRef};
+use canzero_config::{
+    builder::{IdLock, NetworkBuilder},
+    config::NetworkRef,
+};
 use errors::Result;
 
 use crate::parser::parse_included_files;
 
 pub mod errors;
+pub mod formats;
+pub mod id_lock;
 mod parser;
+pub mod templates;
+pub mod validate;
+pub mod variants;
 
 
+pub fn validate_yaml_config_from_file(path : &str) -> Result<Vec<validate::Diagnostic>> {
+    let path = PathBuf::from_str(path).unwrap();
+    let src = std::fs::read_to_string(&path)?;
+    Ok(validate::validate_yaml_config(&src, path.as_path()))
+}
+
+/// Parses the configuration and keeps the ids recorded in its id lock file
+/// (see [id_lock]). The lock file is never written, see [lock_yaml_config_from_file].
 pub fn parse_yaml_config_from_file(path : &str) -> Result<NetworkRef> {
+    parse_yaml_config_variant_from_file(path, None)
+}
+
+/// Parses the configuration with the overlay of `variant` applied (see [variants])
+/// and keeps the ids recorded in the id lock file of the variant.
+/// An out of date lock file is reported as a warning, but not updated.
+pub fn parse_yaml_config_variant_from_file(path : &str, variant : Option<&str>) -> Result<NetworkRef> {
+    let (network, diagnostic) = load_yaml_config_variant_from_file(path, variant)?;
+    if let Some(diagnostic) = diagnostic {
+        eprintln!("{diagnostic}");
+    }
+    Ok(network)
+}
+
+/// Same as [parse_yaml_config_variant_from_file], but returns the warning about an
+/// out of date lock file instead of printing it.
+pub fn load_yaml_config_variant_from_file(
+    path: &str,
+    variant: Option<&str>,
+) -> Result<(NetworkRef, Option<validate::Diagnostic>)> {
+    let path = PathBuf::from_str(path).unwrap();
+    let src = std::fs::read_to_string(&path)?;
+    let lock = id_lock::read_id_lock(&path, variant)?;
+    let (network, new_lock) =
+        parse_yaml_config_variant_locked(&src, path.as_path(), variant, lock.as_ref())?;
+    let diagnostic = (lock.as_ref() != Some(&new_lock)).then(|| {
+        validate::Diagnostic::file_warning(
+            id_lock::lock_file_path(&path, variant),
+            "the id lock file is out of date".to_owned(),
+            Some("`generate` updates it, `config check --relock` reassigns all ids".to_owned()),
+        )
+    });
+    Ok((network, diagnostic))
+}
+
+/// Parses the configuration keeping the ids of its id lock file and creates or
+/// updates the lock file if ids changed.
+pub fn lock_yaml_config_from_file(path : &str, variant : Option<&str>) -> Result<NetworkRef> {
     let path = PathBuf::from_str(path).unwrap();
     let src = std::fs::read_to_string(&path)?;
-    let network = parse_yaml_config(&src, path.as_path())?;
+    let lock = id_lock::read_id_lock(&path, variant)?;
+    let (network, new_lock) =
+        parse_yaml_config_variant_locked(&src, path.as_path(), variant, lock.as_ref())?;
+    if lock.as_ref() != Some(&new_lock) {
+        id_lock::write_id_lock(&path, variant, &new_lock)?;
+    }
+    Ok(network)
+}
+
+/// Parses the configuration ignoring its id lock file and replaces the lock file
+/// with the freshly assigned ids.
+pub fn relock_yaml_config_from_file(path : &str, variant : Option<&str>) -> Result<NetworkRef> {
+    let path = PathBuf::from_str(path).unwrap();
+    let src = std::fs::read_to_string(&path)?;
+    let (network, lock) = parse_yaml_config_variant_locked(&src, path.as_path(), variant, None)?;
+    id_lock::write_id_lock(&path, variant, &lock)?;
     Ok(network)
 }
 
 pub fn parse_yaml_config(src : &str, path : &Path) -> Result<NetworkRef> {
+    Ok(parse_yaml_config_locked(src, path, None)?.0)
+}
+
+/// Parses the configuration keeping the still valid ids of `lock`.
+/// Returns the network and the lock of its ids.
+pub fn parse_yaml_config_locked(
+    src: &str,
+    path: &Path,
+    lock: Option<&IdLock>,
+) -> Result<(NetworkRef, IdLock)> {
+    parse_yaml_config_variant_locked(src, path, None, lock)
+}
+
+/// Same as [parse_yaml_config_locked], but applies the overlay of `variant` first.
+/// The validation only covers the configuration itself, problems of the overlay
+/// are reported while building the network.
+pub fn parse_yaml_config_variant_locked(
+    src: &str,
+    path: &Path,
+    variant: Option<&str>,
+    lock: Option<&IdLock>,
+) -> Result<(NetworkRef, IdLock)> {
+    let diagnostics = validate::validate_yaml_config(src, path);
+    if diagnostics.iter().any(validate::Diagnostic::is_error) {
+        return Err(errors::Error::Validation(diagnostics));
+    }
     let mut network_builder = NetworkBuilder::new();
 
-    let docs = yaml_rust::yaml::YamlLoader::load_from_str(src)?;
-    let doc = &docs[0];
-    //println!("{doc:?}");
-    parser::parse_top_level(doc, &mut network_builder, path)?;
+    let doc = formats::load_document(src, path)?;
+    if doc.is_null() {
+        return Err(errors::Error::YamlInvalidFormat(format!("{path:?} is empty")));
+    }
+    match variant {
+        Some(variant) => {
+            let doc = variants::apply_variant(&doc, variant, path)?;
+            parser::parse_top_level(&doc, &mut network_builder, path)?;
+        }
+        None => parser::parse_top_level(&doc, &mut network_builder, path)?,
+    }
 
-    let network = network_builder.build()?;
-    Ok(network)
+    Ok(network_builder.build_locked(lock)?)
 }
 
-pub fn parse_yaml_config_files_from_file(path: &str) -> Result<Vec<PathBuf>> {
+pub fn parse_yaml_config_files_from_file(path: &str, variant: Option<&str>) -> Result<Vec<PathBuf>> {
     let path = PathBuf::from_str(path).unwrap();
     let src = std::fs::read_to_string(&path)?;
-    parse_yaml_config_files(&src, path.as_path())
+    parse_yaml_config_files(&src, path.as_path(), variant)
 }
 
-pub fn parse_yaml_config_files(src :&str, path: &Path) -> Result<Vec<PathBuf>> {
+/// All files included by the configuration, including the overlay file of `variant`.
+pub fn parse_yaml_config_files(src :&str, path: &Path, variant: Option<&str>) -> Result<Vec<PathBuf>> {
+
+    let doc = formats::load_document(src, path)?;
+    if doc.is_null() {
+        return Err(errors::Error::YamlInvalidFormat(format!("{path:?} is empty")));
+    }
+    let mut files = parse_included_files(&doc, path)?;
+    if let Some(overlay_file) = variant.and_then(|variant| variants::overlay_file(&doc, variant)) {
+        let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        buf.push(overlay_file);
+        files.push(buf);
+    }
+    Ok(files)
+}
 
-    let docs = yaml_rust::yaml::YamlLoader::load_from_str(src)?;
-    let doc = &docs[0];
-    parse_included_files(doc, path)
+/// Names of the variants defined by the configuration (see [variants]).
+pub fn parse_yaml_config_variants_from_file(path: &str) -> Result<Vec<String>> {
+    let src = std::fs::read_to_string(path)?;
+    let doc = formats::load_document(&src, Path::new(path))?;
+    Ok(variants::variant_names(&doc))
 }
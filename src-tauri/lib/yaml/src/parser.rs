@@ -5,10 +5,46 @@ use std::{
 
 use canzero_config::{
     builder::{bus::BusBuilder, EnumBuilder, NetworkBuilder, NodeBuilder, StructBuilder},
-    config::ObjectEntryAccess,
+    config::{ByteOrder, CanController, ObjectEntryAccess},
 };
 
-use crate::errors::{Error, Result};
+use crate::{
+    errors::{Error, Result},
+    formats, templates,
+};
+
+fn parse_time_literal(value: &str, unit: &str) -> Result<Duration> {
+    let value: u64 = value.parse().map_err(|_| {
+        Error::YamlInvalidType(format!("time literal {value}{unit} is out of range"))
+    })?;
+    match unit {
+        "ms" => Ok(Duration::from_millis(value)),
+        "s" => Ok(Duration::from_secs(value)),
+        _ => Err(Error::YamlInvalidType(format!("unknown time unit {unit}"))),
+    }
+}
+
+/// Loads the yaml file `include_path` relative to the directory of `path`.
+pub(crate) fn load_include(path: &Path, include_path: &str) -> Result<yaml_rust::Yaml> {
+    let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
+    buf.push(include_path);
+    let yaml_str = std::fs::read_to_string(&buf).map_err(|err| {
+        Error::Io(std::io::Error::new(
+            err.kind(),
+            format!("Failed to read {buf:?} : {err}"),
+        ))
+    })?;
+    formats::load_document(&yaml_str, &buf)
+}
+
+fn load_include_map(path: &Path, include_path: &str) -> Result<yaml_rust::yaml::Hash> {
+    match load_include(path, include_path)? {
+        yaml_rust::Yaml::Hash(map) => Ok(map),
+        _ => Err(Error::YamlInvalidType(format!(
+            "included file {include_path} has to define a map"
+        ))),
+    }
+}
 
 pub fn parse_object_entry(
     oe_name: &str,
@@ -75,6 +111,66 @@ pub fn parse_object_entry(
         }
     }
 
+    for bound in ["min", "max"] {
+        if map.contains_key(&yaml_rust::Yaml::String(bound.to_owned())) {
+            let value = match &oe_map[bound] {
+                yaml_rust::Yaml::Integer(value) => *value as f64,
+                yaml_rust::Yaml::Real(value) => value.parse::<f64>().map_err(|_| {
+                    Error::YamlInvalidType(format!("{bound} has to be a number"))
+                })?,
+                _ => {
+                    return Err(Error::YamlInvalidType(format!(
+                        "{bound} has to be a number"
+                    )))
+                }
+            };
+            match bound {
+                "min" => oe_builder.set_min(value),
+                _ => oe_builder.set_max(value),
+            }
+        }
+    }
+
+    if map.contains_key(&yaml_rust::Yaml::String("persistent".to_owned())) {
+        let yaml_rust::Yaml::Boolean(persistent) = &oe_map["persistent"] else {
+            return Err(Error::YamlInvalidType(
+                "persistent has to be defined as a boolean".to_owned(),
+            ));
+        };
+        if *persistent {
+            oe_builder.make_persistent();
+        }
+    }
+
+    if map.contains_key(&yaml_rust::Yaml::String("byte_order".to_owned())) {
+        let yaml_rust::Yaml::String(byte_order) = &oe_map["byte_order"] else {
+            return Err(Error::YamlInvalidType(
+                "byte_order has to be defined as a string".to_owned(),
+            ));
+        };
+        let Some(byte_order) = ByteOrder::from_name(byte_order) else {
+            return Err(Error::YamlInvalidType(format!(
+                "unknown byte_order {byte_order}"
+            )));
+        };
+        oe_builder.set_byte_order(byte_order);
+    }
+
+    if map.contains_key(&yaml_rust::Yaml::String("default".to_owned())) {
+        let default = match &oe_map["default"] {
+            yaml_rust::Yaml::Integer(value) => value.to_string(),
+            yaml_rust::Yaml::Real(value) => value.clone(),
+            yaml_rust::Yaml::Boolean(value) => value.to_string(),
+            yaml_rust::Yaml::String(value) => value.clone(),
+            _ => {
+                return Err(Error::YamlInvalidType(
+                    "default has to be a number, boolean or string".to_owned(),
+                ))
+            }
+        };
+        oe_builder.set_default(&default);
+    }
+
     Ok(())
 }
 
@@ -134,23 +230,9 @@ pub fn parse_tx_stream(
                 let max = &captures["max"];
                 let max_unit = &captures["max_unit"];
 
-                let min: u64 = min.parse().unwrap();
-                let min = if min_unit == "ms" {
-                    Duration::from_millis(min)
-                } else if min_unit == "s" {
-                    Duration::from_secs(min)
-                } else {
-                    panic!()
-                };
-                let max: u64 = max.parse().unwrap();
-                let max = if max_unit == "ms" {
-                    Duration::from_millis(max)
-                } else if max_unit == "s" {
-                    Duration::from_secs(max)
-                } else {
-                    panic!()
-                };
-                stream_builder.set_interval(min, max);
+                let min = parse_time_literal(min, min_unit)?;
+                let max = parse_time_literal(max, max_unit)?;
+                stream_builder.set_interval(min, max)?;
             }
             None => {
                 let single_interval = regex::Regex::new(r"(?<x>\d+)\s*(?<unit>(ms|s))").unwrap();
@@ -158,18 +240,13 @@ pub fn parse_tx_stream(
                     Some(captures) => {
                         let interval = &captures["x"];
                         let unit = &captures["unit"];
-                        let interval: u64 = interval.parse().unwrap();
-                        let interval = if unit == "ms" {
-                            Duration::from_millis(interval)
-                        } else if unit == "s" {
-                            Duration::from_secs(interval)
-                        } else {
-                            panic!()
-                        };
-                        stream_builder.set_interval(interval, interval);
+                        let interval = parse_time_literal(interval, unit)?;
+                        stream_builder.set_interval(interval, interval)?;
                     }
                     None => {
-                        panic!("intervals have to be defined as strings with \"\\d+(ms|s)\"");
+                        return Err(Error::YamlInvalidType(
+                            "intervals have to be defined as strings with \"\\d+(ms|s)\"".to_owned(),
+                        ));
                     }
                 }
             }
@@ -307,18 +384,13 @@ pub fn parse_node(
             Some(captures) => {
                 let interval = &captures["x"];
                 let unit = &captures["unit"];
-                let interval: u64 = interval.parse().unwrap();
-                let interval = if unit == "ms" {
-                    Duration::from_millis(interval)
-                } else if unit == "s" {
-                    Duration::from_secs(interval)
-                } else {
-                    panic!()
-                };
+                let interval = parse_time_literal(interval, unit)?;
                 node_builder.heartbeat_timeout(interval);
             }
             None => {
-                panic!("intervals have to be defined as strings with \"\\d+(ms|s)\"");
+                return Err(Error::YamlInvalidType(
+                    "heartbeat_timeout has to be a time literal [for example 100ms]".to_owned(),
+                ));
             }
         };
     }
@@ -331,6 +403,26 @@ pub fn parse_node(
         };
         node_builder.add_description(&description);
     }
+    if map.contains_key(&yaml_rust::Yaml::String("controller".to_owned())) {
+        let yaml_rust::Yaml::String(controller) = &node_map["controller"] else {
+            return Err(Error::YamlInvalidType("controller has to be a string".to_owned()));
+        };
+        let Some(controller) = CanController::from_name(controller) else {
+            return Err(Error::YamlInvalidType(format!(
+                "unknown controller {controller}"
+            )));
+        };
+        node_builder.set_controller(controller);
+    }
+    if map.contains_key(&yaml_rust::Yaml::String("max_filters".to_owned())) {
+        let yaml_rust::Yaml::Integer(max_filters) = &node_map["max_filters"] else {
+            return Err(Error::YamlInvalidType("max_filters has to be an integer".to_owned()));
+        };
+        if *max_filters <= 0 {
+            return Err(Error::YamlInvalidType("max_filters has to be positive".to_owned()));
+        }
+        node_builder.set_max_filters(*max_filters as usize);
+    }
     if map.contains_key(&yaml_rust::Yaml::String("object_dictionary".to_owned())) {
         let yaml_rust::Yaml::Hash(od_map) = &node_map["object_dictionary"] else {
             return Err(Error::YamlInvalidType(format!(
@@ -478,26 +570,37 @@ pub fn parse_bus(
         )));
     };
 
-    bus_hash_map
-        .get(&yaml_rust::yaml::Yaml::String("baudrate".to_owned()))
-        .map(|yaml| {
-            let yaml_rust::Yaml::Integer(baudrate) = yaml else {
-                panic!("baudrate must be integer value");
-            };
-            bus_builder.baudrate(*baudrate as u32);
-        });
-
-    bus_hash_map
-        .get(&yaml_rust::yaml::Yaml::String("database".to_owned()))
-        .map(|yaml| {
-            let yaml_rust::Yaml::String(path) = yaml else {
-                panic!("database paths have to be strings ending in .dbc");
-            };
-            match network_builder.include_dbc(&bus_builder.0.borrow().name, path) {
-                Ok(_) => (),
-                Err(err) => panic!("Failed to include DBC \n{err:?}"),
+    if let Some(yaml) = bus_hash_map.get(&yaml_rust::yaml::Yaml::String("baudrate".to_owned())) {
+        let yaml_rust::Yaml::Integer(baudrate) = yaml else {
+            return Err(Error::YamlInvalidType("baudrate must be integer value".to_owned()));
+        };
+        bus_builder.baudrate(*baudrate as u32);
+    }
+
+    if let Some(yaml) = bus_hash_map.get(&yaml_rust::yaml::Yaml::String("data_baudrate".to_owned())) {
+        let yaml_rust::Yaml::Integer(data_baudrate) = yaml else {
+            return Err(Error::YamlInvalidType("data_baudrate must be integer value".to_owned()));
+        };
+        bus_builder.data_baudrate(*data_baudrate as u32);
+        // CAN FD buses switch the bitrate by default.
+        let brs = match bus_hash_map.get(&yaml_rust::yaml::Yaml::String("brs".to_owned())) {
+            Some(yaml_rust::Yaml::Boolean(brs)) => *brs,
+            Some(_) => {
+                return Err(Error::YamlInvalidType("brs must be a boolean".to_owned()));
             }
-        });
+            None => true,
+        };
+        bus_builder.brs(brs);
+    }
+
+    if let Some(yaml) = bus_hash_map.get(&yaml_rust::yaml::Yaml::String("database".to_owned())) {
+        let yaml_rust::Yaml::String(path) = yaml else {
+            return Err(Error::YamlInvalidType(
+                "database paths have to be strings ending in .dbc".to_owned(),
+            ));
+        };
+        network_builder.include_dbc(&bus_builder.0.borrow().name, path)?;
+    }
 
     Ok(())
 }
@@ -512,13 +615,7 @@ pub fn parse_top_level(
     };
 
     let nodes_map = if let yaml_rust::Yaml::String(include_path) = &yaml["nodes"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
+        load_include_map(path, include_path)?
     } else {
         let yaml_rust::Yaml::Hash(nodes_map) = &yaml["nodes"] else {
             return Err(Error::YamlInvalidType(format!(
@@ -536,30 +633,48 @@ pub fn parse_top_level(
         };
 
         let node_def = if let yaml_rust::Yaml::String(include_path) = node_def {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.clone()
+            load_include(path, include_path)?
         } else {
             node_def.clone()
         };
         parse_node(name, &node_def, network_builder)?;
     }
 
+    if !yaml["instances"].is_null() && !yaml["instances"].is_badvalue() {
+        let yaml_rust::Yaml::Hash(instances_map) = &yaml["instances"] else {
+            return Err(Error::YamlInvalidType("instances must be given as a map".to_owned()));
+        };
+        for (name, instance_def) in instances_map {
+            let yaml_rust::Yaml::String(name) = name else {
+                return Err(Error::YamlInvalidType(
+                    "name of a instance has to be a string".to_owned(),
+                ));
+            };
+            if nodes_map.contains_key(&yaml_rust::Yaml::String(name.clone())) {
+                return Err(Error::YamlInvalidFormat(format!(
+                    "instance {name} has the same name as a node"
+                )));
+            }
+            let yaml_rust::Yaml::String(template_name) = &instance_def[templates::TEMPLATE_KEY]
+            else {
+                return Err(Error::YamlInvalidType(format!(
+                    "instance {name} has to name its template"
+                )));
+            };
+            let template = &yaml["templates"][template_name.as_str()];
+            if template.is_badvalue() {
+                return Err(Error::YamlInvalidFormat(format!(
+                    "instance {name} refers to the undefined template {template_name}"
+                )));
+            }
+            let node_def = templates::instantiate(template, instance_def, name);
+            parse_node(name, &node_def, network_builder)?;
+        }
+    }
+
     if !yaml["struct_types"].is_null() && !yaml["struct_types"].is_badvalue() {
         let structs_map = if let yaml_rust::Yaml::String(include_path) = &yaml["struct_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.as_hash().unwrap().clone()
+            load_include_map(path, include_path)?
         } else {
             let yaml_rust::Yaml::Hash(structs_map) = &yaml["struct_types"] else {
                 return Err(Error::YamlInvalidType(format!(
@@ -582,14 +697,7 @@ pub fn parse_top_level(
 
     if !yaml["enum_types"].is_null() && !yaml["enum_types"].is_badvalue() {
         let enums_map = if let yaml_rust::Yaml::String(include_path) = &yaml["enum_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
-            buf.push(include_path);
-            let path = buf.as_path();
-            let yaml_str =
-                std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-            let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-            let doc = &docs[0];
-            doc.as_hash().unwrap().clone()
+            load_include_map(path, include_path)?
         } else {
             let yaml_rust::Yaml::Hash(enums_map) = &yaml["enum_types"] else {
                 return Err(Error::YamlInvalidType(format!(
@@ -611,13 +719,7 @@ pub fn parse_top_level(
     }
 
     let bus_map = if let yaml_rust::Yaml::String(include_path) = &yaml["buses"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
-        buf.push(include_path);
-        let path = buf.as_path();
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
+        load_include_map(path, include_path)?
     } else {
         let yaml_rust::Yaml::Hash(buses) = &yaml["buses"] else {
             return Err(Error::YamlInvalidType(format!(
@@ -661,14 +763,10 @@ pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result
     };
 
     let nodes_map = if let yaml_rust::Yaml::String(include_path) = &yaml["nodes"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
+        let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
         buf.push(include_path);
-        let path = buf.as_path();
-        paths.push(path.to_path_buf());
-        let yaml_str = std::fs::read_to_string(&path).expect(&format!("Failed to read {path:?}"));
-        let docs = yaml_rust::yaml::YamlLoader::load_from_str(&yaml_str).unwrap();
-        let doc = &docs[0];
-        doc.as_hash().unwrap().clone()
+        paths.push(buf);
+        load_include_map(path, include_path)?
     } else {
         let yaml_rust::Yaml::Hash(nodes_map) = &yaml["nodes"] else {
             return Err(Error::YamlInvalidType(format!(
@@ -680,7 +778,7 @@ pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result
 
     for (_, node_def) in &nodes_map {
         if let yaml_rust::Yaml::String(include_path) = node_def {
-            let mut buf = path.parent().unwrap().to_path_buf();
+            let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
             buf.push(include_path);
             let path = buf.as_path();
             paths.push(path.to_path_buf());
@@ -689,7 +787,7 @@ pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result
 
     if !yaml["struct_types"].is_null() && !yaml["struct_types"].is_badvalue() {
         if let yaml_rust::Yaml::String(include_path) = &yaml["struct_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
+            let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
             buf.push(include_path);
             let path = buf.as_path();
             paths.push(path.to_path_buf());
@@ -698,7 +796,7 @@ pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result
 
     if !yaml["enum_types"].is_null() && !yaml["enum_types"].is_badvalue() {
         if let yaml_rust::Yaml::String(include_path) = &yaml["enum_types"] {
-            let mut buf = path.parent().unwrap().to_path_buf();
+            let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
             buf.push(include_path);
             let path = buf.as_path();
             paths.push(path.to_path_buf());
@@ -706,7 +804,7 @@ pub fn parse_included_files(yaml: &yaml_rust::yaml::Yaml, path: &Path) -> Result
     }
 
     if let yaml_rust::Yaml::String(include_path) = &yaml["buses"] {
-        let mut buf = path.parent().unwrap().to_path_buf();
+        let mut buf = path.parent().map(Path::to_path_buf).unwrap_or_default();
         buf.push(include_path);
         let path = buf.as_path();
         paths.push(path.to_path_buf());
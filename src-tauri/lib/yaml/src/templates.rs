@@ -0,0 +1,78 @@
+//! Node templates.
+//!
+//! A template defines the object dictionary, streams and commands of a node once,
+//! every instance of it becomes a separate node:
+//!
+//! ```yaml
+//! templates:
+//!   mlu:
+//!     object_dictionary:
+//!       temperature:
+//!         type: d8<0..100>
+//!         description: temperature of ${name}
+//! instances:
+//!   mlu1:
+//!     template: mlu
+//!   mlu2:
+//!     template: mlu
+//!     object_dictionary:
+//!       temperature:
+//!         description: temperature of the rear module
+//! ```
+//!
+//! All keys of an instance except `template` override the template (maps are merged,
+//! everything else is replaced). `${name}` is replaced by the name of the instance
+//! in all keys and strings.
+
+use yaml_rust::Yaml;
+
+/// Key of an instance, which names its template.
+pub const TEMPLATE_KEY: &str = "template";
+/// Placeholder, which is replaced by the name of the instance.
+pub const NAME_PLACEHOLDER: &str = "${name}";
+
+/// Overrides `base` with `overrides`, maps are merged recursively.
+fn merge(base: &Yaml, overrides: &Yaml) -> Yaml {
+    match (base, overrides) {
+        (Yaml::Hash(base), Yaml::Hash(overrides)) => {
+            let mut merged = base.clone();
+            for (key, value) in overrides {
+                let value = match base.get(key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => value.clone(),
+                };
+                merged.insert(key.clone(), value);
+            }
+            Yaml::Hash(merged)
+        }
+        (_, overrides) => overrides.clone(),
+    }
+}
+
+fn substitute(yaml: &Yaml, name: &str) -> Yaml {
+    match yaml {
+        Yaml::String(s) => Yaml::String(s.replace(NAME_PLACEHOLDER, name)),
+        Yaml::Array(elements) => Yaml::Array(elements.iter().map(|e| substitute(e, name)).collect()),
+        Yaml::Hash(map) => Yaml::Hash(
+            map.iter()
+                .map(|(key, value)| (substitute(key, name), substitute(value, name)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Definition of the node `name`, which is an instance of `template`.
+/// `instance` is the definition of the instance (including its `template` key).
+pub fn instantiate(template: &Yaml, instance: &Yaml, name: &str) -> Yaml {
+    let overrides = match instance {
+        Yaml::Hash(map) => Yaml::Hash(
+            map.iter()
+                .filter(|(key, _)| key.as_str() != Some(TEMPLATE_KEY))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        ),
+        _ => Yaml::Null,
+    };
+    substitute(&merge(template, &overrides), name)
+}
@@ -0,0 +1,1423 @@
+//! Validation of yaml network configurations.
+//!
+//! The parser stops at the first problem and does not know where in the file it occurred.
+//! The validation pass walks the configuration (including all included files) and reports
+//! every problem at once with its file, line and column.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use canzero_config::config::{signal::MAX_STRING_LEN, ByteOrder, CanController};
+
+use crate::{
+    formats::{toml_to_yaml, ConfigFormat},
+    templates::{NAME_PLACEHOLDER, TEMPLATE_KEY},
+    variants::VARIANTS_KEY,
+};
+use yaml_rust::{
+    parser::{Event, MarkedEventReceiver, Parser},
+    scanner::{Marker, TScalarStyle},
+    Yaml,
+};
+
+const TOP_LEVEL_KEYS: &[&str] = &[
+    "nodes",
+    "buses",
+    "struct_types",
+    "enum_types",
+    "templates",
+    "instances",
+    VARIANTS_KEY,
+];
+const OVERLAY_KEYS: &[&str] = &[
+    "nodes",
+    "buses",
+    "struct_types",
+    "enum_types",
+    "templates",
+    "instances",
+];
+const NODE_KEYS: &[&str] = &[
+    "description",
+    "heartbeat_timeout",
+    "controller",
+    "max_filters",
+    "object_dictionary",
+    "tx_streams",
+    "rx_streams",
+    "commands",
+];
+const OBJECT_ENTRY_KEYS: &[&str] = &[
+    "type",
+    "description",
+    "friend",
+    "unit",
+    "access",
+    "min",
+    "max",
+    "default",
+    "persistent",
+    "byte_order",
+];
+const STREAM_KEYS: &[&str] = &["description", "mapping", "interval"];
+const COMMAND_KEYS: &[&str] = &["description", "arguments", "callee"];
+const BUS_KEYS: &[&str] = &["baudrate", "data_baudrate", "brs", "database"];
+const ACCESS_MODES: &[&str] = &[
+    "const",
+    "local",
+    "readonly",
+    "static",
+    "global",
+    "readwrite",
+    "extern",
+    "external",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// the configuration can't be built.
+    Error,
+    /// the configuration is built, but part of it is ignored.
+    Warning,
+}
+
+/// A problem of the configuration at a location in one of its files.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: PathBuf,
+    /// 1-based line, 0 if the problem does not refer to a line (e.g. unreadable files).
+    pub line: usize,
+    /// 1-based column.
+    pub col: usize,
+    pub message: String,
+    pub help: Option<String>,
+    source_line: Option<String>,
+    len: usize,
+}
+
+impl Diagnostic {
+    /// Warning about a whole file, e.g. an out of date id lock file.
+    pub fn file_warning(file: PathBuf, message: String, help: Option<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            file,
+            line: 0,
+            col: 0,
+            message,
+            help,
+            source_line: None,
+            len: 0,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    /// Compiler style report, including the offending line of the file.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.severity {
+            Severity::Error => writeln!(f, "error: {}", self.message)?,
+            Severity::Warning => writeln!(f, "warning: {}", self.message)?,
+        }
+        let gutter = " ".repeat(self.line.to_string().len());
+        if self.line == 0 {
+            writeln!(f, "{gutter}--> {}", self.file.display())?;
+        } else {
+            writeln!(
+                f,
+                "{gutter}--> {}:{}:{}",
+                self.file.display(),
+                self.line,
+                self.col
+            )?;
+        }
+        if let Some(source_line) = &self.source_line {
+            writeln!(f, "{gutter} |")?;
+            writeln!(f, "{} | {source_line}", self.line)?;
+            writeln!(
+                f,
+                "{gutter} | {}{}",
+                " ".repeat(self.col.saturating_sub(1)),
+                "^".repeat(self.len.max(1))
+            )?;
+        }
+        if let Some(help) = &self.help {
+            writeln!(f, "{gutter} = help: {help}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Value {
+    /// resolved value and the scalar as written in the file.
+    Scalar(Yaml, String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+    Alias,
+}
+
+/// yaml node with the position at which it starts.
+#[derive(Debug, Clone)]
+struct Node {
+    value: Value,
+    line: usize,
+    col: usize,
+}
+
+impl Node {
+    fn as_str(&self) -> Option<&str> {
+        match &self.value {
+            Value::Scalar(Yaml::String(s), _) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn describe(&self) -> &'static str {
+        match &self.value {
+            Value::Scalar(Yaml::String(_), _) => "a string",
+            Value::Scalar(Yaml::Integer(_), _) => "an integer",
+            Value::Scalar(Yaml::Real(_), _) => "a real",
+            Value::Scalar(Yaml::Boolean(_), _) => "a boolean",
+            Value::Scalar(Yaml::Null, _) => "nothing",
+            Value::Scalar(_, _) => "a scalar",
+            Value::Sequence(_) => "a list",
+            Value::Mapping(_) => "a map",
+            Value::Alias => "an alias",
+        }
+    }
+
+    fn len(&self) -> usize {
+        match &self.value {
+            Value::Scalar(_, raw) => raw.chars().count(),
+            _ => 1,
+        }
+    }
+}
+
+/// Overrides `base` with `overrides`, maps are merged recursively (see [crate::templates]).
+fn merge(base: &Node, overrides: &Node) -> Node {
+    match (&base.value, &overrides.value) {
+        (Value::Mapping(base_entries), Value::Mapping(override_entries)) => {
+            let mut entries = base_entries.clone();
+            for (key, value) in override_entries {
+                match entries
+                    .iter_mut()
+                    .find(|(k, _)| k.as_str().is_some() && k.as_str() == key.as_str())
+                {
+                    Some((_, base_value)) => *base_value = merge(base_value, value),
+                    None => entries.push((key.clone(), value.clone())),
+                }
+            }
+            Node {
+                value: Value::Mapping(entries),
+                ..base.clone()
+            }
+        }
+        _ => overrides.clone(),
+    }
+}
+
+fn substitute(node: &Node, name: &str) -> Node {
+    let value = match &node.value {
+        Value::Scalar(Yaml::String(s), raw) => Value::Scalar(
+            Yaml::String(s.replace(NAME_PLACEHOLDER, name)),
+            raw.replace(NAME_PLACEHOLDER, name),
+        ),
+        Value::Sequence(nodes) => {
+            Value::Sequence(nodes.iter().map(|n| substitute(n, name)).collect())
+        }
+        Value::Mapping(entries) => Value::Mapping(
+            entries
+                .iter()
+                .map(|(key, value)| (substitute(key, name), substitute(value, name)))
+                .collect(),
+        ),
+        other => other.clone(),
+    };
+    Node {
+        value,
+        ..node.clone()
+    }
+}
+
+/// Node of a document, which was not read by the yaml parser (see [crate::formats]).
+/// All nodes are placed at line 0, i.e. diagnostics only refer to the file.
+fn node_from_yaml(yaml: &Yaml) -> Node {
+    let value = match yaml {
+        Yaml::Array(elements) => Value::Sequence(elements.iter().map(node_from_yaml).collect()),
+        Yaml::Hash(map) => Value::Mapping(
+            map.iter()
+                .map(|(key, value)| (node_from_yaml(key), node_from_yaml(value)))
+                .collect(),
+        ),
+        Yaml::String(s) => Value::Scalar(yaml.clone(), s.clone()),
+        Yaml::Integer(i) => Value::Scalar(yaml.clone(), i.to_string()),
+        Yaml::Real(r) => Value::Scalar(yaml.clone(), r.clone()),
+        Yaml::Boolean(b) => Value::Scalar(yaml.clone(), b.to_string()),
+        _ => Value::Scalar(Yaml::Null, String::new()),
+    };
+    Node {
+        value,
+        line: 0,
+        col: 0,
+    }
+}
+
+enum Frame {
+    Sequence(Marker, Vec<Node>),
+    Mapping(Marker, Vec<(Node, Node)>, Option<Node>),
+}
+
+/// Builds a tree of [Node]s from the events of the yaml parser,
+/// the yaml loader of yaml_rust drops all positions.
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+}
+
+impl TreeBuilder {
+    fn complete(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(Frame::Sequence(_, nodes)) => nodes.push(node),
+            Some(Frame::Mapping(_, entries, key)) => match key.take() {
+                Some(key) => entries.push((key, node)),
+                None => *key = Some(node),
+            },
+            None => {
+                // only the first document is used.
+                if self.root.is_none() {
+                    self.root = Some(node);
+                }
+            }
+        }
+    }
+}
+
+fn node_at(value: Value, marker: &Marker) -> Node {
+    Node {
+        value,
+        line: marker.line(),
+        col: marker.col() + 1,
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, ev: Event, marker: Marker) {
+        match ev {
+            Event::Scalar(raw, style, _, _) => {
+                // same resolution as the yaml loader.
+                let value = if style == TScalarStyle::Plain {
+                    Yaml::from_str(&raw)
+                } else {
+                    Yaml::String(raw.clone())
+                };
+                self.complete(node_at(Value::Scalar(value, raw), &marker));
+            }
+            Event::Alias(_) => self.complete(node_at(Value::Alias, &marker)),
+            Event::SequenceStart(_) => self.stack.push(Frame::Sequence(marker, vec![])),
+            Event::MappingStart(_) => self.stack.push(Frame::Mapping(marker, vec![], None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                let node = match self.stack.pop() {
+                    Some(Frame::Sequence(marker, nodes)) => {
+                        node_at(Value::Sequence(nodes), &marker)
+                    }
+                    Some(Frame::Mapping(marker, entries, _)) => {
+                        node_at(Value::Mapping(entries), &marker)
+                    }
+                    None => return,
+                };
+                self.complete(node);
+            }
+            _ => (),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Source {
+    file: PathBuf,
+    lines: Vec<String>,
+}
+
+/// Number of single character edits required to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let tmp = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                prev.min(row[j]).min(row[j + 1]) + 1
+            };
+            prev = tmp;
+        }
+    }
+    row[b.len()]
+}
+
+/// Returns the closest candidate, if it is close enough to be a likely typo.
+/// The value of a yaml number.
+fn number(node: &Node) -> Option<f64> {
+    match &node.value {
+        Value::Scalar(Yaml::Integer(value), _) => Some(*value as f64),
+        Value::Scalar(Yaml::Real(value), _) => value.parse().ok(),
+        _ => None,
+    }
+}
+
+fn suggest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| format!("did you mean `{candidate}`?"))
+}
+
+/// Min and max of an interval in milliseconds, `None` if it is not a valid interval.
+fn interval_bounds(interval: &str) -> Option<(u64, u64)> {
+    let cap = regex::Regex::new(
+        r"^\s*(?<min>\d+)\s*(?<min_unit>ms|s)\s*(-\s*(?<max>\d+)\s*(?<max_unit>ms|s)\s*)?$",
+    )
+    .unwrap()
+    .captures(interval)?;
+    let millis = |value: &str, unit: &str| {
+        let value = value.parse::<u64>().ok()?;
+        match unit {
+            "s" => value.checked_mul(1000),
+            _ => Some(value),
+        }
+    };
+    let min = millis(&cap["min"], &cap["min_unit"])?;
+    let max = match cap.name("max") {
+        Some(max) => millis(max.as_str(), &cap["max_unit"])?,
+        None => min,
+    };
+    Some((min, max))
+}
+
+fn is_time(time: &str) -> bool {
+    regex::Regex::new(r"^\s*\d+\s*(ms|s)\s*$")
+        .unwrap()
+        .is_match(time)
+}
+
+/// Checks a type name the same way `NetworkBuilder::resolve_type` resolves it,
+/// returns a description of the problem if it can not be resolved.
+fn check_type(type_name: &str, defined_types: &[String]) -> Option<(String, Option<String>)> {
+    let array_regex = regex::Regex::new(r"^(?<type>.+)\[(?<len>[0-9]+)\]$").unwrap();
+    if let Some(cap) = array_regex.captures(type_name) {
+        if cap["len"].parse::<usize>().map_or(true, |len| len == 0) {
+            return Some((
+                format!("invalid length of type `{type_name}`, arrays have at least one element"),
+                None,
+            ));
+        }
+        return check_type(&cap["type"], defined_types);
+    }
+    if defined_types.iter().any(|ty| ty == type_name) {
+        return None;
+    }
+    let int_regex = regex::Regex::new(r"^[ui](?<size>[0-9]{1,2})$").unwrap();
+    if let Some(cap) = int_regex.captures(type_name) {
+        let size: u8 = cap["size"].parse().unwrap();
+        if size == 0 || size > 64 {
+            return Some((
+                format!(
+                    "invalid size of type `{type_name}`, sizes have to be between 1 and 64 bits"
+                ),
+                None,
+            ));
+        }
+        return None;
+    }
+    if matches!(type_name, "f32" | "f64" | "bool") {
+        return None;
+    }
+    let string_regex = regex::Regex::new(r"^string<(?<len>[0-9]{1,3})>$").unwrap();
+    if let Some(cap) = string_regex.captures(type_name) {
+        let len: u32 = cap["len"].parse().unwrap();
+        if len == 0 || len > MAX_STRING_LEN as u32 {
+            return Some((
+                format!(
+                    "invalid length of type `{type_name}`, strings have between 1 and {MAX_STRING_LEN} characters"
+                ),
+                None,
+            ));
+        }
+        return None;
+    }
+    let dec_regex = regex::Regex::new(
+        r"^(?<signed>s?)d(?<size>[0-9]{1,2})<(?<min>[+-]?([0-9]*[.])?[0-9]+)\.\.(?<max>[+-]?([0-9]*[.])?[0-9]+)>$",
+    )
+    .unwrap();
+    if let Some(cap) = dec_regex.captures(type_name) {
+        let signed = !cap["signed"].is_empty();
+        let size: u8 = cap["size"].parse().unwrap();
+        let min: f64 = cap["min"].parse().unwrap();
+        let max: f64 = cap["max"].parse().unwrap();
+        if size == 0 || size > 64 || (signed && size < 2) {
+            return Some((
+                format!(
+                    "invalid size of type `{type_name}`, sizes have to be between {} and 64 bits",
+                    if signed { 2 } else { 1 }
+                ),
+                None,
+            ));
+        }
+        if min >= max {
+            return Some((
+                format!("invalid range of type `{type_name}`, min has to be less than max"),
+                None,
+            ));
+        }
+        if signed && (min >= 0.0 || max <= 0.0) {
+            return Some((
+                format!(
+                    "invalid range of type `{type_name}`, signed decimals have to include zero"
+                ),
+                Some(format!(
+                    "use `d{size}<{min}..{max}>` for ranges without zero"
+                )),
+            ));
+        }
+        return None;
+    }
+    Some((
+        format!("unknown type `{type_name}`"),
+        suggest(type_name, defined_types.iter().map(String::as_str)),
+    ))
+}
+
+/// Names defined by a node, used to check references between nodes.
+#[derive(Default)]
+struct NodeSymbols {
+    object_entries: Vec<String>,
+    tx_streams: Vec<String>,
+}
+
+struct Validator {
+    diagnostics: Vec<Diagnostic>,
+    defined_types: Vec<String>,
+    enum_variants: HashMap<String, Vec<String>>,
+    nodes: HashMap<String, NodeSymbols>,
+}
+
+impl Validator {
+    fn error(&mut self, src: &Source, node: &Node, message: String, help: Option<String>) {
+        self.report(Severity::Error, src, node, message, help);
+    }
+
+    fn warning(&mut self, src: &Source, node: &Node, message: String, help: Option<String>) {
+        self.report(Severity::Warning, src, node, message, help);
+    }
+
+    fn report(
+        &mut self,
+        severity: Severity,
+        src: &Source,
+        node: &Node,
+        message: String,
+        help: Option<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: src.file.clone(),
+            line: node.line,
+            col: node.col,
+            message,
+            help,
+            source_line: src.lines.get(node.line.wrapping_sub(1)).cloned(),
+            len: node.len(),
+        });
+    }
+
+    fn parse(&mut self, file: &Path, text: &str) -> Option<(Source, Node)> {
+        let src = Source {
+            file: file.to_path_buf(),
+            lines: text.lines().map(str::to_owned).collect(),
+        };
+        if ConfigFormat::from_path(file) == ConfigFormat::Toml {
+            return self.parse_toml(src, text);
+        }
+        let mut builder = TreeBuilder::default();
+        if let Err(err) = Parser::new(text.chars()).load(&mut builder, false) {
+            let marker = err.marker();
+            let message = err.to_string();
+            let message = match message.split_once(" at line ") {
+                Some((info, _)) => info.to_owned(),
+                None => message,
+            };
+            self.error(
+                &src,
+                &node_at(Value::Alias, marker),
+                format!("invalid yaml: {message}"),
+                None,
+            );
+            return None;
+        }
+        let root = builder.root.unwrap_or(Node {
+            value: Value::Scalar(Yaml::Null, String::new()),
+            line: 1,
+            col: 1,
+        });
+        Some((src, root))
+    }
+
+    /// toml values carry no positions, only syntax errors refer to a line.
+    fn parse_toml(&mut self, src: Source, text: &str) -> Option<(Source, Node)> {
+        match toml::from_str::<toml::Table>(text) {
+            Ok(table) => {
+                let root = node_from_yaml(&toml_to_yaml(&toml::Value::Table(table)));
+                Some((src, root))
+            }
+            Err(err) => {
+                let (line, col) = match err.span() {
+                    Some(span) => {
+                        let before = &text[..span.start];
+                        let line = before.matches('\n').count() + 1;
+                        let col = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+                        (line, col)
+                    }
+                    None => (0, 0),
+                };
+                self.error(
+                    &src,
+                    &Node {
+                        value: Value::Alias,
+                        line,
+                        col,
+                    },
+                    format!("invalid toml: {}", err.message()),
+                    None,
+                );
+                None
+            }
+        }
+    }
+
+    /// Loads a file that is included by the string `include` of `src`.
+    /// Includes are relative to the directory of the top level configuration `dir`.
+    fn include(&mut self, src: &Source, include: &Node, dir: &Path) -> Option<(Source, Node)> {
+        let path = include.as_str()?;
+        let file = dir.join(path);
+        match std::fs::read_to_string(&file) {
+            Ok(text) => self.parse(&file, &text),
+            Err(err) => {
+                self.error(
+                    src,
+                    include,
+                    format!("failed to read included file {file:?} : {err}"),
+                    None,
+                );
+                None
+            }
+        }
+    }
+
+    /// Loads the included file, if `node` is a string, otherwise returns `node` itself.
+    fn resolve(&mut self, src: &Source, node: &Node, dir: &Path) -> Option<(Source, Node)> {
+        if node.as_str().is_some() {
+            self.include(src, node, dir)
+        } else {
+            Some((src.clone(), node.clone()))
+        }
+    }
+
+    fn string<'a>(&mut self, src: &Source, node: &'a Node, what: &str) -> Option<&'a str> {
+        let s = node.as_str();
+        if s.is_none() {
+            self.error(
+                src,
+                node,
+                format!("{what} has to be a string, found {}", node.describe()),
+                None,
+            );
+        }
+        s
+    }
+
+    /// Returns the entries of a map with string keys, reports duplicated keys
+    /// and keys which are not in `allowed` (if given).
+    fn map<'a>(
+        &mut self,
+        src: &Source,
+        node: &'a Node,
+        what: &str,
+        allowed: Option<&[&str]>,
+    ) -> Vec<(&'a str, &'a Node, &'a Node)> {
+        let Value::Mapping(entries) = &node.value else {
+            if !matches!(node.value, Value::Scalar(Yaml::Null, _)) {
+                self.error(
+                    src,
+                    node,
+                    format!("{what} has to be a map, found {}", node.describe()),
+                    None,
+                );
+            }
+            return vec![];
+        };
+        let mut result: Vec<(&'a str, &'a Node, &'a Node)> = vec![];
+        for (key, value) in entries {
+            let Some(name) = key.as_str() else {
+                self.error(
+                    src,
+                    key,
+                    format!(
+                        "keys of {what} have to be strings, found {}",
+                        key.describe()
+                    ),
+                    None,
+                );
+                continue;
+            };
+            if result.iter().any(|(other, _, _)| *other == name) {
+                self.error(src, key, format!("duplicated key `{name}` in {what}"), None);
+                continue;
+            }
+            if let Some(allowed) = allowed {
+                if !allowed.contains(&name) {
+                    self.error(
+                        src,
+                        key,
+                        format!("unknown key `{name}` in {what}"),
+                        suggest(name, allowed.iter().copied()),
+                    );
+                    continue;
+                }
+            }
+            result.push((name, key, value));
+        }
+        result
+    }
+
+    fn sequence<'a>(&mut self, src: &Source, node: &'a Node, what: &str) -> &'a [Node] {
+        match &node.value {
+            Value::Sequence(nodes) => nodes,
+            _ => {
+                self.error(
+                    src,
+                    node,
+                    format!("{what} has to be a list, found {}", node.describe()),
+                    None,
+                );
+                &[]
+            }
+        }
+    }
+
+    fn check_type_name(&mut self, src: &Source, node: &Node, what: &str) {
+        let Some(type_name) = self.string(src, node, what) else {
+            return;
+        };
+        if let Some((message, help)) = check_type(type_name, &self.defined_types) {
+            self.error(src, node, message, help);
+        }
+    }
+
+    fn validate_struct_types(&mut self, src: &Source, node: &Node) {
+        for (_, _, attribs) in self.map(src, node, "struct_types", None) {
+            for (_, _, ty) in self.map(src, attribs, "a struct", None) {
+                self.check_type_name(src, ty, "the type of a struct attribute");
+            }
+        }
+    }
+
+    fn validate_enum_types(&mut self, src: &Source, node: &Node) {
+        for (enum_name, _, variants) in self.map(src, node, "enum_types", None) {
+            for (variant, _, value) in self.map(src, variants, "an enum", None) {
+                self.enum_variants
+                    .entry(enum_name.to_owned())
+                    .or_default()
+                    .push(variant.to_owned());
+                match &value.value {
+                    Value::Scalar(Yaml::Integer(value_int), _) if *value_int < 0 => self.error(
+                        src,
+                        value,
+                        "enum values must not be negative".to_owned(),
+                        None,
+                    ),
+                    Value::Scalar(Yaml::Integer(_), _) | Value::Scalar(Yaml::Null, _) => (),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!(
+                            "enum values have to be integers, found {}",
+                            value.describe()
+                        ),
+                        None,
+                    ),
+                }
+            }
+        }
+    }
+
+    fn validate_object_entry(&mut self, src: &Source, oe_name: &str, node: &Node) {
+        let entries = self.map(
+            src,
+            node,
+            &format!("object entry `{oe_name}`"),
+            Some(OBJECT_ENTRY_KEYS),
+        );
+        if !entries.iter().any(|(key, _, _)| *key == "type")
+            && matches!(node.value, Value::Mapping(_))
+        {
+            self.error(
+                src,
+                node,
+                format!("object entry `{oe_name}` has no type"),
+                Some("add `type : <type>`, for example `type : u8`".to_owned()),
+            );
+        }
+        let mut type_name = None;
+        let mut min = None;
+        let mut max = None;
+        let mut default = None;
+        for (key, _, value) in entries {
+            match key {
+                "type" => {
+                    type_name = value.as_str();
+                    self.check_type_name(src, value, "type")
+                }
+                "access" => {
+                    if let Some(access) = self.string(src, value, "access") {
+                        if !ACCESS_MODES.contains(&access.to_lowercase().as_str()) {
+                            self.error(
+                                src,
+                                value,
+                                format!("unknown access `{access}`"),
+                                suggest(&access.to_lowercase(), ACCESS_MODES.iter().copied())
+                                    .or(Some("use one of `const`, `local` or `global`".to_owned())),
+                            );
+                        }
+                    }
+                }
+                "min" | "max" => match &value.value {
+                    Value::Scalar(Yaml::Integer(_) | Yaml::Real(_), _) => {
+                        let bound = number(value).map(|bound| (bound, value));
+                        match key {
+                            "min" => min = bound,
+                            _ => max = bound,
+                        }
+                    }
+                    _ => self.error(
+                        src,
+                        value,
+                        format!("{key} has to be a number, found {}", value.describe()),
+                        Some(format!("for example `{key} : 10`")),
+                    ),
+                },
+                "byte_order" => {
+                    if let Some(byte_order) = self.string(src, value, "byte_order") {
+                        if ByteOrder::from_name(byte_order).is_none() {
+                            let names = ByteOrder::ALL.map(|byte_order| byte_order.name());
+                            self.error(
+                                src,
+                                value,
+                                format!("unknown byte_order `{byte_order}`"),
+                                suggest(&byte_order.to_lowercase(), names.iter().copied())
+                                    .or(Some("use `little_endian` or `big_endian`".to_owned())),
+                            );
+                        }
+                    }
+                }
+                "persistent" => match &value.value {
+                    Value::Scalar(Yaml::Boolean(_), _) => (),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!("persistent has to be a boolean, found {}", value.describe()),
+                        Some("for example `persistent : true`".to_owned()),
+                    ),
+                },
+                "default" => match &value.value {
+                    Value::Scalar(
+                        Yaml::Integer(_) | Yaml::Real(_) | Yaml::Boolean(_) | Yaml::String(_),
+                        _,
+                    ) => default = Some(value),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!(
+                            "default has to be a number, boolean or string, found {}",
+                            value.describe()
+                        ),
+                        None,
+                    ),
+                },
+                _ => {
+                    self.string(src, value, key);
+                }
+            }
+        }
+
+        if let (Some((min, min_node)), Some((max, _))) = (min, max) {
+            if min > max {
+                self.error(
+                    src,
+                    min_node,
+                    format!("the min {min} of `{oe_name}` is greater than its max {max}"),
+                    None,
+                );
+            }
+        }
+        let Some(default) = default else {
+            return;
+        };
+        if let Some(variants) = type_name.and_then(|ty| self.enum_variants.get(ty)) {
+            let variant = default.as_str().unwrap_or_default();
+            if !variants.iter().any(|v| v == variant) {
+                let help = suggest(variant, variants.iter().map(String::as_str))
+                    .or(Some(format!("use one of `{}`", variants.join("`, `"))));
+                self.error(
+                    src,
+                    default,
+                    format!(
+                        "the default of `{oe_name}` is not a variant of `{}`",
+                        type_name.unwrap_or_default()
+                    ),
+                    help,
+                );
+            }
+        } else if let Some(value) = number(default) {
+            if let Some((min, _)) = min {
+                if value < min {
+                    self.error(
+                        src,
+                        default,
+                        format!("the default {value} of `{oe_name}` is less than its min {min}"),
+                        None,
+                    );
+                }
+            }
+            if let Some((max, _)) = max {
+                if value > max {
+                    self.error(
+                        src,
+                        default,
+                        format!("the default {value} of `{oe_name}` is greater than its max {max}"),
+                        None,
+                    );
+                }
+            }
+        }
+    }
+
+    fn validate_tx_stream(
+        &mut self,
+        src: &Source,
+        node_name: &str,
+        stream_name: &str,
+        node: &Node,
+    ) {
+        for (key, _, value) in self.map(
+            src,
+            node,
+            &format!("stream `{stream_name}`"),
+            Some(STREAM_KEYS),
+        ) {
+            match key {
+                "mapping" => {
+                    for oe in self.sequence(src, value, "the mapping of a stream") {
+                        let Some(oe_name) = self.string(src, oe, "a mapping entry") else {
+                            continue;
+                        };
+                        self.check_object_entry_ref(src, oe, node_name, oe_name);
+                    }
+                }
+                "interval" => {
+                    if let Some(interval) = self.string(src, value, "interval") {
+                        match interval_bounds(interval) {
+                            None => self.error(
+                                src,
+                                value,
+                                format!("invalid interval `{interval}`"),
+                                Some("intervals are written as `<n>ms`, `<n>s` or `<min>ms - <max>ms`".to_owned()),
+                            ),
+                            Some((min, max)) if min > max => self.error(
+                                src,
+                                value,
+                                format!("invalid interval `{interval}`, the min is greater than the max"),
+                                None,
+                            ),
+                            Some(_) => (),
+                        }
+                    }
+                }
+                _ => {
+                    self.string(src, value, key);
+                }
+            }
+        }
+    }
+
+    fn check_object_entry_ref(&mut self, src: &Source, at: &Node, node_name: &str, oe_name: &str) {
+        let Some(symbols) = self.nodes.get(node_name) else {
+            return;
+        };
+        if !symbols.object_entries.iter().any(|oe| oe == oe_name) {
+            let help = suggest(oe_name, symbols.object_entries.iter().map(String::as_str));
+            self.error(
+                src,
+                at,
+                format!("node `{node_name}` has no object entry `{oe_name}`"),
+                help,
+            );
+        }
+    }
+
+    fn validate_rx_streams(&mut self, src: &Source, node_name: &str, node: &Node) {
+        for (tx_node_name, tx_node_key, streams) in self.map(src, node, "rx_streams", None) {
+            let Some(tx_streams) = self
+                .nodes
+                .get(tx_node_name)
+                .map(|symbols| symbols.tx_streams.clone())
+            else {
+                let help = suggest(tx_node_name, self.nodes.keys().map(String::as_str));
+                self.error(
+                    src,
+                    tx_node_key,
+                    format!("unknown node `{tx_node_name}`"),
+                    help,
+                );
+                continue;
+            };
+            for (stream_name, stream_key, mapping) in self.map(
+                src,
+                streams,
+                &format!("the streams of `{tx_node_name}`"),
+                None,
+            ) {
+                if !tx_streams.iter().any(|s| s == stream_name) {
+                    self.error(
+                        src,
+                        stream_key,
+                        format!("node `{tx_node_name}` has no tx_stream `{stream_name}`"),
+                        suggest(stream_name, tx_streams.iter().map(String::as_str)),
+                    );
+                    continue;
+                }
+                for (tx_oe, tx_oe_key, rx_oe) in
+                    self.map(src, mapping, &format!("rx_stream `{stream_name}`"), None)
+                {
+                    self.check_object_entry_ref(src, tx_oe_key, tx_node_name, tx_oe);
+                    if let Some(rx_oe_name) = self.string(src, rx_oe, "the receiving object entry")
+                    {
+                        self.check_object_entry_ref(src, rx_oe, node_name, rx_oe_name);
+                    }
+                }
+            }
+        }
+    }
+
+    fn validate_command(&mut self, src: &Source, command_name: &str, node: &Node) {
+        for (key, _, value) in self.map(
+            src,
+            node,
+            &format!("command `{command_name}`"),
+            Some(COMMAND_KEYS),
+        ) {
+            match key {
+                "arguments" => {
+                    for arg in self.sequence(src, value, "the arguments of a command") {
+                        let args = self.map(src, arg, "a command argument", None);
+                        if args.len() != 1 {
+                            self.error(
+                                src,
+                                arg,
+                                "command arguments have to be defined as `<name> : <type>`"
+                                    .to_owned(),
+                                None,
+                            );
+                        }
+                        for (_, _, ty) in args {
+                            self.check_type_name(src, ty, "the type of a command argument");
+                        }
+                    }
+                }
+                "callee" => {
+                    for callee in self.sequence(src, value, "the callees of a command") {
+                        let Some(callee_name) = self.string(src, callee, "a callee") else {
+                            continue;
+                        };
+                        if !self.nodes.contains_key(callee_name) {
+                            let help = suggest(callee_name, self.nodes.keys().map(String::as_str));
+                            self.error(src, callee, format!("unknown node `{callee_name}`"), help);
+                        }
+                    }
+                }
+                _ => {
+                    self.string(src, value, key);
+                }
+            }
+        }
+    }
+
+    fn collect_node_symbols(node: &Node) -> NodeSymbols {
+        let mut symbols = NodeSymbols::default();
+        let Value::Mapping(entries) = &node.value else {
+            return symbols;
+        };
+        for (key, value) in entries {
+            let Value::Mapping(items) = &value.value else {
+                continue;
+            };
+            let names = items
+                .iter()
+                .filter_map(|(name, _)| name.as_str().map(str::to_owned));
+            match key.as_str() {
+                Some("object_dictionary") => symbols.object_entries.extend(names),
+                Some("tx_streams") => symbols.tx_streams.extend(names),
+                _ => (),
+            }
+        }
+        symbols
+    }
+
+    fn validate_node(&mut self, src: &Source, node_name: &str, node: &Node) {
+        for (key, _, value) in self.map(src, node, &format!("node `{node_name}`"), Some(NODE_KEYS))
+        {
+            match key {
+                "heartbeat_timeout" => {
+                    if let Some(timeout) = self.string(src, value, "heartbeat_timeout") {
+                        if !is_time(timeout) {
+                            self.error(
+                                src,
+                                value,
+                                format!("invalid heartbeat_timeout `{timeout}`"),
+                                Some("time literals are written as `<n>ms` or `<n>s`".to_owned()),
+                            );
+                        }
+                    }
+                }
+                "controller" => {
+                    if let Some(controller) = self.string(src, value, "controller") {
+                        if CanController::from_name(controller).is_none() {
+                            let names = CanController::ALL.map(|controller| controller.name());
+                            self.error(
+                                src,
+                                value,
+                                format!("unknown controller `{controller}`"),
+                                suggest(&controller.to_lowercase(), names.iter().copied())
+                                    .or(Some(format!("use one of `{}`", names.join("`, `")))),
+                            );
+                        }
+                    }
+                }
+                "max_filters" => match &value.value {
+                    Value::Scalar(Yaml::Integer(max_filters), _) if *max_filters > 0 => (),
+                    Value::Scalar(Yaml::Integer(max_filters), _) => self.error(
+                        src,
+                        value,
+                        format!("max_filters has to be at least 1, found {max_filters}"),
+                        Some("for example `max_filters : 14`".to_owned()),
+                    ),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!(
+                            "max_filters has to be an integer, found {}",
+                            value.describe()
+                        ),
+                        Some("for example `max_filters : 14`".to_owned()),
+                    ),
+                },
+                "object_dictionary" => {
+                    for (oe_name, _, oe) in self.map(src, value, "object_dictionary", None) {
+                        self.validate_object_entry(src, oe_name, oe);
+                    }
+                }
+                "tx_streams" => {
+                    for (stream_name, _, stream) in self.map(src, value, "tx_streams", None) {
+                        self.validate_tx_stream(src, node_name, stream_name, stream);
+                    }
+                }
+                "rx_streams" => self.validate_rx_streams(src, node_name, value),
+                "commands" => {
+                    for (command_name, _, command) in self.map(src, value, "commands", None) {
+                        self.validate_command(src, command_name, command);
+                    }
+                }
+                _ => {
+                    self.string(src, value, key);
+                }
+            }
+        }
+    }
+
+    fn validate_bus(&mut self, src: &Source, bus_name: &str, node: &Node) {
+        let entries = self.map(src, node, &format!("bus `{bus_name}`"), Some(BUS_KEYS));
+        let fd = entries.iter().any(|(key, _, _)| *key == "data_baudrate");
+        for (key, _, value) in entries {
+            match key {
+                "baudrate" => match &value.value {
+                    Value::Scalar(Yaml::Integer(baudrate), _) if *baudrate > 0 => (),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!(
+                            "baudrate has to be a positive integer, found {}",
+                            value.describe()
+                        ),
+                        Some("for example `baudrate : 1000000`".to_owned()),
+                    ),
+                },
+                "data_baudrate" => match &value.value {
+                    Value::Scalar(Yaml::Integer(data_baudrate), _) if *data_baudrate > 0 => (),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!(
+                            "data_baudrate has to be a positive integer, found {}",
+                            value.describe()
+                        ),
+                        Some("for example `data_baudrate : 5000000`".to_owned()),
+                    ),
+                },
+                "brs" => match &value.value {
+                    Value::Scalar(Yaml::Boolean(_), _) if fd => (),
+                    Value::Scalar(Yaml::Boolean(_), _) => self.error(
+                        src,
+                        value,
+                        "brs is only valid for CAN FD buses".to_owned(),
+                        Some("CAN FD buses require a `data_baudrate`".to_owned()),
+                    ),
+                    _ => self.error(
+                        src,
+                        value,
+                        format!("brs has to be a boolean, found {}", value.describe()),
+                        None,
+                    ),
+                },
+                "database" => {
+                    let Some(path) = self.string(src, value, "database") else {
+                        continue;
+                    };
+                    if !path.ends_with(".dbc") {
+                        self.error(
+                            src,
+                            value,
+                            format!("database `{path}` is not a DBC file"),
+                            Some("database paths have to end in `.dbc`".to_owned()),
+                        );
+                    } else if !Path::new(path).exists() {
+                        self.error(
+                            src,
+                            value,
+                            format!("database `{path}` does not exist"),
+                            None,
+                        );
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn validate_top_level(&mut self, src: &Source, root: &Node) {
+        let Value::Mapping(_) = &root.value else {
+            self.error(
+                src,
+                root,
+                format!(
+                    "the network configuration has to be a map, found {}",
+                    root.describe()
+                ),
+                None,
+            );
+            return;
+        };
+        // unknown sections are ignored by the parser, they only produce a warning.
+        let mut sections = self.map(src, root, "the network configuration", None);
+        sections.retain(|(name, key, _)| {
+            let known = TOP_LEVEL_KEYS.contains(name);
+            if !known {
+                self.warning(
+                    src,
+                    key,
+                    format!("unknown key `{name}` in the network configuration is ignored"),
+                    suggest(name, TOP_LEVEL_KEYS.iter().copied()),
+                );
+            }
+            known
+        });
+        for required in ["nodes", "buses"] {
+            if !sections.iter().any(|(key, _, _)| *key == required) {
+                self.error(
+                    src,
+                    root,
+                    format!("the network configuration has no `{required}`"),
+                    None,
+                );
+            }
+        }
+        let section = |name: &str| {
+            sections
+                .iter()
+                .find(|(key, _, _)| *key == name)
+                .map(|(_, _, value)| *value)
+        };
+
+        let dir = src.file.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        // types and the names of all nodes are collected first, they are referenced by nodes.
+        let mut types = vec![];
+        for name in ["struct_types", "enum_types"] {
+            if let Some((types_src, types_node)) =
+                section(name).and_then(|n| self.resolve(src, n, &dir))
+            {
+                if let Value::Mapping(entries) = &types_node.value {
+                    self.defined_types.extend(
+                        entries
+                            .iter()
+                            .filter_map(|(k, _)| k.as_str().map(str::to_owned)),
+                    );
+                }
+                types.push((name, types_src, types_node));
+            }
+        }
+        for (name, types_src, types_node) in &types {
+            match *name {
+                "struct_types" => self.validate_struct_types(types_src, types_node),
+                _ => self.validate_enum_types(types_src, types_node),
+            }
+        }
+
+        let mut nodes = vec![];
+        if let Some((nodes_src, nodes_node)) =
+            section("nodes").and_then(|n| self.resolve(src, n, &dir))
+        {
+            for (node_name, _, node) in self.map(&nodes_src, &nodes_node, "nodes", None) {
+                let Some((node_src, node)) = self.resolve(&nodes_src, node, &dir) else {
+                    continue;
+                };
+                let symbols = Self::collect_node_symbols(&node);
+                self.nodes.insert(node_name.to_owned(), symbols);
+                nodes.push((node_name.to_owned(), node_src, node));
+            }
+        }
+        let templates = match section("templates") {
+            Some(templates) => self.map(src, templates, "templates", None),
+            None => vec![],
+        };
+        if let Some(instances) = section("instances") {
+            for (instance_name, instance_key, instance) in self.map(src, instances, "instances", None)
+            {
+                if self.nodes.contains_key(instance_name) {
+                    self.error(
+                        src,
+                        instance_key,
+                        format!("instance `{instance_name}` has the same name as a node"),
+                        None,
+                    );
+                    continue;
+                }
+                let Value::Mapping(entries) = &instance.value else {
+                    self.error(
+                        src,
+                        instance,
+                        format!(
+                            "instance `{instance_name}` has to be a map, found {}",
+                            instance.describe()
+                        ),
+                        None,
+                    );
+                    continue;
+                };
+                let Some((_, template_ref)) =
+                    entries.iter().find(|(key, _)| key.as_str() == Some(TEMPLATE_KEY))
+                else {
+                    self.error(
+                        src,
+                        instance,
+                        format!("instance `{instance_name}` has no template"),
+                        Some("add `template : <template>`".to_owned()),
+                    );
+                    continue;
+                };
+                let Some(template_name) = self.string(src, template_ref, "template") else {
+                    continue;
+                };
+                let Some((_, _, template)) =
+                    templates.iter().find(|(name, _, _)| *name == template_name)
+                else {
+                    let help = suggest(template_name, templates.iter().map(|(name, _, _)| *name));
+                    self.error(
+                        src,
+                        template_ref,
+                        format!("unknown template `{template_name}`"),
+                        help,
+                    );
+                    continue;
+                };
+                let overrides = Node {
+                    value: Value::Mapping(
+                        entries
+                            .iter()
+                            .filter(|(key, _)| key.as_str() != Some(TEMPLATE_KEY))
+                            .cloned()
+                            .collect(),
+                    ),
+                    ..instance.clone()
+                };
+                let node = substitute(&merge(template, &overrides), instance_name);
+                let symbols = Self::collect_node_symbols(&node);
+                self.nodes.insert(instance_name.to_owned(), symbols);
+                nodes.push((instance_name.to_owned(), src.clone(), node));
+            }
+        }
+
+        for (node_name, node_src, node) in &nodes {
+            self.validate_node(node_src, node_name, node);
+        }
+
+        if let Some((buses_src, buses_node)) =
+            section("buses").and_then(|n| self.resolve(src, n, &dir))
+        {
+            for (bus_name, _, bus) in self.map(&buses_src, &buses_node, "buses", None) {
+                self.validate_bus(&buses_src, bus_name, bus);
+            }
+        }
+
+        // overlays are only checked for their sections, the patched configuration
+        // is checked when the variant is built.
+        if let Some(variants) = section(VARIANTS_KEY) {
+            for (variant_name, _, overlay) in self.map(src, variants, "variants", None) {
+                if let Some((overlay_src, overlay)) = self.resolve(src, overlay, &dir) {
+                    let what = format!("the overlay of variant `{variant_name}`");
+                    self.map(&overlay_src, &overlay, &what, Some(OVERLAY_KEYS));
+                }
+            }
+        }
+    }
+}
+
+/// Validates the network configuration `src` read from `path`.
+/// Returns all problems of the configuration and its included files,
+/// only diagnostics with [Severity::Error] prevent the network from being built.
+pub fn validate_yaml_config(src: &str, path: &Path) -> Vec<Diagnostic> {
+    let mut validator = Validator {
+        diagnostics: vec![],
+        defined_types: vec![],
+        enum_variants: HashMap::new(),
+        nodes: HashMap::new(),
+    };
+    if let Some((src, root)) = validator.parse(path, src) {
+        validator.validate_top_level(&src, &root);
+    }
+    validator.diagnostics
+}
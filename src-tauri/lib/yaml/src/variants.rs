@@ -0,0 +1,140 @@
+//! Build variants.
+//!
+//! A variant patches the network configuration with an overlay, which is either given
+//! inline or as a file relative to the directory of the configuration:
+//!
+//! ```yaml
+//! variants:
+//!   pod: variants/pod.yaml
+//!   bench:
+//!     nodes:
+//!       mlu2: ~                  # removes the node
+//!       master:
+//!         tx_streams:
+//!           debug: ~             # removes the stream
+//!     buses:
+//!       can0:
+//!         baudrate: 500000       # patches the bus
+//!       can1: ~
+//! ```
+//!
+//! Overlays are applied like a json merge patch: maps are merged recursively,
+//! `~` (null) removes the key and everything else is replaced.
+//! Every variant keeps its own id lock file (see [crate::id_lock]).
+
+use std::path::Path;
+
+use yaml_rust::{yaml::Hash, Yaml};
+
+use crate::{
+    errors::{Error, Result},
+    parser::load_include,
+};
+
+/// Key of the top level section, which defines the variants.
+pub const VARIANTS_KEY: &str = "variants";
+/// Sections, which may be included from another file.
+const INCLUDABLE_SECTIONS: &[&str] = &["nodes", "buses", "struct_types", "enum_types"];
+
+/// Patches `base` with `patch`, the order of existing keys is kept.
+fn merge_patch(base: &Yaml, patch: &Yaml) -> Yaml {
+    match (base, patch) {
+        (Yaml::Hash(base), Yaml::Hash(patch)) => {
+            let mut merged = base.clone();
+            for (key, value) in patch {
+                if value.is_null() {
+                    merged.remove(key);
+                    continue;
+                }
+                match merged.get_mut(key) {
+                    Some(base_value) => *base_value = merge_patch(base_value, value),
+                    None => {
+                        merged.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            Yaml::Hash(merged)
+        }
+        (_, patch) => patch.clone(),
+    }
+}
+
+/// Loads the included file, if `yaml` is a string.
+fn resolve(yaml: &mut Yaml, path: &Path) -> Result<()> {
+    if let Yaml::String(include_path) = yaml {
+        let included = load_include(path, include_path)?;
+        *yaml = included;
+    }
+    Ok(())
+}
+
+/// The overlay of `variant`, `path` is the path of the configuration `doc`.
+fn load_overlay(doc: &Yaml, variant: &str, path: &Path) -> Result<Hash> {
+    let overlay = match &doc[VARIANTS_KEY][variant] {
+        Yaml::BadValue => {
+            return Err(Error::YamlInvalidFormat(format!(
+                "the configuration does not define the variant {variant}"
+            )))
+        }
+        Yaml::String(include_path) => load_include(path, include_path)?,
+        overlay => overlay.clone(),
+    };
+    match overlay {
+        Yaml::Hash(overlay) => Ok(overlay),
+        Yaml::Null => Ok(Hash::new()),
+        _ => Err(Error::YamlInvalidType(format!(
+            "the overlay of variant {variant} has to be a map"
+        ))),
+    }
+}
+
+/// The configuration `doc` read from `path` with the overlay of `variant` applied.
+pub fn apply_variant(doc: &Yaml, variant: &str, path: &Path) -> Result<Yaml> {
+    let overlay = load_overlay(doc, variant, path)?;
+    let Yaml::Hash(mut network) = doc.clone() else {
+        return Err(Error::YamlInvalidFormat(
+            "the network configuration has to be a map".to_owned(),
+        ));
+    };
+    network.remove(&Yaml::String(VARIANTS_KEY.to_owned()));
+
+    // patched sections and nodes are loaded from their files first.
+    for section in INCLUDABLE_SECTIONS {
+        let key = Yaml::String(section.to_string());
+        let (Some(base), Some(patch)) = (network.get_mut(&key), overlay.get(&key)) else {
+            continue;
+        };
+        if patch.is_null() {
+            continue;
+        }
+        resolve(base, path)?;
+        if *section != "nodes" {
+            continue;
+        }
+        let (Yaml::Hash(nodes), Yaml::Hash(node_patches)) = (base, patch) else {
+            continue;
+        };
+        for (name, node_patch) in node_patches {
+            if let (Some(node), Yaml::Hash(_)) = (nodes.get_mut(name), node_patch) {
+                resolve(node, path)?;
+            }
+        }
+    }
+    Ok(merge_patch(&Yaml::Hash(network), &Yaml::Hash(overlay)))
+}
+
+/// Path of the overlay file of `variant`, `None` if the overlay is given inline.
+pub fn overlay_file(doc: &Yaml, variant: &str) -> Option<String> {
+    doc[VARIANTS_KEY][variant].as_str().map(str::to_owned)
+}
+
+/// Names of all variants defined by the configuration `doc`.
+pub fn variant_names(doc: &Yaml) -> Vec<String> {
+    match &doc[VARIANTS_KEY] {
+        Yaml::Hash(variants) => variants
+            .keys()
+            .filter_map(|name| name.as_str().map(str::to_owned))
+            .collect(),
+        _ => vec![],
+    }
+}
@@ -0,0 +1,147 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    decode::{decode_object_entry, DecodedAttribute, DecodedValue, MessageDecoder},
+    encode::{encode_object_entry, MessageEncoder},
+    SignalType, Type,
+};
+use canzero_yaml::parse_yaml_config;
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+struct_types:
+  cell:
+    voltage: u8
+    ok: bool
+nodes:
+  secu:
+    object_dictionary:
+      temperatures:
+        type: i8[3]
+      cells:
+        type: cell[2]
+      matrix:
+        type: u4[2][3]
+    tx_streams:
+      cells:
+        mapping: [temperatures, cells]
+        interval: 10ms - 50ms
+  master:
+    rx_streams:
+      secu:
+        cells:
+          temperatures: secu_temperatures
+          cells: secu_cells
+    object_dictionary:
+      secu_temperatures:
+        type: i8[3]
+      secu_cells:
+        type: cell[2]
+";
+
+fn attribute(name: &str, value: DecodedValue) -> DecodedAttribute {
+    DecodedAttribute {
+        name: name.to_owned(),
+        value,
+    }
+}
+
+fn cell(voltage: u64, ok: bool) -> DecodedValue {
+    DecodedValue::Struct(vec![
+        attribute("voltage", DecodedValue::Unsigned(voltage)),
+        attribute("ok", DecodedValue::Bool(ok)),
+    ])
+}
+
+#[test]
+fn nested_arrays_are_resolved() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let secu = network.nodes().iter().find(|node| node.name() == "secu").unwrap();
+    let matrix = secu
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "matrix")
+        .unwrap();
+    let Type::Array { len: 3, ty: row } = matrix.ty().as_ref() else {
+        panic!("expected the last dimension to be the outermost array");
+    };
+    let Type::Array { len: 2, ty: element } = row.as_ref() else {
+        panic!("expected rows of two elements");
+    };
+    assert_eq!(
+        element.as_ref(),
+        &Type::Primitive(SignalType::UnsignedInt { size: 4 })
+    );
+}
+
+#[test]
+fn array_streams_roundtrip() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let stream = network
+        .messages()
+        .iter()
+        .find(|message| message.name().contains("cells"))
+        .unwrap();
+    let sizes: Vec<u8> = stream.signals().iter().map(|signal| signal.size()).collect();
+    assert_eq!(sizes, vec![8, 8, 8, 8, 1, 8, 1]);
+
+    let attributes = vec![
+        attribute(
+            "temperatures",
+            DecodedValue::Array(vec![
+                DecodedValue::Signed(-3),
+                DecodedValue::Signed(0),
+                DecodedValue::Signed(42),
+            ]),
+        ),
+        attribute(
+            "cells",
+            DecodedValue::Array(vec![cell(12, true), cell(200, false)]),
+        ),
+    ];
+    let data = MessageEncoder::new(stream).encode(&attributes);
+    assert_eq!(MessageDecoder::new(stream).decode(&data), attributes);
+}
+
+#[test]
+fn array_object_entries_roundtrip() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let secu = network.nodes().iter().find(|node| node.name() == "secu").unwrap();
+    let cells = secu
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "cells")
+        .unwrap();
+    let value = DecodedValue::Array(vec![cell(1, false), cell(255, true)]);
+    let (words, last_fill) = encode_object_entry(&value, cells.ty());
+    assert_eq!(words, vec![0x3FE01]);
+    assert_eq!(last_fill, 3);
+    assert_eq!(decode_object_entry(cells.ty(), &words), value);
+}
+
+#[test]
+fn oversized_arrays_are_rejected() {
+    // 4 * 32 bit of samples don't fit into the 8 bytes of a classic CAN frame.
+    let stream = NETWORK.replace("i8[3]", "u32[4]");
+    assert!(parse_yaml_config(&stream, Path::new("network.yaml")).is_err());
+
+    let command = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    commands:
+      flash:
+        arguments:
+          - data: u8[20]
+        callee: [master]
+  master:
+    object_dictionary:
+      x:
+        type: u8
+";
+    assert!(parse_yaml_config(command, Path::new("network.yaml")).is_err());
+}
@@ -0,0 +1,65 @@
+use std::path::Path;
+
+use canzero_config::config::bus_load::{analyze_bus_load, frame_bits};
+use canzero_yaml::parse_yaml_config;
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 500000
+nodes:
+  secu:
+    object_dictionary:
+      temperature:
+        type: u8
+    tx_streams:
+      status:
+        mapping: [temperature]
+        interval: 10ms - 50ms
+  master:
+    rx_streams:
+      secu:
+        status:
+          temperature: secu_temperature
+    object_dictionary:
+      secu_temperature:
+        type: u8
+";
+
+#[test]
+fn frame_bits_include_overhead_and_stuffing() {
+    assert_eq!(frame_bits(8, false, false), 47 + 64);
+    assert_eq!(frame_bits(8, false, true), 47 + 64 + 24);
+    assert_eq!(frame_bits(8, true, true), 67 + 64 + 29);
+    assert_eq!(frame_bits(0, false, true), 47 + 8);
+}
+
+#[test]
+fn stream_load_uses_min_interval() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let bus_loads = analyze_bus_load(&network);
+    assert_eq!(bus_loads.len(), 1);
+    let bus_load = &bus_loads[0];
+
+    let stream = bus_load
+        .messages()
+        .iter()
+        .find(|message_load| message_load.message().name().contains("status"))
+        .unwrap();
+    let dlc = stream.message().dlc();
+    let ide = stream.message().id().ide();
+    let worst_case = frame_bits(dlc, ide, true) as f64 * 100.0;
+    let average = frame_bits(dlc, ide, false) as f64 / 0.030;
+    assert!((stream.load().worst_case - worst_case).abs() < 1e-6);
+    assert!((stream.load().average - average).abs() < 1e-6);
+
+    let secu = bus_load
+        .nodes()
+        .iter()
+        .find(|node_load| node_load.node().is_some_and(|node| node.name() == "secu"))
+        .unwrap();
+    assert!(secu.load().worst_case >= stream.load().worst_case);
+    let total: f64 = bus_load.messages().iter().map(|m| m.load().worst_case).sum();
+    assert!((bus_load.load().worst_case - total).abs() < 1e-6);
+    assert!(bus_load.worst_case_utilisation() > bus_load.average_utilisation());
+}
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue, MessageDecoder, SignalDecoder},
+    encode::MessageEncoder,
+    ByteOrder, MessageRef, NetworkRef,
+};
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+enum_types:
+  state:
+    idle: 0
+    running: 1
+    error: 2
+struct_types:
+  pair:
+    a: u5
+    b: i11
+nodes:
+  secu:
+    object_dictionary:
+      flag:
+        type: bool
+      current:
+        type: sd12<-500..500>
+        byte_order: big_endian
+      state:
+        type: state
+        byte_order: big_endian
+      pair:
+        type: pair
+        byte_order: big_endian
+      tail:
+        type: i7
+    tx_streams:
+      status:
+        mapping: [flag, current, state, pair, tail]
+        interval: 10ms
+  master:
+    rx_streams:
+      secu:
+        status:
+          flag: secu_flag
+          current: secu_current
+          state: secu_state
+          pair: secu_pair
+          tail: secu_tail
+    object_dictionary:
+      secu_flag:
+        type: bool
+      secu_current:
+        type: sd12<-500..500>
+      secu_state:
+        type: state
+      secu_pair:
+        type: pair
+      secu_tail:
+        type: i7
+";
+
+fn build(src: &str) -> NetworkRef {
+    assert!(validate_yaml_config(src, Path::new("network.yaml")).is_empty());
+    parse_yaml_config(src, Path::new("network.yaml")).unwrap()
+}
+
+fn status(network: &NetworkRef) -> MessageRef {
+    network
+        .messages()
+        .iter()
+        .find(|message| message.name() == "secu_stream_status")
+        .unwrap()
+        .clone()
+}
+
+fn attribute(name: &str, value: DecodedValue) -> DecodedAttribute {
+    DecodedAttribute {
+        name: name.to_owned(),
+        value,
+    }
+}
+
+#[test]
+fn big_endian_signals_fill_whole_bytes() {
+    let network = build(NETWORK);
+    let message = status(&network);
+    let layout: Vec<(usize, ByteOrder, usize)> = message
+        .signals()
+        .iter()
+        .map(|signal| (signal.byte_offset(), signal.byte_order(), signal.end_bit()))
+        .collect();
+    assert_eq!(
+        layout,
+        vec![
+            (0, ByteOrder::LittleEndian, 1),
+            (11, ByteOrder::BigEndian, 24),
+            (25, ByteOrder::BigEndian, 32),
+            (36, ByteOrder::BigEndian, 40),
+            (42, ByteOrder::BigEndian, 56),
+            (56, ByteOrder::LittleEndian, 63),
+        ]
+    );
+    assert_eq!(message.dlc(), 8);
+}
+
+#[test]
+fn big_endian_payload_matches_generated_code() {
+    let network = build(NETWORK);
+    let message = status(&network);
+    let attributes = vec![
+        attribute("flag", DecodedValue::Bool(true)),
+        attribute("current", DecodedValue::Real(-123.4)),
+        attribute("state", DecodedValue::Enum("error".to_owned())),
+        attribute(
+            "pair",
+            DecodedValue::Struct(vec![
+                attribute("a", DecodedValue::Unsigned(21)),
+                attribute("b", DecodedValue::Signed(-1000)),
+            ]),
+        ),
+        attribute("tail", DecodedValue::Signed(-5)),
+    ];
+    // payload of the generated serialize function for the same values.
+    let payload = u64::from_le_bytes([0x01, 0x0e, 0x07, 0x02, 0x15, 0x04, 0x18, 0x7b]);
+    assert_eq!(MessageEncoder::new(&message).encode(&attributes), vec![payload]);
+
+    let decoded = MessageDecoder::new(&message).decode(&[payload]);
+    let DecodedValue::Real(current) = decoded[1].value else {
+        panic!("current is not a decimal");
+    };
+    assert!((current + 123.4).abs() <= 500.0 / 2047.0 / 2.0);
+    assert_eq!(decoded[2].value, attributes[2].value);
+    assert_eq!(decoded[3].value, attributes[3].value);
+    assert_eq!(decoded[4].value, attributes[4].value);
+}
+
+#[test]
+fn signed_decimal_roundtrip() {
+    for byte_order in ["little_endian", "big_endian"] {
+        let src = NETWORK.replacen(
+            "byte_order: big_endian",
+            &format!("byte_order: {byte_order}"),
+            1,
+        );
+        let network = build(&src);
+        let message = status(&network);
+        let encoder = MessageEncoder::new(&message);
+        let decoder = MessageDecoder::new(&message);
+        let scale = 500.0 / 2047.0;
+        for (value, raw) in [(-2048.0 * scale, 0x800), (0.0, 0x000), (500.0, 0x7FF)] {
+            let payload = encoder.encode(&[attribute("current", DecodedValue::Real(value))]);
+            let signal_decoder = SignalDecoder::new(&message.signals()[1]);
+            assert_eq!(signal_decoder.raw(&payload), raw, "{byte_order} {value}");
+            let DecodedValue::Real(decoded) = decoder.decode(&payload)[1].value else {
+                panic!("current is not a decimal");
+            };
+            assert!((decoded - value).abs() < 1e-9, "{byte_order} {value} {decoded}");
+        }
+        // values outside of the range are clamped.
+        let payload = encoder.encode(&[attribute("current", DecodedValue::Real(-1000.0))]);
+        assert_eq!(
+            decoder.decode(&payload)[1].value,
+            DecodedValue::Real(-2048.0 * scale)
+        );
+    }
+}
+
+#[test]
+fn unknown_byte_order() {
+    let src = NETWORK.replacen("byte_order: big_endian", "byte_order: big", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "unknown byte_order `big`");
+    assert!(parse_yaml_config(&src, Path::new("network.yaml")).is_err());
+}
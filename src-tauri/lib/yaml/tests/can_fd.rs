@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    bus_load::{bus_frame_bits, frame_bits},
+    decode::{DecodedAttribute, DecodedValue, MessageDecoder},
+    encode::MessageEncoder,
+};
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+  can1:
+    baudrate: 1000000
+    data_baudrate: 4000000
+struct_types:
+  samples:
+    a: u32
+    b: u40
+    c: i24
+nodes:
+  secu:
+    object_dictionary:
+      samples:
+        type: samples
+    tx_streams:
+      samples:
+        mapping: [samples]
+        interval: 10ms - 50ms
+  master:
+    rx_streams:
+      secu:
+        samples:
+          samples: secu_samples
+    object_dictionary:
+      secu_samples:
+        type: samples
+";
+
+#[test]
+fn large_messages_are_assigned_to_fd_buses() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let can1 = network.buses().iter().find(|bus| bus.name() == "can1").unwrap();
+    assert!(can1.is_fd());
+    assert!(can1.brs());
+    assert_eq!(can1.data_baudrate(), Some(4000000));
+    assert_eq!(can1.max_dlc(), 64);
+
+    let stream = network
+        .messages()
+        .iter()
+        .find(|message| message.name().contains("samples"))
+        .unwrap();
+    // 96 bits are rounded up to the next valid CAN FD data length.
+    assert_eq!(stream.dlc(), 12);
+    assert_eq!(stream.bus().name(), "can1");
+    // the data phase is transmitted with the data bitrate.
+    assert!(bus_frame_bits(stream.bus(), 12, false, true) < frame_bits(12, false, true));
+
+    let attributes = vec![DecodedAttribute {
+        name: "samples".to_owned(),
+        value: DecodedValue::Struct(vec![
+            DecodedAttribute {
+                name: "a".to_owned(),
+                value: DecodedValue::Unsigned(0xDEADBEEF),
+            },
+            DecodedAttribute {
+                name: "b".to_owned(),
+                value: DecodedValue::Unsigned(0xAB_1234_5678),
+            },
+            DecodedAttribute {
+                name: "c".to_owned(),
+                value: DecodedValue::Signed(-42),
+            },
+        ]),
+    }];
+    let data = MessageEncoder::new(stream).encode(&attributes);
+    assert_eq!(data.len(), 2);
+    assert_eq!(MessageDecoder::new(stream).decode(&data), attributes);
+}
+
+#[test]
+fn brs_requires_data_baudrate() {
+    let src = NETWORK.replace("    data_baudrate: 4000000\n", "    brs: false\n");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].message, "brs is only valid for CAN FD buses");
+}
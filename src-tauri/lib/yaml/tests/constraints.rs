@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use canzero_config::config::decode::DecodedValue;
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+enum_types:
+  mode:
+    idle: 0
+    run: 1
+nodes:
+  secu:
+    object_dictionary:
+      target_speed:
+        type: d16<0..100>
+        min: 5
+        max: 80.5
+        default: 10
+      mode:
+        type: mode
+        default: run
+      count:
+        type: u8
+        max: 10
+";
+
+#[test]
+fn constraints_are_resolved() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let secu = network.nodes().iter().find(|node| node.name() == "secu").unwrap();
+    let oe = |name: &str| {
+        secu.object_entries()
+            .iter()
+            .find(|oe| oe.name() == name)
+            .unwrap()
+            .clone()
+    };
+    let target_speed = oe("target_speed");
+    assert_eq!(target_speed.min(), Some(5.0));
+    assert_eq!(target_speed.max(), Some(80.5));
+    assert_eq!(target_speed.default(), Some(&DecodedValue::Real(10.0)));
+    assert!(target_speed.in_range(80.5));
+    assert!(!target_speed.in_range(4.9));
+
+    assert_eq!(oe("mode").default(), Some(&DecodedValue::Enum("run".to_owned())));
+    let count = oe("count");
+    assert_eq!(count.min(), None);
+    assert!(count.in_range(0.0));
+    assert!(!count.in_range(11.0));
+}
+
+#[test]
+fn invalid_constraints_are_rejected() {
+    for (from, to) in [
+        ("default: 10", "default: 90"),
+        ("default: run", "default: walk"),
+        ("max: 10", "max: 300"),
+        ("min: 5", "min: 90"),
+    ] {
+        let src = NETWORK.replace(from, to);
+        assert!(
+            parse_yaml_config(&src, Path::new("network.yaml")).is_err(),
+            "{to} should be rejected"
+        );
+    }
+}
+
+#[test]
+fn constraints_are_validated() {
+    assert!(validate_yaml_config(NETWORK, Path::new("network.yaml")).is_empty());
+    let src = NETWORK.replace("min: 5", "min: [5]");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].message, "min has to be a number, found a list");
+
+    for (from, to, message, line) in [
+        (
+            "min: 5",
+            "min: 90",
+            "the min 90 of `target_speed` is greater than its max 80.5",
+            14,
+        ),
+        (
+            "default: 10",
+            "default: 90",
+            "the default 90 of `target_speed` is greater than its max 80.5",
+            16,
+        ),
+        (
+            "default: 10",
+            "default: 2",
+            "the default 2 of `target_speed` is less than its min 5",
+            16,
+        ),
+        (
+            "default: run",
+            "default: walk",
+            "the default of `mode` is not a variant of `mode`",
+            19,
+        ),
+    ] {
+        let src = NETWORK.replace(from, to);
+        let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+        let diagnostic = diagnostics
+            .iter()
+            .find(|diagnostic| diagnostic.message == message)
+            .unwrap_or_else(|| panic!("{to}: {diagnostics:?}"));
+        assert_eq!(diagnostic.line, line, "{to}");
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+
+use canzero_config::config::diff::{diff_networks, ChangeKind, Compatibility, DiffSubject};
+use canzero_yaml::parse_yaml_config;
+
+const BASE: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+      pressure:
+        type: u8
+    tx_streams:
+      status:
+        mapping: [temperature]
+        interval: 10ms - 50ms
+  master:
+    object_dictionary:
+      x:
+        type: u8
+";
+
+fn diff(old: &str, new: &str) -> canzero_config::config::diff::NetworkDiff {
+    let old = parse_yaml_config(old, Path::new("old.yaml")).unwrap();
+    let new = parse_yaml_config(new, Path::new("new.yaml")).unwrap();
+    diff_networks(&old, &new)
+}
+
+#[test]
+fn identical_configs_have_no_changes() {
+    let diff = diff(BASE, BASE);
+    assert!(diff.is_empty(), "{:?}", diff.changes());
+    assert_eq!(diff.old_hash(), diff.new_hash());
+}
+
+#[test]
+fn description_change_is_wire_compatible() {
+    let new = BASE.replace(
+        "      pressure:\n        type: u8",
+        "      pressure:\n        type: u8\n        description: tank pressure",
+    );
+    let diff = diff(BASE, &new);
+    assert_eq!(diff.changes().len(), 1, "{:?}", diff.changes());
+    assert_eq!(diff.changes()[0].compatibility(), Compatibility::WireCompatible);
+    assert!(!diff.is_breaking());
+    assert_eq!(diff.old_hash(), diff.new_hash());
+}
+
+#[test]
+fn renamed_object_entry_is_breaking() {
+    let new = BASE.replace("      pressure:", "      tank_pressure:");
+    let diff = diff(BASE, &new);
+    let change = diff
+        .changes()
+        .iter()
+        .find(|change| change.subject() == DiffSubject::ObjectEntry)
+        .unwrap();
+    assert_eq!(change.path(), "secu::tank_pressure");
+    assert_eq!(change.kind(), &ChangeKind::Renamed("pressure".to_owned()));
+    assert!(change.is_breaking());
+    assert_ne!(diff.old_hash(), diff.new_hash());
+}
+
+#[test]
+fn changed_signal_type_is_breaking() {
+    let new = BASE.replace("d8<0..100>", "d16<0..100>");
+    let diff = diff(BASE, &new);
+    assert!(diff.is_breaking());
+    assert!(diff.changes().iter().any(|change| {
+        change.subject() == DiffSubject::ObjectEntry && change.path() == "secu::temperature"
+    }));
+    assert!(diff
+        .changes()
+        .iter()
+        .any(|change| change.subject() == DiffSubject::Message));
+}
@@ -0,0 +1,194 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    filters::{node_filter_stats, reception, FilterReport, Reception},
+    serialize::SerializedNetwork,
+    CanController, NetworkRef, NodeRef,
+};
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  master:
+    controller: bxcan
+    rx_streams:
+      secu:
+        state:
+          state: secu_state
+      becu:
+        state:
+          state: becu_state
+      pdu:
+        state:
+          state: pdu_state
+    object_dictionary:
+      secu_state:
+        type: u8
+      becu_state:
+        type: u8
+      pdu_state:
+        type: u8
+  secu:
+    object_dictionary:
+      state:
+        type: u8
+    tx_streams:
+      state:
+        mapping: [state]
+        interval: 50ms
+  becu:
+    object_dictionary:
+      state:
+        type: u8
+    tx_streams:
+      state:
+        mapping: [state]
+        interval: 50ms
+  pdu:
+    object_dictionary:
+      state:
+        type: u8
+    tx_streams:
+      state:
+        mapping: [state]
+        interval: 50ms
+";
+
+fn build(src: &str) -> NetworkRef {
+    assert!(validate_yaml_config(src, Path::new("network.yaml")).is_empty());
+    parse_yaml_config(src, Path::new("network.yaml")).unwrap()
+}
+
+fn node(network: &NetworkRef, name: &str) -> NodeRef {
+    network
+        .nodes()
+        .iter()
+        .find(|node| node.name() == name)
+        .unwrap()
+        .clone()
+}
+
+fn assert_receives_all(node: &NodeRef) {
+    for message in node.rx_messages() {
+        assert!(
+            node.filters().iter().any(|filter| filter.accepts(message.id())),
+            "{} drops {}",
+            node.name(),
+            message.name()
+        );
+    }
+}
+
+#[test]
+fn controller_limits_filters() {
+    let network = build(NETWORK);
+    let master = node(&network, "master");
+    assert_eq!(master.controller(), Some(CanController::BxCan));
+    assert_eq!(master.max_filters(), Some(14));
+    assert!(master.filters().len() <= 14);
+    assert_eq!(node(&network, "secu").max_filters(), None);
+}
+
+#[test]
+fn filters_are_merged_to_fit() {
+    let exact = build(NETWORK);
+    let exact_master = node(&exact, "master");
+
+    let src = NETWORK.replacen("controller: bxcan", "controller: bxcan\n    max_filters: 1", 1);
+    let merged = build(&src);
+    let master = node(&merged, "master");
+    assert_eq!(master.max_filters(), Some(1));
+    assert_eq!(master.filters().len(), 1);
+    assert_receives_all(&master);
+
+    let exact_rate = node_filter_stats(&exact, &exact_master).false_positive_rate();
+    let rate = node_filter_stats(&merged, &master).false_positive_rate();
+    assert!(exact_rate <= rate);
+    assert!((0.0..=1.0).contains(&rate));
+}
+
+#[test]
+fn unknown_controller() {
+    let src = NETWORK.replacen("controller: bxcan", "controller: bxcn", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "unknown controller `bxcn`");
+    assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean `bxcan`?"));
+}
+
+#[test]
+fn max_filters_has_to_be_positive() {
+    let src = NETWORK.replacen("controller: bxcan", "max_filters: 0", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].message, "max_filters has to be at least 1, found 0");
+    assert!(parse_yaml_config(&src, Path::new("network.yaml")).is_err());
+}
+
+#[test]
+fn report_of_merged_filters() {
+    let src = NETWORK.replacen("controller: bxcan", "max_filters: 1", 1);
+    let network = build(&src);
+    let report = FilterReport::new(&network);
+    assert!(report.dropped().is_empty());
+    let master = report
+        .nodes()
+        .iter()
+        .position(|node| node.name() == "master")
+        .unwrap();
+    let unnecessary = report
+        .unnecessary()
+        .iter()
+        .filter(|(node, _)| node.name() == "master")
+        .count();
+    assert_eq!(report.node_stats(master).accepted_unwanted, unnecessary);
+    assert_eq!(
+        report.node_stats(master),
+        node_filter_stats(&network, &report.nodes()[master])
+    );
+}
+
+#[test]
+fn report_finds_dropped_messages() {
+    let network = build(NETWORK);
+    let mut serialized = SerializedNetwork::from_network(&network);
+    let secu = serialized
+        .nodes
+        .iter()
+        .position(|node| node.name == "secu")
+        .unwrap();
+    serialized.nodes[secu].filters.clear();
+    let network = serialized.to_network().unwrap();
+
+    let report = FilterReport::new(&network);
+    let dropped = report.dropped();
+    assert!(!dropped.is_empty());
+    assert!(dropped.iter().all(|(node, _)| node.name() == "secu"));
+    for (node, message) in dropped {
+        assert_eq!(reception(node, message), Reception::Dropped);
+    }
+    assert!(report.to_string().contains('!'));
+}
+
+#[test]
+fn commands_and_persistent_entries_pass_the_filters() {
+    let src = NETWORK.replacen(
+        "  secu:\n    object_dictionary:\n      state:\n        type: u8\n",
+        "  secu:\n    object_dictionary:\n      state:\n        type: u8\n        persistent: true\n    commands:\n      calibrate:\n        arguments:\n          - offset: d8<-1..1>\n        callee: [master]\n",
+        1,
+    );
+    assert_ne!(src, NETWORK);
+    let network = build(&src);
+    assert!(FilterReport::new(&network).dropped().is_empty());
+    for node in network.nodes() {
+        assert_receives_all(node);
+    }
+    let master = node(&network, "master");
+    assert!(master
+        .rx_messages()
+        .iter()
+        .any(|message| message.name() == "secu_calibrate_command_resp"));
+}
@@ -0,0 +1,158 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    serialize::{network_from_json, network_to_json, SerializedNetwork},
+    NetworkRef,
+};
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const YAML: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+enum_types:
+  state:
+    idle: 0
+    running: 1
+struct_types:
+  cell:
+    voltage: d8<0..5>
+    ok: bool
+nodes:
+  secu:
+    object_dictionary:
+      state:
+        type: state
+      cells:
+        type: cell[2]
+        persistent: true
+    tx_streams:
+      cells:
+        mapping: [state, cells]
+        interval: 10ms - 50ms
+    commands:
+      calibrate:
+        arguments:
+          - offset: d8<-1..1>
+        callee: [master]
+  master:
+    rx_streams:
+      secu:
+        cells:
+          state: secu_state
+    object_dictionary:
+      secu_state:
+        type: state
+";
+
+const TOML: &str = r#"
+[buses.can0]
+baudrate = 1000000
+
+[enum_types.state]
+idle = 0
+running = 1
+
+[struct_types.cell]
+voltage = "d8<0..5>"
+ok = "bool"
+
+[nodes.secu.object_dictionary.state]
+type = "state"
+
+[nodes.secu.object_dictionary.cells]
+type = "cell[2]"
+persistent = true
+
+[nodes.secu.tx_streams.cells]
+mapping = ["state", "cells"]
+interval = "10ms - 50ms"
+
+[nodes.secu.commands.calibrate]
+arguments = [{ offset = "d8<-1..1>" }]
+callee = ["master"]
+
+[nodes.master.rx_streams.secu.cells]
+state = "secu_state"
+
+[nodes.master.object_dictionary.secu_state]
+type = "state"
+"#;
+
+const JSON: &str = r#"{
+  "buses": { "can0": { "baudrate": 1000000 } },
+  "enum_types": { "state": { "idle": 0, "running": 1 } },
+  "struct_types": { "cell": { "voltage": "d8<0..5>", "ok": "bool" } },
+  "nodes": {
+    "secu": {
+      "object_dictionary": {
+        "state": { "type": "state" },
+        "cells": { "type": "cell[2]", "persistent": true }
+      },
+      "tx_streams": {
+        "cells": { "mapping": ["state", "cells"], "interval": "10ms - 50ms" }
+      },
+      "commands": {
+        "calibrate": { "arguments": [{ "offset": "d8<-1..1>" }], "callee": ["master"] }
+      }
+    },
+    "master": {
+      "rx_streams": { "secu": { "cells": { "state": "secu_state" } } },
+      "object_dictionary": { "secu_state": { "type": "state" } }
+    }
+  }
+}"#;
+
+fn build(src: &str, path: &str) -> NetworkRef {
+    assert!(validate_yaml_config(src, Path::new(path)).is_empty());
+    parse_yaml_config(src, Path::new(path)).unwrap()
+}
+
+#[test]
+fn formats_build_the_same_network() {
+    let network = build(YAML, "network.yaml");
+    assert_eq!(
+        build(TOML, "network.toml").portable_hash(),
+        network.portable_hash()
+    );
+    assert_eq!(
+        build(JSON, "network.json").portable_hash(),
+        network.portable_hash()
+    );
+}
+
+#[test]
+fn invalid_toml_is_reported() {
+    let src = TOML.replacen("baudrate = 1000000", "baudrate = ", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.toml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with("invalid toml"));
+    assert_ne!(diagnostics[0].line, 0);
+}
+
+#[test]
+fn serialized_network_roundtrip() {
+    let network = build(YAML, "network.yaml");
+    let json = network_to_json(&network);
+    let loaded = network_from_json(&json).unwrap();
+
+    assert_eq!(loaded.portable_hash(), network.portable_hash());
+    assert_eq!(loaded.build_time(), network.build_time());
+    for (node, loaded_node) in network.nodes().iter().zip(loaded.nodes()) {
+        assert_eq!(loaded_node.portable_hash(), node.portable_hash());
+        assert_eq!(loaded_node.filters(), node.filters());
+        for oe in loaded_node.object_entries() {
+            assert_eq!(oe.node().name(), loaded_node.name());
+        }
+    }
+    // the serialized form is canonical.
+    assert_eq!(network_to_json(&loaded), json);
+}
+
+#[test]
+fn invalid_index_is_rejected() {
+    let network = build(YAML, "network.yaml");
+    let mut serialized = SerializedNetwork::from_network(&network);
+    serialized.nodes[0].tx_messages.push(serialized.messages.len());
+    assert!(serialized.to_network().is_err());
+}
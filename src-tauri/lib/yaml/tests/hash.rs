@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use canzero_yaml::parse_yaml_config;
+
+const BASE: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+  master:
+    object_dictionary:
+      x:
+        type: u8
+";
+
+fn node_hash(src: &str, node_name: &str) -> u64 {
+    let network = parse_yaml_config(src, Path::new("network.yaml")).unwrap();
+    let node = network
+        .nodes()
+        .iter()
+        .find(|node| node.name() == node_name)
+        .unwrap();
+    node.portable_hash()
+}
+
+#[test]
+fn unrelated_change_keeps_node_hash() {
+    let new = BASE.replace("        type: u8", "        type: u16");
+    assert_eq!(node_hash(BASE, "secu"), node_hash(&new, "secu"));
+    assert_ne!(node_hash(BASE, "master"), node_hash(&new, "master"));
+}
+
+#[test]
+fn node_hashes_differ() {
+    assert_ne!(node_hash(BASE, "secu"), node_hash(BASE, "master"));
+}
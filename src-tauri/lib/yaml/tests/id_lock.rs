@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use canzero_config::{builder::IdLock, config::NetworkRef};
+use canzero_yaml::{
+    id_lock::lock_file_path, load_yaml_config_variant_from_file, lock_yaml_config_from_file,
+    parse_yaml_config_from_file, parse_yaml_config_locked,
+};
+
+const BASE: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+    commands:
+      calibrate:
+        arguments:
+          - offset : d8<-1..1>
+        callee: [master]
+  master:
+    object_dictionary:
+      x:
+        type: u8
+";
+
+fn build(src: &str, lock: Option<&IdLock>) -> (NetworkRef, IdLock) {
+    parse_yaml_config_locked(src, Path::new("network.yaml"), lock).unwrap()
+}
+
+fn message_id(network: &NetworkRef, name: &str) -> u32 {
+    network
+        .messages()
+        .iter()
+        .find(|message| message.name() == name)
+        .unwrap()
+        .id()
+        .as_u32()
+}
+
+fn object_entry_id(network: &NetworkRef, node_name: &str, oe_name: &str) -> u32 {
+    network
+        .nodes()
+        .iter()
+        .find(|node| node.name() == node_name)
+        .unwrap()
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == oe_name)
+        .unwrap()
+        .id()
+}
+
+#[test]
+fn rebuild_with_lock_is_stable() {
+    let (network, lock) = build(BASE, None);
+    let (rebuilt, new_lock) = build(BASE, Some(&lock));
+    assert_eq!(lock, new_lock);
+    for message in network.messages() {
+        assert_eq!(
+            message.id().as_u32(),
+            message_id(&rebuilt, message.name()),
+            "{}",
+            message.name()
+        );
+    }
+}
+
+#[test]
+fn additions_keep_locked_ids() {
+    let (network, lock) = build(BASE, None);
+    let new = BASE
+        .replace(
+            "    object_dictionary:\n      temperature:",
+            "    object_dictionary:\n      aaa:\n        type: u8\n      temperature:",
+        )
+        .replace(
+            "        callee: [master]",
+            "        callee: [master]\n      reset:\n        arguments:\n          - count : i4\n        callee: [master]",
+        );
+    let (new_network, new_lock) = build(&new, Some(&lock));
+
+    for locked in &lock.messages {
+        assert_eq!(locked.id, message_id(&new_network, &locked.name), "{}", locked.name);
+    }
+    assert_eq!(
+        object_entry_id(&network, "secu", "temperature"),
+        object_entry_id(&new_network, "secu", "temperature")
+    );
+    assert!(new_lock.message("secu_reset_command_req").is_some());
+    assert!(new_lock.object_entry("secu", "aaa").is_some());
+}
+
+#[test]
+fn only_locking_writes_the_lock_file() {
+    let dir = std::env::temp_dir().join(format!("canzero-id-lock-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config_path = dir.join("network.yaml");
+    std::fs::write(&config_path, BASE).unwrap();
+    let path = config_path.to_str().unwrap();
+    let lock_path = lock_file_path(&config_path, None);
+
+    parse_yaml_config_from_file(path).unwrap();
+    assert!(!lock_path.exists());
+    let (_, diagnostic) = load_yaml_config_variant_from_file(path, None).unwrap();
+    let diagnostic = diagnostic.expect("a missing lock file is out of date");
+    assert!(!diagnostic.is_error());
+    assert_eq!(diagnostic.file, lock_path);
+
+    lock_yaml_config_from_file(path, None).unwrap();
+    assert!(lock_path.exists());
+    let (_, diagnostic) = load_yaml_config_variant_from_file(path, None).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(diagnostic.is_none());
+}
@@ -0,0 +1,102 @@
+use std::path::Path;
+
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue, MessageDecoder},
+    encode::MessageEncoder,
+    SignalType, Type,
+};
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+struct_types:
+  status:
+    voltage: f32
+    enabled: bool
+    name: string<3>
+nodes:
+  secu:
+    object_dictionary:
+      status:
+        type: status
+      energy:
+        type: f64
+    tx_streams:
+      status:
+        mapping: [status]
+        interval: 10ms - 50ms
+  master:
+    rx_streams:
+      secu:
+        status:
+          status: secu_status
+    object_dictionary:
+      secu_status:
+        type: status
+";
+
+fn attribute(name: &str, value: DecodedValue) -> DecodedAttribute {
+    DecodedAttribute {
+        name: name.to_owned(),
+        value,
+    }
+}
+
+#[test]
+fn native_types_are_resolved() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let secu = network.nodes().iter().find(|node| node.name() == "secu").unwrap();
+    let energy = secu
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "energy")
+        .unwrap();
+    assert_eq!(
+        energy.ty().as_ref(),
+        &Type::Primitive(SignalType::Float { size: 64 })
+    );
+
+    let stream = network
+        .messages()
+        .iter()
+        .find(|message| message.name().contains("status"))
+        .unwrap();
+    let sizes: Vec<u8> = stream.signals().iter().map(|signal| signal.size()).collect();
+    assert_eq!(sizes, vec![32, 1, 24]);
+}
+
+#[test]
+fn native_types_roundtrip() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let stream = network
+        .messages()
+        .iter()
+        .find(|message| message.name().contains("status"))
+        .unwrap();
+
+    let attributes = vec![attribute(
+        "status",
+        DecodedValue::Struct(vec![
+            attribute("voltage", DecodedValue::Real(-12.5)),
+            attribute("enabled", DecodedValue::Bool(true)),
+            attribute("name", DecodedValue::String("ab".to_owned())),
+        ]),
+    )];
+    let data = MessageEncoder::new(stream).encode(&attributes);
+    assert_eq!(data[0] & 0xFFFFFFFF, (-12.5f32).to_bits() as u64);
+    assert_eq!(MessageDecoder::new(stream).decode(&data), attributes);
+}
+
+#[test]
+fn string_length_is_validated() {
+    let src = NETWORK.replace("string<3>", "string<9>");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message,
+        "invalid length of type `string<9>`, strings have between 1 and 8 characters"
+    );
+    assert!(parse_yaml_config(&src, Path::new("network.yaml")).is_err());
+}
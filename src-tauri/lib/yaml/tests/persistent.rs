@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use canzero_config::config::SAVE_PARAMETERS_COMMAND;
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+nodes:
+  secu:
+    object_dictionary:
+      gain:
+        type: f32
+        persistent: true
+      offset:
+        type: i16
+        persistent: true
+      temperature:
+        type: d8<0..100>
+  master:
+    object_dictionary:
+      x:
+        type: u8
+";
+
+fn layout_hash(src: &str) -> u64 {
+    let network = parse_yaml_config(src, Path::new("network.yaml")).unwrap();
+    let secu = network.nodes().iter().find(|node| node.name() == "secu").unwrap();
+    secu.parameter_layout_hash()
+}
+
+#[test]
+fn save_parameters_command_is_added() {
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let has_command = |node_name: &str| {
+        network
+            .nodes()
+            .iter()
+            .find(|node| node.name() == node_name)
+            .unwrap()
+            .commands()
+            .iter()
+            .any(|command| command.name() == SAVE_PARAMETERS_COMMAND)
+    };
+    assert!(has_command("secu"));
+    assert!(!has_command("master"));
+}
+
+#[test]
+fn layout_hash_covers_persistent_object_entries() {
+    let base = layout_hash(NETWORK);
+    let volatile_change = NETWORK.replace("d8<0..100>", "d16<0..100>");
+    assert_eq!(base, layout_hash(&volatile_change));
+    let persistent_change = NETWORK.replace("i16", "i32");
+    assert_ne!(base, layout_hash(&persistent_change));
+}
+
+#[test]
+fn save_parameters_is_reserved() {
+    let src = NETWORK.replace(
+        "  master:\n",
+        "    commands:\n      save_parameters:\n        description: custom\n  master:\n",
+    );
+    assert!(parse_yaml_config(&src, Path::new("network.yaml")).is_err());
+}
+
+#[test]
+fn persistent_is_validated() {
+    let src = NETWORK.replacen("persistent: true", "persistent: yes please", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message,
+        "persistent has to be a boolean, found a string"
+    );
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use canzero_config::config::response_time::analyze_response_times;
+use canzero_yaml::parse_yaml_config;
+
+fn network(baudrate: u32, interval: &str) -> String {
+    format!(
+        "
+buses:
+  can0:
+    baudrate: {baudrate}
+nodes:
+  secu:
+    object_dictionary:
+      temperature:
+        type: u8
+    tx_streams:
+      status:
+        mapping: [temperature]
+        interval: {interval}
+  master:
+    rx_streams:
+      secu:
+        status:
+          temperature: secu_temperature
+    object_dictionary:
+      secu_temperature:
+        type: u8
+"
+    )
+}
+
+#[test]
+fn stream_meets_deadline() {
+    let src = network(1000000, "10ms - 50ms");
+    let network = parse_yaml_config(&src, Path::new("network.yaml")).unwrap();
+    let response_times = analyze_response_times(&network);
+    assert_eq!(response_times.len(), 1);
+    let (_, messages) = &response_times[0];
+    assert_eq!(messages.len(), network.messages().len());
+    // sorted by priority
+    assert!(messages
+        .windows(2)
+        .all(|w| w[0].message().id().as_u32() <= w[1].message().id().as_u32()));
+    let stream = messages
+        .iter()
+        .find(|m| m.message().name().contains("status"))
+        .unwrap();
+    assert!(stream.response_time().is_some());
+    assert!(!stream.misses_deadline());
+    assert!(messages.iter().all(|m| !m.misses_deadline()));
+}
+
+#[test]
+fn overloaded_bus_misses_deadline() {
+    let src = network(10000, "1ms - 1ms");
+    let network = parse_yaml_config(&src, Path::new("network.yaml")).unwrap();
+    let (_, messages) = &analyze_response_times(&network)[0];
+    let stream = messages
+        .iter()
+        .find(|m| m.message().name().contains("status"))
+        .unwrap();
+    assert!(stream.misses_deadline());
+}
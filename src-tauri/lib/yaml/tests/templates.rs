@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use canzero_yaml::{parse_yaml_config, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+templates:
+  mlu:
+    description: levitation unit ${name}
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+        unit: C
+      gain:
+        type: f32
+    tx_streams:
+      state:
+        mapping: [temperature]
+        interval: 10ms - 50ms
+instances:
+  mlu1:
+    template: mlu
+  mlu2:
+    template: mlu
+    object_dictionary:
+      gain:
+        type: f64
+nodes:
+  master:
+    rx_streams:
+      mlu2:
+        state:
+          temperature: mlu2_temperature
+    object_dictionary:
+      mlu2_temperature:
+        type: d8<0..100>
+";
+
+#[test]
+fn instances_are_expanded() {
+    assert!(validate_yaml_config(NETWORK, Path::new("network.yaml")).is_empty());
+    let network = parse_yaml_config(NETWORK, Path::new("network.yaml")).unwrap();
+    let node = |name: &str| {
+        network
+            .nodes()
+            .iter()
+            .find(|node| node.name() == name)
+            .unwrap()
+            .clone()
+    };
+    let mlu1 = node("mlu1");
+    let mlu2 = node("mlu2");
+    assert_eq!(mlu1.description().map(String::as_str), Some("levitation unit mlu1"));
+    assert_eq!(mlu2.description().map(String::as_str), Some("levitation unit mlu2"));
+    assert_eq!(mlu1.tx_streams().len(), 1);
+    assert_eq!(mlu2.tx_streams().len(), 1);
+
+    let gain = |node: &canzero_config::config::NodeRef| {
+        node.object_entries()
+            .iter()
+            .find(|oe| oe.name() == "gain")
+            .unwrap()
+            .ty()
+            .name()
+    };
+    assert_eq!(gain(&mlu1), "f32");
+    assert_eq!(gain(&mlu2), "f64");
+    // overrides are merged, the unit of the template is kept.
+    let temperature = mlu2
+        .object_entries()
+        .iter()
+        .find(|oe| oe.name() == "temperature")
+        .unwrap()
+        .clone();
+    assert_eq!(temperature.unit(), Some("C"));
+}
+
+#[test]
+fn unknown_template_is_reported() {
+    let src = NETWORK.replacen("template: mlu\n", "template: mlv\n", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].message, "unknown template `mlv`");
+    assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean `mlu`?"));
+}
+
+#[test]
+fn instance_names_are_unique() {
+    let src = NETWORK.replace("  master:\n", "  mlu1:\n    description: x\n  master:\n");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message,
+        "instance `mlu1` has the same name as a node"
+    );
+}
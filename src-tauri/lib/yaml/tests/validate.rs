@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+
+use canzero_yaml::validate::validate_yaml_config;
+
+const VALID: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+enum_types:
+  state:
+    idle: 0
+    running: 1
+nodes:
+  secu:
+    heartbeat_timeout: 100ms
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+        access: global
+      state:
+        type: state
+    tx_streams:
+      status:
+        mapping: [temperature, state]
+        interval: 10ms - 50ms
+  master:
+    rx_streams:
+      secu:
+        status:
+          temperature: secu_temperature
+    object_dictionary:
+      secu_temperature:
+        type: d8<0..100>
+";
+
+#[test]
+fn valid_config_has_no_diagnostics() {
+    let diagnostics = validate_yaml_config(VALID, Path::new("network.yaml"));
+    assert!(diagnostics.is_empty(), "{diagnostics:?}");
+}
+
+#[test]
+fn reports_all_problems_with_location() {
+    let src = VALID
+        .replace("heartbeat_timeout", "heartbeat_timout")
+        .replace("baudrate: 1000000", "baudrate: fast")
+        .replace("type: state", "type: stat");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 3, "{diagnostics:?}");
+
+    let typo = diagnostics
+        .iter()
+        .find(|d| d.message.contains("heartbeat_timout"))
+        .unwrap();
+    assert_eq!((typo.line, typo.col), (11, 5));
+    assert_eq!(
+        typo.help.as_deref(),
+        Some("did you mean `heartbeat_timeout`?")
+    );
+    let ty = diagnostics
+        .iter()
+        .find(|d| d.message.contains("stat"))
+        .unwrap();
+    assert_eq!(ty.help.as_deref(), Some("did you mean `state`?"));
+}
+
+#[test]
+fn reports_invalid_references() {
+    let src = VALID.replace("temperature: secu_temperature", "temprature: secu_temp");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 2, "{diagnostics:?}");
+    assert!(diagnostics
+        .iter()
+        .all(|d| d.message.contains("has no object entry")));
+}
+
+#[test]
+fn reports_syntax_errors() {
+    let diagnostics = validate_yaml_config("nodes: [a, b\nbuses: {}\n", Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].message.starts_with("invalid yaml"));
+}
+
+#[test]
+fn reports_problems_in_included_files() {
+    let dir: PathBuf =
+        std::env::temp_dir().join(format!("canzero-validate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("nodes.yaml"), "secu:\n  descripton: typo\n").unwrap();
+    let src = "buses:\n  can0:\n    baudrate: 1000000\nnodes: nodes.yaml\n";
+    let diagnostics = validate_yaml_config(src, &dir.join("network.yaml"));
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(diagnostics[0].file, dir.join("nodes.yaml"));
+    assert_eq!((diagnostics[0].line, diagnostics[0].col), (2, 3));
+}
+
+#[test]
+fn unknown_top_level_keys_are_warnings() {
+    let src = format!("{VALID}baudrate : 10\n");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert!(!diagnostics[0].is_error());
+    assert!(diagnostics[0].to_string().starts_with("warning: unknown key `baudrate`"));
+    assert!(canzero_yaml::parse_yaml_config(&src, Path::new("network.yaml")).is_ok());
+
+    let src = VALID.replacen("nodes:", "node:\nnodes:", 1);
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean `nodes`?"));
+}
+
+#[test]
+fn stream_example_is_valid() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("stream_example.yaml");
+    let src = std::fs::read_to_string(&path).unwrap();
+    let diagnostics = validate_yaml_config(&src, &path);
+    assert!(diagnostics.iter().all(|d| !d.is_error()), "{diagnostics:?}");
+}
+
+#[test]
+fn inverted_intervals_are_rejected() {
+    let src = VALID.replace("interval: 10ms - 50ms", "interval: 10ms - 5ms");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1, "{diagnostics:?}");
+    assert_eq!(
+        diagnostics[0].message,
+        "invalid interval `10ms - 5ms`, the min is greater than the max"
+    );
+    assert_eq!(diagnostics[0].line, 21);
+    assert!(canzero_yaml::parse_yaml_config(&src, Path::new("network.yaml")).is_err());
+
+    let src = VALID.replace("interval: 10ms - 50ms", "interval: 1s - 999ms");
+    assert_eq!(validate_yaml_config(&src, Path::new("network.yaml")).len(), 1);
+}
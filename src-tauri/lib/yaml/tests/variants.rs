@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use canzero_config::config::NetworkRef;
+use canzero_yaml::{parse_yaml_config_variant_locked, validate::validate_yaml_config};
+
+const NETWORK: &str = "
+buses:
+  can0:
+    baudrate: 1000000
+  can1:
+    baudrate: 1000000
+nodes:
+  master:
+    object_dictionary:
+      state:
+        type: u8
+      debug:
+        type: u8
+    tx_streams:
+      state:
+        mapping: [state]
+        interval: 50ms
+      debug:
+        mapping: [debug]
+        interval: 100ms
+  mlu2:
+    object_dictionary:
+      temperature:
+        type: d8<0..100>
+variants:
+  pod:
+  bench:
+    nodes:
+      mlu2: ~
+      master:
+        tx_streams:
+          debug: ~
+      dummy:
+        object_dictionary:
+          x:
+            type: u8
+    buses:
+      can0:
+        baudrate: 500000
+      can1: ~
+";
+
+fn build(variant: Option<&str>) -> NetworkRef {
+    parse_yaml_config_variant_locked(NETWORK, Path::new("network.yaml"), variant, None)
+        .unwrap()
+        .0
+}
+
+fn node_names(network: &NetworkRef) -> Vec<String> {
+    let mut names: Vec<String> = network
+        .nodes()
+        .iter()
+        .map(|node| node.name().to_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn base_ignores_variants() {
+    assert!(validate_yaml_config(NETWORK, Path::new("network.yaml")).is_empty());
+    let network = build(None);
+    assert_eq!(node_names(&network), ["master", "mlu2"]);
+    assert_eq!(network.buses().len(), 2);
+}
+
+#[test]
+fn empty_overlay_keeps_the_network() {
+    let network = build(Some("pod"));
+    assert_eq!(network.portable_hash(), build(None).portable_hash());
+}
+
+#[test]
+fn overlay_patches_the_network() {
+    let network = build(Some("bench"));
+    assert_eq!(node_names(&network), ["dummy", "master"]);
+
+    let master = network
+        .nodes()
+        .iter()
+        .find(|node| node.name() == "master")
+        .unwrap()
+        .clone();
+    let streams: Vec<&str> = master.tx_streams().iter().map(|s| s.name()).collect();
+    assert_eq!(streams, ["state"]);
+    // the object entries of the removed stream are kept.
+    assert_eq!(master.object_entries().iter().filter(|oe| oe.name() == "debug").count(), 1);
+
+    assert_eq!(network.buses().len(), 1);
+    assert_eq!(network.buses()[0].name(), "can0");
+    assert_eq!(network.buses()[0].baudrate(), 500000);
+}
+
+#[test]
+fn unknown_variant() {
+    assert!(
+        parse_yaml_config_variant_locked(NETWORK, Path::new("network.yaml"), Some("hil"), None)
+            .is_err()
+    );
+}
+
+#[test]
+fn unknown_overlay_section() {
+    let src = format!("{NETWORK}  hil:\n    busses:\n      can1: ~\n");
+    let diagnostics = validate_yaml_config(&src, Path::new("network.yaml"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "unknown key `busses` in the overlay of variant `hil`"
+    );
+    assert_eq!(diagnostics[0].help.as_deref(), Some("did you mean `buses`?"));
+}
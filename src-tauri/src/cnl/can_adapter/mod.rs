@@ -1,23 +1,32 @@
+use std::{path::Path, sync::Arc, time::Instant};
+
 use canzero_config::config::{bus::BusRef, NetworkRef};
 use canzero_udp::frame::NetworkDescription;
 
-use self::tcp::TcpCanAdapter;
-use canzero_common::{CanFrame, TCanFrame, TCanError};
+use self::{replay::ReplayCanAdapter, tcp::TcpCanAdapter};
+use canzero_common::{
+    session_log::SessionLogger, CanFrame, NetworkFrame, TCanError, TCanFrame, TNetworkFrame,
+    Timestamped,
+};
 
 #[cfg(feature = "socket-can")]
 mod socket_can;
 
+mod replay;
 mod tcp;
 
 pub struct CanAdapter {
     bus: BusRef,
     imp: CanAdapterImpl,
+    timebase: Instant,
+    session_log: Option<Arc<SessionLogger>>,
 }
 
 pub enum CanAdapterImpl {
     TcpCanAdapter(TcpCanAdapter),
     #[cfg(feature = "socket-can")]
     SocketCanAdapter(socket_can::SocketCanAdapter),
+    ReplayCanAdapter(ReplayCanAdapter),
 }
 
 impl CanAdapter {
@@ -25,6 +34,7 @@ impl CanAdapter {
         network_config: &NetworkRef,
         app_handle: &tauri::AppHandle,
         network_description: &NetworkDescription,
+        session_log: Option<Arc<SessionLogger>>,
     ) -> std::io::Result<(Vec<Self>,u8, tokio::sync::oneshot::Receiver<()>)> {
         let channels: Vec<(
             tokio::sync::mpsc::Sender<Option<Result<TCanFrame, TCanError>>>,
@@ -61,6 +71,8 @@ impl CanAdapter {
                     tx,
                     network_description.timebase,
                 )),
+                timebase: network_description.timebase,
+                session_log: session_log.clone(),
             });
         }
         Ok((adapters, tcp_client.node_id(), sync_complete))
@@ -70,6 +82,7 @@ impl CanAdapter {
     pub fn create_socketcan_adapters(
         network_config: &NetworkRef,
         app_handle: &tauri::AppHandle,
+        session_log: Option<Arc<SessionLogger>>,
     ) -> std::io::Result<Vec<Self>> {
         let start_of_run = std::time::Instant::now();
         let mut adapters = vec![];
@@ -81,27 +94,81 @@ impl CanAdapter {
                     start_of_run,
                     app_handle,
                 )?),
+                timebase: start_of_run,
+                session_log: session_log.clone(),
             });
         }
         Ok(adapters)
     }
 
+    /// Creates adapters that replay a recorded session log instead of connecting to a network.
+    /// Returns the adapters, the config hash stored in the log and the timebase of the replay.
+    pub fn create_replay_adapters(
+        network_config: &NetworkRef,
+        path: &Path,
+    ) -> std::io::Result<(Vec<Self>, u64, Instant)> {
+        let bus_ids: Vec<u32> = network_config.buses().iter().map(|bus| bus.id()).collect();
+        let (replay_adapters, config_hash, timebase) = ReplayCanAdapter::create(path, &bus_ids)?;
+        let adapters = network_config
+            .buses()
+            .iter()
+            .zip(replay_adapters.into_iter())
+            .map(|(bus, adapter)| Self {
+                bus: bus.clone(),
+                imp: CanAdapterImpl::ReplayCanAdapter(adapter),
+                timebase,
+                session_log: None,
+            })
+            .collect();
+        Ok((adapters, config_hash, timebase))
+    }
+
     pub async fn receive(&self) -> std::io::Result<Result<TCanFrame, TCanError>> {
-        match &self.imp {
+        let frame = match &self.imp {
             CanAdapterImpl::TcpCanAdapter(adapter) => adapter.receive().await,
             #[cfg(feature = "socket-can")]
             CanAdapterImpl::SocketCanAdapter(adapter) => adapter.receive().await,
+            CanAdapterImpl::ReplayCanAdapter(adapter) => adapter.receive().await,
+        };
+        if let Ok(Ok(frame)) = &frame {
+            self.record(frame);
         }
+        frame
     }
 
     pub async fn send(&self, frame: CanFrame, loopback:  bool) -> std::io::Result<()> {
         match &self.imp {
-            CanAdapterImpl::TcpCanAdapter(adapter) => adapter.send(frame, loopback).await,
+            CanAdapterImpl::TcpCanAdapter(adapter) => {
+                // looped back frames are recorded on receive.
+                if !loopback {
+                    self.record(&TCanFrame::now(self.timebase, frame.clone()));
+                }
+                adapter.send(frame, loopback).await
+            }
             #[cfg(feature = "socket-can")]
-            CanAdapterImpl::SocketCanAdapter(adapter) => adapter.send(frame).await,
+            CanAdapterImpl::SocketCanAdapter(adapter) => {
+                // SocketCAN never loops back our own frames.
+                self.record(&TCanFrame::now(self.timebase, frame.clone()));
+                adapter.send(frame).await
+            }
+            CanAdapterImpl::ReplayCanAdapter(adapter) => adapter.send(frame, loopback).await,
         }
     }
 
+    fn record(&self, frame: &TCanFrame) {
+        let Some(session_log) = &self.session_log else {
+            return;
+        };
+        let network_frame: TNetworkFrame = Timestamped::new(
+            frame.timestamp,
+            NetworkFrame {
+                bus_id: self.bus.id(),
+                can_frame: frame.value.clone(),
+            },
+        );
+        session_log.record(&network_frame);
+    }
+
     pub fn bus(&self) -> &BusRef {
         &self.bus
     }
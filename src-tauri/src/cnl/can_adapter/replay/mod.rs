@@ -0,0 +1,108 @@
+use std::{path::Path, time::Instant};
+
+use canzero_common::{
+    session_log::SessionLogReader, CanFrame, TCanError, TCanFrame, TNetworkFrame, Timestamped,
+};
+use color_print::cprintln;
+use tokio::{
+    runtime::Handle,
+    sync::{
+        mpsc::{Receiver, Sender},
+        Mutex,
+    },
+};
+
+pub struct ReplayCanAdapter {
+    rx: Mutex<Receiver<Result<TCanFrame, TCanError>>>,
+    tx: Sender<Result<TCanFrame, TCanError>>,
+    timebase: Instant,
+}
+
+impl ReplayCanAdapter {
+    /// Replays the session log at `path` in realtime.
+    /// Frames of bus `bus_ids[i]` are forwarded to the i-th returned adapter,
+    /// frames of unknown buses are dropped.
+    /// Timestamps are rebased such that the first frame of the log is received at the returned timebase.
+    pub fn create(path: &Path, bus_ids: &[u32]) -> std::io::Result<(Vec<Self>, u64, Instant)> {
+        let mut reader = SessionLogReader::open(path)?;
+        let config_hash = reader.header().config_hash;
+        let first_frame = reader.read_frame()?;
+        let timebase = Instant::now();
+
+        let mut adapters = vec![];
+        let mut senders = vec![];
+        for bus_id in bus_ids {
+            let (tx, rx) = tokio::sync::mpsc::channel::<Result<TCanFrame, TCanError>>(16);
+            senders.push((*bus_id, tx.clone()));
+            adapters.push(Self {
+                rx: Mutex::new(rx),
+                tx,
+                timebase,
+            });
+        }
+
+        let Some(first_frame) = first_frame else {
+            cprintln!("<yellow>Session log {path:?} is empty</yellow>");
+            return Ok((adapters, config_hash, timebase));
+        };
+        let offset = first_frame.timestamp;
+
+        let path = path.to_owned();
+        tokio::task::spawn_blocking(move || {
+            let frames = std::iter::once(Ok(first_frame)).chain(reader);
+            for frame in frames {
+                let frame: TNetworkFrame = match frame {
+                    Ok(frame) => frame,
+                    Err(err) => {
+                        cprintln!("<red>Failed to read session log {path:?} : {err:?}</red>");
+                        return;
+                    }
+                };
+                let Some((_, tx)) = senders.iter().find(|(id, _)| *id == frame.bus_id) else {
+                    continue;
+                };
+                let timestamp = frame.timestamp.saturating_sub(offset);
+                let due = timebase + timestamp;
+                let now = Instant::now();
+                if due > now {
+                    std::thread::sleep(due - now);
+                }
+                let can_frame = frame.value.can_frame;
+                let sent = Handle::current().block_on(async {
+                    tx.send(Ok(Timestamped::new(timestamp, can_frame))).await
+                });
+                if sent.is_err() {
+                    // the control panel was closed.
+                    return;
+                }
+            }
+            cprintln!("<green>Replay of {path:?} completed</green>");
+        });
+
+        Ok((adapters, config_hash, timebase))
+    }
+
+    pub async fn receive(&self) -> std::io::Result<Result<TCanFrame, TCanError>> {
+        let frame = self.rx.lock().await.recv().await;
+        match frame {
+            Some(frame) => Ok(frame),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionAborted,
+                "Replay aborted",
+            )),
+        }
+    }
+
+    /// Frames send during a replay never reach the network,
+    /// but they are looped back if requested.
+    pub async fn send(&self, frame: CanFrame, loopback: bool) -> std::io::Result<()> {
+        if loopback {
+            let timestamp = Instant::now().duration_since(self.timebase);
+            let _ = self
+                .tx
+                .send(Ok(Timestamped::new(timestamp, frame)))
+                .await;
+        }
+        Ok(())
+    }
+}
@@ -1,11 +1,11 @@
-use canzero_config::config::MessageRef;
+use canzero_common::CanFrame;
+use canzero_config::config::{
+    decode::{DecodedAttribute, DecodedValue, MessageDecoder},
+    MessageRef,
+};
 
-use self::encoding_deserializer::EncodedDeserializer;
+use super::frame::{Attribute, Frame, FrameValue, Value};
 
-use super::frame::{Attribute, Frame, FrameValue};
-
-mod encoding_deserializer;
-mod signal_deserializer;
 pub mod type_deserializer;
 
 pub struct FrameDeserializer {
@@ -20,77 +20,55 @@ impl FrameDeserializer {
             value_deserializer: FrameValueDeserializer::new(message_config),
         }
     }
-    pub fn deserialize(&self, data: u64) -> Frame {
+    pub fn deserialize(&self, can_frame: &CanFrame) -> Frame {
         Frame::new(
             self.message_config.clone(),
-            data,
-            self.value_deserializer.deserialize(data),
+            can_frame.clone(),
+            self.value_deserializer.deserialize(can_frame.get_data()),
         )
     }
 }
 
+/// Deserializes frame payloads with the decoder of the config,
+/// which is shared with the cli (e.g. `canzero dump --decode`).
 pub struct FrameValueDeserializer {
-    attribute_deserializer: Vec<AttributeDeserializer>,
+    decoder: MessageDecoder,
 }
 
-struct AttributeDeserializer {
-    attribute_name: String,
-    encoded_deserializer: EncodedDeserializer,
+fn to_attribute(attribute: DecodedAttribute) -> Attribute {
+    Attribute::new(&attribute.name, to_value(attribute.value))
 }
 
-impl AttributeDeserializer {
-    pub fn new(name: &str, encoded_deserializer: EncodedDeserializer) -> Self {
-        Self {
-            attribute_name: name.to_owned(),
-            encoded_deserializer,
+fn to_value(value: DecodedValue) -> Value {
+    match value {
+        DecodedValue::Unsigned(value) => Value::UnsignedValue(value),
+        DecodedValue::Signed(value) => Value::SignedValue(value),
+        DecodedValue::Real(value) => Value::RealValue(value),
+        DecodedValue::Enum(value) => Value::EnumValue(value),
+        DecodedValue::Bool(value) => Value::BoolValue(value),
+        DecodedValue::String(value) => Value::StringValue(value),
+        DecodedValue::Struct(attributes) => {
+            Value::StructValue(attributes.into_iter().map(to_attribute).collect())
+        }
+        DecodedValue::Array(values) => {
+            Value::ArrayValue(values.into_iter().map(to_value).collect())
         }
-    }
-    pub fn deserialize(&self, data: u64) -> Attribute {
-        Attribute::new(
-            &self.attribute_name,
-            self.encoded_deserializer.deserializer(data),
-        )
     }
 }
 
 impl FrameValueDeserializer {
     pub fn new(message_config: &MessageRef) -> FrameValueDeserializer {
-        match message_config.encoding() {
-            Some(message_encoding) => {
-                // NOTE create encoded deserializer based on encoding
-                Self {
-                    attribute_deserializer: message_encoding
-                        .attributes()
-                        .iter()
-                        .map(|type_encoding| {
-                            AttributeDeserializer::new(
-                                type_encoding.name(),
-                                EncodedDeserializer::new(type_encoding),
-                            )
-                        })
-                        .collect(),
-                }
-            }
-            None => Self {
-                attribute_deserializer: message_config
-                    .signals()
-                    .iter()
-                    .map(|signal| {
-                        AttributeDeserializer::new(
-                            signal.name(),
-                            EncodedDeserializer::new_from_signal(signal),
-                        )
-                    })
-                    .collect(),
-            },
+        Self {
+            decoder: MessageDecoder::new(message_config),
         }
     }
 
-    pub fn deserialize(&self, data: u64) -> FrameValue {
+    pub fn deserialize(&self, data: &[u64]) -> FrameValue {
         FrameValue::new(
-            self.attribute_deserializer
-                .iter()
-                .map(|attrib_deserializer| attrib_deserializer.deserialize(data))
+            self.decoder
+                .decode(data)
+                .into_iter()
+                .map(to_attribute)
                 .collect(),
         )
     }
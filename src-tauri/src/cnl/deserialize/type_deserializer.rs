@@ -14,6 +14,7 @@ enum TypeDeserilaizeInfo {
     PrimitiveInfo(SignalType),
     StructInfo { attributes: Vec<StructAttribute> },
     EnumInfo { entries: Vec<(String, u64)> },
+    ArrayInfo { len: usize, element_deserializer: Box<TypeDeserializer> },
 }
 
 struct StructAttribute {
@@ -59,7 +60,10 @@ impl TypeDeserializer {
                 } => TypeDeserilaizeInfo::EnumInfo {
                     entries: entries.clone(),
                 },
-                Type::Array { len : _, ty : _ } => todo!("FUCK YOU ARRAYS"),
+                Type::Array { len, ty } => TypeDeserilaizeInfo::ArrayInfo {
+                    len: *len,
+                    element_deserializer: Box::new(TypeDeserializer::new(ty)),
+                },
             },
         }
     }
@@ -98,10 +102,34 @@ impl TypeDeserializer {
                         }
                     }
                     SignalType::Decimal {
-                        size: _,
+                        size,
                         offset,
                         scale,
-                    } => Value::RealValue(unsigned_value as f64 * (*scale) + (*offset)),
+                        signed,
+                    } => {
+                        let raw = if *signed {
+                            // shift the sign bit into the msb and back to pad with the sign.
+                            let shift = u64::BITS - *size as u32;
+                            (((unsigned_value << shift) as i64) >> shift) as f64
+                        } else {
+                            unsigned_value as f64
+                        };
+                        Value::RealValue(raw * (*scale) + (*offset))
+                    }
+                    SignalType::Float { size: 32 } => {
+                        Value::RealValue(f32::from_bits(unsigned_value as u32) as f64)
+                    }
+                    SignalType::Float { size: _ } => {
+                        Value::RealValue(f64::from_bits(unsigned_value))
+                    }
+                    SignalType::Bool => Value::BoolValue(unsigned_value != 0),
+                    SignalType::String { len } => Value::StringValue(
+                        unsigned_value.to_le_bytes()[..*len as usize]
+                            .iter()
+                            .take_while(|byte| **byte != 0)
+                            .map(|byte| *byte as char)
+                            .collect(),
+                    ),
                 }
             }
             TypeDeserilaizeInfo::EnumInfo { entries } => {
@@ -146,6 +174,17 @@ impl TypeDeserializer {
                     })
                     .collect(),
             ),
+            TypeDeserilaizeInfo::ArrayInfo {
+                len,
+                element_deserializer,
+            } => Value::ArrayValue(
+                (0..*len)
+                    .map(|i| {
+                        element_deserializer
+                            .deserialize(&bitslice[i * element_deserializer.bit_size..])
+                    })
+                    .collect(),
+            ),
         }
     }
 }
@@ -1,7 +1,10 @@
 use canzero_config::config::{MessageId, MessageRef, SignalType, Type};
-use serde::{ser::SerializeMap, Serialize};
+use serde::{
+    ser::{SerializeMap, SerializeSeq},
+    Serialize,
+};
 
-use canzero_common::Timestamped;
+use canzero_common::{CanFrame, Timestamped};
 
 use bitvec::{
     prelude::{BitOrder, Lsb0},
@@ -12,7 +15,7 @@ use bitvec::{
 #[derive(Clone, Debug)]
 pub struct Frame {
     config: MessageRef,
-    data: u64,
+    can_frame: CanFrame,
     value: FrameValue,
 }
 
@@ -25,6 +28,9 @@ pub enum Value {
     RealValue(f64),
     StructValue(Vec<Attribute>),
     EnumValue(String),
+    BoolValue(bool),
+    StringValue(String),
+    ArrayValue(Vec<Value>),
 }
 
 impl Value {
@@ -73,10 +79,10 @@ impl Attribute {
 }
 
 impl Frame {
-    pub fn new(config: MessageRef, data: u64, value: FrameValue) -> Self {
+    pub fn new(config: MessageRef, can_frame: CanFrame, value: FrameValue) -> Self {
         Self {
             config,
-            data,
+            can_frame,
             value,
         }
     }
@@ -87,8 +93,13 @@ impl Frame {
     pub fn ide(&self) -> bool {
         self.config.id().ide()
     }
-    pub fn data(&self) -> u64 {
-        self.data
+    /// payload in words of 8 bytes (more than one for CAN FD frames).
+    pub fn data(&self) -> &[u64] {
+        self.can_frame.get_data()
+    }
+    /// The raw frame as received.
+    pub fn can_frame(&self) -> &CanFrame {
+        &self.can_frame
     }
     pub fn rtr(&self) -> bool {
         // TODO am i even required still
@@ -101,7 +112,7 @@ impl Frame {
         self.config.name()
     }
     pub fn dlc(&self) -> u8 {
-        self.config.dlc()
+        self.can_frame.get_dlc()
     }
     pub fn attribute(&self, name: &str) -> Option<&Value> {
         self.value
@@ -146,19 +157,51 @@ impl Value {
                         size,
                         offset,
                         scale,
+                        signed,
                     }),
                 ) => {
                     let base_float = (val - offset) / scale;
-                    let mut base_bits = base_float.round() as u64;
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    if base_bits > max_uvalue {
-                        base_bits = max_uvalue;
-                    }
+                    let base_bits = if *signed {
+                        let max_ivalue = u64::MAX.checked_shr(65 - *size as u32).unwrap_or(0) as i64;
+                        // two's complement, bits above size are ignored.
+                        (base_float.round() as i64).clamp(-max_ivalue - 1, max_ivalue) as u64
+                    } else {
+                        let mut base_bits = base_float.round() as u64;
+                        let max_uvalue = u64::MAX >> (64 - *size as u32);
+                        if base_bits > max_uvalue {
+                            base_bits = max_uvalue;
+                        }
+                        base_bits
+                    };
                     for i in 0..*size {
                         let bit_int = (base_bits >> (i as u32)) & 0x1;
                         bit_vec.push(if bit_int == 0 { false } else { true });
                     }
                 }
+                (Value::RealValue(val), Type::Primitive(SignalType::Float { size })) => {
+                    let bits = if *size == 32 {
+                        (*val as f32).to_bits() as u64
+                    } else {
+                        val.to_bits()
+                    };
+                    for i in 0..*size {
+                        let bit_int = (bits >> i) & 0x1;
+                        bit_vec.push(if bit_int == 0 { false } else { true });
+                    }
+                }
+                (Value::BoolValue(val), Type::Primitive(SignalType::Bool)) => {
+                    bit_vec.push(*val);
+                }
+                (Value::StringValue(val), Type::Primitive(SignalType::String { len })) => {
+                    // zero padded, the first character is the lowest byte.
+                    let mut bytes = val.as_bytes().to_vec();
+                    bytes.resize(*len as usize, 0);
+                    for byte in bytes {
+                        for i in 0..8 {
+                            bit_vec.push((byte >> i) & 0x1 != 0);
+                        }
+                    }
+                }
                 (
                     Value::StructValue(value_attributes),
                     Type::Struct {
@@ -192,6 +235,11 @@ impl Value {
                         panic!("enum variant name not known!");
                     };
                 }
+                (Value::ArrayValue(elements), Type::Array { len: _, ty }) => {
+                    for element in elements {
+                        continue_get_as_bin(element, ty, bit_vec);
+                    }
+                }
                 _ => panic!("TypeValue and config::Type did not match!"),
             };
         }
@@ -249,6 +297,15 @@ impl Serialize for Value {
                 map.end()
             }
             Value::EnumValue(value) => serializer.serialize_str(value),
+            Value::BoolValue(value) => serializer.serialize_bool(*value),
+            Value::StringValue(value) => serializer.serialize_str(value),
+            Value::ArrayValue(elements) => {
+                let mut seq = serializer.serialize_seq(Some(elements.len()))?;
+                for element in elements {
+                    seq.serialize_element(element)?;
+                }
+                seq.end()
+            }
         }
     }
 }
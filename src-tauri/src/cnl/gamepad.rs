@@ -48,6 +48,7 @@ impl Gamepad {
             size,
             offset: lt2_offset,
             scale: lt2_scale,
+            signed: _,
         } = lt2_signal.ty()
         else {
             cprintln!(
@@ -67,6 +68,7 @@ impl Gamepad {
             size,
             offset: rt2_offset,
             scale: rt2_scale,
+            signed: _,
         } = rt2_signal.ty()
         else {
             cprintln!(
@@ -86,6 +88,7 @@ impl Gamepad {
             size,
             offset: lsb_x_offset,
             scale: lsb_x_scale,
+            signed: _,
         } = lsb_x_signal.ty()
         else {
             cprintln!(
@@ -105,6 +108,7 @@ impl Gamepad {
             size,
             offset: lsb_y_offset,
             scale: lsb_y_scale,
+            signed: _,
         } = lsb_y_signal.ty()
         else {
             cprintln!(
@@ -124,6 +128,7 @@ impl Gamepad {
             size,
             offset: rsb_x_offset,
             scale: rsb_x_scale,
+            signed: _,
         } = rsb_x_signal.ty()
         else {
             cprintln!(
@@ -143,6 +148,7 @@ impl Gamepad {
             size,
             offset: rsb_y_offset,
             scale: rsb_y_scale,
+            signed: _,
         } = rsb_y_signal.ty()
         else {
             cprintln!(
@@ -319,7 +325,7 @@ impl Gamepad {
                     data[6] |= 128;
                 }
 
-                tx.send_native(&msg, unsafe { std::mem::transmute(data) })
+                tx.send_native(&msg, &[u64::from_le_bytes(data)])
                     .await;
             }
         }
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use canzero_config::config::MessageRef;
+use canzero_common::TCanFrame;
+
+use crate::cnl::{
+    deserialize::FrameDeserializer,
+    errors::Result,
+    frame::TFrame,
+    network::command_object::CommandObject,
+};
+
+pub struct CommandRespFrameHandler {
+    frame_deserializer: FrameDeserializer,
+    command_object: Option<Arc<CommandObject>>,
+}
+
+impl CommandRespFrameHandler {
+    pub fn create(
+        command_resp_message: &MessageRef,
+        command_object: Option<Arc<CommandObject>>,
+    ) -> Self {
+        Self {
+            frame_deserializer: FrameDeserializer::new(command_resp_message),
+            command_object,
+        }
+    }
+    pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
+        let frame = self
+            .frame_deserializer
+            .deserialize(can_frame);
+        if let Some(command_object) = &self.command_object {
+            command_object.push_response(frame.attributes().clone()).await;
+        }
+        Ok(can_frame.new_value(frame))
+    }
+}
@@ -22,7 +22,7 @@ impl GetReqFrameHandler {
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(can_frame);
         Ok(can_frame.new_value(frame))
     }
 }
@@ -21,7 +21,8 @@ struct GetRespFrame {
     server_id: u8,
     client_id: u8,
     object_entry_id: u16,
-    data: u32,
+    /// one fragment for classic frames, up to 15 for CAN FD frames.
+    data: Vec<u32>,
 }
 
 impl GetRespFrame {
@@ -58,6 +59,14 @@ impl GetRespFrame {
                 "DETECTED INVALID CONFIG: invalid format of get_resp_frame : header.data missing"
             );
         };
+        // further fragments of CAN FD frames follow the first one.
+        let bytes = frame.can_frame().get_data_bytes();
+        let mut data = vec![*data as u32];
+        data.extend(bytes.get(8..).unwrap_or_default().chunks(4).map(|chunk| {
+            let mut word = [0u8; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            u32::from_le_bytes(word)
+        }));
         Self {
             sof: *sof != 0,
             eof: *eof != 0,
@@ -65,7 +74,7 @@ impl GetRespFrame {
             server_id: *server_id as u8,
             client_id: *client_id as u8,
             object_entry_id: *object_entry_id as u16,
-            data: *data as u32,
+            data,
         }
     }
 }
@@ -74,6 +83,8 @@ impl GetRespFrame {
 struct GetRespState {
     fragmentation_offset: usize,
     single_lookahead: bool,
+    /// number of received frames (CAN FD only, otherwise equal to the fragmentation_offset).
+    frame_count: usize,
 }
 
 struct GetResp {
@@ -82,13 +93,41 @@ struct GetResp {
     type_deserializer: TypeDeserializer,
     size: u32,
     buffer: Vec<u32>,
+    /// get responses on CAN FD buses carry multiple fragments per frame.
+    fd: bool,
 }
 
 impl GetResp {
+    /// CAN FD frames are received in order, every frame carries up to 15 fragments.
+    fn receive_fd(&mut self, frame: GetRespFrame) -> Result<()> {
+        let fragments = self.size.div_ceil(32) as usize;
+        if frame.sof {
+            self.state.fragmentation_offset = 0;
+            self.state.frame_count = 0;
+        } else if self.state.fragmentation_offset == 0 {
+            return Err(Error::InvalidGetResponseToggleFlag);
+        }
+        if frame.toggle != (self.state.frame_count % 2 == 1) {
+            return Err(Error::InvalidGetResponseToggleFlag);
+        }
+        let offset = self.state.fragmentation_offset;
+        let received = frame.data.len().min(fragments - offset);
+        self.buffer[offset..offset + received].copy_from_slice(&frame.data[..received]);
+        self.state.fragmentation_offset += received;
+        self.state.frame_count += 1;
+        if frame.eof != (self.state.fragmentation_offset == fragments) {
+            self.state.fragmentation_offset = 0;
+            return Err(Error::InvalidGetResponseEofFlag);
+        }
+        Ok(())
+    }
+
     async fn receive(&mut self, frame: GetRespFrame, timestamp: &Duration, unsolicited: bool) -> Result<()> {
         assert_eq!(frame.object_entry_id, self.object_entry.id() as u16);
 
-        if frame.sof {
+        if self.fd {
+            self.receive_fd(frame)?;
+        } else if frame.sof {
             let (expected_toggle, expected_eof) = (false, self.size.div_ceil(32) == 1);
             if expected_toggle != frame.toggle {
                 return Err(Error::InvalidGetResponseToggleFlag);
@@ -97,7 +136,7 @@ impl GetResp {
                 return Err(Error::InvalidGetResponseEofFlag);
             }
             self.state.fragmentation_offset = 0;
-            self.buffer[self.state.fragmentation_offset] = frame.data;
+            self.buffer[self.state.fragmentation_offset] = frame.data[0];
             match self.state.single_lookahead {
                 true => {
                     self.state.fragmentation_offset += 2;
@@ -114,7 +153,7 @@ impl GetResp {
                 && self.state.fragmentation_offset != 0
             {
                 // everything as expected
-                self.buffer[self.state.fragmentation_offset] = frame.data;
+                self.buffer[self.state.fragmentation_offset] = frame.data[0];
                 match self.state.single_lookahead {
                     true => {
                         self.state.fragmentation_offset += 2;
@@ -130,7 +169,7 @@ impl GetResp {
                     self.state.fragmentation_offset + 2 == self.size.div_ceil(32) as usize;
                 if accepted_toggle == frame.toggle && accepted_eof == frame.eof {
                     // assume that this frame arrived before previous one
-                    self.buffer[self.state.fragmentation_offset + 1] = frame.data;
+                    self.buffer[self.state.fragmentation_offset + 1] = frame.data[0];
                     self.state.single_lookahead = true;
                 }
             } else {
@@ -150,6 +189,7 @@ impl GetResp {
             }
             self.state.fragmentation_offset = 0;
             self.state.single_lookahead = false;
+            self.state.frame_count = 0;
             self.buffer.fill(0);
         }
         Ok(())
@@ -188,9 +228,10 @@ impl GetRespFrameHandler {
                     tokio::sync::Mutex::new(GetResp {
                         object_entry: object_entry.clone(),
                         buffer: vec![0; object_entry.ty().size().div_ceil(32) as usize],
-                        state: GetRespState { fragmentation_offset: 0, single_lookahead: false },
+                        state: GetRespState { fragmentation_offset: 0, single_lookahead: false, frame_count: 0 },
                         size: object_entry.ty().size(),
                         type_deserializer: TypeDeserializer::new(object_entry.ty()),
+                        fd: get_resp_msg.bus().is_fd(),
                     }),
                 );
                 get_resp_lookup.insert(
@@ -202,9 +243,10 @@ impl GetRespFrameHandler {
                     tokio::sync::Mutex::new(GetResp {
                         object_entry: object_entry.clone(),
                         buffer: vec![0; object_entry.ty().size().div_ceil(32) as usize],
-                        state: GetRespState { fragmentation_offset: 0, single_lookahead: false },
+                        state: GetRespState { fragmentation_offset: 0, single_lookahead: false, frame_count: 0 },
                         size: object_entry.ty().size(),
                         type_deserializer: TypeDeserializer::new(object_entry.ty()),
+                        fd: get_resp_msg.bus().is_fd(),
                     }),
                 );
             }
@@ -220,7 +262,7 @@ impl GetRespFrameHandler {
         println!("Handling get response");
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(can_frame);
 
         let get_resp_frame = GetRespFrame::new(&frame);
 
@@ -94,7 +94,7 @@ impl SetRespFrameHandler {
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(can_frame);
         let set_resp = SetResponseFrame::create(&frame);
 
         if set_resp.client_id == self.node_id {
@@ -29,7 +29,7 @@ impl StreamFrameHandler {
     pub async fn handle(&self, can_frame: &TCanFrame) -> Result<TFrame> {
         let frame = self
             .frame_deserializer
-            .deserialize(can_frame.get_data_u64());
+            .deserialize(can_frame);
         for (attrib, oeo) in frame.attributes().iter().zip(&self.object_entries) {
             oeo.push_value(attrib.value().clone(), &can_frame.timestamp).await
         }
@@ -70,7 +70,7 @@ impl CNL {
 
         let node_id = node_id.unwrap_or(network_config.nodes().len() as u8);
 
-        let trace = Arc::new(TraceObject::create(app_handle));
+        let trace = Arc::new(TraceObject::create(app_handle, network_config, timebase));
 
         let tx = Arc::new(TxCom::create(
             &network_config,
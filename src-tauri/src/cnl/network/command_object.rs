@@ -1,13 +1,59 @@
-use canzero_config::config;
+use std::sync::Arc;
+
+use canzero_config::config::{
+    self,
+    decode::{DecodedAttribute, DecodedValue},
+    encode::MessageEncoder,
+};
+use tokio::sync::{oneshot, Mutex};
+
+use crate::cnl::{
+    frame::{Attribute, Value},
+    tx::TxCom,
+};
 
 pub struct CommandObject {
     command_ref: config::CommandRef,
+    tx: Arc<TxCom>,
+    encoder: MessageEncoder,
+    // resolved by the command_resp frame handler.
+    open_call: Mutex<Option<oneshot::Sender<Vec<Attribute>>>>,
+}
+
+fn to_decoded_value(value: &Value) -> DecodedValue {
+    match value {
+        Value::UnsignedValue(value) => DecodedValue::Unsigned(*value),
+        Value::SignedValue(value) => DecodedValue::Signed(*value),
+        Value::RealValue(value) => DecodedValue::Real(*value),
+        Value::EnumValue(value) => DecodedValue::Enum(value.clone()),
+        Value::BoolValue(value) => DecodedValue::Bool(*value),
+        Value::StringValue(value) => DecodedValue::String(value.clone()),
+        Value::StructValue(attributes) => DecodedValue::Struct(
+            attributes
+                .iter()
+                .map(|attribute| DecodedAttribute {
+                    name: attribute.name().to_owned(),
+                    value: to_decoded_value(attribute.value()),
+                })
+                .collect(),
+        ),
+        Value::ArrayValue(elements) => {
+            DecodedValue::Array(elements.iter().map(to_decoded_value).collect())
+        }
+    }
 }
 
 impl CommandObject {
-    pub fn create(command_config: &config::CommandRef, _app_handle: &tauri::AppHandle) -> Self {
+    pub fn create(
+        command_config: &config::CommandRef,
+        _app_handle: &tauri::AppHandle,
+        tx: Arc<TxCom>,
+    ) -> Self {
         Self {
             command_ref: command_config.clone(),
+            tx,
+            encoder: MessageEncoder::new(command_config.tx_message()),
+            open_call: Mutex::new(None),
         }
     }
     pub fn name(&self) -> &str {
@@ -16,31 +62,65 @@ impl CommandObject {
     pub fn description(&self) -> Option<&String> {
         self.command_ref.description()
     }
-
-    #[allow(unused)] //FIXME
-    pub fn build_invoke(&self) -> InvokeCommandBuilder {
-        InvokeCommandBuilder::new(self)
+    pub fn config(&self) -> &config::CommandRef {
+        &self.command_ref
     }
 
-    pub async fn deadlock_watchdog(&self) {
-        // pass
+    /// Arguments of the command (name and type) in the order of their definition.
+    pub fn arguments(&self) -> Vec<(&str, &config::TypeRef)> {
+        self.command_ref
+            .tx_message()
+            .encoding()
+            .map(|encoding| {
+                encoding
+                    .attributes()
+                    .iter()
+                    .map(|attribute| (attribute.name(), attribute.ty()))
+                    .collect()
+            })
+            .unwrap_or_default()
     }
-}
 
-pub struct InvokeCommandBuilder {
-}
+    /// Transmits the command request and waits for the response of the node,
+    /// returns the attributes of the response (e.g. erno).
+    pub async fn call(&self, arguments: Vec<Attribute>) -> Result<Vec<Attribute>, String> {
+        let mut open_call = self.open_call.lock().await;
+        if open_call.as_ref().is_some_and(|tx| !tx.is_closed()) {
+            return Err(format!("Older call of {} still in progress", self.name()));
+        }
+        let (resp_tx, resp_rx) = oneshot::channel();
+        *open_call = Some(resp_tx);
+        drop(open_call);
+
+        let data = self.encoder.encode(
+            &arguments
+                .iter()
+                .map(|attribute| DecodedAttribute {
+                    name: attribute.name().to_owned(),
+                    value: to_decoded_value(attribute.value()),
+                })
+                .collect::<Vec<DecodedAttribute>>(),
+        );
+        self.tx.send_native(self.command_ref.tx_message(), &data).await;
 
-impl InvokeCommandBuilder {
-    pub fn new(_command_object: &CommandObject) -> Self {
-        InvokeCommandBuilder {
+        // the node is expected to respond within the expected interval of the command.
+        let timeout = *self.command_ref.expected_interval() * 2;
+        match tokio::time::timeout(timeout, resp_rx).await {
+            Ok(Ok(response)) => Ok(response),
+            _ => {
+                self.open_call.lock().await.take();
+                Err(format!("No response for {}", self.name()))
+            }
         }
     }
-}
 
+    pub async fn push_response(&self, response: Vec<Attribute>) {
+        if let Some(resp_tx) = self.open_call.lock().await.take() {
+            let _ = resp_tx.send(response);
+        }
+    }
 
-#[allow(unused)] //FIXME
-enum CommandEvent {
-    Resp,
-    Req(u32),
-    Timeout(u32),
+    pub async fn deadlock_watchdog(&self) {
+        // pass
+    }
 }
@@ -83,7 +83,9 @@ impl NodeObject {
             commands: node_config
                 .commands()
                 .iter()
-                .map(|command| Arc::new(CommandObject::create(command, app_handle)))
+                .map(|command| {
+                    Arc::new(CommandObject::create(command, app_handle, tx_com.clone()))
+                })
                 .collect(),
             node_ref: node_config.clone(),
             heartbeat_wdgs,
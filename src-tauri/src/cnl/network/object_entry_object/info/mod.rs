@@ -16,6 +16,8 @@ pub struct ObjectEntryInformation {
     unit: Option<String>,
     ty : ObjectEntryType,
     plottable : bool,
+    min : Option<f64>,
+    max : Option<f64>,
 }
 
 impl ObjectEntryInformation {
@@ -23,7 +25,9 @@ impl ObjectEntryInformation {
                friend : Option<String>,
                id : u16,
                unit : Option<String>,
-               ty : ObjectEntryType, plottable : bool) -> Self {
+               ty : ObjectEntryType, plottable : bool,
+               min : Option<f64>,
+               max : Option<f64>) -> Self {
         Self {
             name,
             description,
@@ -31,8 +35,9 @@ impl ObjectEntryInformation {
             id,
             unit,
             ty,
-            plottable
-
+            plottable,
+            min,
+            max,
         }
     }
     pub fn description(&self) -> Option<&str> {
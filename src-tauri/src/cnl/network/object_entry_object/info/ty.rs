@@ -18,6 +18,11 @@ pub struct ObjectEntryRealType {
     max: f64,
 }
 
+#[derive(Clone, Serialize)]
+pub struct ObjectEntryStringType {
+    len: u8,
+}
+
 #[derive(Clone, Serialize)]
 pub struct ObjectEntryEnumType {
     name: String,
@@ -30,6 +35,12 @@ pub struct ObjectEntryStructType {
     attributes: ObjectEntryStructAttributeMap,
 }
 
+#[derive(Clone, Serialize)]
+pub struct ObjectEntryArrayType {
+    len: usize,
+    ty: Box<ObjectEntryType>,
+}
+
 #[derive(Clone)]
 pub struct ObjectEntryStructAttributeMap(Vec<(String, ObjectEntryType)>);
 
@@ -38,8 +49,11 @@ pub enum ObjectEntryType {
     Int(ObjectEntryIntType),
     Uint(ObjectEntryUIntType),
     Real(ObjectEntryRealType),
+    Bool,
+    String(ObjectEntryStringType),
     Enum(ObjectEntryEnumType),
     Struct(ObjectEntryStructType),
+    Array(ObjectEntryArrayType),
 }
 
 impl ObjectEntryType {
@@ -54,20 +68,29 @@ impl ObjectEntryType {
                 }
                 config::SignalType::Decimal {
                     size,
-                    offset,
-                    scale,
+                    offset: _,
+                    scale: _,
+                    signed: _,
                 } => {
-                    let unsigned_max =
-                        u64::MAX >> (u64::BITS - *size as u32);
-                    let decimal_range = unsigned_max as f64 * *scale;
-                    let min = *offset;
-                    let max = decimal_range + min;
+                    let (min, max) = signal_type.range();
                     ObjectEntryType::Real(ObjectEntryRealType {
                         bit_size: *size,
                         min,
                         max,
                     })
                 }
+                config::SignalType::Float { size } => {
+                    let (min, max) = signal_type.range();
+                    ObjectEntryType::Real(ObjectEntryRealType {
+                        bit_size: *size,
+                        min,
+                        max,
+                    })
+                }
+                config::SignalType::Bool => ObjectEntryType::Bool,
+                config::SignalType::String { len } => {
+                    ObjectEntryType::String(ObjectEntryStringType { len: *len })
+                }
             },
 
             config::Type::Struct {
@@ -96,7 +119,10 @@ impl ObjectEntryType {
                 name: name.clone(),
                 variants: entries.iter().map(|(variant, _)| variant.clone()).collect(),
             }),
-            config::Type::Array { len : _, ty : _ } => todo!("FUCK YOU"),
+            config::Type::Array { len, ty } => ObjectEntryType::Array(ObjectEntryArrayType {
+                len: *len,
+                ty: Box::new(ObjectEntryType::new(ty)),
+            }),
         }
     }
 }
@@ -120,6 +146,14 @@ impl Serialize for ObjectEntryType {
                 map.serialize_entry("id", "real")?;
                 map.serialize_entry("info", info)?;
             }
+            ObjectEntryType::Bool => {
+                map.serialize_entry("id", "bool")?;
+                map.serialize_entry("info", &())?;
+            }
+            ObjectEntryType::String(info) => {
+                map.serialize_entry("id", "string")?;
+                map.serialize_entry("info", info)?;
+            }
             ObjectEntryType::Enum(info) => {
                 map.serialize_entry("id", "enum")?;
                 map.serialize_entry("info", info)?;
@@ -128,6 +162,10 @@ impl Serialize for ObjectEntryType {
                 map.serialize_entry("id", "struct")?;
                 map.serialize_entry("info", info)?;
             }
+            ObjectEntryType::Array(info) => {
+                map.serialize_entry("id", "array")?;
+                map.serialize_entry("info", info)?;
+            }
         }
         map.end()
     }
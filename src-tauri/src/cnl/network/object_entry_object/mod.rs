@@ -397,6 +397,10 @@ impl ObjectEntryObject {
         &self.object_entry_ref.ty()
     }
 
+    pub fn config(&self) -> &ObjectEntryRef {
+        &self.object_entry_ref
+    }
+
     fn tx(&self) -> Arc<TxCom> {
         self.tx_com.clone()
     }
@@ -501,6 +505,8 @@ impl ObjectEntryObject {
             self.object_entry_ref.unit().map(str::to_owned),
             ObjectEntryType::new(self.object_entry_ref.ty()),
             self.plottable,
+            self.object_entry_ref.min(),
+            self.object_entry_ref.max(),
         )
     }
 
@@ -4,6 +4,8 @@ use canzero_config::config::{message, MessageId, MessageRef};
 
 use crate::cnl::{
     handler::{
+        command_req_frame_handler::CommandReqFrameHandler,
+        command_resp_frame_handler::CommandRespFrameHandler,
         get_req_frame_handler::GetReqFrameHandler, get_resp_frame_handler::GetRespFrameHandler,
         heartbeat_frame_handler::HeartbeatFrameHandler, set_req_frame_handler::SetReqFrameHandler,
         set_resp_frame_handler::SetRespFrameHandler, stream_frame_handler::StreamFrameHandler,
@@ -52,8 +54,27 @@ impl HandlerLookup {
                                 .collect(),
                         )),
                     )),
-                    message::MessageUsage::CommandReq(_) => None, //TODO
-                    message::MessageUsage::CommandResp(_) => None, //TODO
+                    message::MessageUsage::CommandReq(_) => Some((
+                        key,
+                        MessageHandler::CommandReqFrameHandler(CommandReqFrameHandler::create(
+                            msg,
+                        )),
+                    )),
+                    message::MessageUsage::CommandResp(command) => Some((
+                        key,
+                        MessageHandler::CommandRespFrameHandler(CommandRespFrameHandler::create(
+                            msg,
+                            network_object
+                                .nodes()
+                                .iter()
+                                .flat_map(|node_object| node_object.commands())
+                                .find(|command_object| {
+                                    command_object.config().tx_message().name()
+                                        == command.tx_message().name()
+                                })
+                                .cloned(),
+                        )),
+                    )),
                     message::MessageUsage::GetResp => Some((
                         key,
                         MessageHandler::GetRespFrameHandler(GetRespFrameHandler::create(
@@ -1,5 +1,10 @@
-use std::{cmp::Reverse, collections::HashMap, time::Duration};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet, VecDeque},
+    time::Duration,
+};
 
+use canzero_common::{CanFrame, NetworkFrame, TNetworkFrame};
 use canzero_config::config;
 use tokio::sync::Mutex;
 
@@ -25,9 +30,23 @@ pub enum SortOrder {
     Desc,
 }
 
+// NOTE upper bound for the raw history (~64MB),
+// the oldest frames are dropped first.
+const MAX_HISTORY_LENGTH: usize = 2_000_000;
+
+/// A raw frame in the order of arrival, the trace itself only holds the latest
+/// event of each frame.
+struct TraceRecord {
+    timestamp: Duration,
+    bus_id: u32,
+    can_frame: CanFrame,
+    trace_index: usize,
+}
+
 pub struct TraceDatabaseData {
     lookup: HashMap<TraceFrameKey, usize>,
     trace: Vec<TraceEvent>,
+    history: VecDeque<TraceRecord>,
     trace_id_strings: Vec<String>,
     sort_criteria: SortCriteria,
     sort_order: SortOrder,
@@ -147,6 +166,7 @@ impl TraceDatabase {
             data: Mutex::new(TraceDatabaseData {
                 lookup: HashMap::new(),
                 trace: vec![],
+                history: VecDeque::new(),
                 trace_id_strings: vec![],
                 filter_string: None,
                 sort_order: SortOrder::Desc,
@@ -159,6 +179,18 @@ impl TraceDatabase {
     pub async fn update(&self, frame: TraceFrame, arrive: Duration, bus_name : &str, bus_id : u32) {
         let mut unlocked_data = self.data.lock().await;
         let trace_lookup_entry = unlocked_data.lookup.get(&frame.key()).cloned();
+        if let Some(can_frame) = frame.can_frame() {
+            if unlocked_data.history.len() >= MAX_HISTORY_LENGTH {
+                unlocked_data.history.pop_front();
+            }
+            let trace_index = trace_lookup_entry.unwrap_or(unlocked_data.trace.len());
+            unlocked_data.history.push_back(TraceRecord {
+                timestamp: arrive,
+                bus_id,
+                can_frame,
+                trace_index,
+            });
+        }
         match trace_lookup_entry {
             Some(trace_lookup_index) => {
                 // NOTE overwrite previous trace event! }
@@ -223,6 +255,44 @@ impl TraceDatabase {
             .collect()
     }
 
+    /// All recorded frames in the order of arrival.
+    /// If `filtered` is set only frames matching the current filter of the trace view are returned.
+    pub async fn history(&self, filtered: bool) -> Vec<TNetworkFrame> {
+        let mut unlocked_data = self.data.lock().await;
+        let filter: Option<HashSet<usize>> = if filtered {
+            unlocked_data.update_filter();
+            Some(unlocked_data.sorted_filter.iter().cloned().collect())
+        } else {
+            None
+        };
+        unlocked_data
+            .history
+            .iter()
+            .filter(|record| match &filter {
+                Some(filter) => filter.contains(&record.trace_index),
+                None => true,
+            })
+            .map(|record| {
+                TNetworkFrame::new(
+                    record.timestamp,
+                    NetworkFrame {
+                        bus_id: record.bus_id,
+                        can_frame: record.can_frame.clone(),
+                    },
+                )
+            })
+            .collect()
+    }
+
+    pub async fn clear(&self) {
+        let mut unlocked_data = self.data.lock().await;
+        unlocked_data.lookup.clear();
+        unlocked_data.trace.clear();
+        unlocked_data.trace_id_strings.clear();
+        unlocked_data.history.clear();
+        unlocked_data.sorted_filter.clear();
+    }
+
     pub async fn deadlock_watchdog(&self) {
         let _ = self.data.lock().await;
     }
@@ -98,6 +98,15 @@ impl TraceFrame {
         }
     }
 
+    /// The raw frame as received, error frames have no raw representation.
+    pub fn can_frame(&self) -> Option<CanFrame> {
+        match &self {
+            TraceFrame::Undefined(can_frame) => Some(can_frame.clone()),
+            TraceFrame::Error(_) => None,
+            TraceFrame::Frame(frame) => Some(frame.can_frame().clone()),
+        }
+    }
+
     pub fn key_u32(&self) -> u32 {
         match &self {
             TraceFrame::Undefined(can_frame) => can_frame.key(),
@@ -153,7 +162,9 @@ impl Serialize for TraceFrame {
                         Value::UnsignedValue(_)
                         | Value::SignedValue(_)
                         | Value::RealValue(_)
-                        | Value::EnumValue(_) => attributes.push(Attribute::new(
+                        | Value::EnumValue(_)
+                        | Value::BoolValue(_)
+                        | Value::StringValue(_) => attributes.push(Attribute::new(
                             &format!("{prefix}{}", current.name()),
                             current.value().clone(),
                         )),
@@ -166,6 +177,19 @@ impl Serialize for TraceFrame {
                                 );
                             }
                         }
+                        Value::ArrayValue(elements) => {
+                            // elements are flattened to `name[index]`.
+                            for (index, element) in elements.iter().enumerate() {
+                                build_flat_attributes(
+                                    attributes,
+                                    &Attribute::new(
+                                        &format!("{}[{index}]", current.name()),
+                                        element.clone(),
+                                    ),
+                                    prefix,
+                                );
+                            }
+                        }
                     }
                 }
                 for attrib in frame.attributes() {
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use self::{
     database::{SortCriteria, SortOrder, TraceDatabase},
@@ -6,9 +6,10 @@ use self::{
     observable::TraceObservable,
 };
 
-use super::frame::TFrame;
+use super::{deserialize::FrameDeserializer, frame::TFrame};
 
-use canzero_common::{TCanFrame, TCanError};
+use canzero_common::{TCanFrame, TCanError, TNetworkFrame};
+use canzero_config::config::NetworkRef;
 
 pub mod database;
 pub mod event;
@@ -20,14 +21,43 @@ const TRACE_EVENT_NAME: &'static str = "trace";
 pub struct TraceObject {
     database: Arc<TraceDatabase>,
     observable: TraceObservable,
+    // (bus_id, key) -> deserializer, used to decode imported frames.
+    deserializers: HashMap<(u32, u32), FrameDeserializer>,
+    buses: Vec<(u32, String)>,
+    start_time: chrono::DateTime<chrono::Local>,
 }
 
 impl TraceObject {
-    pub fn create(app_handle: &tauri::AppHandle) -> Self {
+    pub fn create(
+        app_handle: &tauri::AppHandle,
+        network_config: &NetworkRef,
+        timebase: Instant,
+    ) -> Self {
         let database = Arc::new(TraceDatabase::new());
+        let deserializers = network_config
+            .messages()
+            .iter()
+            .map(|msg| {
+                (
+                    (msg.bus().id(), msg.id().as_u32() | if msg.id().ide() { 0x80000000 } else { 0 }),
+                    FrameDeserializer::new(msg),
+                )
+            })
+            .collect();
+        let buses = network_config
+            .buses()
+            .iter()
+            .map(|bus| (bus.id(), bus.name().to_owned()))
+            .collect();
+        let start_time = chrono::Local::now()
+            - chrono::Duration::from_std(Instant::now().duration_since(timebase))
+                .unwrap_or_else(|_| chrono::Duration::zero());
         Self {
             observable: TraceObservable::new(app_handle, TRACE_EVENT_NAME, &database),
             database,
+            deserializers,
+            buses,
+            start_time,
         }
     }
 
@@ -75,6 +105,51 @@ impl TraceObject {
             .await;
     }
 
+    /// Raw frames of the trace in the order of arrival (optionally only frames matching the current filter).
+    pub async fn history(&self, filtered: bool) -> Vec<TNetworkFrame> {
+        self.database.history(filtered).await
+    }
+
+    /// Replaces the content of the trace with the given frames.
+    /// Frames of known messages are decoded with the current network configuration.
+    pub async fn import(&self, frames: Vec<TNetworkFrame>) {
+        self.database.clear().await;
+        for frame in frames {
+            let (timestamp, network_frame) = frame.destruct();
+            let bus_id = network_frame.bus_id;
+            let bus_name = self.bus_name(bus_id);
+            let can_frame = network_frame.can_frame;
+            let trace_frame = match self.deserializers.get(&(bus_id, can_frame.key())) {
+                Some(deserializer) => {
+                    TraceFrame::Frame(deserializer.deserialize(&can_frame))
+                }
+                None => TraceFrame::Undefined(can_frame),
+            };
+            self.database
+                .update(trace_frame, timestamp, &bus_name, bus_id)
+                .await;
+        }
+    }
+
+    pub fn bus_name(&self, bus_id: u32) -> String {
+        match self.buses.iter().find(|(id, _)| *id == bus_id) {
+            Some((_, name)) => name.clone(),
+            None => format!("can{bus_id}"),
+        }
+    }
+
+    pub fn bus_id(&self, bus_name: &str) -> Option<u32> {
+        self.buses
+            .iter()
+            .find(|(_, name)| name == bus_name)
+            .map(|(id, _)| *id)
+    }
+
+    /// Wall clock time of the start of the run.
+    pub fn start_time(&self) -> chrono::DateTime<chrono::Local> {
+        self.start_time
+    }
+
     pub async fn filter_by(&self, filter_string: Option<String>) {
         self.database.filter_by(filter_string).await;
     }
@@ -3,7 +3,7 @@ use std::{
     time::{Duration, Instant},
 };
 
-use canzero_config::config::{self, MessageRef};
+use canzero_config::config::{self, Message};
 use color_print::cprintln;
 use tokio::time;
 
@@ -69,35 +69,48 @@ impl TxCom {
             config::MessageId::StandardId(id) => (*id, false),
             config::MessageId::ExtendedId(id) => (*id, true),
         };
+        let set_req_bus = self.network_ref.set_req_message().bus();
+        // CAN FD frames carry up to 15 fragments after the header.
+        let fragments_per_frame = if set_req_bus.is_fd() { 15 } else { 1 };
+        let chunks: Vec<&[u32]> = val.chunks(fragments_per_frame).collect();
         let mut frame_data: Vec<CanFrame> = vec![];
-        let frames_to_send = val.len();
+        let frames_to_send = chunks.len();
 
-        for i in 0..frames_to_send {
+        for (i, chunk) in chunks.iter().enumerate() {
             // SOF
-            let mut data_curr = if i == 0 { 1u64 } else { 0u64 };
+            let mut header = if i == 0 { 1u32 } else { 0u32 };
             // EOF
-            data_curr |= if i == frames_to_send - 1 {
-                1u64 << 1
+            header |= if i == frames_to_send - 1 {
+                1u32 << 1
             } else {
-                0u64
+                0u32
             };
             // toggle
-            data_curr |= ((i % 2) as u64) << 2;
+            header |= ((i % 2) as u32) << 2;
             // oe-id
-            data_curr |= (oe_id as u64) << 3;
+            header |= oe_id << 3;
             // client-id
-            data_curr |= (self.my_node_id as u64) << 16;
+            header |= (self.my_node_id as u32) << 16;
             // server-id
-            data_curr |= (server_id as u64) << 24;
+            header |= (server_id as u32) << 24;
             // data
-            data_curr |= (val[i] as u64) << 32;
+            let mut bytes = header.to_le_bytes().to_vec();
+            for word in chunk.iter() {
+                bytes.extend_from_slice(&word.to_le_bytes());
+            }
 
             let dlc = if i == (frames_to_send - 1) {
-                4 + last_fill
+                bytes.len() as u8 - (4 - last_fill)
             } else {
-                8
+                bytes.len() as u8
             };
-            frame_data.push(CanFrame::new(set_request_id, ide, false, dlc, data_curr));
+            let data = CanFrame::data_from_bytes(&bytes);
+            frame_data.push(if set_req_bus.is_fd() {
+                let len = config::bus::can_fd_len(dlc).unwrap();
+                CanFrame::new_fd(set_request_id, ide, set_req_bus.brs(), len, data)
+            } else {
+                CanFrame::new(set_request_id, ide, false, dlc, data[0])
+            });
         }
 
         fragmented_can_send(
@@ -109,24 +122,27 @@ impl TxCom {
         .await;
     }
 
-    pub async fn send_native(&self, msg : &MessageRef, data : u64) {
+    /// Sends the payload words `data` as `msg`, frames on CAN FD buses are sent as CAN FD frames.
+    pub async fn send_native(&self, msg : &Message, data : &[u64]) {
         let adapter = self.can_adapters.iter().find(|adap| adap.bus().id() == msg.bus().id()).unwrap();
-        let _ = adapter.send(CanFrame::new(msg.id().as_u32(), msg.id().ide(), false, msg.dlc(), data), true).await;
+        let frame = if msg.bus().is_fd() {
+            let mut words = [0u64; 8];
+            words[..data.len()].copy_from_slice(data);
+            CanFrame::new_fd(msg.id().as_u32(), msg.id().ide(), msg.bus().brs(), msg.dlc(), words)
+        } else {
+            CanFrame::new(msg.id().as_u32(), msg.id().ide(), false, msg.dlc(), data[0])
+        };
+        let _ = adapter.send(frame, true).await;
     }
 
     pub async fn send_get_req(&self, server_id: u8, object_entry_id: u16) {
         let mut data: u64 = 0;
         data |= object_entry_id as u64;
-        data |= (self.network_ref.nodes().len() as u64) << 13;
+        data |= (self.my_node_id as u64) << 13;
         data |= (server_id as u64) << 21;
 
-        let get_req_frame = CanFrame::new(
-            self.network_ref.get_req_message().id().as_u32(),
-            self.network_ref.get_req_message().id().ide(),
-            false,
-            self.network_ref.get_req_message().dlc(),
-            data,
-        );
+        let get_req_message = self.network_ref.get_req_message();
+        let get_req_frame = single_word_frame(get_req_message, get_req_message.dlc(), data);
 
         if let Err(err) = self.get_req_can_adapter.send(get_req_frame, true).await {
             println!("<red>Failed to send get req </red>: {err:?}");
@@ -156,8 +172,7 @@ impl TxCom {
                     can_adapter.bus().name()
                 ));
 
-            let heartbeat_frame =
-                CanFrame::new(msg.id().as_u32(), msg.id().ide(), false, 2, msg_data);
+            let heartbeat_frame = single_word_frame(msg, 2, msg_data);
 
             if let Err(err) = can_adapter.send(heartbeat_frame, true).await {
                 println!("<red>Failed to send heartbeat </red>: {err:?}");
@@ -186,3 +201,15 @@ async fn fragmented_can_send(
         }
     }
 }
+
+/// Frame of `msg` with a payload of at most 8 bytes, frames on CAN FD buses are sent as CAN FD frames.
+fn single_word_frame(msg: &Message, dlc: u8, data: u64) -> CanFrame {
+    if msg.bus().is_fd() {
+        let mut words = [0u64; 8];
+        words[0] = data;
+        let len = config::bus::can_fd_len(dlc).unwrap();
+        CanFrame::new_fd(msg.id().as_u32(), msg.id().ide(), msg.bus().brs(), len, words)
+    } else {
+        CanFrame::new(msg.id().as_u32(), msg.id().ide(), false, dlc, data)
+    }
+}
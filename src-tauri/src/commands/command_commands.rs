@@ -0,0 +1,51 @@
+use crate::cnl::frame::{Attribute, Value};
+use crate::state::cnl_state::CNLState;
+
+use super::object_entry_commands::parse_value;
+
+/// Calls a command of a node and waits for the response of the node.
+/// The arguments are given as a json object, mapping the argument names to their values.
+/// Returns the attributes of the response (e.g. erno).
+#[tauri::command]
+pub async fn invoke_command(
+    state: tauri::State<'_, CNLState>,
+    node_name: String,
+    command_name: String,
+    arguments_json: String,
+) -> Result<Value, String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: invoke_command({node_name:?}, {command_name:?}, {arguments_json:?})");
+    let cnl = state.lock().await;
+
+    let Some(node) = cnl.nodes().iter().find(|no| no.name() == &node_name) else {
+        return Err("Invalid node name".to_owned());
+    };
+    let Some(command_object) = node
+        .commands()
+        .iter()
+        .find(|command| command.name() == &command_name)
+        .cloned()
+    else {
+        return Err("Invalid command name".to_owned());
+    };
+    // the response may take a while, don't block other invokes.
+    drop(cnl);
+
+    let json_value = match serde_json::from_str::<serde_json::Value>(&arguments_json) {
+        Ok(v) => v,
+        Err(_) => return Err("Failed to parse JSON.".to_owned()),
+    };
+    let Some(json_arguments) = json_value.as_object() else {
+        return Err("Expected Object".to_owned());
+    };
+    let mut arguments = vec![];
+    for (name, ty) in command_object.arguments() {
+        let Some(json_argument) = json_arguments.get(name) else {
+            return Err(format!("Argument {name} is missing"));
+        };
+        arguments.push(Attribute::new(name, parse_value(ty, json_argument)?));
+    }
+
+    let response = command_object.call(arguments).await?;
+    Ok(Value::StructValue(response))
+}
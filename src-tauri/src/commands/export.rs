@@ -171,7 +171,12 @@ async fn write_to_fs(export_data: Vec<NodeExport>) -> Result<(), ()> {
                                         values: vec![],
                                     });
                                 }
-                                Type::Array { len: _, ty: _ } => panic!("Arrays not implemented"),
+                                Type::Array { len, ty } => {
+                                    let column_name = column_name.unwrap_or("value".to_owned());
+                                    for i in 0..*len {
+                                        create_columns(ty, Some(format!("{column_name}[{i}]")), columns);
+                                    }
+                                }
                             }
                         }
                         create_columns(&&oe.ty, None, &mut columns);
@@ -203,6 +208,19 @@ async fn write_to_fs(export_data: Vec<NodeExport>) -> Result<(), ()> {
                                     columns[*c].values.push(format!("{v}"));
                                     *c += 1;
                                 }
+                                Value::BoolValue(v) => {
+                                    columns[*c].values.push(format!("{v}"));
+                                    *c += 1;
+                                }
+                                Value::StringValue(v) => {
+                                    columns[*c].values.push(format!("{v}"));
+                                    *c += 1;
+                                }
+                                Value::ArrayValue(elements) => {
+                                    for element in elements {
+                                        add_value_to_columns(element, c, columns);
+                                    }
+                                }
                             }
                         }
 
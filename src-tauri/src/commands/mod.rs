@@ -3,6 +3,7 @@ pub mod trace;
 pub mod object_entry_commands;
 pub mod connection_status;
 pub mod node_commands;
+pub mod command_commands;
 pub mod export;
 pub mod startup;
 pub mod settings;
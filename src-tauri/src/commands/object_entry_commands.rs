@@ -1,7 +1,6 @@
 use std::time::Duration;
 
 use serde::Serialize;
-use tauri::utils::config::parse::parse_value;
 use tauri::Manager;
 
 use crate::cnl::frame::{Attribute, Value};
@@ -42,109 +41,175 @@ pub async fn set_object_entry_value(
         Err(_) => return Err("Failed to parse JSON.".to_owned()),
     };
 
-    fn parse_value(oe_type: &config::TypeRef, json_value: &serde_json::Value) -> Result<Value, String> {
-        match oe_type.as_ref() {
-            Type::Primitive(SignalType::SignedInt { size }) => {
-                if let Some(val) = json_value.as_i64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    let max_ivalue: i64 = (max_uvalue >> 1) as i64;
-                    let min_ivalue: i64 = unsafe {
-                        std::mem::transmute(u64::MAX << (*size as u32 - 1))
-                    };
-                    if val <= max_ivalue && val >= min_ivalue {
-                        Ok(Value::SignedValue(val))
-                    } else {
-                        return Err("Expected Signed Value".to_owned());
-                    }
+    let value = parse_value(oe_type, &json_value)?;
+    check_range(object_entry_object.config(), &value)?;
+
+    object_entry_object.set_request(value).await;
+
+    Ok(())
+}
+
+/// Parses the json representation of a value of the given type (as send by the frontend).
+pub fn parse_value(oe_type: &config::TypeRef, json_value: &serde_json::Value) -> Result<Value, String> {
+    match oe_type.as_ref() {
+        Type::Primitive(SignalType::SignedInt { size }) => {
+            if let Some(val) = json_value.as_i64() {
+                let max_uvalue = u64::MAX >> (64 - *size as u32);
+                let max_ivalue: i64 = (max_uvalue >> 1) as i64;
+                let min_ivalue: i64 = unsafe {
+                    std::mem::transmute(u64::MAX << (*size as u32 - 1))
+                };
+                if val <= max_ivalue && val >= min_ivalue {
+                    Ok(Value::SignedValue(val))
                 } else {
-                    return Err("Expected primitive value".to_owned());
+                    return Err("Expected Signed Value".to_owned());
                 }
+            } else {
+                return Err("Expected primitive value".to_owned());
             }
-            Type::Primitive(SignalType::UnsignedInt { size }) => {
-                if let Some(val) = json_value.as_u64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    if val <= max_uvalue {
-                        Ok(Value::UnsignedValue(val))
-                    } else {
-                        return Err("Expected unsigned value".to_owned());
-                    }
+        }
+        Type::Primitive(SignalType::UnsignedInt { size }) => {
+            if let Some(val) = json_value.as_u64() {
+                let max_uvalue = u64::MAX >> (64 - *size as u32);
+                if val <= max_uvalue {
+                    Ok(Value::UnsignedValue(val))
                 } else {
-                    return Err("Expected primitive value".to_owned());
+                    return Err("Expected unsigned value".to_owned());
                 }
+            } else {
+                return Err("Expected primitive value".to_owned());
             }
-            Type::Primitive(SignalType::Decimal {
-                size,
-                offset,
-                scale,
-            }) => {
-                if let Some(val) = json_value.as_f64() {
-                    let max_uvalue = u64::MAX >> (64 - *size as u32);
-                    let min = *offset;
-                    let max = (max_uvalue as f64) * scale + offset;
-                    if val <= max && val >= min {
-                        Ok(Value::RealValue(val))
-                    } else {
-                        return Err("Expected float value".to_owned());
-                    }
+        }
+        Type::Primitive(
+            signal_type @ SignalType::Decimal {
+                size: _,
+                offset: _,
+                scale: _,
+                signed: _,
+            },
+        ) => {
+            if let Some(val) = json_value.as_f64() {
+                let (min, max) = signal_type.range();
+                if val <= max && val >= min {
+                    Ok(Value::RealValue(val))
                 } else {
                     return Err("Expected float value".to_owned());
                 }
+            } else {
+                return Err("Expected float value".to_owned());
             }
-
-            Type::Struct {
-                name: _,
-                description: _,
-                attribs,
-                visibility: _,
-            } => {
-                if let Some(map) = json_value.as_object() {
-                    let mut attributes: Vec<Attribute> = vec![];
-
-                    for (name, attr_type) in attribs {
-                        if let Some(val) = map.get(name) {
-                            let type_val = parse_value(attr_type,val)?;
-                            attributes.push(Attribute::new(name, type_val));
-                            // if let Ok(type_val) = parse_value(attr_type, val) {
-                            // } else {
-                            //     return Err("Failed to parse attr");
-                            // }
-                        } else {
-                            return Err(format!("Attribute {name} does not exist"));
-                        }
-                    }
-                    Ok(Value::StructValue(attributes))
+        }
+        Type::Primitive(signal_type @ SignalType::Float { size: _ }) => {
+            if let Some(val) = json_value.as_f64() {
+                let (min, max) = signal_type.range();
+                if val <= max && val >= min {
+                    Ok(Value::RealValue(val))
                 } else {
-                    return Err("Expected Object".to_owned());
+                    return Err("Expected float value".to_owned());
                 }
+            } else {
+                return Err("Expected float value".to_owned());
             }
-            Type::Enum {
-                name: _,
-                description: _,
-                size: _,
-                entries,
-                visibility: _,
-            } => {
-                if let Some(variant_str) = json_value.as_str() {
-                    if entries.iter().any(|e| e.0 == variant_str) {
-                        Ok(Value::EnumValue(variant_str.to_string()))
-                        // Ok(TypeValue::Enum(oe_type.clone(), variant_str.to_string()))
+        }
+        Type::Primitive(SignalType::Bool) => {
+            if let Some(val) = json_value.as_bool() {
+                Ok(Value::BoolValue(val))
+            } else {
+                return Err("Expected bool value".to_owned());
+            }
+        }
+        Type::Primitive(SignalType::String { len }) => {
+            if let Some(val) = json_value.as_str() {
+                if val.is_ascii() && val.len() <= *len as usize {
+                    Ok(Value::StringValue(val.to_owned()))
+                } else {
+                    return Err(format!("Expected ASCII string of at most {len} characters"));
+                }
+            } else {
+                return Err("Expected string value".to_owned());
+            }
+        }
+
+        Type::Struct {
+            name: _,
+            description: _,
+            attribs,
+            visibility: _,
+        } => {
+            if let Some(map) = json_value.as_object() {
+                let mut attributes: Vec<Attribute> = vec![];
+
+                for (name, attr_type) in attribs {
+                    if let Some(val) = map.get(name) {
+                        let type_val = parse_value(attr_type,val)?;
+                        attributes.push(Attribute::new(name, type_val));
+                        // if let Ok(type_val) = parse_value(attr_type, val) {
+                        // } else {
+                        //     return Err("Failed to parse attr");
+                        // }
                     } else {
-                        return Err("Invalid variant".to_owned());
+                        return Err(format!("Attribute {name} does not exist"));
                     }
+                }
+                Ok(Value::StructValue(attributes))
+            } else {
+                return Err("Expected Object".to_owned());
+            }
+        }
+        Type::Enum {
+            name: _,
+            description: _,
+            size: _,
+            entries,
+            visibility: _,
+        } => {
+            if let Some(variant_str) = json_value.as_str() {
+                if entries.iter().any(|e| e.0 == variant_str) {
+                    Ok(Value::EnumValue(variant_str.to_string()))
+                    // Ok(TypeValue::Enum(oe_type.clone(), variant_str.to_string()))
                 } else {
-                    return Err("Expected Enum value".to_owned());
+                    return Err("Invalid variant".to_owned());
                 }
+            } else {
+                return Err("Expected Enum value".to_owned());
+            }
+        }
+        Type::Array { len, ty } => {
+            if let Some(elements) = json_value.as_array() {
+                if elements.len() != *len {
+                    return Err(format!("Expected array of {len} elements"));
+                }
+                let mut values = vec![];
+                for element in elements {
+                    values.push(parse_value(ty, element)?);
+                }
+                Ok(Value::ArrayValue(values))
+            } else {
+                return Err("Expected Array".to_owned());
             }
-            Type::Array { len: _, ty: _ } => todo!(),
         }
     }
+}
 
-
-    let value = parse_value(oe_type, &json_value)?;
-
-    object_entry_object.set_request(value).await;
-
-    Ok(())
+/// Rejects values outside of the `min` and `max` of the object entry,
+/// the node would reject the set request anyway.
+pub fn check_range(object_entry: &config::ObjectEntryRef, value: &Value) -> Result<(), String> {
+    let number = match value {
+        Value::UnsignedValue(v) => *v as f64,
+        Value::SignedValue(v) => *v as f64,
+        Value::RealValue(v) => *v,
+        _ => return Ok(()),
+    };
+    if object_entry.in_range(number) {
+        return Ok(());
+    }
+    let name = object_entry.name();
+    match (object_entry.min(), object_entry.max()) {
+        (Some(min), Some(max)) => Err(format!("{name} has to be between {min} and {max}")),
+        (Some(min), None) => Err(format!("{name} has to be at least {min}")),
+        (None, Some(max)) => Err(format!("{name} has to be at most {max}")),
+        (None, None) => Ok(()),
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
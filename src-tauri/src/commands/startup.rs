@@ -37,6 +37,7 @@ pub async fn download_network_configuration(
 pub enum ConnectionType {
     SocketCan = 0,
     Tcp = 1,
+    Replay = 2,
 }
 
 impl Serialize for ConnectionType {
@@ -47,6 +48,7 @@ impl Serialize for ConnectionType {
         serializer.serialize_u8(match self {
             ConnectionType::SocketCan => 0,
             ConnectionType::Tcp => 1,
+            ConnectionType::Replay => 2,
         })
     }
 }
@@ -61,6 +63,16 @@ pub struct ConnectionDescription {
 pub async fn discover_servers(
     state: tauri::State<'_, StartupState>,
 ) -> Result<Vec<ConnectionDescription>, String> {
+    if let Some(replay) = state.replay() {
+        let replay = replay.clone();
+        state
+            .set_connections(vec![NetworkConnectionCreateInfo::Replay(replay.clone())])
+            .await;
+        return Ok(vec![ConnectionDescription {
+            tag: ConnectionType::Replay,
+            description: format!("Replay of {replay:?}"),
+        }]);
+    }
     let scanner = UdpNetworkScanner::create()
         .await
         .map_err(|err| format!("{err:?}"))?;
@@ -123,6 +135,10 @@ pub async fn discover_servers(
                         .collect::<Vec<&str>>()
                 ),
             },
+            NetworkConnectionCreateInfo::Replay(path) => ConnectionDescription {
+                tag: ConnectionType::Replay,
+                description: format!("Replay of {path:?}"),
+            },
         })
         .collect())
 }
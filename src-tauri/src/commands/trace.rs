@@ -1,3 +1,12 @@
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::PathBuf,
+};
+
+use canzero_common::log_format::{self, LogFormat};
+use tauri::api::dialog::FileDialogBuilder;
+
 use crate::{
     cnl::trace::database::{SortCriteria, SortOrder},
     state::cnl_state::CNLState,
@@ -65,3 +74,91 @@ pub async fn filter_trace_by(
     state.lock().await.trace().filter_by(filter_string).await;
     Ok(())
 }
+
+/// Opens a file dialog, returns None if the dialog was canceled.
+async fn pick_log_file(save: bool) -> Option<PathBuf> {
+    let (tx, rx) = tokio::sync::oneshot::channel::<Option<PathBuf>>();
+    let dialog = FileDialogBuilder::new()
+        .add_filter("candump", &["log"])
+        .add_filter("Vector ASC", &["asc"]);
+    if save {
+        dialog
+            .set_title("Export trace")
+            .save_file(move |path| tx.send(path).unwrap());
+    } else {
+        dialog
+            .set_title("Import trace")
+            .pick_file(move |path| tx.send(path).unwrap());
+    }
+    rx.await.ok().flatten()
+}
+
+fn log_format_of(path: &PathBuf) -> Result<LogFormat, String> {
+    LogFormat::from_path(path).ok_or_else(|| {
+        format!("unsupported log format {path:?} (expected a candump .log or a Vector .asc file)")
+    })
+}
+
+#[tauri::command]
+pub async fn export_trace(
+    state: tauri::State<'_, CNLState>,
+    filtered: bool,
+) -> Result<(), String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: export_trace({filtered:?})");
+    let Some(path) = pick_log_file(true).await else {
+        return Ok(());
+    };
+    let format = log_format_of(&path)?;
+    let cnl = state.lock().await;
+    let trace = cnl.trace().clone();
+    drop(cnl);
+    let frames = trace.history(filtered).await;
+    let start_time = trace.start_time();
+
+    tokio::task::spawn_blocking(move || {
+        let mut writer = BufWriter::new(File::create(&path)?);
+        match format {
+            LogFormat::Candump => {
+                log_format::write_candump(&mut writer, &frames, start_time, |bus_id| {
+                    trace.bus_name(bus_id)
+                })?
+            }
+            LogFormat::Asc => log_format::write_asc(&mut writer, &frames, start_time)?,
+        }
+        writer.flush()
+    })
+    .await
+    .expect("Failed to join blocking task (during export_trace)")
+    .map_err(|err| format!("{err:?}"))
+}
+
+#[tauri::command]
+pub async fn import_trace(state: tauri::State<'_, CNLState>) -> Result<(), String> {
+    #[cfg(feature = "logging-invoke")]
+    println!("invoke: import_trace()");
+    let Some(path) = pick_log_file(false).await else {
+        return Ok(());
+    };
+    let format = log_format_of(&path)?;
+    let cnl = state.lock().await;
+    let trace = cnl.trace().clone();
+    drop(cnl);
+
+    let trace_lookup = trace.clone();
+    let frames = tokio::task::spawn_blocking(move || {
+        let reader = BufReader::new(File::open(&path)?);
+        match format {
+            LogFormat::Candump => {
+                log_format::read_candump(reader, |ifname| trace_lookup.bus_id(ifname))
+            }
+            LogFormat::Asc => log_format::read_asc(reader),
+        }
+    })
+    .await
+    .expect("Failed to join blocking task (during import_trace)")
+    .map_err(|err| format!("{err:?}"))?;
+
+    trace.import(frames).await;
+    Ok(())
+}
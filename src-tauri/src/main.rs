@@ -1,6 +1,6 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use canzero_cli::run_cli;
+use canzero_cli::{run_cli, GuiMode};
 use tauri::Manager;
 
 use crate::{
@@ -28,10 +28,12 @@ async fn main() {
                     .path_resolver()
                     .resolve_resource("xcompl/canzero-cli-armv7-unknown-linux-gnueabihf")
                     .unwrap();
-                if !run_cli(Some(armv7_binary)).await {
-                    std::process::exit(0);
-                }
-                handle.manage(StartupState::new());
+                let replay = match run_cli(Some(armv7_binary)).await {
+                    Some(GuiMode::Live) => None,
+                    Some(GuiMode::Replay(path)) => Some(path),
+                    None => std::process::exit(0),
+                };
+                handle.manage(StartupState::new(replay));
                 handle.manage(SearchStringStorage{store : Default::default()});
                 tauri::WindowBuilder::new(
                     &handle,
@@ -52,6 +54,8 @@ async fn main() {
             commands::trace::unlisten_from_trace,
             commands::trace::sort_trace_by,
             commands::trace::filter_trace_by,
+            commands::trace::export_trace,
+            commands::trace::import_trace,
             network_information::network_information,
             network_information::node_information,
             network_information::object_entry_information,
@@ -76,6 +80,7 @@ async fn main() {
             commands::node_commands::get_heartbeat_status,
             commands::node_commands::listen_to_heartbeat,
             commands::node_commands::unlisten_from_heartbeat,
+            commands::command_commands::invoke_command,
             commands::export::export,
             commands::export::export_all,
             commands::startup::download_network_configuration,
@@ -89,6 +94,7 @@ async fn main() {
             commands::settings::get_settings,
             commands::settings::set_frontend_lvl,
             commands::settings::set_deadlock_lvl,
+            commands::settings::set_record_sessions,
             commands::search_string_storage::get_stored_search_string,
             commands::search_string_storage::store_search_string,
             commands::errors::listen_to_errors,
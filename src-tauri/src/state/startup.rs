@@ -1,14 +1,18 @@
 use std::{
     future::IntoFuture,
+    path::PathBuf,
     sync::{Arc, OnceLock},
     time::Instant,
 };
 
+use canzero_appdata::AppData;
+use canzero_common::session_log::SessionLogger;
 use canzero_config::config::NetworkRef;
 use canzero_udp::frame::NetworkDescription;
+use color_print::cprintln;
 use tokio::sync::Mutex;
 
-use crate::cnl::can_adapter::CanAdapter;
+use crate::{cnl::can_adapter::CanAdapter, notification::notify_warning};
 
 use super::cnl_state::CNLState;
 
@@ -17,9 +21,11 @@ pub enum NetworkConnectionCreateInfo {
     Tcp(NetworkDescription),
     #[cfg(feature = "socket-can")]
     SocketCan,
+    Replay(PathBuf),
 }
 
 pub struct StartupState {
+    replay: Option<PathBuf>,
     network_config: Mutex<Option<NetworkRef>>,
     connections: Mutex<Vec<NetworkConnectionCreateInfo>>,
     established_connection: Mutex<(
@@ -31,8 +37,10 @@ pub struct StartupState {
 }
 
 impl StartupState {
-    pub fn new() -> Self {
+    /// If `replay` is set the only possible connection is the replay of the given session log.
+    pub fn new(replay: Option<PathBuf>) -> Self {
         StartupState {
+            replay,
             network_config: Mutex::new(None),
             connections: Mutex::new(vec![]),
             established_connection: Mutex::new((vec![], None, None, None)),
@@ -48,6 +56,10 @@ impl StartupState {
         self.network_config.lock().await.as_ref().cloned()
     }
 
+    pub fn replay(&self) -> Option<&PathBuf> {
+        self.replay.as_ref()
+    }
+
     pub async fn set_connections(&self, connections: Vec<NetworkConnectionCreateInfo>) {
         *self.connections.lock().await = connections;
     }
@@ -72,7 +84,7 @@ impl StartupState {
         match connection {
             NetworkConnectionCreateInfo::Tcp(nd) => {
                 let (can_adapters, node_id, sync_complete) =
-                    CanAdapter::create_tcp_adapters(&network_ref, app_handle, nd)
+                    CanAdapter::create_tcp_adapters(&network_ref, app_handle, nd, Self::create_session_log(&network_ref))
                         .await
                         .map_err(|err| format!("{err:?}"))?;
                 let adapters = can_adapters.into_iter().map(Arc::new).collect();
@@ -85,7 +97,7 @@ impl StartupState {
             }
             #[cfg(feature = "socket-can")]
             NetworkConnectionCreateInfo::SocketCan => {
-                let can_adapter = CanAdapter::create_socketcan_adapters(&network_ref, app_handle)
+                let can_adapter = CanAdapter::create_socketcan_adapters(&network_ref, app_handle, Self::create_session_log(&network_ref))
                     .map_err(|err| format!("{err:?}"))?;
                 *self.established_connection.lock().await = (
                     can_adapter.into_iter().map(Arc::new).collect(),
@@ -93,10 +105,56 @@ impl StartupState {
                     None,
                 );
             }
+            NetworkConnectionCreateInfo::Replay(path) => {
+                let (can_adapters, config_hash, timebase) =
+                    CanAdapter::create_replay_adapters(&network_ref, path)
+                        .map_err(|err| format!("{err:?}"))?;
+                if config_hash != network_ref.portable_hash() {
+                    notify_warning(
+                        app_handle,
+                        "Replay with a different network configuration",
+                        &format!("{path:?} was recorded with another network configuration, frames might be decoded incorrectly"),
+                        chrono::Local::now(),
+                    );
+                }
+                *self.established_connection.lock().await = (
+                    can_adapters.into_iter().map(Arc::new).collect(),
+                    Some(timebase),
+                    None,
+                    None,
+                );
+            }
         };
         Ok(())
     }
 
+    /// Session logs are best effort, a failure to create one never prevents a connection.
+    /// No session log is recorded if recording sessions is disabled in the settings.
+    fn create_session_log(network_ref: &NetworkRef) -> Option<Arc<SessionLogger>> {
+        let session_log = AppData::read()
+            .and_then(|appdata| {
+                Ok((appdata.new_session_log_path()?, appdata.max_session_log_size()))
+            })
+            .map_err(|err| format!("{err:?}"))
+            .and_then(|(path, max_size)| {
+                let Some(path) = path else {
+                    return Ok(None);
+                };
+                let logger =
+                    SessionLogger::create(&path, network_ref.portable_hash(), Some(max_size))
+                        .map_err(|err| format!("{err:?}"))?;
+                cprintln!("<green>Recording session to {path:?}</green>");
+                Ok(Some(logger))
+            });
+        match session_log {
+            Ok(logger) => logger.map(Arc::new),
+            Err(err) => {
+                cprintln!("<red>Failed to create session log : {err}</red>");
+                None
+            }
+        }
+    }
+
     pub async fn complete_setup(&self, app_handle: &tauri::AppHandle) -> Result<CNLState, String> {
         let Some(network_config) = self.network_config.lock().await.as_ref().cloned() else {
             return Err("Failed to complete setup. No network configuration avaiable".to_owned());
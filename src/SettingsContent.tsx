@@ -12,8 +12,10 @@ enum WdgLevel {
 
 interface Settings {
   configPath: string,
+  variant?: string,
   frontendWdgLvl: WdgLevel,
   deadlockWdgLvl: WdgLevel,
+  recordSessions: boolean,
 }
 
 
@@ -22,13 +24,16 @@ function SettingsContent() {
   const [frontendLvl, setFrontendLvl] = useState<WdgLevel>(WdgLevel.Active);
   const [deadlockLvl, setDeadlockLvl] = useState<WdgLevel>(WdgLevel.Active);
   const [configPath, setConfigPath] = useState<string>();
-
+  const [variant, setVariant] = useState<string>();
+  const [recordSessions, setRecordSessions] = useState<boolean>(true);
 
   useEffect(() => {
     invoke<Settings>("get_settings").then(s => {
       setFrontendLvl(s.frontendWdgLvl);
       setDeadlockLvl(s.deadlockWdgLvl);
       setConfigPath(s.configPath);
+      setVariant(s.variant);
+      setRecordSessions(s.recordSessions);
     }).catch(console.error);
 
   }, []);
@@ -78,7 +83,7 @@ function SettingsContent() {
               <ListItemIcon sx={{ justifyContent: "center" }}>
                 <SettingsApplicationsIcon />
               </ListItemIcon>
-              <ListItemText primary="Select network configuration" secondary={configPath} />
+              <ListItemText primary="Select network configuration" secondary={variant ? `${configPath} (${variant})` : configPath} />
             </ListItemButton>
           </ListItem>
           <ListItem disablePadding>
@@ -150,6 +155,22 @@ function SettingsContent() {
                 secondary="Danger: A deadlocked backend might not lead to a shutdown" />
             </ListItemButton>
           </ListItem>
+          <ListItem disablePadding>
+            <ListItemButton>
+              <ListItemIcon sx={{ justifyContent: "center" }}>
+                <Checkbox checked={recordSessions} onChange={(evt) => {
+                  invoke<boolean>("set_record_sessions", {
+                    recordSessions: evt.target.checked
+                  })
+                    .then(r => setRecordSessions(r))
+                    .catch(console.error);
+                }} />
+              </ListItemIcon>
+              <ListItemText
+                primary="Record Sessions"
+                secondary="Session logs are kept in ~/.canzero/sessions" />
+            </ListItemButton>
+          </ListItem>
         </List>
       </Container>
 
@@ -2,6 +2,7 @@ import { Alert, AlertTitle, AppBar, Box, Container, CssBaseline, IconButton, Lin
 import AccessibleIcon from '@mui/icons-material/Accessible';
 import LanIcon from '@mui/icons-material/Lan';
 import CellTowerIcon from '@mui/icons-material/CellTower';
+import HistoryIcon from '@mui/icons-material/History';
 import ArrowBackIcon from '@mui/icons-material/ArrowBack';
 import RefreshIcon from '@mui/icons-material/Refresh';
 import { invoke } from "@tauri-apps/api";
@@ -21,6 +22,7 @@ enum ConnectionState {
 enum ConnectionType {
   SocketCan = 0,
   Tcp = 1,
+  Replay = 2,
 }
 
 interface ConnectionDescription {
@@ -219,7 +221,7 @@ function StartupConnection({ onSettings }: StartupConnectionProps) {
                                         setConnectionState(ConnectionState.Connecting);
                                       }}>
                                         <ListItemIcon>
-                                          {connection.tag == ConnectionType.SocketCan ? <LanIcon /> : <CellTowerIcon />}
+                                          {connection.tag == ConnectionType.SocketCan ? <LanIcon /> : connection.tag == ConnectionType.Replay ? <HistoryIcon /> : <CellTowerIcon />}
                                         </ListItemIcon>
                                         <ListItemText>
                                           {connection.description}
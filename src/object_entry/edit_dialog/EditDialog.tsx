@@ -77,8 +77,8 @@ function sendSetRequest(nodeName: string, objectEntryName: string, value: Option
     nodeName,
     objectEntryName,
     newValueJson: JSON.stringify(autocompletedValue),
-  }).catch((_) => {
-    console.error("Setter failed in the backend");
+  }).catch((error) => {
+    console.error(`Setter failed in the backend: ${error}`);
   });
 }
 
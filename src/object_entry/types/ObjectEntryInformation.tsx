@@ -8,9 +8,14 @@ export interface ObjectEntryInformation {
   unit?: string,
   ty: Type,
   plottable: boolean,
+  min?: number,
+  max?: number,
 }
 
 export function getMax(info: ObjectEntryInformation | undefined) {
+  if (info?.max !== undefined && info.max !== null) {
+    return info.max
+  }
   let max: number = 0
 
   switch (info?.ty.id) {
@@ -36,6 +41,9 @@ export function getMax(info: ObjectEntryInformation | undefined) {
 
 
 export function getMin(info: ObjectEntryInformation | undefined) {
+  if (info?.min !== undefined && info.min !== null) {
+    return info.min
+  }
   let min: number = 0
 
   switch (info?.ty.id) {
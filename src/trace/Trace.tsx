@@ -2,6 +2,8 @@ import { IconButton, InputAdornment, Paper, Stack, Table, TableBody, TableCell,
 import SearchIcon from '@mui/icons-material/Search';
 import AccessTimeIcon from '@mui/icons-material/AccessTime';
 import ChangeHistoryIcon from '@mui/icons-material/ChangeHistory';
+import FileDownloadIcon from '@mui/icons-material/FileDownload';
+import FileUploadIcon from '@mui/icons-material/FileUpload';
 import { useEffect, useRef, useState } from 'react';
 import Sortable, { Sorting } from './Sortable';
 import useFocusOnCtrlShortcut from './FocusOnKey';
@@ -221,6 +223,26 @@ function Trace() {
                   }}
               >
               </TextField>
+              <Stack direction="row">
+                <IconButton
+                    size="small"
+                    title="Export trace (candump .log / Vector .asc)"
+                    onClick={() => {
+                      invoke("export_trace", { filtered: searchString.length != 0 }).catch(console.error);
+                    }}
+                >
+                  <FileDownloadIcon fontSize="small" />
+                </IconButton>
+                <IconButton
+                    size="small"
+                    title="Import trace (candump .log / Vector .asc)"
+                    onClick={() => {
+                      invoke("import_trace").catch(console.error);
+                    }}
+                >
+                  <FileUploadIcon fontSize="small" />
+                </IconButton>
+              </Stack>
             </Stack>
 
           </TableCell>